@@ -0,0 +1,10 @@
+-- Migration 002: Turnaround tiers
+-- Express delivery moves from a zero-priced "Express Delivery" addon to
+-- configurable turnaround tiers; the turnaround_tiers table itself is
+-- created by schema.sql.
+
+ALTER TABLE invoices ADD COLUMN turnaround_tier TEXT DEFAULT 'standard';
+
+UPDATE invoices SET turnaround_tier = 'next_day' WHERE express_charge > 0 AND turnaround_tier = 'standard';
+
+UPDATE service_addons SET is_active = 0 WHERE id = 10 AND name = 'Express Delivery';
@@ -0,0 +1,9 @@
+-- Migration 003: Price lists
+-- Price list tables are created by schema.sql; this adds the columns that
+-- link customers and invoice lines to them.
+
+ALTER TABLE customers ADD COLUMN customer_group TEXT;
+
+ALTER TABLE invoice_items ADD COLUMN price_list_id INTEGER REFERENCES price_lists(id);
+
+ALTER TABLE invoice_item_addons ADD COLUMN price_list_id INTEGER REFERENCES price_lists(id);
@@ -0,0 +1,14 @@
+-- Migration 004: Customer rate cards
+-- Records where each invoice line's rate came from.
+
+ALTER TABLE invoice_items ADD COLUMN rate_source TEXT DEFAULT 'base_price';
+
+ALTER TABLE invoice_items ADD COLUMN rate_card_id INTEGER REFERENCES rate_cards(id);
+
+ALTER TABLE invoice_item_addons ADD COLUMN rate_source TEXT DEFAULT 'base_price';
+
+ALTER TABLE invoice_item_addons ADD COLUMN rate_card_id INTEGER REFERENCES rate_cards(id);
+
+UPDATE invoice_items SET rate_source = 'price_list' WHERE price_list_id IS NOT NULL;
+
+UPDATE invoice_item_addons SET rate_source = 'price_list' WHERE price_list_id IS NOT NULL;
@@ -0,0 +1,19 @@
+-- Migration 005: Addon pricing modes and addon GST
+-- Existing addons and invoice lines keep the per-unit behaviour; addon lines
+-- were always taxed at the parent service's rate.
+
+ALTER TABLE service_addons ADD COLUMN pricing_mode TEXT DEFAULT 'per_unit';
+
+ALTER TABLE service_addons ADD COLUMN gst_rate REAL;
+
+ALTER TABLE invoice_item_addons ADD COLUMN pricing_mode TEXT DEFAULT 'per_unit';
+
+ALTER TABLE invoice_item_addons ADD COLUMN gst_rate REAL DEFAULT 18.0;
+
+ALTER TABLE invoice_item_addons ADD COLUMN sgst REAL DEFAULT 0;
+
+ALTER TABLE invoice_item_addons ADD COLUMN cgst REAL DEFAULT 0;
+
+UPDATE invoice_item_addons SET gst_rate = (
+    SELECT ii.gst_rate FROM invoice_items ii WHERE ii.id = invoice_item_addons.invoice_item_id
+) WHERE sgst = 0 AND cgst = 0;
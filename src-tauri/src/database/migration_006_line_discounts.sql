@@ -0,0 +1,35 @@
+-- Migration 006: Invoice discounts allocated across lines
+-- Older invoices charged GST on undiscounted lines, so their taxable value
+-- is the GST base of the full line amount and no discount share.
+
+ALTER TABLE invoice_items ADD COLUMN discount_amount REAL DEFAULT 0;
+
+ALTER TABLE invoice_items ADD COLUMN taxable_value REAL DEFAULT 0;
+
+ALTER TABLE invoice_item_addons ADD COLUMN discount_amount REAL DEFAULT 0;
+
+ALTER TABLE invoice_item_addons ADD COLUMN taxable_value REAL DEFAULT 0;
+
+UPDATE invoice_items SET taxable_value = CASE
+    WHEN (SELECT gst_inclusive FROM invoices WHERE invoices.id = invoice_items.invoice_id) = 1
+    THEN ROUND(amount * 100.0 / (100.0 + gst_rate), 2)
+    ELSE amount
+END WHERE taxable_value = 0;
+
+UPDATE invoice_item_addons SET taxable_value = CASE
+    WHEN (
+        SELECT i.gst_inclusive FROM invoices i
+        JOIN invoice_items ii ON ii.invoice_id = i.id
+        WHERE ii.id = invoice_item_addons.invoice_item_id
+    ) = 1
+    THEN ROUND(amount * 100.0 / (100.0 + gst_rate), 2)
+    ELSE amount
+END WHERE taxable_value = 0;
+
+-- Totals are now written by create_invoice; these triggers recomputed them
+-- without the discount allocation or addon lines
+DROP TRIGGER IF EXISTS trg_invoice_items_insert;
+
+DROP TRIGGER IF EXISTS trg_invoice_items_update;
+
+DROP TRIGGER IF EXISTS trg_invoice_items_delete;
@@ -0,0 +1,8 @@
+-- Migration 007: Garment lifecycle tracking
+-- Existing tags start at 'received'; their history begins with the next scan.
+
+ALTER TABLE clothing_tags ADD COLUMN status TEXT DEFAULT 'received';
+
+ALTER TABLE clothing_tags ADD COLUMN status_updated_at TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_clothing_tags_status ON clothing_tags(status);
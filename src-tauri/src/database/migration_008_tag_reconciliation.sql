@@ -0,0 +1,7 @@
+-- Migration 008: Tag reconciliation
+-- Tags for pieces removed from an invoice are voided rather than deleted so
+-- their print and scan history survives.
+
+ALTER TABLE clothing_tags ADD COLUMN is_void INTEGER DEFAULT 0;
+
+ALTER TABLE clothing_tags ADD COLUMN voided_at TEXT;
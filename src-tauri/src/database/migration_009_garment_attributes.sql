@@ -0,0 +1,12 @@
+-- Migration 009: Garment attributes recorded at intake
+-- Condition notes and photos give the store a record to answer damage claims.
+
+ALTER TABLE clothing_tags ADD COLUMN garment_type TEXT;
+
+ALTER TABLE clothing_tags ADD COLUMN colour TEXT;
+
+ALTER TABLE clothing_tags ADD COLUMN brand TEXT;
+
+ALTER TABLE clothing_tags ADD COLUMN damage_notes TEXT;
+
+ALTER TABLE clothing_tags ADD COLUMN photo_paths TEXT DEFAULT '[]';
@@ -0,0 +1,4 @@
+-- Migration 010: Raw printing to thermal label printers
+-- Tags go to the printer as ZPL, TSPL or ESC/POS instead of through the print dialog.
+
+ALTER TABLE tag_settings ADD COLUMN printer_language TEXT DEFAULT 'html';
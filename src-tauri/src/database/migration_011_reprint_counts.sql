@@ -0,0 +1,4 @@
+-- Migration 011: Reprint counts
+-- The first print used to count as a reprint; take it back off printed tags.
+
+UPDATE clothing_tags SET reprint_count = MAX(reprint_count - 1, 0) WHERE printed_at IS NOT NULL;
@@ -0,0 +1,8 @@
+-- Migration 012: Per-store tag display options
+-- Stores can show the customer's phone and their store code on tags, and scale tag text.
+
+ALTER TABLE tag_settings ADD COLUMN show_customer_phone INTEGER DEFAULT 0;
+
+ALTER TABLE tag_settings ADD COLUMN show_store_code INTEGER DEFAULT 0;
+
+ALTER TABLE tag_settings ADD COLUMN font_scale REAL DEFAULT 1.0;
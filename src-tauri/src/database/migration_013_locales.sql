@@ -0,0 +1,9 @@
+-- Migration 013: Invoice and tag languages
+-- Stores print in their own language, optionally with a second one alongside;
+-- customers can ask for invoices in their language.
+
+ALTER TABLE stores ADD COLUMN locale TEXT;
+
+ALTER TABLE stores ADD COLUMN secondary_locale TEXT;
+
+ALTER TABLE customers ADD COLUMN locale TEXT;
@@ -8,6 +8,25 @@ pub struct DatabaseManager {
     pool: SqlitePool,
 }
 
+/// Incremental migrations for databases created by an older schema.sql.
+/// Fresh databases already have these columns, so "duplicate column"
+/// failures are expected and skipped. Keep each file to plain statements
+/// (no triggers) since they are split on `;`.
+const MIGRATIONS: &[(&str, &str)] = &[
+    ("002_turnaround_tiers", include_str!("migration_002_turnaround_tiers.sql")),
+    ("003_price_lists", include_str!("migration_003_price_lists.sql")),
+    ("004_rate_cards", include_str!("migration_004_rate_cards.sql")),
+    ("005_addon_pricing_modes", include_str!("migration_005_addon_pricing_modes.sql")),
+    ("006_line_discounts", include_str!("migration_006_line_discounts.sql")),
+    ("007_garment_tracking", include_str!("migration_007_garment_tracking.sql")),
+    ("008_tag_reconciliation", include_str!("migration_008_tag_reconciliation.sql")),
+    ("009_garment_attributes", include_str!("migration_009_garment_attributes.sql")),
+    ("010_raw_printing", include_str!("migration_010_raw_printing.sql")),
+    ("011_reprint_counts", include_str!("migration_011_reprint_counts.sql")),
+    ("012_tag_display_options", include_str!("migration_012_tag_display_options.sql")),
+    ("013_locales", include_str!("migration_013_locales.sql")),
+];
+
 impl DatabaseManager {
     pub async fn new(app_handle: &AppHandle) -> Result<Self> {
         let database_path = Self::get_database_path(app_handle)?;
@@ -60,12 +79,49 @@ impl DatabaseManager {
             .await
             .context("Failed to initialize database schema")?;
 
+        // Bring older databases up to date before seeding new columns
+        self.run_migrations().await?;
+
         // Insert seed data
         self.seed_data().await?;
 
         Ok(())
     }
 
+    async fn run_migrations(&self) -> Result<()> {
+        for (version, sql) in MIGRATIONS {
+            let applied = sqlx::query("SELECT 1 FROM schema_migrations WHERE version = ?")
+                .bind(version)
+                .fetch_optional(&self.pool)
+                .await
+                .context("Failed to read schema migrations")?
+                .is_some();
+
+            if applied {
+                continue;
+            }
+
+            for statement in split_sql_statements(sql) {
+                if let Err(e) = sqlx::query(&statement).execute(&self.pool).await {
+                    if e.to_string().contains("duplicate column name") {
+                        continue;
+                    }
+                    return Err(e).context(format!("Failed to apply migration {}", version));
+                }
+            }
+
+            sqlx::query("INSERT INTO schema_migrations (version) VALUES (?)")
+                .bind(version)
+                .execute(&self.pool)
+                .await
+                .context("Failed to record schema migration")?;
+
+            log::info!("Applied database migration {}", version);
+        }
+
+        Ok(())
+    }
+
     async fn seed_data(&self) -> Result<()> {
         let seed_sql = include_str!("seed.sql");
         sqlx::query(seed_sql)
@@ -120,4 +176,77 @@ impl DatabaseManager {
     pub async fn close(&self) {
         self.pool.close().await;
     }
-}
\ No newline at end of file
+}
+
+fn split_sql_statements(sql: &str) -> Vec<String> {
+    let without_comments = sql
+        .lines()
+        .filter(|line| !line.trim_start().starts_with("--"))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    without_comments
+        .split(';')
+        .map(|statement| statement.trim())
+        .filter(|statement| !statement.is_empty())
+        .map(|statement| statement.to_string())
+        .collect()
+}
+
+/// Fixtures for tests that need an invoice in the database
+#[cfg(test)]
+pub(crate) mod test_fixtures {
+    use super::DatabaseManager;
+
+    /// Id of the customer and invoice `invoice_db` creates
+    pub const TEST_INVOICE_ID: i64 = 900;
+
+    /// A fresh database with one invoice at the seeded store 1, for a
+    /// customer named `customer_name`
+    pub async fn invoice_db(invoice_no: &str, customer_name: &str) -> DatabaseManager {
+        let db = DatabaseManager::new_with_url("sqlite::memory:").await.unwrap();
+        db.initialize_schema().await.unwrap();
+        sqlx::query("INSERT INTO customers (id, name, phone) VALUES (?, ?, '9000000000')")
+            .bind(TEST_INVOICE_ID)
+            .bind(customer_name)
+            .execute(db.get_pool())
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO invoices (id, invoice_no, customer_id, store_id) VALUES (?, ?, ?, 1)")
+            .bind(TEST_INVOICE_ID)
+            .bind(invoice_no)
+            .bind(TEST_INVOICE_ID)
+            .execute(db.get_pool())
+            .await
+            .unwrap();
+        db
+    }
+
+    /// Add a line of `pieces` garments of the seeded service 1 to the
+    /// invoice, returning the item id
+    pub async fn add_invoice_item(db: &DatabaseManager, pieces: i64) -> i64 {
+        sqlx::query_scalar(
+            "INSERT INTO invoice_items (invoice_id, service_id, piece_count, rate, amount) VALUES (?, 1, ?, 50, 50) RETURNING id",
+        )
+        .bind(TEST_INVOICE_ID)
+        .bind(pieces)
+        .fetch_one(db.get_pool())
+        .await
+        .unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_sql_statements_skips_comments() {
+        let sql = "-- header\nALTER TABLE a ADD COLUMN b TEXT;\n\n-- note\nCREATE INDEX IF NOT EXISTS idx ON a(b);\n";
+        let statements = split_sql_statements(sql);
+
+        assert_eq!(statements.len(), 2);
+        assert_eq!(statements[0], "ALTER TABLE a ADD COLUMN b TEXT");
+        assert!(statements[1].starts_with("CREATE INDEX"));
+    }
+}
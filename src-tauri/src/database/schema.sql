@@ -12,6 +12,8 @@ CREATE TABLE IF NOT EXISTS customers (
     email TEXT,
     address TEXT,
     notes TEXT,
+    customer_group TEXT, -- selects group-specific price lists (e.g. corporate, staff)
+    locale TEXT, -- en, hi, ta; NULL prints in the store's language
     is_active INTEGER DEFAULT 1,
     created_at TEXT DEFAULT CURRENT_TIMESTAMP,
     updated_at TEXT DEFAULT CURRENT_TIMESTAMP
@@ -31,6 +33,8 @@ CREATE TABLE IF NOT EXISTS stores (
     gstin TEXT,
     pan_number TEXT,
     owner_name TEXT,
+    locale TEXT, -- en, hi, ta; NULL prints in English
+    secondary_locale TEXT, -- printed alongside for bilingual invoices
     is_active INTEGER DEFAULT 1,
     created_at TEXT DEFAULT CURRENT_TIMESTAMP,
     updated_at TEXT DEFAULT CURRENT_TIMESTAMP
@@ -78,11 +82,13 @@ CREATE TABLE IF NOT EXISTS service_variants (
 -- 6. Service add-ons table
 CREATE TABLE IF NOT EXISTS service_addons (
     id INTEGER PRIMARY KEY AUTOINCREMENT,
-    service_id INTEGER NOT NULL REFERENCES services(id),
+    service_id INTEGER NOT NULL REFERENCES services(id), -- owning service; see service_addon_links for sharing
     name TEXT NOT NULL,
     description TEXT,
-    price REAL NOT NULL,
+    price REAL NOT NULL, -- amount per unit, or the percentage for percent_of_line
     unit TEXT NOT NULL, -- kg, piece, stain, order
+    pricing_mode TEXT DEFAULT 'per_unit', -- per_unit, percent_of_line, per_order, per_piece
+    gst_rate REAL, -- NULL = parent service's GST rate
     is_active INTEGER DEFAULT 1,
     created_at TEXT DEFAULT CURRENT_TIMESTAMP,
     updated_at TEXT DEFAULT CURRENT_TIMESTAMP
@@ -121,8 +127,11 @@ CREATE TABLE IF NOT EXISTS invoices (
     -- Piece tracking
     total_pieces INTEGER DEFAULT 0,
 
+    -- Turnaround tier (drives express surcharge and delivery date)
+    turnaround_tier TEXT DEFAULT 'standard', -- standard, next_day, same_day
+
     -- Status and tracking
-    status TEXT DEFAULT 'pending', -- pending, in-progress, completed, paid, cancelled
+    status TEXT DEFAULT 'pending', -- pending, in-progress, ready, completed, paid, cancelled
     notes TEXT,
 
     -- Audit fields
@@ -144,8 +153,13 @@ CREATE TABLE IF NOT EXISTS invoice_items (
     rate REAL NOT NULL, -- snapshot rate at invoice time
     amount REAL NOT NULL, -- snapshot amount at invoice time
     gst_rate REAL DEFAULT 18.0,
+    discount_amount REAL DEFAULT 0, -- share of the invoice discount
+    taxable_value REAL DEFAULT 0, -- GST base after discount
     sgst REAL DEFAULT 0,
     cgst REAL DEFAULT 0,
+    price_list_id INTEGER REFERENCES price_lists(id), -- NULL = service base price
+    rate_source TEXT DEFAULT 'base_price', -- base_price, price_list, rate_card
+    rate_card_id INTEGER REFERENCES rate_cards(id),
     created_at TEXT DEFAULT CURRENT_TIMESTAMP
 );
 
@@ -155,8 +169,17 @@ CREATE TABLE IF NOT EXISTS invoice_item_addons (
     invoice_item_id INTEGER NOT NULL REFERENCES invoice_items(id),
     addon_id INTEGER NOT NULL REFERENCES service_addons(id),
     qty REAL DEFAULT 1,
-    rate REAL NOT NULL,
+    rate REAL NOT NULL, -- per-unit amount, or the percentage for percent_of_line
     amount REAL NOT NULL,
+    pricing_mode TEXT DEFAULT 'per_unit',
+    gst_rate REAL DEFAULT 18.0,
+    discount_amount REAL DEFAULT 0, -- share of the invoice discount
+    taxable_value REAL DEFAULT 0, -- GST base after discount
+    sgst REAL DEFAULT 0,
+    cgst REAL DEFAULT 0,
+    price_list_id INTEGER REFERENCES price_lists(id), -- NULL = addon base price
+    rate_source TEXT DEFAULT 'base_price', -- base_price, price_list, rate_card
+    rate_card_id INTEGER REFERENCES rate_cards(id),
     created_at TEXT DEFAULT CURRENT_TIMESTAMP
 );
 
@@ -230,6 +253,15 @@ CREATE TABLE IF NOT EXISTS clothing_tags (
     printed_at TEXT,
     printed_by TEXT,
     reprint_count INTEGER DEFAULT 0,
+    status TEXT DEFAULT 'received', -- received, sorted, washing, drying, ironing, qc, packed, delivered, missing
+    status_updated_at TEXT,
+    is_void INTEGER DEFAULT 0, -- piece no longer on the invoice; kept for history
+    voided_at TEXT,
+    garment_type TEXT, -- shirt, saree, blazer...
+    colour TEXT,
+    brand TEXT,
+    damage_notes TEXT, -- stains, tears or wear noted at intake
+    photo_paths TEXT DEFAULT '[]', -- JSON array of intake photo file paths
     created_at TEXT DEFAULT CURRENT_TIMESTAMP,
     UNIQUE(invoice_item_id, tag_number)
 );
@@ -243,6 +275,10 @@ CREATE TABLE IF NOT EXISTS tag_settings (
     printer_name TEXT,
     template_style TEXT DEFAULT 'standard', -- standard, compact
     include_barcode INTEGER DEFAULT 1,
+    printer_language TEXT DEFAULT 'html', -- html, zpl, tspl, escpos
+    show_customer_phone INTEGER DEFAULT 0,
+    show_store_code INTEGER DEFAULT 0,
+    font_scale REAL DEFAULT 1.0,
     created_at TEXT DEFAULT CURRENT_TIMESTAMP,
     updated_at TEXT DEFAULT CURRENT_TIMESTAMP
 );
@@ -255,97 +291,249 @@ CREATE INDEX IF NOT EXISTS idx_clothing_tags_printed_at ON clothing_tags(printed
 
 CREATE INDEX IF NOT EXISTS idx_tag_settings_store_id ON tag_settings(store_id);
 
--- Triggers for automatic invoice calculation
-CREATE TRIGGER IF NOT EXISTS trg_invoice_items_insert
-AFTER INSERT ON invoice_items
-FOR EACH ROW
-BEGIN
-    UPDATE invoices
-    SET
-        subtotal = (
-            SELECT COALESCE(SUM(amount), 0)
-            FROM invoice_items
-            WHERE invoice_id = NEW.invoice_id
-        ),
-        sgst_amount = (
-            SELECT COALESCE(SUM(sgst), 0)
-            FROM invoice_items
-            WHERE invoice_id = NEW.invoice_id
-        ),
-        cgst_amount = (
-            SELECT COALESCE(SUM(cgst), 0)
-            FROM invoice_items
-            WHERE invoice_id = NEW.invoice_id
-        ),
-        total = (
-            SELECT COALESCE(SUM(amount), 0) + COALESCE(SUM(sgst), 0) + COALESCE(SUM(cgst), 0)
-            FROM invoice_items
-            WHERE invoice_id = NEW.invoice_id
-        ) + COALESCE(express_charge, 0) - COALESCE(discount, 0),
-        updated_at = CURRENT_TIMESTAMP
-    WHERE id = NEW.invoice_id;
-END;
+-- 15. Applied schema migrations
+CREATE TABLE IF NOT EXISTS schema_migrations (
+    version TEXT PRIMARY KEY,
+    applied_at TEXT DEFAULT CURRENT_TIMESTAMP
+);
 
-CREATE TRIGGER IF NOT EXISTS trg_invoice_items_update
-AFTER UPDATE ON invoice_items
-FOR EACH ROW
+-- 16. Turnaround tiers (express delivery surcharges)
+CREATE TABLE IF NOT EXISTS turnaround_tiers (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    store_id INTEGER REFERENCES stores(id), -- NULL = default for all stores
+    code TEXT NOT NULL, -- standard, next_day, same_day
+    name TEXT NOT NULL,
+    surcharge_type TEXT DEFAULT 'percent', -- percent, flat
+    surcharge_value REAL DEFAULT 0,
+    min_surcharge REAL DEFAULT 0,
+    turnaround_hours INTEGER NOT NULL,
+    excluded_category_ids TEXT DEFAULT '[]', -- JSON array of service_categories ids exempt from the surcharge
+    is_active INTEGER DEFAULT 1,
+    created_at TEXT DEFAULT CURRENT_TIMESTAMP,
+    updated_at TEXT DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE(store_id, code)
+);
+
+CREATE INDEX IF NOT EXISTS idx_turnaround_tiers_store_id ON turnaround_tiers(store_id);
+
+-- 17. Price lists
+CREATE TABLE IF NOT EXISTS price_lists (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL UNIQUE,
+    description TEXT,
+    is_active INTEGER DEFAULT 1,
+    created_at TEXT DEFAULT CURRENT_TIMESTAMP,
+    updated_at TEXT DEFAULT CURRENT_TIMESTAMP
+);
+
+-- 18. Price list assignments (store and/or customer group; NULL = any)
+CREATE TABLE IF NOT EXISTS price_list_assignments (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    price_list_id INTEGER NOT NULL REFERENCES price_lists(id),
+    store_id INTEGER REFERENCES stores(id),
+    customer_group TEXT,
+    priority INTEGER DEFAULT 0, -- higher wins among equally specific assignments
+    created_at TEXT DEFAULT CURRENT_TIMESTAMP
+);
+
+-- 19. Price list items (effective-dated service, variant and addon prices)
+CREATE TABLE IF NOT EXISTS price_list_items (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    price_list_id INTEGER NOT NULL REFERENCES price_lists(id),
+    service_id INTEGER REFERENCES services(id),
+    variant_id INTEGER REFERENCES service_variants(id), -- NULL = all variants (multiplier applies)
+    addon_id INTEGER REFERENCES service_addons(id),
+    price REAL NOT NULL,
+    effective_from TEXT NOT NULL, -- YYYY-MM-DD, inclusive
+    effective_to TEXT, -- YYYY-MM-DD, exclusive; NULL = open-ended
+    created_at TEXT DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_price_list_assignments_store_id ON price_list_assignments(store_id);
+CREATE INDEX IF NOT EXISTS idx_price_list_items_service ON price_list_items(price_list_id, service_id, variant_id);
+CREATE INDEX IF NOT EXISTS idx_price_list_items_addon ON price_list_items(price_list_id, addon_id);
+
+-- 20. Customer rate cards (negotiated contract rates)
+CREATE TABLE IF NOT EXISTS rate_cards (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    customer_id INTEGER NOT NULL REFERENCES customers(id),
+    name TEXT NOT NULL,
+    valid_from TEXT NOT NULL, -- YYYY-MM-DD, inclusive
+    valid_to TEXT, -- YYYY-MM-DD, exclusive; NULL = until further notice
+    rates_include_gst INTEGER DEFAULT 0, -- 1 = fixed rates are GST-inclusive
+    notes TEXT,
+    is_active INTEGER DEFAULT 1,
+    created_at TEXT DEFAULT CURRENT_TIMESTAMP,
+    updated_at TEXT DEFAULT CURRENT_TIMESTAMP
+);
+
+-- 21. Rate card items (per service, variant or addon)
+CREATE TABLE IF NOT EXISTS rate_card_items (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    rate_card_id INTEGER NOT NULL REFERENCES rate_cards(id),
+    service_id INTEGER REFERENCES services(id),
+    variant_id INTEGER REFERENCES service_variants(id), -- NULL = all variants
+    addon_id INTEGER REFERENCES service_addons(id),
+    rate_type TEXT DEFAULT 'fixed', -- fixed, percent_off
+    value REAL NOT NULL,
+    created_at TEXT DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_rate_cards_customer_id ON rate_cards(customer_id);
+CREATE INDEX IF NOT EXISTS idx_rate_card_items_rate_card_id ON rate_card_items(rate_card_id);
+
+-- 22. Add-ons shared with services other than their owning service
+CREATE TABLE IF NOT EXISTS service_addon_links (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    addon_id INTEGER NOT NULL REFERENCES service_addons(id),
+    service_id INTEGER NOT NULL REFERENCES services(id),
+    created_at TEXT DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE(addon_id, service_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_service_addon_links_service_id ON service_addon_links(service_id);
+
+-- 23. Garment stage changes recorded by tag scans
+CREATE TABLE IF NOT EXISTS tag_scan_events (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    tag_id INTEGER NOT NULL REFERENCES clothing_tags(id),
+    from_status TEXT,
+    to_status TEXT NOT NULL,
+    scanned_by TEXT,
+    notes TEXT,
+    scanned_at TEXT DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_tag_scan_events_tag_id ON tag_scan_events(tag_id);
+
+-- 24. Tag print jobs, kept as an audit trail of first prints and reprints
+CREATE TABLE IF NOT EXISTS print_jobs (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    invoice_id INTEGER NOT NULL REFERENCES invoices(id),
+    printed_by TEXT,
+    printer_name TEXT,
+    printer_language TEXT NOT NULL DEFAULT 'html',
+    is_reprint INTEGER NOT NULL DEFAULT 0,
+    reprint_reason TEXT,
+    tag_count INTEGER NOT NULL DEFAULT 0,
+    status TEXT NOT NULL DEFAULT 'printed', -- printed, failed
+    error_message TEXT,
+    created_at TEXT DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE IF NOT EXISTS print_job_tags (
+    print_job_id INTEGER NOT NULL REFERENCES print_jobs(id) ON DELETE CASCADE,
+    tag_id INTEGER NOT NULL REFERENCES clothing_tags(id),
+    is_reprint INTEGER NOT NULL DEFAULT 0,
+    PRIMARY KEY (print_job_id, tag_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_print_jobs_invoice_id ON print_jobs(invoice_id);
+CREATE INDEX IF NOT EXISTS idx_print_jobs_created_at ON print_jobs(created_at);
+CREATE INDEX IF NOT EXISTS idx_print_job_tags_tag_id ON print_job_tags(tag_id);
+
+-- 25. Print queue drained by the background print worker
+CREATE TABLE IF NOT EXISTS print_queue (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    invoice_id INTEGER NOT NULL REFERENCES invoices(id),
+    kind TEXT NOT NULL, -- receipt, tags
+    status TEXT NOT NULL DEFAULT 'pending', -- pending, printing, printed, failed, cancelled
+    attempts INTEGER NOT NULL DEFAULT 0,
+    max_attempts INTEGER NOT NULL DEFAULT 5,
+    next_attempt_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    last_error TEXT,
+    print_job_id INTEGER REFERENCES print_jobs(id),
+    created_at TEXT DEFAULT CURRENT_TIMESTAMP,
+    updated_at TEXT DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_print_queue_status ON print_queue(status, next_attempt_at);
+CREATE INDEX IF NOT EXISTS idx_print_queue_invoice_id ON print_queue(invoice_id);
+
+-- 26. Invoice templates edited per store (NULL store_id = shared by all
+--     stores). A store prints a format with its own default, else the
+--     shared default, else the built-in template.
+CREATE TABLE IF NOT EXISTS invoice_templates (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    store_id INTEGER REFERENCES stores(id),
+    name TEXT NOT NULL,
+    format TEXT NOT NULL, -- a4, a5, thermal
+    content TEXT NOT NULL, -- Tera template source
+    layout TEXT NOT NULL, -- LayoutSettings as JSON
+    is_default INTEGER NOT NULL DEFAULT 0,
+    created_at TEXT DEFAULT CURRENT_TIMESTAMP,
+    updated_at TEXT DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_invoice_templates_store_format ON invoice_templates(store_id, format);
+CREATE UNIQUE INDEX IF NOT EXISTS idx_invoice_templates_default
+    ON invoice_templates(COALESCE(store_id, 0), format) WHERE is_default = 1;
+
+-- 27. Branding printed on a store's invoices. Terms come from
+--     terms_conditions; anything left NULL falls back to the built-in look.
+CREATE TABLE IF NOT EXISTS store_branding (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    store_id INTEGER NOT NULL UNIQUE REFERENCES stores(id),
+    business_name TEXT, -- NULL prints the store name
+    tagline TEXT,
+    logo_path TEXT, -- copy kept in the app data folder
+    primary_color TEXT,
+    secondary_color TEXT,
+    website TEXT,
+    support_phone TEXT,
+    support_email TEXT,
+    thank_you_message TEXT,
+    created_at TEXT DEFAULT CURRENT_TIMESTAMP,
+    updated_at TEXT DEFAULT CURRENT_TIMESTAMP
+);
+
+-- 28. ESC/POS receipt printer per store (NULL store_id = shared by all
+--     stores). Without a printer, receipts print as thermal HTML.
+CREATE TABLE IF NOT EXISTS receipt_settings (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    store_id INTEGER REFERENCES stores(id),
+    printer_name TEXT, -- same forms as tag_settings.printer_name
+    line_chars INTEGER NOT NULL DEFAULT 48, -- 42 or 48 characters per line
+    include_qr INTEGER NOT NULL DEFAULT 1,
+    qr_url TEXT, -- lookup URL with {invoice_no}; NULL encodes the invoice number
+    cut_paper INTEGER NOT NULL DEFAULT 1,
+    created_at TEXT DEFAULT CURRENT_TIMESTAMP,
+    updated_at TEXT DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_receipt_settings_store ON receipt_settings(COALESCE(store_id, 0));
+
+-- 29. Each invoice as issued, written in the transaction that creates it.
+--     Reprints come from the snapshot, so renaming a service or changing a
+--     template doesn't rewrite old invoices. Rows can't be changed or
+--     deleted; content_hash catches edits made around that.
+CREATE TABLE IF NOT EXISTS invoice_archives (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    invoice_id INTEGER NOT NULL UNIQUE, -- no foreign key: the archive outlives a deleted invoice
+    invoice_no TEXT NOT NULL,
+    format TEXT NOT NULL, -- format of the stored document
+    document TEXT NOT NULL, -- rendered HTML
+    data_json TEXT NOT NULL, -- InvoiceHtmlData as JSON
+    settings_json TEXT NOT NULL, -- HtmlSettings per format as JSON
+    content_hash TEXT NOT NULL, -- HMAC-SHA256 hex of the fields above, keyed by the app's archive key
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TRIGGER IF NOT EXISTS trg_invoice_archives_no_update
+BEFORE UPDATE ON invoice_archives
 BEGIN
-    UPDATE invoices
-    SET
-        subtotal = (
-            SELECT COALESCE(SUM(amount), 0)
-            FROM invoice_items
-            WHERE invoice_id = NEW.invoice_id
-        ),
-        sgst_amount = (
-            SELECT COALESCE(SUM(sgst), 0)
-            FROM invoice_items
-            WHERE invoice_id = NEW.invoice_id
-        ),
-        cgst_amount = (
-            SELECT COALESCE(SUM(cgst), 0)
-            FROM invoice_items
-            WHERE invoice_id = NEW.invoice_id
-        ),
-        total = (
-            SELECT COALESCE(SUM(amount), 0) + COALESCE(SUM(sgst), 0) + COALESCE(SUM(cgst), 0)
-            FROM invoice_items
-            WHERE invoice_id = NEW.invoice_id
-        ) + COALESCE(express_charge, 0) - COALESCE(discount, 0),
-        updated_at = CURRENT_TIMESTAMP
-    WHERE id = NEW.invoice_id;
+    SELECT RAISE(ABORT, 'Invoice archives are read-only');
 END;
 
-CREATE TRIGGER IF NOT EXISTS trg_invoice_items_delete
-AFTER DELETE ON invoice_items
-FOR EACH ROW
+CREATE TRIGGER IF NOT EXISTS trg_invoice_archives_no_delete
+BEFORE DELETE ON invoice_archives
 BEGIN
-    UPDATE invoices
-    SET
-        subtotal = (
-            SELECT COALESCE(SUM(amount), 0)
-            FROM invoice_items
-            WHERE invoice_id = OLD.invoice_id
-        ),
-        sgst_amount = (
-            SELECT COALESCE(SUM(sgst), 0)
-            FROM invoice_items
-            WHERE invoice_id = OLD.invoice_id
-        ),
-        cgst_amount = (
-            SELECT COALESCE(SUM(cgst), 0)
-            FROM invoice_items
-            WHERE invoice_id = OLD.invoice_id
-        ),
-        total = (
-            SELECT COALESCE(SUM(amount), 0) + COALESCE(SUM(sgst), 0) + COALESCE(SUM(cgst), 0)
-            FROM invoice_items
-            WHERE invoice_id = OLD.invoice_id
-        ) + COALESCE(express_charge, 0) - COALESCE(discount, 0),
-        updated_at = CURRENT_TIMESTAMP
-    WHERE id = OLD.invoice_id;
+    SELECT RAISE(ABORT, 'Invoice archives are read-only');
 END;
 
+-- Invoice totals are computed by create_invoice after the discount is
+-- allocated across lines, so there are no amount triggers on invoice_items
+
 -- Audit triggers
 CREATE TRIGGER IF NOT EXISTS trg_audit_customers_update
 AFTER UPDATE ON customers
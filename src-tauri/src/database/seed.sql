@@ -25,17 +25,27 @@ INSERT OR IGNORE INTO services (id, category_id, name, unit, base_price, min_qua
 (4, 2, 'Premium Laundry', 'kg', 159, 0, 18);
 
 -- Insert laundry add-ons
-INSERT OR IGNORE INTO service_addons (id, service_id, name, unit, price) VALUES
-(1, 1, 'Moth Proofing', 'kg', 20),
-(2, 1, 'Antiseptic', 'kg', 10),
-(3, 1, 'Stain Removal', 'stain', 30),
-(4, 1, 'Starch', 'piece', 25),
-(5, 1, 'Softener', 'kg', 5),
-(6, 1, 'Extra Soiled', 'kg', 10),
-(7, 1, 'Shoe Laundry', 'pair', 149),
-(8, 1, 'Hanger Packing', 'piece', 30),
-(9, 1, 'Shirt Packing', 'piece', 15),
-(10, 1, 'Express Delivery', 'order', 0); -- calculated as percentage
+INSERT OR IGNORE INTO service_addons (id, service_id, name, unit, price, pricing_mode) VALUES
+(1, 1, 'Moth Proofing', 'kg', 20, 'per_unit'),
+(2, 1, 'Antiseptic', 'kg', 10, 'per_unit'),
+(3, 1, 'Stain Removal', 'stain', 30, 'per_unit'),
+(4, 1, 'Starch', 'piece', 25, 'per_piece'),
+(5, 1, 'Softener', 'kg', 5, 'per_unit'),
+(6, 1, 'Extra Soiled', 'kg', 10, 'per_unit'),
+(7, 1, 'Shoe Laundry', 'pair', 149, 'per_unit'),
+(8, 1, 'Hanger Packing', 'piece', 30, 'per_piece'),
+(9, 1, 'Shirt Packing', 'piece', 15, 'per_piece');
+
+-- Share the kg-based laundry add-ons with the other kg laundry services
+INSERT OR IGNORE INTO service_addon_links (addon_id, service_id) VALUES
+(1, 2), (2, 2), (3, 2), (5, 2), (6, 2),
+(1, 4), (2, 4), (3, 4), (5, 4), (6, 4);
+
+-- Insert default turnaround tiers (express surcharge applies to the regular tariff)
+INSERT OR IGNORE INTO turnaround_tiers (id, store_id, code, name, surcharge_type, surcharge_value, min_surcharge, turnaround_hours) VALUES
+(1, NULL, 'standard', 'Standard', 'percent', 0, 0, 72),
+(2, NULL, 'next_day', 'Next Day', 'percent', 50, 50, 24),
+(3, NULL, 'same_day', 'Same Day', 'percent', 100, 100, 8);
 
 -- Insert household items
 INSERT OR IGNORE INTO services (id, category_id, name, unit, base_price, gst_rate, hsn_sac_code) VALUES
@@ -1,5 +1,6 @@
 use crate::database::DatabaseManager;
 use crate::models::{Customer, CreateCustomerRequest, UpdateCustomerRequest, CustomerWithStats, ApiResult, ApiError};
+use crate::services::Locale;
 use sqlx::Row;
 use tauri::State;
 
@@ -41,15 +42,17 @@ pub async fn create_customer(
 
     // Insert new customer
     let result = sqlx::query(
-        "INSERT INTO customers (name, phone, email, address, notes, is_active, created_at, updated_at)
-         VALUES (?, ?, ?, ?, ?, 1, datetime('now'), datetime('now'))
-         RETURNING id, name, phone, email, address, notes, is_active, created_at, updated_at"
+        "INSERT INTO customers (name, phone, email, address, notes, customer_group, locale, is_active, created_at, updated_at)
+         VALUES (?, ?, ?, ?, ?, ?, ?, 1, datetime('now'), datetime('now'))
+         RETURNING id, name, phone, email, address, notes, customer_group, locale, is_active, created_at, updated_at"
     )
     .bind(&request.name)
     .bind(&request.phone)
     .bind(&request.email)
     .bind(&request.address)
     .bind(&request.notes)
+    .bind(&request.customer_group)
+    .bind(Locale::validate(request.locale.as_deref())?)
     .fetch_one(&pool)
     .await
     .map_err(|e| ApiError {
@@ -64,6 +67,8 @@ pub async fn create_customer(
         email: result.get("email"),
         address: result.get("address"),
         notes: result.get("notes"),
+        customer_group: result.get("customer_group"),
+        locale: result.get("locale"),
         is_active: result.get("is_active"),
         created_at: result.get("created_at"),
         updated_at: result.get("updated_at"),
@@ -189,17 +194,25 @@ pub async fn update_customer(
     let email = request.email.as_ref().or(existing.email.as_ref());
     let address = request.address.as_ref().or(existing.address.as_ref());
     let notes = request.notes.as_ref().or(existing.notes.as_ref());
+    let customer_group = request.customer_group.as_ref().or(existing.customer_group.as_ref());
+    // An empty locale clears it; a missing one keeps the current language
+    let locale = match &request.locale {
+        Some(code) => Locale::validate(Some(code))?,
+        None => existing.locale.clone(),
+    };
 
     let result = sqlx::query(
-        "UPDATE customers SET name = ?, phone = ?, email = ?, address = ?, notes = ?, updated_at = datetime('now')
+        "UPDATE customers SET name = ?, phone = ?, email = ?, address = ?, notes = ?, customer_group = ?, locale = ?, updated_at = datetime('now')
          WHERE id = ?
-         RETURNING id, name, phone, email, address, notes, is_active, created_at, updated_at"
+         RETURNING id, name, phone, email, address, notes, customer_group, locale, is_active, created_at, updated_at"
     )
     .bind(name)
     .bind(phone)
     .bind(email)
     .bind(address)
     .bind(notes)
+    .bind(customer_group)
+    .bind(&locale)
     .bind(customer_id)
     .fetch_one(&pool)
     .await
@@ -215,6 +228,8 @@ pub async fn update_customer(
         email: result.get("email"),
         address: result.get("address"),
         notes: result.get("notes"),
+        customer_group: result.get("customer_group"),
+        locale: result.get("locale"),
         is_active: result.get("is_active"),
         created_at: result.get("created_at"),
         updated_at: result.get("updated_at"),
@@ -1,12 +1,16 @@
-use crate::models::{Invoice, Customer, Store, InvoiceItem, ApiResult, ApiError};
+use crate::models::{Invoice, Customer, Store, InvoiceItem, ClothingTag, ApiResult, ApiError};
+use crate::handlers::invoice_archive_handler::get_archive_key;
 use crate::services::html_generator::{
-    HtmlGenerator, InvoiceHtmlData, InvoiceItemWithDetails, InvoiceAddonDetail, HtmlTotals
+    HtmlGenerator, HtmlSettings, InvoiceHtmlData, InvoiceItemWithDetails, InvoiceAddonDetail, HtmlTotals
 };
-use sqlx::Row;
+use crate::services::{
+    DocumentPrinterService, InvoiceArchiveService, Locale, LocaleInfo, PdfGenerator, SilentPrintOptions,
+    TemplateConfig,
+};
+use sqlx::{Row, SqliteConnection};
 use tauri::{State, AppHandle, Manager};
-use std::process::Command;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 #[tauri::command]
 pub async fn save_invoice_html_a5(
@@ -14,11 +18,7 @@ pub async fn save_invoice_html_a5(
     state: State<'_, crate::AppState>,
     invoice_id: i64,
 ) -> ApiResult<String> {
-    let html_data = get_invoice_html_data(state.clone(), invoice_id).await?;
-    let settings = HtmlGenerator::get_a5_settings(&html_data.store);
-    let output_dir = get_invoice_output_dir(&app_handle)?;
-
-    HtmlGenerator::save_invoice_html(&html_data, &settings, &output_dir, "a5")
+    save_invoice_html(&app_handle, state, invoice_id, "a5").await
 }
 
 #[tauri::command]
@@ -27,11 +27,7 @@ pub async fn save_invoice_html_a4(
     state: State<'_, crate::AppState>,
     invoice_id: i64,
 ) -> ApiResult<String> {
-    let html_data = get_invoice_html_data(state.clone(), invoice_id).await?;
-    let settings = HtmlGenerator::get_a4_settings(&html_data.store);
-    let output_dir = get_invoice_output_dir(&app_handle)?;
-
-    HtmlGenerator::save_invoice_html(&html_data, &settings, &output_dir, "a4")
+    save_invoice_html(&app_handle, state, invoice_id, "a4").await
 }
 
 #[tauri::command]
@@ -40,11 +36,7 @@ pub async fn save_invoice_html_thermal(
     state: State<'_, crate::AppState>,
     invoice_id: i64,
 ) -> ApiResult<String> {
-    let html_data = get_invoice_html_data(state.clone(), invoice_id).await?;
-    let settings = HtmlGenerator::get_thermal_settings(&html_data.store);
-    let output_dir = get_invoice_output_dir(&app_handle)?;
-
-    HtmlGenerator::save_invoice_html(&html_data, &settings, &output_dir, "thermal")
+    save_invoice_html(&app_handle, state, invoice_id, "thermal").await
 }
 
 #[tauri::command]
@@ -55,59 +47,124 @@ pub async fn save_and_open_invoice_html(
     format: String,
 ) -> ApiResult<String> {
     // Save HTML file first
-    let file_path = match format.as_str() {
-        "a4" => save_invoice_html_a4(app_handle.clone(), state, invoice_id).await?,
-        "a5" => save_invoice_html_a5(app_handle.clone(), state, invoice_id).await?,
-        "thermal" => save_invoice_html_thermal(app_handle.clone(), state, invoice_id).await?,
-        _ => return Err(ApiError {
-            message: format!("Invalid format: {}. Supported formats: a4, a5, thermal", format),
-            code: Some("INVALID_FORMAT".to_string()),
+    let file_path = save_invoice_html(&app_handle, state, invoice_id, &format).await?;
+
+    log::info!("Attempting to open HTML file: {}", file_path);
+
+    // Open the file in the system's default browser
+    DocumentPrinterService::open(&app_handle.shell_scope(), Path::new(&file_path)).map_err(|e| ApiError {
+        message: format!("Failed to open HTML file: {}", e),
+        code: Some("BROWSER_OPEN_ERROR".to_string()),
+    })?;
+
+    log::info!("Successfully opened HTML file in browser: {}", file_path);
+    Ok(file_path)
+}
+
+/// Save the invoice as a PDF (a4, a5 or thermal) and return the file path
+#[tauri::command]
+pub async fn save_invoice_pdf(
+    app_handle: AppHandle,
+    state: State<'_, crate::AppState>,
+    invoice_id: i64,
+    format: String,
+) -> ApiResult<String> {
+    let (html_data, settings) = get_issued_invoice(&app_handle, &state, invoice_id, &format).await?;
+    let output_dir = get_invoice_output_dir(&app_handle)?;
+    let font_dir = get_font_dir(&app_handle);
+
+    PdfGenerator::save_invoice_pdf(&html_data, &settings, &output_dir, &format, font_dir.as_deref())
+}
+
+/// Print the invoice as a PDF straight to a system printer, without a print
+/// dialog. Paper size defaults to the format's (A4/A5); thermal receipts use
+/// the PDF's own page size.
+#[tauri::command]
+pub async fn print_invoice_silent(
+    app_handle: AppHandle,
+    state: State<'_, crate::AppState>,
+    invoice_id: i64,
+    format: String,
+    printer_name: String,
+    copies: Option<u32>,
+    paper_size: Option<String>,
+) -> ApiResult<String> {
+    let file_path = save_invoice_pdf(app_handle, state, invoice_id, format.clone()).await?;
+
+    let options = SilentPrintOptions {
+        printer_name,
+        copies,
+        paper_size: paper_size.or_else(|| match format.as_str() {
+            "a4" => Some("A4".to_string()),
+            "a5" => Some("A5".to_string()),
+            _ => None,
         }),
     };
 
-    log::info!("Attempting to open HTML file: {}", file_path);
+    log::info!("Printing {} to {} ({} copies)", file_path, options.printer_name, options.copies.unwrap_or(1));
 
-    // Open the file in browser using system command
-    Command::new("open")
-        .arg(&file_path)
-        .spawn()
+    let path = PathBuf::from(&file_path);
+    tokio::task::spawn_blocking(move || DocumentPrinterService::print_pdf(&path, &options))
+        .await
         .map_err(|e| ApiError {
-            message: format!("Failed to open HTML file: {}", e),
-            code: Some("BROWSER_OPEN_ERROR".to_string()),
+            message: format!("Print task failed: {}", e),
+            code: Some("PRINT_ERROR".to_string()),
+        })?
+        .map_err(|e| ApiError {
+            message: format!("Failed to print invoice: {}", e),
+            code: Some("PRINT_ERROR".to_string()),
         })?;
 
-    log::info!("Successfully opened HTML file in browser: {}", file_path);
     Ok(file_path)
 }
 
+/// Printers installed on this machine, for the silent-print printer picker
+#[tauri::command]
+pub async fn get_system_printers() -> ApiResult<Vec<String>> {
+    tokio::task::spawn_blocking(DocumentPrinterService::list_printers)
+        .await
+        .map_err(|e| ApiError {
+            message: format!("Printer lookup failed: {}", e),
+            code: Some("PRINTER_LIST_ERROR".to_string()),
+        })?
+        .map_err(|e| ApiError {
+            message: format!("Failed to list printers: {}", e),
+            code: Some("PRINTER_LIST_ERROR".to_string()),
+        })
+}
+
 #[tauri::command]
 pub async fn preview_invoice_html(
+    app_handle: AppHandle,
     state: State<'_, crate::AppState>,
     invoice_id: i64,
     paper_size: String,
 ) -> ApiResult<String> {
-    let html_data = get_invoice_html_data(state.clone(), invoice_id).await?;
-
-    // Generate HTML based on paper size
-    let settings = match paper_size.as_str() {
-        "A4" => HtmlGenerator::get_a4_settings(&html_data.store),
-        "A5" => HtmlGenerator::get_a5_settings(&html_data.store),
-        "thermal" => HtmlGenerator::get_thermal_settings(&html_data.store),
-        _ => HtmlGenerator::get_a5_settings(&html_data.store), // Default to A5
-    };
+    // Preview sizes are A4, A5 or thermal; anything else previews as A5
+    let format = paper_size.to_lowercase();
+    let format = if TemplateConfig::FORMATS.contains(&format.as_str()) { format.as_str() } else { "a5" };
+    let (html_data, settings) = get_issued_invoice(&app_handle, &state, invoice_id, format).await?;
 
-    // Generate and return HTML content
-    match paper_size.as_str() {
-        "A4" => HtmlGenerator::generate_a4_invoice(&html_data),
-        "A5" => HtmlGenerator::generate_a5_invoice(&html_data),
-        "thermal" => HtmlGenerator::generate_thermal_invoice(&html_data),
-        _ => HtmlGenerator::generate_a5_invoice(&html_data), // Default to A5
-    }
+    HtmlGenerator::render_invoice_html(&html_data, &settings)
 }
 
+/// Built-in invoice formats with their paper size and layout
 #[tauri::command]
-pub async fn convert_amount_to_words(amount: f64) -> ApiResult<String> {
-    Ok(HtmlGenerator::amount_to_words(amount))
+pub async fn get_invoice_formats() -> ApiResult<Vec<TemplateConfig>> {
+    Ok(TemplateConfig::builtin())
+}
+
+/// Amount in words in a supported language, English by default
+#[tauri::command]
+pub async fn convert_amount_to_words(amount: f64, locale: Option<String>) -> ApiResult<String> {
+    let locale = Locale::validate(locale.as_deref())?.and_then(|code| Locale::parse(&code)).unwrap_or(Locale::En);
+    Ok(locale.amount_in_words(amount))
+}
+
+/// Languages stores and customers can pick for invoices and tags
+#[tauri::command]
+pub async fn get_invoice_locales() -> ApiResult<Vec<LocaleInfo>> {
+    Ok(Locale::all())
 }
 
 #[tauri::command]
@@ -142,30 +199,57 @@ pub async fn validate_html_output_path(path: String) -> ApiResult<bool> {
 pub async fn open_html_file(file_path: String, app_handle: AppHandle) -> ApiResult<()> {
     log::info!("Attempting to open HTML file: {}", file_path);
 
-    // Open the file in browser using system command
-    Command::new("open")
-        .arg(&file_path)
-        .spawn()
-        .map_err(|e| ApiError {
-            message: format!("Failed to open HTML file: {}", e),
+    // Only generated documents may be opened from here, not arbitrary files
+    let path = Path::new(&file_path);
+    let extension = path.extension().and_then(|ext| ext.to_str()).unwrap_or("").to_lowercase();
+    if !matches!(extension.as_str(), "html" | "htm" | "pdf") {
+        return Err(ApiError {
+            message: format!("Not an HTML or PDF file: {}", file_path),
             code: Some("FILE_OPEN_ERROR".to_string()),
-        })?;
+        });
+    }
+
+    DocumentPrinterService::open(&app_handle.shell_scope(), path).map_err(|e| ApiError {
+        message: format!("Failed to open HTML file: {}", e),
+        code: Some("FILE_OPEN_ERROR".to_string()),
+    })?;
 
     log::info!("Successfully opened HTML file: {}", file_path);
     Ok(())
 }
 
+/// Invoice data and a format's settings as issued, from the verified archive
+pub(crate) async fn get_issued_invoice(
+    app_handle: &AppHandle,
+    state: &crate::AppState,
+    invoice_id: i64,
+    format: &str,
+) -> ApiResult<(InvoiceHtmlData, HtmlSettings)> {
+    let key = get_archive_key(app_handle)?;
+    InvoiceArchiveService::issued(&state.db, &key, invoice_id, format).await
+}
+
 // Helper function to get invoice data for HTML generation
-async fn get_invoice_html_data(
+pub(crate) async fn get_invoice_html_data(
     state: State<'_, crate::AppState>,
     invoice_id: i64,
+) -> ApiResult<InvoiceHtmlData> {
+    let mut conn = state.db.get_pool().acquire().await.map_err(ApiError::database)?;
+    load_invoice_html_data(&mut conn, invoice_id).await
+}
+
+/// Invoice data for HTML generation, read on the given connection so a
+/// transaction sees its own uncommitted invoice
+pub(crate) async fn load_invoice_html_data(
+    conn: &mut SqliteConnection,
+    invoice_id: i64,
 ) -> ApiResult<InvoiceHtmlData> {
     // Get invoice
     let invoice = sqlx::query_as::<_, Invoice>(
         "SELECT * FROM invoices WHERE id = ?"
     )
     .bind(invoice_id)
-    .fetch_one(state.db.get_pool())
+    .fetch_one(&mut *conn)
     .await
     .map_err(|e| ApiError {
         message: format!("Failed to fetch invoice: {}", e),
@@ -178,7 +262,7 @@ async fn get_invoice_html_data(
         "SELECT * FROM customers WHERE id = ?"
     )
     .bind(invoice.customer_id)
-    .fetch_one(state.db.get_pool())
+    .fetch_one(&mut *conn)
     .await
     .map_err(|e| ApiError {
         message: format!("Failed to fetch customer: {}", e),
@@ -191,7 +275,7 @@ async fn get_invoice_html_data(
         "SELECT * FROM stores WHERE id = ?"
     )
     .bind(invoice.store_id)
-    .fetch_one(state.db.get_pool())
+    .fetch_one(&mut *conn)
     .await
     .map_err(|e| ApiError {
         message: format!("Failed to fetch store: {}", e),
@@ -215,7 +299,7 @@ async fn get_invoice_html_data(
         "#
     )
     .bind(invoice_id)
-    .fetch_all(state.db.get_pool())
+    .fetch_all(&mut *conn)
     .await
     .map_err(|e| ApiError {
         message: format!("Failed to fetch invoice items: {}", e),
@@ -237,8 +321,13 @@ async fn get_invoice_html_data(
             rate: item_row.get("rate"),
             amount: item_row.get("amount"),
             gst_rate: item_row.get("gst_rate"),
+            discount_amount: item_row.get("discount_amount"),
+            taxable_value: item_row.get("taxable_value"),
             sgst: item_row.get("sgst"),
             cgst: item_row.get("cgst"),
+            price_list_id: item_row.get("price_list_id"),
+            rate_source: item_row.get("rate_source"),
+            rate_card_id: item_row.get("rate_card_id"),
             created_at: item_row.get("created_at"),
         };
 
@@ -254,7 +343,7 @@ async fn get_invoice_html_data(
             "#
         )
         .bind(item.id)
-        .fetch_all(state.db.get_pool())
+        .fetch_all(&mut *conn)
         .await
         .map_err(|e| ApiError {
             message: format!("Failed to fetch item addons: {}", e),
@@ -265,18 +354,33 @@ async fn get_invoice_html_data(
             .into_iter()
             .map(|addon_row| InvoiceAddonDetail {
                 addon_name: addon_row.get("addon_name"),
+                pricing_mode: addon_row.get("pricing_mode"),
                 quantity: addon_row.get("qty"),
                 rate: addon_row.get("rate"),
                 amount: addon_row.get("amount"),
+                discount_amount: addon_row.get("discount_amount"),
+                taxable_value: addon_row.get("taxable_value"),
             })
             .collect();
 
+        let garments = sqlx::query_as::<_, ClothingTag>(
+            "SELECT * FROM clothing_tags WHERE invoice_item_id = ? AND is_void = 0 ORDER BY tag_number"
+        )
+        .bind(item.id)
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| ApiError {
+            message: format!("Failed to fetch item tags: {}", e),
+            code: Some("TAGS_FETCH_ERROR".to_string()),
+        })?;
+
         items.push(InvoiceItemWithDetails {
             item,
             service_name: item_row.get("service_name"),
             variant_name: item_row.get("variant_name"),
             unit: item_row.get("unit"),
             addons,
+            garments,
         });
     }
 
@@ -301,15 +405,44 @@ async fn get_invoice_html_data(
     Ok(InvoiceHtmlData {
         invoice,
         customer,
-        store: store.clone(),
+        store,
         items,
         totals,
-        settings: HtmlGenerator::get_a5_settings(&store), // Default settings
     })
 }
 
+/// Render an invoice format and save it to the invoices folder
+async fn save_invoice_html(
+    app_handle: &AppHandle,
+    state: State<'_, crate::AppState>,
+    invoice_id: i64,
+    format: &str,
+) -> ApiResult<String> {
+    let output_dir = get_invoice_output_dir(app_handle)?;
+
+    let key = get_archive_key(app_handle)?;
+    let archive = InvoiceArchiveService::get_issued(&state.db, invoice_id).await?;
+    let (html_data, settings) = InvoiceArchiveService::snapshot(&archive, &key, format)?;
+
+    // The archived document is served as issued
+    if archive.format == format {
+        return HtmlGenerator::write_invoice_html(&archive.invoice_no, &archive.document, &output_dir, format);
+    }
+
+    HtmlGenerator::save_invoice_html(&html_data, &settings, &output_dir, format)
+}
+
+/// Extra fonts for PDFs (e.g. a Devanagari font for Hindi names) can be
+/// dropped here
+pub(crate) fn get_font_dir(app_handle: &AppHandle) -> Option<PathBuf> {
+    app_handle
+        .path_resolver()
+        .app_data_dir()
+        .map(|dir| dir.join("UCLEAN").join("Fonts"))
+}
+
 // Helper function to get invoice output directory
-fn get_invoice_output_dir(app_handle: &AppHandle) -> ApiResult<PathBuf> {
+pub(crate) fn get_invoice_output_dir(app_handle: &AppHandle) -> ApiResult<PathBuf> {
     let app_data_dir = app_handle
         .path_resolver()
         .app_data_dir()
@@ -329,4 +462,85 @@ fn get_invoice_output_dir(app_handle: &AppHandle) -> ApiResult<PathBuf> {
 
     log::info!("Invoice output directory: {}", invoices_dir.display());
     Ok(invoices_dir)
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::DatabaseManager;
+
+    #[tokio::test]
+    async fn test_reprint_shows_invoice_as_issued() {
+        let db = DatabaseManager::new_with_url("sqlite::memory:").await.unwrap();
+        db.initialize_schema().await.unwrap();
+        let key = [3u8; 32];
+        sqlx::query("INSERT INTO customers (id, name, phone) VALUES (910, 'Archive Test', '9100000000')")
+            .execute(db.get_pool())
+            .await
+            .unwrap();
+
+        // Issue the invoice and archive it in one transaction, as create_invoice does
+        let mut tx = db.begin_transaction().await.unwrap();
+        sqlx::query(
+            "INSERT INTO invoices (id, invoice_no, customer_id, store_id, status, payment_method, payment_amount, total) \
+             VALUES (910, 'INV910', 910, 1, 'pending', NULL, 0, 150)",
+        )
+        .execute(&mut *tx)
+        .await
+        .unwrap();
+        sqlx::query("INSERT INTO invoice_items (invoice_id, service_id, piece_count, rate, amount) VALUES (910, 1, 3, 50, 150)")
+            .execute(&mut *tx)
+            .await
+            .unwrap();
+        let issued = load_invoice_html_data(&mut tx, 910).await.unwrap();
+        let settings = InvoiceArchiveService::current_settings(&db, &issued.store).await.unwrap();
+        InvoiceArchiveService::archive(&mut tx, &key, &issued, &settings).await.unwrap();
+        tx.commit().await.unwrap();
+
+        // Paid and delivered later
+        sqlx::query("UPDATE invoices SET status = 'paid', payment_method = 'upi', payment_amount = 150 WHERE id = 910")
+            .execute(db.get_pool())
+            .await
+            .unwrap();
+
+        let (reprint, settings) = InvoiceArchiveService::issued(&db, &key, 910, "thermal").await.unwrap();
+        assert_eq!(reprint.invoice.status, "pending");
+        assert_eq!(reprint.invoice.payment_method, None);
+        assert_eq!(reprint.invoice.payment_amount, Some(0.0));
+        assert_eq!(reprint.items.len(), 1);
+        assert_eq!(settings.template.name, "thermal");
+
+        let mut conn = db.get_pool().acquire().await.unwrap();
+        let live = load_invoice_html_data(&mut conn, 910).await.unwrap();
+        assert_eq!(live.invoice.status, "paid");
+        assert_eq!(live.invoice.payment_method.as_deref(), Some("upi"));
+    }
+
+    #[tokio::test]
+    async fn test_failed_archive_rolls_back_the_invoice() {
+        let db = DatabaseManager::new_with_url("sqlite::memory:").await.unwrap();
+        db.initialize_schema().await.unwrap();
+        sqlx::query("INSERT INTO customers (id, name, phone) VALUES (911, 'Rollback Test', '9110000000')")
+            .execute(db.get_pool())
+            .await
+            .unwrap();
+
+        let mut tx = db.begin_transaction().await.unwrap();
+        sqlx::query("INSERT INTO invoices (id, invoice_no, customer_id, store_id) VALUES (911, 'INV911', 911, 1)")
+            .execute(&mut *tx)
+            .await
+            .unwrap();
+        let issued = load_invoice_html_data(&mut tx, 911).await.unwrap();
+        // Without settings for the archived format the archive fails, and the invoice goes with it
+        let error = InvoiceArchiveService::archive(&mut tx, &[3u8; 32], &issued, &Default::default()).await.unwrap_err();
+        assert_eq!(error.code.as_deref(), Some("ARCHIVE_ERROR"));
+        drop(tx);
+
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM invoices WHERE id = 911")
+            .fetch_one(db.get_pool())
+            .await
+            .unwrap();
+        assert_eq!(count, 0);
+        let error = InvoiceArchiveService::issued(&db, &[3u8; 32], 911, "a4").await.unwrap_err();
+        assert_eq!(error.code.as_deref(), Some("ARCHIVE_NOT_FOUND"));
+    }
+}
@@ -0,0 +1,80 @@
+use crate::handlers::html_handler::{get_font_dir, get_invoice_html_data, get_invoice_output_dir};
+use crate::models::{ApiError, ApiResult, InvoiceArchive, InvoiceArchiveCheck, InvoiceArchiveReport};
+use crate::services::html_generator::HtmlGenerator;
+use crate::services::{ExportFileType, InvoiceArchiveService, InvoiceTemplateService, PdfGenerator, ARCHIVE_KEY_FILE};
+use tauri::{AppHandle, State};
+
+/// The key invoice archives are signed with, created on first use
+pub(crate) fn get_archive_key(app_handle: &AppHandle) -> ApiResult<Vec<u8>> {
+    let app_data_dir = app_handle
+        .path_resolver()
+        .app_data_dir()
+        .ok_or_else(|| ApiError {
+            message: "Failed to get app data directory".to_string(),
+            code: Some("APP_DATA_DIR_ERROR".to_string()),
+        })?;
+
+    InvoiceArchiveService::load_or_create_key(&app_data_dir.join("UCLEAN").join(ARCHIVE_KEY_FILE))
+}
+
+#[tauri::command]
+pub async fn get_invoice_archive(
+    state: State<'_, crate::AppState>,
+    invoice_id: i64,
+) -> ApiResult<Option<InvoiceArchive>> {
+    InvoiceArchiveService::get(&state.db, invoice_id).await
+}
+
+#[tauri::command]
+pub async fn verify_invoice_archive(
+    app_handle: AppHandle,
+    state: State<'_, crate::AppState>,
+    invoice_id: i64,
+) -> ApiResult<InvoiceArchiveCheck> {
+    let key = get_archive_key(&app_handle)?;
+    let archive = InvoiceArchiveService::get(&state.db, invoice_id).await?.ok_or_else(|| ApiError {
+        message: "Invoice has no archived copy".to_string(),
+        code: Some("ARCHIVE_NOT_FOUND".to_string()),
+    })?;
+
+    Ok(InvoiceArchiveService::check(&archive, &key))
+}
+
+/// Check every archived invoice; the report lists the ones that fail
+#[tauri::command]
+pub async fn verify_invoice_archives(
+    app_handle: AppHandle,
+    state: State<'_, crate::AppState>,
+) -> ApiResult<InvoiceArchiveReport> {
+    let key = get_archive_key(&app_handle)?;
+    let report = InvoiceArchiveService::verify_all(&state.db, &key).await?;
+    if !report.failed.is_empty() {
+        log::error!("{} of {} invoice archives failed verification", report.failed.len(), report.checked);
+    }
+    Ok(report)
+}
+
+/// Render the invoice again from live customer, store and service data,
+/// marked "duplicate copy", and return the file path
+#[tauri::command]
+pub async fn save_invoice_duplicate(
+    app_handle: AppHandle,
+    state: State<'_, crate::AppState>,
+    invoice_id: i64,
+    format: String,
+    file_type: ExportFileType,
+) -> ApiResult<String> {
+    let html_data = get_invoice_html_data(state.clone(), invoice_id).await?;
+    let mut settings = InvoiceTemplateService::get_settings(&state.db, &html_data.store, &format).await?;
+    settings.duplicate_copy = true;
+    let output_dir = get_invoice_output_dir(&app_handle)?;
+    let file_format = format!("{}_duplicate", format);
+
+    match file_type {
+        ExportFileType::Html => HtmlGenerator::save_invoice_html(&html_data, &settings, &output_dir, &file_format),
+        ExportFileType::Pdf => {
+            let font_dir = get_font_dir(&app_handle);
+            PdfGenerator::save_invoice_pdf(&html_data, &settings, &output_dir, &file_format, font_dir.as_deref())
+        }
+    }
+}
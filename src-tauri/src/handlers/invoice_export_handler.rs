@@ -0,0 +1,211 @@
+use crate::handlers::html_handler::{get_font_dir, get_invoice_html_data};
+use crate::handlers::invoice_archive_handler::get_archive_key;
+use crate::handlers::invoice_handler::{find_invoices, InvoiceSearchQuery};
+use crate::models::{ApiError, ApiResult, Invoice};
+use crate::services::html_generator::HtmlSettings;
+use crate::services::{
+    archive_error, ExportFileType, InvoiceArchiveService, InvoiceExportProgress, InvoiceExportService,
+    InvoiceTemplateService, TemplateConfig, ZipWriter, INVOICE_EXPORT_EVENT,
+};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs;
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use tauri::{AppHandle, Manager, State};
+
+// Only one export runs at a time, so a pair of flags is all the state it needs
+static EXPORT_RUNNING: AtomicBool = AtomicBool::new(false);
+static EXPORT_CANCELLED: AtomicBool = AtomicBool::new(false);
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct InvoiceExportRequest {
+    pub query: InvoiceSearchQuery,
+    pub format: String, // a4, a5 or thermal
+    pub file_type: ExportFileType,
+    pub output_path: String, // the .zip to create
+}
+
+/// Export every invoice matching the query into a ZIP of HTML or PDF files
+/// plus an index.csv. Runs in the background and reports through
+/// `invoice-export-progress` events; returns the number of invoices.
+#[tauri::command]
+pub async fn start_invoice_export(
+    app_handle: AppHandle,
+    state: State<'_, crate::AppState>,
+    request: InvoiceExportRequest,
+) -> ApiResult<usize> {
+    TemplateConfig::for_format(&request.format)?;
+    if !request.output_path.to_lowercase().ends_with(".zip") {
+        return Err(ApiError {
+            message: "Export file must be a .zip".to_string(),
+            code: Some("INVALID_PATH".to_string()),
+        });
+    }
+
+    let invoice_ids: Vec<i64> = find_invoices(state.db.get_pool(), &request.query)
+        .await?
+        .iter()
+        .map(|invoice| invoice.id)
+        .collect();
+    if invoice_ids.is_empty() {
+        return Err(ApiError {
+            message: "No invoices match the search".to_string(),
+            code: Some("NO_INVOICES".to_string()),
+        });
+    }
+
+    if EXPORT_RUNNING.compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst).is_err() {
+        return Err(ApiError {
+            message: "An invoice export is already running".to_string(),
+            code: Some("EXPORT_IN_PROGRESS".to_string()),
+        });
+    }
+    EXPORT_CANCELLED.store(false, Ordering::SeqCst);
+
+    let total = invoice_ids.len();
+    tauri::async_runtime::spawn(async move {
+        let progress = run_export(&app_handle, &invoice_ids, &request).await;
+        EXPORT_RUNNING.store(false, Ordering::SeqCst);
+
+        match &progress.error {
+            Some(error) => log::error!("Invoice export failed: {}", error),
+            None => log::info!("Invoice export {}: {} of {} invoices", progress.status, progress.done, progress.total),
+        }
+        emit_progress(&app_handle, &progress);
+    });
+
+    Ok(total)
+}
+
+/// Stop the running export after the invoice in progress. Returns false when
+/// no export is running.
+#[tauri::command]
+pub async fn cancel_invoice_export() -> ApiResult<bool> {
+    if !EXPORT_RUNNING.load(Ordering::SeqCst) {
+        return Ok(false);
+    }
+    EXPORT_CANCELLED.store(true, Ordering::SeqCst);
+    Ok(true)
+}
+
+/// Write the archive to a `.part` file and move it into place only once it
+/// is complete, so a cancelled or failed export never leaves a broken ZIP
+async fn run_export(app_handle: &AppHandle, invoice_ids: &[i64], request: &InvoiceExportRequest) -> InvoiceExportProgress {
+    let part_path = PathBuf::from(format!("{}.part", request.output_path));
+    let mut progress = InvoiceExportProgress {
+        status: "running".to_string(),
+        done: 0,
+        total: invoice_ids.len(),
+        invoice_no: None,
+        path: None,
+        error: None,
+    };
+
+    let result = match write_archive(app_handle, invoice_ids, request, &part_path, &mut progress).await {
+        Ok(true) => fs::rename(&part_path, &request.output_path).map(|_| true).map_err(archive_error),
+        other => other,
+    };
+
+    match result {
+        Ok(true) => {
+            progress.status = "completed".to_string();
+            progress.path = Some(request.output_path.clone());
+        }
+        Ok(false) => progress.status = "cancelled".to_string(),
+        Err(e) => {
+            progress.status = "failed".to_string();
+            progress.error = Some(e.message);
+        }
+    }
+    if progress.path.is_none() {
+        let _ = fs::remove_file(&part_path);
+    }
+    progress
+}
+
+/// Returns false if the export was cancelled
+async fn write_archive(
+    app_handle: &AppHandle,
+    invoice_ids: &[i64],
+    request: &InvoiceExportRequest,
+    part_path: &Path,
+    progress: &mut InvoiceExportProgress,
+) -> ApiResult<bool> {
+    let state = app_handle.state::<crate::AppState>();
+    if let Some(parent) = part_path.parent() {
+        fs::create_dir_all(parent).map_err(archive_error)?;
+    }
+    let file = fs::File::create(part_path).map_err(archive_error)?;
+    let mut zip = ZipWriter::new(BufWriter::new(file));
+
+    let key = get_archive_key(app_handle)?;
+    let font_dir = get_font_dir(app_handle);
+    let mut store_settings: HashMap<i64, HtmlSettings> = HashMap::new();
+    let mut index = InvoiceExportService::index_header().to_string();
+
+    for &invoice_id in invoice_ids {
+        if EXPORT_CANCELLED.load(Ordering::SeqCst) {
+            return Ok(false);
+        }
+
+        let (data, settings) = match InvoiceArchiveService::get(&state.db, invoice_id).await? {
+            Some(archive) => InvoiceArchiveService::snapshot(&archive, &key, &request.format)?,
+            // Invoices issued before archiving began go in as marked duplicate copies
+            None => {
+                let data = get_invoice_html_data(app_handle.state(), invoice_id).await?;
+                let settings = match store_settings.get(&data.store.id) {
+                    Some(settings) => settings.clone(),
+                    None => {
+                        let mut settings =
+                            InvoiceTemplateService::get_settings(&state.db, &data.store, &request.format).await?;
+                        settings.duplicate_copy = true;
+                        store_settings.insert(data.store.id, settings.clone());
+                        settings
+                    }
+                };
+                (data, settings)
+            }
+        };
+
+        // PDF layout is CPU-bound, so keep it off the async workers
+        let (file_type, font_dir) = (request.file_type, font_dir.clone());
+        let (data, bytes) = tauri::async_runtime::spawn_blocking(move || {
+            let bytes = InvoiceExportService::render(&data, &settings, file_type, font_dir.as_deref());
+            (data, bytes)
+        })
+        .await
+        .map_err(|e| ApiError {
+            message: format!("Invoice rendering stopped: {}", e),
+            code: Some("EXPORT_RENDER_ERROR".to_string()),
+        })?;
+        let bytes = bytes.map_err(|e| ApiError {
+            message: format!("Failed to render invoice {}: {}", data.invoice.invoice_no, e.message),
+            code: e.code,
+        })?;
+
+        zip.add_file(&InvoiceExportService::entry_name(&data, &request.format, file_type), &bytes)
+            .map_err(archive_error)?;
+        let current = sqlx::query_as::<_, Invoice>("SELECT * FROM invoices WHERE id = ?")
+            .bind(invoice_id)
+            .fetch_one(state.db.get_pool())
+            .await
+            .map_err(ApiError::database)?;
+        index.push_str(&InvoiceExportService::index_row(&data, &current));
+
+        progress.done += 1;
+        progress.invoice_no = Some(data.invoice.invoice_no);
+        emit_progress(app_handle, progress);
+    }
+
+    zip.add_file("index.csv", index.as_bytes()).map_err(archive_error)?;
+    zip.finish().map_err(archive_error)?;
+    Ok(true)
+}
+
+fn emit_progress(app_handle: &AppHandle, progress: &InvoiceExportProgress) {
+    if let Err(e) = app_handle.emit_all(INVOICE_EXPORT_EVENT, progress) {
+        log::warn!("Failed to emit invoice export progress: {}", e);
+    }
+}
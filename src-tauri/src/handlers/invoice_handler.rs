@@ -1,10 +1,14 @@
 use crate::database::DatabaseManager;
-use crate::models::{Invoice, InvoiceItem, Customer, Store, CreateInvoiceRequest, CreateInvoiceItemRequest, CreateInvoiceItemAddonRequest, UpdateInvoiceDetailsRequest, ApiResult, ApiError};
-use crate::services::pricing_engine::{PricingEngine, SimplePricing};
-use sqlx::Row;
-use tauri::State;
+use crate::models::{Invoice, InvoiceItem, Customer, Store, CreateInvoiceRequest, CreateInvoiceItemRequest, CreateInvoiceItemAddonRequest, UpdateInvoiceDetailsRequest, TurnaroundTier, ApiResult, ApiError};
+use crate::services::pricing_engine::{PricingEngine, PricingTotals, SimplePricing, SurchargeLine};
+use crate::services::{TurnaroundService, DEFAULT_TURNAROUND_TIER, PriceResolver, ResolvedPrice, InvoiceArchiveService};
+use crate::handlers::html_handler::load_invoice_html_data;
+use crate::handlers::invoice_archive_handler::get_archive_key;
+use sqlx::{Row, SqliteConnection};
+use tauri::{AppHandle, State};
 use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
+use std::collections::HashSet;
 
 
 #[derive(Debug, Serialize)]
@@ -27,12 +31,70 @@ pub struct InvoiceItemWithDetails {
 #[derive(Debug, Serialize)]
 pub struct InvoiceAddonDetail {
     pub addon_name: String,
+    pub pricing_mode: String,
     pub quantity: f64,
     pub rate: f64,
     pub amount: f64,
+    pub discount_amount: f64,
+    pub taxable_value: f64,
 }
 
-#[derive(Debug, Deserialize)]
+/// Totals create_invoice would charge, for the invoice form's summary
+#[derive(Debug, Serialize)]
+pub struct InvoicePreview {
+    pub totals: PricingTotals,
+    pub gst_rate: Option<f64>, // shared by every line; None when rates are mixed
+}
+
+/// An invoice line priced with its share of the invoice discount
+struct PricedLine {
+    amount: f64,
+    gst_rate: f64,
+    discount_amount: f64,
+    taxable_value: f64,
+    sgst: f64,
+    cgst: f64,
+}
+
+impl PricedLine {
+    fn new(amount: f64, gst_rate: f64) -> Self {
+        Self {
+            amount,
+            gst_rate,
+            discount_amount: 0.0,
+            taxable_value: 0.0,
+            sgst: 0.0,
+            cgst: 0.0,
+        }
+    }
+}
+
+struct PricedAddon {
+    addon_id: i64,
+    pricing_mode: String,
+    quantity: f64,
+    price: ResolvedPrice,
+    line: PricedLine,
+}
+
+struct PricedItem<'a> {
+    request: &'a CreateInvoiceItemRequest,
+    piece_count: i32,
+    price: ResolvedPrice,
+    line: PricedLine,
+    addons: Vec<PricedAddon>,
+}
+
+/// An invoice request priced but not yet written
+struct PricedInvoice<'a> {
+    tier: TurnaroundTier,
+    items: Vec<PricedItem<'a>>,
+    total_pieces: i32,
+    totals: PricingTotals,
+    gst_rate: Option<f64>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
 pub struct InvoiceSearchQuery {
     pub query: Option<String>,
     pub customer_id: Option<i64>,
@@ -46,11 +108,24 @@ pub struct InvoiceSearchQuery {
 
 #[tauri::command]
 pub async fn create_invoice(
+    app_handle: AppHandle,
     state: State<'_, crate::AppState>,
     request: CreateInvoiceRequest,
 ) -> ApiResult<InvoiceResponse> {
     let pool = state.db.get_pool_cloned();
 
+    // The archive is written in the transaction below, so read what it needs first
+    let archive_key = get_archive_key(&app_handle)?;
+    let store = sqlx::query_as::<_, Store>("SELECT * FROM stores WHERE id = ?")
+        .bind(request.store_id)
+        .fetch_one(&pool)
+        .await
+        .map_err(|e| ApiError {
+            message: format!("Failed to fetch store: {}", e),
+            code: Some("STORE_NOT_FOUND".to_string()),
+        })?;
+    let archive_settings = InvoiceArchiveService::current_settings(&state.db, &store).await?;
+
     // Start transaction
     let mut tx = pool.begin().await.map_err(|e| ApiError {
         message: format!("Database error: {}", e),
@@ -60,26 +135,42 @@ pub async fn create_invoice(
     // Generate invoice number
     let invoice_no = generate_invoice_number(&pool).await?;
 
-    // Create invoice record
+    let priced = price_invoice(&mut tx, &request).await?;
+    let totals = &priced.totals;
+
+    // The tier's turnaround sets the promised delivery unless one was given
+    let order_local = PriceResolver::order_datetime(request.order_datetime.as_deref())?;
+    let delivery_datetime = request.delivery_datetime.clone().unwrap_or_else(|| {
+        TurnaroundService::derive_delivery_datetime(order_local, &priced.tier)
+    });
+
+    // Create invoice record; the stored discount is in GST-exclusive terms
+    // so subtotal - discount + express_charge is the taxable value
     let invoice_id = sqlx::query(
         r#"
         INSERT INTO invoices (
             invoice_no, customer_id, store_id, order_source, order_datetime, delivery_datetime,
             subtotal, discount, discount_type, express_charge, sgst_amount, cgst_amount, igst_amount,
-            total, gst_inclusive, status, notes, payment_method, payment_amount
-        ) VALUES (?, ?, ?, ?, ?, ?, 0, ?, ?, ?, 0, 0, 0, 0, ?, 'pending', ?, ?, ?)
+            total, total_pieces, gst_inclusive, turnaround_tier, status, notes, payment_method, payment_amount
+        ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, 0, ?, ?, ?, ?, 'pending', ?, ?, ?)
         "#
     )
     .bind(&invoice_no)
     .bind(request.customer_id)
     .bind(request.store_id)
     .bind(request.order_source.as_deref().unwrap_or("WALK-IN"))
-    .bind(Utc::now().to_rfc3339())
-    .bind(request.delivery_datetime.as_deref().unwrap_or(&Utc::now().to_rfc3339()))
-    .bind(request.discount.unwrap_or(0.0))
+    .bind(request.order_datetime.clone().unwrap_or_else(|| Utc::now().to_rfc3339()))
+    .bind(&delivery_datetime)
+    .bind(totals.subtotal)
+    .bind(totals.discount_amount)
     .bind(request.discount_type.as_deref().unwrap_or("flat"))
-    .bind(request.express_charge.unwrap_or(0.0))
-    .bind(if request.gst_inclusive.unwrap_or(false) { 1 } else { 0 })
+    .bind(totals.express_charge)
+    .bind(totals.sgst_amount)
+    .bind(totals.cgst_amount)
+    .bind(totals.total_amount)
+    .bind(priced.total_pieces)
+    .bind(if totals.gst_inclusive { 1 } else { 0 })
+    .bind(&priced.tier.code)
     .bind(request.notes.as_deref())
     .bind(request.payment_method.as_deref())
     .bind(request.payment_amount.unwrap_or(0.0))
@@ -91,19 +182,157 @@ pub async fn create_invoice(
     })?
     .last_insert_rowid();
 
-    let mut subtotal = 0.0;
-    let mut total_sgst = 0.0;
-    let mut total_cgst = 0.0;
+    for item in &priced.items {
+        let item_id = sqlx::query(
+            r#"
+            INSERT INTO invoice_items (
+                invoice_id, service_id, variant_id, description, qty, piece_count, weight_kg, area_sqft,
+                rate, amount, gst_rate, price_list_id, rate_source, rate_card_id,
+                discount_amount, taxable_value, sgst, cgst
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#
+        )
+        .bind(invoice_id)
+        .bind(item.request.service_id)
+        .bind(item.request.variant_id)
+        .bind(item.request.description.as_deref())
+        .bind(item.request.qty)
+        .bind(item.piece_count)
+        .bind(item.request.weight_kg)
+        .bind(item.request.area_sqft)
+        .bind(item.price.rate)
+        .bind(item.line.amount)
+        .bind(item.line.gst_rate)
+        .bind(item.price.price_list_id)
+        .bind(&item.price.rate_source)
+        .bind(item.price.rate_card_id)
+        .bind(item.line.discount_amount)
+        .bind(item.line.taxable_value)
+        .bind(item.line.sgst)
+        .bind(item.line.cgst)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| ApiError {
+            message: format!("Failed to create invoice item: {}", e),
+            code: Some("CREATE_ITEM_ERROR".to_string()),
+        })?
+        .last_insert_rowid();
+
+        for addon in &item.addons {
+            sqlx::query(
+                r#"
+                INSERT INTO invoice_item_addons (
+                    invoice_item_id, addon_id, qty, rate, amount, pricing_mode, gst_rate,
+                    price_list_id, rate_source, rate_card_id,
+                    discount_amount, taxable_value, sgst, cgst
+                ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                "#
+            )
+            .bind(item_id)
+            .bind(addon.addon_id)
+            .bind(addon.quantity)
+            .bind(addon.price.rate)
+            .bind(addon.line.amount)
+            .bind(&addon.pricing_mode)
+            .bind(addon.line.gst_rate)
+            .bind(addon.price.price_list_id)
+            .bind(&addon.price.rate_source)
+            .bind(addon.price.rate_card_id)
+            .bind(addon.line.discount_amount)
+            .bind(addon.line.taxable_value)
+            .bind(addon.line.sgst)
+            .bind(addon.line.cgst)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| ApiError {
+                message: format!("Failed to create addon: {}", e),
+                code: Some("CREATE_ADDON_ERROR".to_string()),
+            })?;
+        }
+    }
+
+    // Archive the invoice as issued; reprints come from this snapshot, so
+    // an invoice that can't be archived isn't issued at all
+    let html_data = load_invoice_html_data(&mut tx, invoice_id).await?;
+    InvoiceArchiveService::archive(&mut tx, &archive_key, &html_data, &archive_settings).await?;
+
+    // Commit transaction
+    tx.commit().await.map_err(|e| ApiError {
+        message: format!("Transaction commit failed: {}", e),
+        code: Some("COMMIT_ERROR".to_string()),
+    })?;
+
+    // Auto-generate tags for the invoice
+    if let Err(e) = crate::services::TagGeneratorService::generate_tags_for_invoice(&state.db, invoice_id).await {
+        log::warn!("Failed to generate tags for invoice {}: {}", invoice_id, e);
+        // Don't fail the invoice creation if tag generation fails
+    }
+
+    // Queue the receipt and tags when the store prints automatically
+    if let Err(e) = crate::services::PrintQueueService::enqueue_auto_print(&state.db, invoice_id).await {
+        log::warn!("Failed to queue auto-print for invoice {}: {}", invoice_id, e);
+    }
+
+    // Return the created invoice with full details
+    get_invoice_by_id(state, invoice_id).await
+}
+
+/// Totals create_invoice would charge for a request, without saving anything
+#[tauri::command]
+pub async fn preview_invoice(
+    state: State<'_, crate::AppState>,
+    request: CreateInvoiceRequest,
+) -> ApiResult<InvoicePreview> {
+    invoice_preview(&state.db, &request).await
+}
+
+async fn invoice_preview(db: &DatabaseManager, request: &CreateInvoiceRequest) -> ApiResult<InvoicePreview> {
+    let mut conn = db.get_pool().acquire().await.map_err(|e| ApiError {
+        message: format!("Database error: {}", e),
+        code: Some("DATABASE_ERROR".to_string()),
+    })?;
+    let priced = price_invoice(&mut conn, request).await?;
+
+    Ok(InvoicePreview {
+        totals: priced.totals,
+        gst_rate: priced.gst_rate,
+    })
+}
+
+/// Price every line of an invoice request: rates from the customer's rate card,
+/// then the store's price lists, as of the order date; addons at their own GST
+/// rate; the invoice discount spread across lines; the turnaround surcharge on top
+async fn price_invoice<'a>(
+    conn: &mut SqliteConnection,
+    request: &'a CreateInvoiceRequest,
+) -> ApiResult<PricedInvoice<'a>> {
+    let gst_inclusive = request.gst_inclusive.unwrap_or(false);
+
+    // Resolve the turnaround tier; it drives the surcharge and the promised delivery date
+    let tier_code = request.turnaround_tier.as_deref().unwrap_or(DEFAULT_TURNAROUND_TIER);
+    let tier = TurnaroundService::resolve_tier(conn, request.store_id, tier_code).await?;
+
+    let pricing_context = PriceResolver::load_context(
+        conn,
+        Some(request.store_id),
+        Some(request.customer_id),
+        request.order_datetime.as_deref(),
+        gst_inclusive,
+    ).await?;
+
+    let mut items = Vec::new();
     let mut total_pieces = 0i32;
+    let mut surcharge_lines = Vec::new();
+    let mut per_order_addons = HashSet::new();
 
     // Process each invoice item
-    for item_request in request.items {
+    for item_request in &request.items {
         // Get service details for pricing
         let service_row = sqlx::query(
             "SELECT * FROM services WHERE id = ?"
         )
         .bind(item_request.service_id)
-        .fetch_optional(&mut *tx)
+        .fetch_optional(&mut *conn)
         .await
         .map_err(|e| ApiError {
             message: format!("Database error: {}", e),
@@ -116,15 +345,16 @@ pub async fn create_invoice(
 
         let base_rate: f64 = service_row.get("base_price");
         let gst_rate: f64 = service_row.get("gst_rate");
+        let category_id: Option<i64> = service_row.get("category_id");
 
-        // Calculate variant rate if applicable
-        let variant_rate = if let Some(variant_id) = item_request.variant_id {
+        // Variant multiplier if applicable
+        let price_multiplier = if let Some(variant_id) = item_request.variant_id {
             let variant_row = sqlx::query(
                 "SELECT price_multiplier FROM service_variants WHERE id = ? AND service_id = ?"
             )
             .bind(variant_id)
             .bind(item_request.service_id)
-            .fetch_optional(&mut *tx)
+            .fetch_optional(&mut *conn)
             .await
             .map_err(|e| ApiError {
                 message: format!("Database error: {}", e),
@@ -132,162 +362,191 @@ pub async fn create_invoice(
             })?;
 
             variant_row
-                .map(|row| {
-                    let multiplier: f64 = row.get("price_multiplier");
-                    base_rate * multiplier
-                })
-                .unwrap_or(base_rate)
+                .map(|row| row.get::<f64, _>("price_multiplier"))
+                .unwrap_or(1.0)
         } else {
-            base_rate
+            1.0
         };
 
+        let resolved = PriceResolver::service_rate(
+            conn,
+            &pricing_context,
+            item_request.service_id,
+            item_request.variant_id,
+            base_rate,
+            price_multiplier,
+            gst_rate,
+        ).await?;
+
         // Calculate item pricing
         let pricing = PricingEngine::calculate_simple_pricing(
-            variant_rate,
+            resolved.rate,
             item_request.qty,
             item_request.weight_kg,
             item_request.area_sqft,
             gst_rate,
-            request.gst_inclusive.unwrap_or(false),
+            gst_inclusive,
         )?;
 
         // Calculate piece count (default to qty if not provided)
         let piece_count = item_request.piece_count.unwrap_or(item_request.qty as i32);
 
-        // Insert invoice item
-        let item_id = sqlx::query(
-            r#"
-            INSERT INTO invoice_items (
-                invoice_id, service_id, variant_id, description, qty, piece_count, weight_kg, area_sqft,
-                rate, amount, gst_rate, sgst, cgst
-            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
-            "#
-        )
-        .bind(invoice_id)
-        .bind(item_request.service_id)
-        .bind(item_request.variant_id)
-        .bind(item_request.description.as_deref())
-        .bind(item_request.qty)
-        .bind(piece_count)
-        .bind(item_request.weight_kg)
-        .bind(item_request.area_sqft)
-        .bind(variant_rate)
-        .bind(pricing.line_total)
-        .bind(gst_rate)
-        .bind(pricing.sgst_amount)
-        .bind(pricing.cgst_amount)
-        .execute(&mut *tx)
-        .await
-        .map_err(|e| ApiError {
-            message: format!("Failed to create invoice item: {}", e),
-            code: Some("CREATE_ITEM_ERROR".to_string()),
-        })?
-        .last_insert_rowid();
-
-        subtotal += pricing.subtotal;
-        total_sgst += pricing.sgst_amount;
-        total_cgst += pricing.cgst_amount;
         total_pieces += piece_count;
+        surcharge_lines.push(SurchargeLine {
+            amount: pricing.line_total,
+            category_id,
+            gst_rate,
+        });
 
         // Process addons for this item
-        if let Some(addons) = &item_request.addons {
-        for addon_request in addons {
+        let mut addons = Vec::new();
+        if let Some(addon_requests) = &item_request.addons {
+        for addon_request in addon_requests {
+            // Addons belong to a service or are shared with it through a link
             let addon_row = sqlx::query(
-                "SELECT * FROM service_addons WHERE id = ?"
+                r#"
+                SELECT * FROM service_addons
+                WHERE id = ?1
+                  AND is_active = 1
+                  AND (service_id = ?2 OR EXISTS (
+                      SELECT 1 FROM service_addon_links WHERE addon_id = ?1 AND service_id = ?2
+                  ))
+                "#
             )
             .bind(addon_request.addon_id)
-            .fetch_optional(&mut *tx)
+            .bind(item_request.service_id)
+            .fetch_optional(&mut *conn)
             .await
             .map_err(|e| ApiError {
                 message: format!("Database error: {}", e),
                 code: Some("DATABASE_ERROR".to_string()),
             })?
             .ok_or_else(|| ApiError {
-                message: "Addon not found".to_string(),
+                message: format!("Addon {} is not available for this service", addon_request.addon_id),
                 code: Some("ADDON_NOT_FOUND".to_string()),
             })?;
 
-            let addon_rate: f64 = addon_row.get("price");
-            let addon_gst_rate: f64 = gst_rate; // Use parent service's GST rate
+            let pricing_mode: String = addon_row.get("pricing_mode");
 
-            let addon_pricing = PricingEngine::calculate_simple_pricing(
-                addon_rate,
+            // Per-order addons are charged once per invoice
+            if pricing_mode == "per_order" && !per_order_addons.insert(addon_request.addon_id) {
+                continue;
+            }
+
+            // Addons may carry their own GST rate, otherwise the parent service's applies
+            let addon_gst_rate: f64 = addon_row
+                .get::<Option<f64>, _>("gst_rate")
+                .unwrap_or(gst_rate);
+
+            // Percentages are not GST-inclusive amounts, so skip the rate card's GST conversion
+            let addon_resolved = PriceResolver::addon_rate(
+                conn,
+                &pricing_context,
+                addon_request.addon_id,
+                addon_row.get("price"),
+                if pricing_mode == "percent_of_line" { 0.0 } else { addon_gst_rate },
+            ).await?;
+
+            let charge = PricingEngine::calculate_addon_charge(
+                &pricing_mode,
+                addon_resolved.rate,
                 addon_request.qty,
-                None,
-                None,
-                addon_gst_rate,
-                request.gst_inclusive.unwrap_or(false),
+                pricing.line_total,
+                piece_count,
             )?;
 
-            // Insert addon
-            sqlx::query(
-                r#"
-                INSERT INTO invoice_item_addons (
-                    invoice_item_id, addon_id, qty, rate, amount
-                ) VALUES (?, ?, ?, ?, ?)
-                "#
-            )
-            .bind(item_id)
-            .bind(addon_request.addon_id)
-            .bind(addon_request.qty)
-            .bind(addon_rate)
-            .bind(addon_pricing.line_total)
-            .execute(&mut *tx)
-            .await
-            .map_err(|e| ApiError {
-                message: format!("Failed to create addon: {}", e),
-                code: Some("CREATE_ADDON_ERROR".to_string()),
-            })?;
-
-            subtotal += addon_pricing.subtotal;
-            total_sgst += addon_pricing.sgst_amount;
-            total_cgst += addon_pricing.cgst_amount;
+            surcharge_lines.push(SurchargeLine {
+                amount: charge.amount,
+                category_id,
+                gst_rate: addon_gst_rate,
+            });
+            addons.push(PricedAddon {
+                addon_id: addon_request.addon_id,
+                pricing_mode,
+                quantity: charge.quantity,
+                price: addon_resolved,
+                line: PricedLine::new(charge.amount, addon_gst_rate),
+            });
         }
         }
+
+        items.push(PricedItem {
+            request: item_request,
+            piece_count,
+            price: resolved,
+            line: PricedLine::new(pricing.line_total, gst_rate),
+            addons,
+        });
     }
 
-    // Calculate final totals
-    let final_total = subtotal - request.discount.unwrap_or(0.0) + request.express_charge.unwrap_or(0.0) + total_sgst + total_cgst;
+    // Allocate the invoice discount across lines so GST is charged on the discounted value
+    let mut lines: Vec<&mut PricedLine> = items
+        .iter_mut()
+        .flat_map(|item| {
+            std::iter::once(&mut item.line).chain(item.addons.iter_mut().map(|addon| &mut addon.line))
+        })
+        .collect();
+    let line_amounts: Vec<f64> = lines.iter().map(|line| line.amount).collect();
+    let discount_amount = PricingEngine::calculate_invoice_discount(
+        request.discount.unwrap_or(0.0),
+        request.discount_type.as_deref().unwrap_or("flat"),
+        line_amounts.iter().sum(),
+    )?;
+    let discount_shares = PricingEngine::allocate_discount(discount_amount, &line_amounts);
 
-    // Update invoice with calculated totals
-    sqlx::query(
-        r#"
-        UPDATE invoices SET
-            subtotal = ?,
-            sgst_amount = ?,
-            cgst_amount = ?,
-            total = ?,
-            total_pieces = ?
-        WHERE id = ?
-        "#
-    )
-    .bind(subtotal)
-    .bind(total_sgst)
-    .bind(total_cgst)
-    .bind(final_total)
-    .bind(total_pieces)
-    .bind(invoice_id)
-    .execute(&mut *tx)
-    .await
-    .map_err(|e| ApiError {
-        message: format!("Failed to update invoice totals: {}", e),
-        code: Some("UPDATE_TOTALS_ERROR".to_string()),
-    })?;
+    let mut subtotal = 0.0;
+    let mut taxable_total = 0.0;
+    let mut total_sgst = 0.0;
+    let mut total_cgst = 0.0;
 
-    // Commit transaction
-    tx.commit().await.map_err(|e| ApiError {
-        message: format!("Transaction commit failed: {}", e),
-        code: Some("COMMIT_ERROR".to_string()),
-    })?;
+    for (line, line_discount) in lines.iter_mut().zip(discount_shares) {
+        let undiscounted = PricingEngine::calculate_gst(line.amount, line.gst_rate, gst_inclusive)?;
+        let gst = PricingEngine::calculate_gst(line.amount - line_discount, line.gst_rate, gst_inclusive)?;
 
-    // Auto-generate tags for the invoice
-    if let Err(e) = crate::services::TagGeneratorService::generate_tags_for_invoice(&state.db, invoice_id).await {
-        log::warn!("Failed to generate tags for invoice {}: {}", invoice_id, e);
-        // Don't fail the invoice creation if tag generation fails
+        line.discount_amount = line_discount;
+        line.taxable_value = gst.base_amount;
+        line.sgst = gst.sgst_amount;
+        line.cgst = gst.cgst_amount;
+
+        subtotal += undiscounted.base_amount;
+        taxable_total += gst.base_amount;
+        total_sgst += gst.sgst_amount;
+        total_cgst += gst.cgst_amount;
     }
 
-    // Return the created invoice with full details
-    get_invoice_by_id(state, invoice_id).await
+    // The summary can only label SGST/CGST with a rate when every line shares it
+    let gst_rate = lines.first().map(|line| line.gst_rate)
+        .filter(|rate| lines.iter().all(|line| line.gst_rate == *rate));
+
+    // Turnaround surcharge is charged on the regular tariff of eligible lines
+    let surcharge = PricingEngine::calculate_turnaround_surcharge(
+        &tier,
+        &surcharge_lines,
+        gst_inclusive,
+    )?;
+    let express_charge = surcharge.base_amount;
+    total_sgst += surcharge.sgst_amount;
+    total_cgst += surcharge.cgst_amount;
+
+    let totals = PricingTotals {
+        subtotal,
+        discount_amount: subtotal - taxable_total,
+        express_charge,
+        base_amount: taxable_total + express_charge,
+        total_gst_amount: total_sgst + total_cgst,
+        sgst_amount: total_sgst,
+        cgst_amount: total_cgst,
+        total_amount: taxable_total + express_charge + total_sgst + total_cgst,
+        gst_inclusive,
+    };
+
+    Ok(PricedInvoice {
+        tier,
+        items,
+        total_pieces,
+        totals,
+        gst_rate,
+    })
 }
 
 #[tauri::command]
@@ -377,8 +636,13 @@ pub async fn get_invoice_by_id(
             rate: row.get("rate"),
             amount: row.get("amount"),
             gst_rate: row.get("gst_rate"),
+            discount_amount: row.get("discount_amount"),
+            taxable_value: row.get("taxable_value"),
             sgst: row.get("sgst"),
             cgst: row.get("cgst"),
+            price_list_id: row.get("price_list_id"),
+            rate_source: row.get("rate_source"),
+            rate_card_id: row.get("rate_card_id"),
             created_at: row.get("created_at"),
         };
 
@@ -404,9 +668,12 @@ pub async fn get_invoice_by_id(
         let addons = addons_raw.into_iter().map(|addon_row| {
             InvoiceAddonDetail {
                 addon_name: addon_row.get("addon_name"),
+                pricing_mode: addon_row.get("pricing_mode"),
                 quantity: addon_row.get("qty"),
                 rate: addon_row.get("rate"),
                 amount: addon_row.get("amount"),
+                discount_amount: addon_row.get("discount_amount"),
+                taxable_value: addon_row.get("taxable_value"),
             }
         }).collect();
 
@@ -432,8 +699,11 @@ pub async fn search_invoices(
     state: State<'_, crate::AppState>,
     query: InvoiceSearchQuery,
 ) -> ApiResult<Vec<Invoice>> {
-    let pool = state.db.get_pool_cloned();
+    find_invoices(state.db.get_pool(), &query).await
+}
 
+/// Invoices matching a search, newest first
+pub(crate) async fn find_invoices(pool: &sqlx::SqlitePool, query: &InvoiceSearchQuery) -> ApiResult<Vec<Invoice>> {
     let mut sql = "SELECT * FROM invoices WHERE 1=1".to_string();
     let mut params = Vec::new();
 
@@ -487,7 +757,7 @@ pub async fn search_invoices(
     }
 
     let invoices = query_builder
-        .fetch_all(&pool)
+        .fetch_all(pool)
         .await
         .map_err(|e| ApiError {
             message: format!("Database error: {}", e),
@@ -765,4 +1035,84 @@ async fn generate_invoice_number(pool: &sqlx::SqlitePool) -> ApiResult<String> {
 
     let invoice_no = format!("INV{}{}__{:03}", year, month, sequence);
     Ok(invoice_no)
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(service_id: i64, qty: f64, addons: Vec<CreateInvoiceItemAddonRequest>) -> CreateInvoiceItemRequest {
+        CreateInvoiceItemRequest {
+            service_id,
+            variant_id: None,
+            description: None,
+            qty,
+            piece_count: None,
+            weight_kg: None,
+            area_sqft: None,
+            addons: Some(addons),
+        }
+    }
+
+    fn request(items: Vec<CreateInvoiceItemRequest>, tier: &str) -> CreateInvoiceRequest {
+        CreateInvoiceRequest {
+            customer_id: 900,
+            store_id: 900,
+            order_source: None,
+            pickup_datetime: None,
+            delivery_datetime: None,
+            items,
+            discount: Some(10.0),
+            discount_type: Some("percent".to_string()),
+            order_datetime: None,
+            turnaround_tier: Some(tier.to_string()),
+            gst_inclusive: Some(false),
+            payment_method: None,
+            payment_amount: None,
+            notes: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_preview_prices_like_create_invoice() {
+        let db = DatabaseManager::new_with_url("sqlite::memory:").await.unwrap();
+        db.initialize_schema().await.unwrap();
+        sqlx::query(
+            r#"
+            INSERT INTO stores (id, name, address) VALUES (900, 'Preview Store', 'Main Road');
+            INSERT INTO customers (id, name) VALUES (900, 'Preview Customer');
+            INSERT INTO services (id, name, base_price, gst_rate, unit) VALUES
+                (900, 'Dry Clean', 100, 18, 'piece'),
+                (901, 'Ironing', 200, 5, 'piece');
+            INSERT INTO service_addons (id, service_id, name, price, unit, pricing_mode, gst_rate)
+                VALUES (900, 900, 'Starch', 20, 'piece', 'per_unit', 12);
+            "#
+        )
+        .execute(db.get_pool())
+        .await
+        .unwrap();
+
+        // Lines of 200 @ 18%, 20 @ 12% and 200 @ 5%, less 10%, plus the next-day 50%
+        let starch = CreateInvoiceItemAddonRequest { addon_id: 900, qty: 1.0 };
+        let mixed = invoice_preview(&db, &request(vec![item(900, 2.0, vec![starch]), item(901, 1.0, vec![])], "next_day"))
+            .await
+            .unwrap();
+        let totals = mixed.totals;
+        assert!((totals.subtotal - 420.0).abs() < 0.001);
+        assert!((totals.discount_amount - 42.0).abs() < 0.001);
+        assert!((totals.express_charge - 210.0).abs() < 0.001);
+        // GST follows each line's rate: 32.40 + 2.16 + 9.00 on the lines, 24.20 on the surcharge
+        assert!((totals.total_gst_amount - 67.76).abs() < 0.001);
+        assert!((totals.total_amount - 655.76).abs() < 0.001);
+        assert_eq!(mixed.gst_rate, None);
+
+        let single = invoice_preview(&db, &request(vec![item(900, 1.0, vec![])], "standard")).await.unwrap();
+        assert_eq!(single.gst_rate, Some(18.0));
+
+        // Nothing is written
+        let invoices: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM invoices")
+            .fetch_one(db.get_pool())
+            .await
+            .unwrap();
+        assert_eq!(invoices, 0);
+    }
+}
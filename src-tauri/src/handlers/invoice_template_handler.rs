@@ -0,0 +1,122 @@
+use crate::models::{ApiError, ApiResult, InvoiceTemplate};
+use crate::services::{
+    CreateInvoiceTemplateRequest, InvoiceTemplateExport, InvoiceTemplateService, LayoutSettings, TemplateEngine,
+    UpdateInvoiceTemplateRequest,
+};
+use tauri::State;
+
+/// Templates a store can print with (its own and the shared ones)
+#[tauri::command]
+pub async fn get_invoice_templates(
+    state: State<'_, crate::AppState>,
+    store_id: Option<i64>,
+    format: Option<String>,
+) -> ApiResult<Vec<InvoiceTemplate>> {
+    InvoiceTemplateService::list(&state.db, store_id, format.as_deref()).await
+}
+
+#[tauri::command]
+pub async fn get_invoice_template(state: State<'_, crate::AppState>, template_id: i64) -> ApiResult<InvoiceTemplate> {
+    InvoiceTemplateService::get(&state.db, template_id).await
+}
+
+/// Source of a built-in template ("base", "a4", "a5" or "thermal") to start
+/// editing from
+#[tauri::command]
+pub async fn get_builtin_invoice_template(name: String) -> ApiResult<String> {
+    TemplateEngine::builtin_invoice_template(&name).map(str::to_string)
+}
+
+#[tauri::command]
+pub async fn create_invoice_template(
+    state: State<'_, crate::AppState>,
+    request: CreateInvoiceTemplateRequest,
+) -> ApiResult<InvoiceTemplate> {
+    InvoiceTemplateService::create(&state.db, request).await
+}
+
+#[tauri::command]
+pub async fn update_invoice_template(
+    state: State<'_, crate::AppState>,
+    template_id: i64,
+    request: UpdateInvoiceTemplateRequest,
+) -> ApiResult<InvoiceTemplate> {
+    InvoiceTemplateService::update(&state.db, template_id, request).await
+}
+
+#[tauri::command]
+pub async fn clone_invoice_template(
+    state: State<'_, crate::AppState>,
+    template_id: i64,
+    name: Option<String>,
+    store_id: Option<i64>,
+) -> ApiResult<InvoiceTemplate> {
+    InvoiceTemplateService::clone_template(&state.db, template_id, name, store_id).await
+}
+
+#[tauri::command]
+pub async fn delete_invoice_template(state: State<'_, crate::AppState>, template_id: i64) -> ApiResult<()> {
+    InvoiceTemplateService::delete(&state.db, template_id).await
+}
+
+#[tauri::command]
+pub async fn set_default_invoice_template(
+    state: State<'_, crate::AppState>,
+    template_id: i64,
+) -> ApiResult<InvoiceTemplate> {
+    InvoiceTemplateService::set_default(&state.db, template_id).await
+}
+
+/// Stop using a saved template as the store's default for a format
+#[tauri::command]
+pub async fn clear_default_invoice_template(
+    state: State<'_, crate::AppState>,
+    store_id: Option<i64>,
+    format: String,
+) -> ApiResult<()> {
+    InvoiceTemplateService::clear_default(&state.db, store_id, &format).await
+}
+
+/// Check a template against a sample invoice without saving it
+#[tauri::command]
+pub async fn validate_invoice_template(content: String, format: String, layout: LayoutSettings) -> ApiResult<()> {
+    TemplateEngine::new()?.validate_invoice_template(&content, &format, &layout)
+}
+
+/// Write a template to a JSON file that another install can import
+#[tauri::command]
+pub async fn export_invoice_template(
+    state: State<'_, crate::AppState>,
+    template_id: i64,
+    path: String,
+) -> ApiResult<()> {
+    let template = InvoiceTemplateService::get(&state.db, template_id).await?;
+    let export = InvoiceTemplateService::export(&template)?;
+    let json = serde_json::to_string_pretty(&export).map_err(|e| ApiError {
+        message: format!("Failed to serialize template: {}", e),
+        code: Some("SERIALIZATION_ERROR".to_string()),
+    })?;
+
+    std::fs::write(&path, json).map_err(|e| ApiError {
+        message: format!("Failed to write template file: {}", e),
+        code: Some("FILE_WRITE_ERROR".to_string()),
+    })
+}
+
+#[tauri::command]
+pub async fn import_invoice_template(
+    state: State<'_, crate::AppState>,
+    path: String,
+    store_id: Option<i64>,
+) -> ApiResult<InvoiceTemplate> {
+    let json = std::fs::read_to_string(&path).map_err(|e| ApiError {
+        message: format!("Failed to read template file: {}", e),
+        code: Some("FILE_READ_ERROR".to_string()),
+    })?;
+    let export: InvoiceTemplateExport = serde_json::from_str(&json).map_err(|e| ApiError {
+        message: format!("Not an invoice template file: {}", e),
+        code: Some("INVALID_TEMPLATE".to_string()),
+    })?;
+
+    InvoiceTemplateService::import(&state.db, export, store_id).await
+}
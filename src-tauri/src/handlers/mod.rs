@@ -5,4 +5,12 @@ pub mod store_handler;
 pub mod report_handler;
 pub mod pricing_handler;
 pub mod html_handler;
-pub mod tag_handler;
\ No newline at end of file
+pub mod tag_handler;
+pub mod turnaround_handler;
+pub mod price_list_handler;
+pub mod rate_card_handler;
+pub mod print_queue_handler;
+pub mod invoice_template_handler;
+pub mod receipt_handler;
+pub mod invoice_export_handler;
+pub mod invoice_archive_handler;
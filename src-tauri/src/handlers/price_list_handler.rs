@@ -0,0 +1,378 @@
+use crate::database::DatabaseManager;
+use crate::models::{
+    PriceList, CreatePriceListRequest, PriceListAssignment, AssignPriceListRequest,
+    PriceListItem, SchedulePriceChangeRequest, ApiResult, ApiError,
+};
+use crate::services::{PriceResolver, ResolvedPrice};
+use tauri::State;
+
+#[tauri::command]
+pub async fn get_price_lists(
+    state: State<'_, crate::AppState>,
+) -> ApiResult<Vec<PriceList>> {
+    sqlx::query_as::<_, PriceList>("SELECT * FROM price_lists ORDER BY name")
+        .fetch_all(state.db.get_pool())
+        .await
+        .map_err(|e| ApiError {
+            message: format!("Database error: {}", e),
+            code: Some("DATABASE_ERROR".to_string()),
+        })
+}
+
+#[tauri::command]
+pub async fn create_price_list(
+    state: State<'_, crate::AppState>,
+    request: CreatePriceListRequest,
+) -> ApiResult<PriceList> {
+    if request.name.trim().is_empty() {
+        return Err(ApiError {
+            message: "Price list name is required".to_string(),
+            code: Some("VALIDATION_ERROR".to_string()),
+        });
+    }
+
+    sqlx::query_as::<_, PriceList>(
+        "INSERT INTO price_lists (name, description) VALUES (?, ?) RETURNING *"
+    )
+    .bind(request.name.trim())
+    .bind(&request.description)
+    .fetch_one(state.db.get_pool())
+    .await
+    .map_err(|e| ApiError {
+        message: format!("Failed to create price list: {}", e),
+        code: Some("DATABASE_ERROR".to_string()),
+    })
+}
+
+#[tauri::command]
+pub async fn assign_price_list(
+    state: State<'_, crate::AppState>,
+    request: AssignPriceListRequest,
+) -> ApiResult<PriceListAssignment> {
+    let customer_group = request.customer_group
+        .as_deref()
+        .map(str::trim)
+        .filter(|group| !group.is_empty());
+
+    sqlx::query_as::<_, PriceListAssignment>(
+        r#"
+        INSERT INTO price_list_assignments (price_list_id, store_id, customer_group, priority)
+        VALUES (?, ?, ?, ?)
+        RETURNING *
+        "#
+    )
+    .bind(request.price_list_id)
+    .bind(request.store_id)
+    .bind(customer_group)
+    .bind(request.priority.unwrap_or(0))
+    .fetch_one(state.db.get_pool())
+    .await
+    .map_err(|e| ApiError {
+        message: format!("Failed to assign price list: {}", e),
+        code: Some("DATABASE_ERROR".to_string()),
+    })
+}
+
+#[tauri::command]
+pub async fn get_price_list_assignments(
+    state: State<'_, crate::AppState>,
+    price_list_id: Option<i64>,
+) -> ApiResult<Vec<PriceListAssignment>> {
+    sqlx::query_as::<_, PriceListAssignment>(
+        "SELECT * FROM price_list_assignments WHERE ?1 IS NULL OR price_list_id = ?1 ORDER BY id"
+    )
+    .bind(price_list_id)
+    .fetch_all(state.db.get_pool())
+    .await
+    .map_err(|e| ApiError {
+        message: format!("Database error: {}", e),
+        code: Some("DATABASE_ERROR".to_string()),
+    })
+}
+
+#[tauri::command]
+pub async fn remove_price_list_assignment(
+    state: State<'_, crate::AppState>,
+    assignment_id: i64,
+) -> ApiResult<String> {
+    sqlx::query("DELETE FROM price_list_assignments WHERE id = ?")
+        .bind(assignment_id)
+        .execute(state.db.get_pool())
+        .await
+        .map_err(|e| ApiError {
+            message: format!("Failed to remove assignment: {}", e),
+            code: Some("DATABASE_ERROR".to_string()),
+        })?;
+
+    Ok("Price list assignment removed".to_string())
+}
+
+/// Price history of a list, or only the prices in effect on `as_of`
+#[tauri::command]
+pub async fn get_price_list_items(
+    state: State<'_, crate::AppState>,
+    price_list_id: i64,
+    as_of: Option<String>,
+) -> ApiResult<Vec<PriceListItem>> {
+    let as_of = match as_of {
+        Some(date) => Some(PriceResolver::price_date(Some(&date))?),
+        None => None,
+    };
+
+    sqlx::query_as::<_, PriceListItem>(
+        r#"
+        SELECT * FROM price_list_items
+        WHERE price_list_id = ?1
+          AND (?2 IS NULL OR (effective_from <= ?2 AND (effective_to IS NULL OR effective_to > ?2)))
+        ORDER BY service_id, variant_id, addon_id, effective_from
+        "#
+    )
+    .bind(price_list_id)
+    .bind(as_of)
+    .fetch_all(state.db.get_pool())
+    .await
+    .map_err(|e| ApiError {
+        message: format!("Database error: {}", e),
+        code: Some("DATABASE_ERROR".to_string()),
+    })
+}
+
+/// Add a price effective from a (possibly future) date. The price in effect
+/// before that date is closed off so validity periods never overlap.
+#[tauri::command]
+pub async fn schedule_price_change(
+    state: State<'_, crate::AppState>,
+    request: SchedulePriceChangeRequest,
+) -> ApiResult<PriceListItem> {
+    schedule_price(&state.db, request).await
+}
+
+async fn schedule_price(db: &DatabaseManager, request: SchedulePriceChangeRequest) -> ApiResult<PriceListItem> {
+    let targets_service = request.service_id.is_some();
+    let targets_addon = request.addon_id.is_some();
+
+    if targets_service == targets_addon || (request.variant_id.is_some() && !targets_service) {
+        return Err(ApiError {
+            message: "A price must target either a service (optionally a variant) or an addon".to_string(),
+            code: Some("VALIDATION_ERROR".to_string()),
+        });
+    }
+
+    if request.price < 0.0 {
+        return Err(ApiError {
+            message: "Price cannot be negative".to_string(),
+            code: Some("VALIDATION_ERROR".to_string()),
+        });
+    }
+
+    let (effective_from, effective_to) =
+        PriceResolver::validity_period(&request.effective_from, request.effective_to.as_deref())?;
+
+    let mut tx = db.get_pool().begin().await.map_err(|e| ApiError {
+        message: format!("Database error: {}", e),
+        code: Some("DATABASE_ERROR".to_string()),
+    })?;
+
+    const TARGET: &str = "price_list_id = ? AND service_id IS ? AND variant_id IS ? AND addon_id IS ?";
+
+    // Same start date: treat as a correction of that price
+    let existing: Option<i64> = sqlx::query_scalar(&format!(
+        "SELECT id FROM price_list_items WHERE {} AND effective_from = ?", TARGET
+    ))
+    .bind(request.price_list_id)
+    .bind(request.service_id)
+    .bind(request.variant_id)
+    .bind(request.addon_id)
+    .bind(&effective_from)
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(|e| ApiError {
+        message: format!("Database error: {}", e),
+        code: Some("DATABASE_ERROR".to_string()),
+    })?;
+
+    // A price runs at most until the next already-scheduled change
+    let next_change: Option<String> = sqlx::query_scalar(&format!(
+        "SELECT MIN(effective_from) FROM price_list_items WHERE {} AND effective_from > ?", TARGET
+    ))
+    .bind(request.price_list_id)
+    .bind(request.service_id)
+    .bind(request.variant_id)
+    .bind(request.addon_id)
+    .bind(&effective_from)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| ApiError {
+        message: format!("Database error: {}", e),
+        code: Some("DATABASE_ERROR".to_string()),
+    })?;
+
+    if let (Some(to), Some(next)) = (&effective_to, &next_change) {
+        if to > next {
+            return Err(ApiError {
+                message: format!("Effective-to date overlaps the price already scheduled from {}", next),
+                code: Some("VALIDATION_ERROR".to_string()),
+            });
+        }
+    }
+
+    let item = if let Some(id) = existing {
+        sqlx::query_as::<_, PriceListItem>(
+            "UPDATE price_list_items SET price = ?, effective_to = COALESCE(?, effective_to) WHERE id = ? RETURNING *"
+        )
+        .bind(request.price)
+        .bind(&effective_to)
+        .bind(id)
+        .fetch_one(&mut *tx)
+        .await
+    } else {
+        // Close the period running into the new start date
+        sqlx::query(&format!(
+            "UPDATE price_list_items SET effective_to = ? WHERE {} AND effective_from < ? AND (effective_to IS NULL OR effective_to > ?)",
+            TARGET
+        ))
+        .bind(&effective_from)
+        .bind(request.price_list_id)
+        .bind(request.service_id)
+        .bind(request.variant_id)
+        .bind(request.addon_id)
+        .bind(&effective_from)
+        .bind(&effective_from)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| ApiError {
+            message: format!("Failed to close previous price: {}", e),
+            code: Some("DATABASE_ERROR".to_string()),
+        })?;
+
+        sqlx::query_as::<_, PriceListItem>(
+            r#"
+            INSERT INTO price_list_items (
+                price_list_id, service_id, variant_id, addon_id, price, effective_from, effective_to
+            ) VALUES (?, ?, ?, ?, ?, ?, ?)
+            RETURNING *
+            "#
+        )
+        .bind(request.price_list_id)
+        .bind(request.service_id)
+        .bind(request.variant_id)
+        .bind(request.addon_id)
+        .bind(request.price)
+        .bind(&effective_from)
+        .bind(effective_to.or(next_change))
+        .fetch_one(&mut *tx)
+        .await
+    }
+    .map_err(|e| ApiError {
+        message: format!("Failed to save price: {}", e),
+        code: Some("DATABASE_ERROR".to_string()),
+    })?;
+
+    tx.commit().await.map_err(|e| ApiError {
+        message: format!("Transaction commit failed: {}", e),
+        code: Some("COMMIT_ERROR".to_string()),
+    })?;
+
+    Ok(item)
+}
+
+/// GST-exclusive rate a service (or variant) would be charged at for a store and customer on a date
+#[tauri::command]
+pub async fn get_effective_price(
+    state: State<'_, crate::AppState>,
+    store_id: i64,
+    customer_id: Option<i64>,
+    service_id: i64,
+    variant_id: Option<i64>,
+    on_date: Option<String>,
+) -> ApiResult<ResolvedPrice> {
+    let mut conn = state.db.get_pool().acquire().await.map_err(|e| ApiError {
+        message: format!("Database error: {}", e),
+        code: Some("DATABASE_ERROR".to_string()),
+    })?;
+
+    let (base_price, gst_rate): (f64, f64) = sqlx::query_as("SELECT base_price, gst_rate FROM services WHERE id = ?")
+        .bind(service_id)
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(|e| ApiError {
+            message: format!("Database error: {}", e),
+            code: Some("DATABASE_ERROR".to_string()),
+        })?
+        .ok_or_else(|| ApiError {
+            message: "Service not found".to_string(),
+            code: Some("SERVICE_NOT_FOUND".to_string()),
+        })?;
+
+    let price_multiplier: f64 = match variant_id {
+        Some(variant_id) => sqlx::query_scalar(
+            "SELECT price_multiplier FROM service_variants WHERE id = ? AND service_id = ?"
+        )
+        .bind(variant_id)
+        .bind(service_id)
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(|e| ApiError {
+            message: format!("Database error: {}", e),
+            code: Some("DATABASE_ERROR".to_string()),
+        })?
+        .unwrap_or(1.0),
+        None => 1.0,
+    };
+
+    let context = PriceResolver::load_context(&mut conn, Some(store_id), customer_id, on_date.as_deref(), false).await?;
+
+    PriceResolver::service_rate(
+        &mut conn,
+        &context,
+        service_id,
+        variant_id,
+        base_price,
+        price_multiplier,
+        gst_rate,
+    ).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn change(price: f64, from: &str, to: Option<&str>) -> SchedulePriceChangeRequest {
+        SchedulePriceChangeRequest {
+            price_list_id: 1,
+            service_id: Some(1),
+            variant_id: None,
+            addon_id: None,
+            price,
+            effective_from: from.to_string(),
+            effective_to: to.map(str::to_string),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_scheduled_prices_never_overlap() {
+        let db = DatabaseManager::new_with_url("sqlite::memory:").await.unwrap();
+        db.initialize_schema().await.unwrap();
+        sqlx::query("INSERT INTO price_lists (id, name) VALUES (1, 'Festive')")
+            .execute(db.get_pool())
+            .await
+            .unwrap();
+
+        schedule_price(&db, change(60.0, "2025-11-01", None)).await.unwrap();
+        let earlier = schedule_price(&db, change(55.0, "2025-10-01", None)).await.unwrap();
+        assert_eq!(earlier.effective_to.as_deref(), Some("2025-11-01"));
+
+        // An explicit end may stop short of the next change but not run past it
+        let error = schedule_price(&db, change(50.0, "2025-09-01", Some("2025-10-15"))).await.unwrap_err();
+        assert_eq!(error.code.as_deref(), Some("VALIDATION_ERROR"));
+        let short = schedule_price(&db, change(50.0, "2025-09-01", Some("2025-09-20"))).await.unwrap();
+        assert_eq!(short.effective_to.as_deref(), Some("2025-09-20"));
+
+        // Corrections to an existing start date are held to the same limit
+        let error = schedule_price(&db, change(56.0, "2025-10-01", Some("2025-12-01"))).await.unwrap_err();
+        assert_eq!(error.code.as_deref(), Some("VALIDATION_ERROR"));
+        let corrected = schedule_price(&db, change(56.0, "2025-10-01", None)).await.unwrap();
+        assert_eq!((corrected.id, corrected.price), (earlier.id, 56.0));
+        assert_eq!(corrected.effective_to.as_deref(), Some("2025-11-01"));
+    }
+}
@@ -1,6 +1,7 @@
 use crate::database::DatabaseManager;
 use crate::models::{Service, ServiceVariant, ServiceAddon, ApiResult, ApiError};
-use crate::services::pricing_engine::{PricingEngine, PricingRequest, PricingResult, GstCalculation};
+use crate::services::pricing_engine::{PricingEngine, PricingRequest, PricingResult, GstCalculation, SurchargeLine, TurnaroundSurcharge, ItemRates};
+use crate::services::{TurnaroundService, PriceResolver};
 use sqlx::Row;
 use tauri::State;
 
@@ -57,7 +58,13 @@ pub async fn calculate_service_pricing(
     let mut addons = Vec::new();
     for addon_request in &request.addons {
         let addon = sqlx::query_as::<_, ServiceAddon>(
-            "SELECT * FROM service_addons WHERE id = ? AND service_id = ? AND is_active = 1"
+            r#"
+            SELECT * FROM service_addons
+            WHERE id = ?1 AND is_active = 1
+              AND (service_id = ?2 OR EXISTS (
+                  SELECT 1 FROM service_addon_links WHERE addon_id = ?1 AND service_id = ?2
+              ))
+            "#
         )
         .bind(addon_request.addon_id)
         .bind(request.service_id)
@@ -75,8 +82,42 @@ pub async fn calculate_service_pricing(
         addons.push(addon);
     }
 
+    // Resolve rates the same way create_invoice does
+    let mut conn = pool.acquire().await.map_err(|e| ApiError {
+        message: format!("Database error: {}", e),
+        code: Some("DATABASE_ERROR".to_string()),
+    })?;
+    let context = PriceResolver::load_context(&mut conn, request.store_id, request.customer_id, None, request.gst_inclusive).await?;
+    let price_multiplier = variant.as_ref().map(|v| v.price_multiplier).unwrap_or(1.0);
+
+    let service_rate = PriceResolver::service_rate(
+        &mut conn,
+        &context,
+        service.id,
+        request.variant_id,
+        service.base_price,
+        price_multiplier,
+        service.gst_rate,
+    ).await?;
+
+    let mut addon_rates = Vec::new();
+    for addon in &addons {
+        // Percentages are not GST-inclusive amounts, so skip the rate card's GST conversion
+        let gst_rate = match addon.pricing_mode.as_str() {
+            "percent_of_line" => 0.0,
+            _ => addon.gst_rate.unwrap_or(service.gst_rate),
+        };
+        let rate = PriceResolver::addon_rate(&mut conn, &context, addon.id, addon.price, gst_rate).await?;
+        addon_rates.push((addon.id, rate));
+    }
+
+    let rates = ItemRates {
+        service: service_rate,
+        addons: addon_rates,
+    };
+
     // Calculate pricing
-    PricingEngine::calculate_item_pricing(&service, variant.as_ref(), &addons, &request)
+    PricingEngine::calculate_item_pricing(&service, variant.as_ref(), &addons, &request, &rates)
 }
 
 #[tauri::command]
@@ -90,19 +131,33 @@ pub async fn calculate_gst_only(
 
 #[tauri::command]
 pub async fn calculate_express_delivery_charge(
+    state: State<'_, crate::AppState>,
+    store_id: i64,
+    turnaround_tier: String,
     base_amount: f64,
-    express_rate: Option<f64>,
-) -> ApiResult<f64> {
-    let rate = express_rate.unwrap_or(50.0); // Default 50% surcharge
-
-    if rate < 0.0 {
+    gst_rate: Option<f64>,
+    gst_inclusive: Option<bool>,
+) -> ApiResult<TurnaroundSurcharge> {
+    if base_amount < 0.0 {
         return Err(ApiError {
-            message: "Express rate cannot be negative".to_string(),
-            code: Some("INVALID_EXPRESS_RATE".to_string()),
+            message: "Base amount cannot be negative".to_string(),
+            code: Some("INVALID_AMOUNT".to_string()),
         });
     }
 
-    Ok(PricingEngine::calculate_express_charge(base_amount, rate))
+    let mut conn = state.db.get_pool().acquire().await.map_err(|e| ApiError {
+        message: format!("Database error: {}", e),
+        code: Some("DATABASE_ERROR".to_string()),
+    })?;
+
+    let tier = TurnaroundService::resolve_tier(&mut conn, store_id, &turnaround_tier).await?;
+    let line = SurchargeLine {
+        amount: base_amount,
+        category_id: None,
+        gst_rate: gst_rate.unwrap_or(18.0),
+    };
+
+    PricingEngine::calculate_turnaround_surcharge(&tier, &[line], gst_inclusive.unwrap_or(false))
 }
 
 #[tauri::command]
@@ -119,6 +174,8 @@ pub async fn get_service_price_preview(
     service_id: i64,
     variant_id: Option<i64>,
     quantity: f64,
+    store_id: Option<i64>,
+    customer_id: Option<i64>,
 ) -> ApiResult<ServicePricePreview> {
     let pool = state.db.get_pool_cloned();
 
@@ -155,7 +212,23 @@ pub async fn get_service_price_preview(
 
     let base_price = service.base_price;
     let price_multiplier = variant.as_ref().map(|v| v.price_multiplier).unwrap_or(1.0);
-    let effective_rate = base_price * price_multiplier;
+
+    let mut conn = pool.acquire().await.map_err(|e| ApiError {
+        message: format!("Database error: {}", e),
+        code: Some("DATABASE_ERROR".to_string()),
+    })?;
+    let context = PriceResolver::load_context(&mut conn, store_id, customer_id, None, false).await?;
+    let resolved = PriceResolver::service_rate(
+        &mut conn,
+        &context,
+        service_id,
+        variant_id,
+        base_price,
+        price_multiplier,
+        service.gst_rate,
+    ).await?;
+
+    let effective_rate = resolved.rate;
     let amount = effective_rate * quantity;
 
     // Calculate GST (assuming exclusive by default for preview)
@@ -169,6 +242,7 @@ pub async fn get_service_price_preview(
         base_price,
         price_multiplier,
         effective_rate,
+        rate_source: resolved.rate_source,
         quantity,
         unit: service.unit,
         amount,
@@ -189,6 +263,7 @@ pub struct ServicePricePreview {
     pub base_price: f64,
     pub price_multiplier: f64,
     pub effective_rate: f64,
+    pub rate_source: String,
     pub quantity: f64,
     pub unit: String,
     pub amount: f64,
@@ -0,0 +1,173 @@
+use crate::handlers::{receipt_handler, tag_handler};
+use crate::models::{PrintQueueItem, TagPrintRequest};
+use crate::services::{PrintQueueService, TagGeneratorService, PRINT_KIND_RECEIPT, PRINT_KIND_TAGS};
+use tauri::{AppHandle, Manager};
+use std::time::Duration;
+
+/// Emitted with the `PrintQueueItem` whenever a queued print changes state
+pub const PRINT_QUEUE_EVENT: &str = "print-queue-updated";
+
+const POLL_INTERVAL: Duration = Duration::from_secs(3);
+const AUTO_PRINT_USER: &str = "auto-print";
+
+/// Start the background worker that drains the print queue. Call once the
+/// database is ready.
+pub fn start_print_worker(app_handle: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let state = app_handle.state::<crate::AppState>();
+
+        match PrintQueueService::reset_interrupted(&state.db).await {
+            Ok(0) => {}
+            Ok(count) => log::info!("Requeued {} prints interrupted by the last shutdown", count),
+            Err(e) => log::error!("Failed to requeue interrupted prints: {}", e),
+        }
+
+        loop {
+            match PrintQueueService::claim_next(&state.db).await {
+                Ok(Some(item)) => {
+                    process_queue_item(&app_handle, item).await;
+                    continue;
+                }
+                Ok(None) => {}
+                Err(e) => log::error!("Print queue error: {}", e),
+            }
+
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    });
+}
+
+async fn process_queue_item(app_handle: &AppHandle, item: PrintQueueItem) {
+    let state = app_handle.state::<crate::AppState>();
+    emit_queue_update(app_handle, &item);
+
+    let result = match item.kind.as_str() {
+        PRINT_KIND_TAGS => print_queued_tags(app_handle, item.invoice_id).await,
+        PRINT_KIND_RECEIPT => receipt_handler::send_invoice_receipt(app_handle, &state, item.invoice_id)
+            .await
+            .map(|_| None)
+            .map_err(|e| e.message),
+        other => Err(format!("Unknown print kind: {}", other)),
+    };
+
+    let updated = match result {
+        Ok(print_job_id) => PrintQueueService::complete(&state.db, item.id, print_job_id).await,
+        Err(message) => {
+            log::warn!(
+                "Print {} ({} for invoice {}) failed on attempt {}: {}",
+                item.id, item.kind, item.invoice_id, item.attempts, message
+            );
+            PrintQueueService::fail(&state.db, &item, &message).await
+        }
+    };
+
+    match updated {
+        Ok(item) => emit_queue_update(app_handle, &item),
+        Err(e) => log::error!("Failed to update print {}: {}", item.id, e),
+    }
+}
+
+/// Print the invoice's tags that have not been printed yet, so a retry
+/// never reprints tags an earlier attempt or the counter already printed
+async fn print_queued_tags(app_handle: &AppHandle, invoice_id: i64) -> Result<Option<i64>, String> {
+    let state = app_handle.state::<crate::AppState>();
+
+    let tags = TagGeneratorService::get_tags_for_printing(&state.db, invoice_id, None, None)
+        .await
+        .map_err(|e| format!("Failed to get tags: {}", e))?;
+    let unprinted: Vec<i64> = tags.iter().filter(|tag| tag.printed_at.is_none()).map(|tag| tag.id).collect();
+
+    if unprinted.is_empty() && !tags.is_empty() {
+        return Ok(None);
+    }
+
+    let response = tag_handler::print_tags_for_invoice(
+        app_handle,
+        TagPrintRequest {
+            invoice_id,
+            item_ids: None,
+            tag_ids: if tags.is_empty() { None } else { Some(unprinted) },
+            printed_by: Some(AUTO_PRINT_USER.to_string()),
+            reprint_reason: None,
+        },
+        true,
+    )
+    .await?;
+
+    if response.success {
+        Ok(response.print_job_id)
+    } else {
+        Err(response.message)
+    }
+}
+
+fn emit_queue_update(app_handle: &AppHandle, item: &PrintQueueItem) {
+    if let Err(e) = app_handle.emit_all(PRINT_QUEUE_EVENT, item) {
+        log::warn!("Failed to emit print queue update: {}", e);
+    }
+}
+
+#[tauri::command]
+pub async fn get_print_queue(
+    app_handle: AppHandle,
+    status: Option<String>,
+    limit: Option<i64>,
+) -> Result<Vec<PrintQueueItem>, String> {
+    let state = app_handle.state::<crate::AppState>();
+
+    PrintQueueService::list(&state.db, status.as_deref(), limit.unwrap_or(100))
+        .await
+        .map_err(|e| {
+            log::error!("Failed to get print queue: {}", e);
+            format!("Failed to get print queue: {}", e)
+        })
+}
+
+#[tauri::command]
+pub async fn queue_invoice_print(
+    app_handle: AppHandle,
+    invoice_id: i64,
+    kind: String,
+) -> Result<PrintQueueItem, String> {
+    let state = app_handle.state::<crate::AppState>();
+
+    let item = PrintQueueService::enqueue(&state.db, invoice_id, &kind).await.map_err(|e| {
+        log::error!("Failed to queue {} print for invoice {}: {}", kind, invoice_id, e);
+        format!("Failed to queue print: {}", e)
+    })?;
+
+    emit_queue_update(&app_handle, &item);
+    Ok(item)
+}
+
+#[tauri::command]
+pub async fn retry_print_queue_item(
+    app_handle: AppHandle,
+    item_id: i64,
+) -> Result<PrintQueueItem, String> {
+    let state = app_handle.state::<crate::AppState>();
+
+    let item = PrintQueueService::retry(&state.db, item_id).await.map_err(|e| {
+        log::error!("Failed to retry print {}: {}", item_id, e);
+        format!("Failed to retry print: {}", e)
+    })?;
+
+    emit_queue_update(&app_handle, &item);
+    Ok(item)
+}
+
+#[tauri::command]
+pub async fn cancel_print_queue_item(
+    app_handle: AppHandle,
+    item_id: i64,
+) -> Result<PrintQueueItem, String> {
+    let state = app_handle.state::<crate::AppState>();
+
+    let item = PrintQueueService::cancel(&state.db, item_id).await.map_err(|e| {
+        log::error!("Failed to cancel print {}: {}", item_id, e);
+        format!("Failed to cancel print: {}", e)
+    })?;
+
+    emit_queue_update(&app_handle, &item);
+    Ok(item)
+}
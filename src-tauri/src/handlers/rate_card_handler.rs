@@ -0,0 +1,209 @@
+use crate::models::{RateCard, RateCardItem, RateCardWithItems, SaveRateCardRequest, ApiResult, ApiError};
+use crate::services::PriceResolver;
+use sqlx::SqliteConnection;
+use tauri::State;
+
+#[tauri::command]
+pub async fn get_customer_rate_cards(
+    state: State<'_, crate::AppState>,
+    customer_id: i64,
+) -> ApiResult<Vec<RateCardWithItems>> {
+    let mut conn = state.db.get_pool().acquire().await.map_err(|e| ApiError {
+        message: format!("Database error: {}", e),
+        code: Some("DATABASE_ERROR".to_string()),
+    })?;
+
+    let rate_cards = sqlx::query_as::<_, RateCard>(
+        "SELECT * FROM rate_cards WHERE customer_id = ? ORDER BY valid_from DESC, id DESC"
+    )
+    .bind(customer_id)
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(|e| ApiError {
+        message: format!("Database error: {}", e),
+        code: Some("DATABASE_ERROR".to_string()),
+    })?;
+
+    let mut result = Vec::new();
+    for rate_card in rate_cards {
+        let items = get_rate_card_items(&mut conn, rate_card.id).await?;
+        result.push(RateCardWithItems { rate_card, items });
+    }
+
+    Ok(result)
+}
+
+/// Create a rate card, or replace an existing one and its items
+#[tauri::command]
+pub async fn save_rate_card(
+    state: State<'_, crate::AppState>,
+    rate_card_id: Option<i64>,
+    request: SaveRateCardRequest,
+) -> ApiResult<RateCardWithItems> {
+    validate_rate_card(&request)?;
+
+    let (valid_from, valid_to) = PriceResolver::validity_period(&request.valid_from, request.valid_to.as_deref())?;
+
+    let mut tx = state.db.get_pool().begin().await.map_err(|e| ApiError {
+        message: format!("Database error: {}", e),
+        code: Some("DATABASE_ERROR".to_string()),
+    })?;
+
+    let rate_card = match rate_card_id {
+        Some(id) => sqlx::query_as::<_, RateCard>(
+            r#"
+            UPDATE rate_cards SET
+                customer_id = ?, name = ?, valid_from = ?, valid_to = ?,
+                rates_include_gst = ?, notes = ?, is_active = ?, updated_at = datetime('now')
+            WHERE id = ?
+            RETURNING *
+            "#
+        )
+        .bind(request.customer_id)
+        .bind(request.name.trim())
+        .bind(&valid_from)
+        .bind(&valid_to)
+        .bind(if request.rates_include_gst { 1 } else { 0 })
+        .bind(&request.notes)
+        .bind(if request.is_active.unwrap_or(true) { 1 } else { 0 })
+        .bind(id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| ApiError {
+            message: format!("Failed to update rate card: {}", e),
+            code: Some("DATABASE_ERROR".to_string()),
+        })?
+        .ok_or_else(|| ApiError {
+            message: "Rate card not found".to_string(),
+            code: Some("RATE_CARD_NOT_FOUND".to_string()),
+        })?,
+        None => sqlx::query_as::<_, RateCard>(
+            r#"
+            INSERT INTO rate_cards (customer_id, name, valid_from, valid_to, rates_include_gst, notes, is_active)
+            VALUES (?, ?, ?, ?, ?, ?, ?)
+            RETURNING *
+            "#
+        )
+        .bind(request.customer_id)
+        .bind(request.name.trim())
+        .bind(&valid_from)
+        .bind(&valid_to)
+        .bind(if request.rates_include_gst { 1 } else { 0 })
+        .bind(&request.notes)
+        .bind(if request.is_active.unwrap_or(true) { 1 } else { 0 })
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| ApiError {
+            message: format!("Failed to create rate card: {}", e),
+            code: Some("DATABASE_ERROR".to_string()),
+        })?,
+    };
+
+    // Replace items
+    sqlx::query("DELETE FROM rate_card_items WHERE rate_card_id = ?")
+        .bind(rate_card.id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| ApiError {
+            message: format!("Failed to replace rate card items: {}", e),
+            code: Some("DATABASE_ERROR".to_string()),
+        })?;
+
+    for item in &request.items {
+        sqlx::query(
+            r#"
+            INSERT INTO rate_card_items (rate_card_id, service_id, variant_id, addon_id, rate_type, value)
+            VALUES (?, ?, ?, ?, ?, ?)
+            "#
+        )
+        .bind(rate_card.id)
+        .bind(item.service_id)
+        .bind(item.variant_id)
+        .bind(item.addon_id)
+        .bind(&item.rate_type)
+        .bind(item.value)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| ApiError {
+            message: format!("Failed to save rate card item: {}", e),
+            code: Some("DATABASE_ERROR".to_string()),
+        })?;
+    }
+
+    let items = get_rate_card_items(&mut tx, rate_card.id).await?;
+
+    tx.commit().await.map_err(|e| ApiError {
+        message: format!("Transaction commit failed: {}", e),
+        code: Some("COMMIT_ERROR".to_string()),
+    })?;
+
+    Ok(RateCardWithItems { rate_card, items })
+}
+
+#[tauri::command]
+pub async fn deactivate_rate_card(
+    state: State<'_, crate::AppState>,
+    rate_card_id: i64,
+) -> ApiResult<String> {
+    sqlx::query("UPDATE rate_cards SET is_active = 0, updated_at = datetime('now') WHERE id = ?")
+        .bind(rate_card_id)
+        .execute(state.db.get_pool())
+        .await
+        .map_err(|e| ApiError {
+            message: format!("Failed to deactivate rate card: {}", e),
+            code: Some("DATABASE_ERROR".to_string()),
+        })?;
+
+    Ok("Rate card deactivated".to_string())
+}
+
+async fn get_rate_card_items(conn: &mut SqliteConnection, rate_card_id: i64) -> ApiResult<Vec<RateCardItem>> {
+    sqlx::query_as::<_, RateCardItem>(
+        "SELECT * FROM rate_card_items WHERE rate_card_id = ? ORDER BY service_id, variant_id, addon_id"
+    )
+    .bind(rate_card_id)
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(|e| ApiError {
+        message: format!("Database error: {}", e),
+        code: Some("DATABASE_ERROR".to_string()),
+    })
+}
+
+fn validate_rate_card(request: &SaveRateCardRequest) -> ApiResult<()> {
+    if request.name.trim().is_empty() {
+        return Err(ApiError {
+            message: "Rate card name is required".to_string(),
+            code: Some("VALIDATION_ERROR".to_string()),
+        });
+    }
+
+    for item in &request.items {
+        let targets_service = item.service_id.is_some();
+        if targets_service == item.addon_id.is_some() || (item.variant_id.is_some() && !targets_service) {
+            return Err(ApiError {
+                message: "Each rate must target either a service (optionally a variant) or an addon".to_string(),
+                code: Some("VALIDATION_ERROR".to_string()),
+            });
+        }
+
+        match item.rate_type.as_str() {
+            "fixed" if item.value >= 0.0 => {}
+            "percent_off" if (0.0..=100.0).contains(&item.value) => {}
+            "fixed" | "percent_off" => {
+                return Err(ApiError {
+                    message: format!("Invalid {} value: {}", item.rate_type, item.value),
+                    code: Some("VALIDATION_ERROR".to_string()),
+                });
+            }
+            other => {
+                return Err(ApiError {
+                    message: format!("Unknown rate type: {}", other),
+                    code: Some("VALIDATION_ERROR".to_string()),
+                });
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,77 @@
+use crate::handlers::html_handler;
+use crate::models::{ApiError, ApiResult, ReceiptSettings, SaveReceiptSettingsRequest};
+use crate::services::{PdfGenerator, ReceiptPrinterService};
+use tauri::{AppHandle, State};
+
+#[tauri::command]
+pub async fn get_receipt_settings(
+    state: State<'_, crate::AppState>,
+    store_id: Option<i64>,
+) -> ApiResult<ReceiptSettings> {
+    ReceiptPrinterService::get_settings(&state.db, store_id).await.map_err(|e| ApiError {
+        message: format!("Failed to get receipt settings: {}", e),
+        code: Some("DATABASE_ERROR".to_string()),
+    })
+}
+
+#[tauri::command]
+pub async fn save_receipt_settings(
+    state: State<'_, crate::AppState>,
+    request: SaveReceiptSettingsRequest,
+) -> ApiResult<ReceiptSettings> {
+    ReceiptPrinterService::save_settings(&state.db, request).await.map_err(|e| ApiError {
+        message: format!("Failed to save receipt settings: {}", e),
+        code: Some("VALIDATION_ERROR".to_string()),
+    })
+}
+
+/// Print the invoice's receipt as ESC/POS on the store's receipt printer,
+/// or open the thermal HTML receipt when no receipt printer is configured
+#[tauri::command]
+pub async fn print_invoice_receipt(
+    app_handle: AppHandle,
+    state: State<'_, crate::AppState>,
+    invoice_id: i64,
+) -> ApiResult<()> {
+    match send_invoice_receipt(&app_handle, &state, invoice_id).await {
+        Err(e) if e.code.as_deref() == Some("NO_RECEIPT_PRINTER") => {
+            html_handler::save_and_open_invoice_html(app_handle, state, invoice_id, "thermal".to_string()).await?;
+            Ok(())
+        }
+        result => result,
+    }
+}
+
+/// Send the invoice's receipt to the store's receipt printer. Never opens
+/// anything on screen, so the print queue can use it unattended.
+pub(crate) async fn send_invoice_receipt(
+    app_handle: &AppHandle,
+    state: &crate::AppState,
+    invoice_id: i64,
+) -> ApiResult<()> {
+    let (html_data, settings) = html_handler::get_issued_invoice(app_handle, state, invoice_id, "thermal").await?;
+    let receipt = ReceiptPrinterService::get_settings(&state.db, Some(html_data.store.id))
+        .await
+        .map_err(|e| ApiError {
+            message: format!("Failed to get receipt settings: {}", e),
+            code: Some("DATABASE_ERROR".to_string()),
+        })?;
+
+    if receipt.printer_name.is_none() {
+        return Err(ApiError {
+            message: "No receipt printer configured".to_string(),
+            code: Some("NO_RECEIPT_PRINTER".to_string()),
+        });
+    }
+
+    let font = PdfGenerator::devanagari_font_for(&html_data, html_handler::get_font_dir(app_handle).as_deref());
+    let sent = ReceiptPrinterService::print_receipt(&receipt, &html_data, &settings, font.as_deref())
+        .await
+        .map_err(|e| ApiError {
+            message: format!("Failed to print receipt: {}", e),
+            code: Some("PRINT_ERROR".to_string()),
+        })?;
+
+    log::info!("Sent {} byte receipt for invoice {} to {:?}", sent, invoice_id, receipt.printer_name);
+    Ok(())
+}
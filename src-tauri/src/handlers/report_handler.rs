@@ -109,6 +109,37 @@ pub struct ServicePopularity {
     pub growth_percentage: f64,
 }
 
+#[derive(Debug, Serialize)]
+pub struct TagReprintReport {
+    pub total_print_jobs: i64,
+    pub reprint_jobs: i64,
+    pub tags_first_printed: i64,
+    pub tags_reprinted: i64,
+    pub by_operator: Vec<ReprintOperatorSummary>,
+    pub tags: Vec<ReprintedTag>,
+    pub period_start: String,
+    pub period_end: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReprintOperatorSummary {
+    pub printed_by: String,
+    pub reprint_jobs: i64,
+    pub tags_reprinted: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReprintedTag {
+    pub tag_id: i64,
+    pub tag_code: String,
+    pub invoice_no: String,
+    pub customer_name: String,
+    pub reprints: i64,
+    pub printed_by: Option<String>,
+    pub reasons: Option<String>,
+    pub last_reprinted_at: String,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct ReportDateRange {
     pub start_date: String,
@@ -610,4 +641,190 @@ pub async fn get_express_delivery_summary(
         "period_start": date_range.start_date,
         "period_end": date_range.end_date,
     }))
-}
\ No newline at end of file
+}
+
+/// Reprint frequency over a period, to spot tags being reprinted more than
+/// lost or damaged tags would explain. Only tags reprinted at least
+/// `min_reprints` times (default 1) are listed.
+#[tauri::command]
+pub async fn get_tag_reprint_report(
+    state: State<'_, crate::AppState>,
+    date_range: ReportDateRange,
+    min_reprints: Option<i64>,
+) -> ApiResult<TagReprintReport> {
+    tag_reprint_report(&state.db, date_range, min_reprints).await
+}
+
+async fn tag_reprint_report(
+    db: &DatabaseManager,
+    date_range: ReportDateRange,
+    min_reprints: Option<i64>,
+) -> ApiResult<TagReprintReport> {
+    let pool = db.get_pool();
+    let database_error = |e: sqlx::Error| ApiError {
+        message: format!("Database error: {}", e),
+        code: Some("DATABASE_ERROR".to_string()),
+    };
+
+    let totals_row = sqlx::query(
+        r#"
+        SELECT
+            COUNT(DISTINCT pj.id) as total_print_jobs,
+            COUNT(DISTINCT CASE WHEN pj.is_reprint = 1 THEN pj.id END) as reprint_jobs,
+            COALESCE(SUM(CASE WHEN pjt.is_reprint = 0 THEN 1 ELSE 0 END), 0) as tags_first_printed,
+            COALESCE(SUM(CASE WHEN pjt.is_reprint = 1 THEN 1 ELSE 0 END), 0) as tags_reprinted
+        FROM print_jobs pj
+        LEFT JOIN print_job_tags pjt ON pjt.print_job_id = pj.id
+        WHERE pj.status = 'printed'
+        AND DATE(pj.created_at) >= ? AND DATE(pj.created_at) <= ?
+        "#
+    )
+    .bind(&date_range.start_date)
+    .bind(&date_range.end_date)
+    .fetch_one(pool)
+    .await
+    .map_err(database_error)?;
+
+    let operator_rows = sqlx::query(
+        r#"
+        SELECT
+            COALESCE(pj.printed_by, 'unknown') as printed_by,
+            COUNT(DISTINCT pj.id) as reprint_jobs,
+            COUNT(*) as tags_reprinted
+        FROM print_jobs pj
+        JOIN print_job_tags pjt ON pjt.print_job_id = pj.id AND pjt.is_reprint = 1
+        WHERE pj.status = 'printed'
+        AND DATE(pj.created_at) >= ? AND DATE(pj.created_at) <= ?
+        GROUP BY COALESCE(pj.printed_by, 'unknown')
+        ORDER BY tags_reprinted DESC
+        "#
+    )
+    .bind(&date_range.start_date)
+    .bind(&date_range.end_date)
+    .fetch_all(pool)
+    .await
+    .map_err(database_error)?;
+
+    let tag_rows = sqlx::query(
+        r#"
+        SELECT
+            ct.id as tag_id,
+            ct.tag_code,
+            i.invoice_no,
+            c.name as customer_name,
+            COUNT(*) as reprints,
+            GROUP_CONCAT(DISTINCT pj.printed_by) as printed_by,
+            GROUP_CONCAT(DISTINCT pj.reprint_reason) as reasons,
+            MAX(pj.created_at) as last_reprinted_at
+        FROM print_job_tags pjt
+        JOIN print_jobs pj ON pj.id = pjt.print_job_id
+        JOIN clothing_tags ct ON ct.id = pjt.tag_id
+        JOIN invoices i ON i.id = ct.invoice_id
+        JOIN customers c ON c.id = i.customer_id
+        WHERE pjt.is_reprint = 1 AND pj.status = 'printed'
+        AND DATE(pj.created_at) >= ? AND DATE(pj.created_at) <= ?
+        GROUP BY ct.id
+        HAVING COUNT(*) >= ?
+        ORDER BY reprints DESC, last_reprinted_at DESC
+        LIMIT 200
+        "#
+    )
+    .bind(&date_range.start_date)
+    .bind(&date_range.end_date)
+    .bind(min_reprints.unwrap_or(1).max(1))
+    .fetch_all(pool)
+    .await
+    .map_err(database_error)?;
+
+    Ok(TagReprintReport {
+        total_print_jobs: totals_row.get("total_print_jobs"),
+        reprint_jobs: totals_row.get("reprint_jobs"),
+        tags_first_printed: totals_row.get("tags_first_printed"),
+        tags_reprinted: totals_row.get("tags_reprinted"),
+        by_operator: operator_rows.into_iter().map(|row| ReprintOperatorSummary {
+            printed_by: row.get("printed_by"),
+            reprint_jobs: row.get("reprint_jobs"),
+            tags_reprinted: row.get("tags_reprinted"),
+        }).collect(),
+        tags: tag_rows.into_iter().map(|row| ReprintedTag {
+            tag_id: row.get("tag_id"),
+            tag_code: row.get("tag_code"),
+            invoice_no: row.get("invoice_no"),
+            customer_name: row.get("customer_name"),
+            reprints: row.get("reprints"),
+            printed_by: row.get("printed_by"),
+            reasons: row.get("reasons"),
+            last_reprinted_at: row.get("last_reprinted_at"),
+        }).collect(),
+        period_start: date_range.start_date,
+        period_end: date_range.end_date,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::test_fixtures::{add_invoice_item, invoice_db};
+    use crate::models::{ClothingTag, CreatePrintJobRequest};
+    use crate::services::tag_generator::TagGeneratorService;
+
+    fn all_time() -> ReportDateRange {
+        ReportDateRange { start_date: "2000-01-01".to_string(), end_date: "2999-12-31".to_string() }
+    }
+
+    async fn print(db: &DatabaseManager, tags: &[ClothingTag], printed_by: &str, reason: Option<&str>) {
+        let request = CreatePrintJobRequest {
+            invoice_id: 900,
+            printed_by: Some(printed_by.to_string()),
+            printer_name: None,
+            printer_language: "zpl".to_string(),
+            reprint_reason: reason.map(str::to_string),
+            error_message: None,
+        };
+        let tags = TagGeneratorService::get_tags_for_printing(db, 900, None, Some(&tags.iter().map(|tag| tag.id).collect::<Vec<_>>()))
+            .await
+            .unwrap();
+        TagGeneratorService::record_print_job(db, &request, &tags).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_tag_reprint_report() {
+        let db = invoice_db("INV900", "Reprint Test").await;
+        add_invoice_item(&db, 3).await;
+        let tags = TagGeneratorService::generate_tags_for_invoice(&db, 900).await.unwrap();
+
+        let empty = tag_reprint_report(&db, all_time(), None).await.unwrap();
+        assert_eq!((empty.total_print_jobs, empty.tags_reprinted), (0, 0));
+        assert!(empty.tags.is_empty());
+
+        print(&db, &tags, "asha", None).await;
+        print(&db, &tags[..1], "ravi", Some("torn")).await;
+        print(&db, &tags[..2], "ravi", Some("lost")).await;
+
+        let report = tag_reprint_report(&db, all_time(), None).await.unwrap();
+        assert_eq!(report.total_print_jobs, 3);
+        assert_eq!(report.reprint_jobs, 2);
+        assert_eq!(report.tags_first_printed, 3);
+        assert_eq!(report.tags_reprinted, 3);
+
+        assert_eq!(report.by_operator.len(), 1);
+        assert_eq!(report.by_operator[0].printed_by, "ravi");
+        assert_eq!((report.by_operator[0].reprint_jobs, report.by_operator[0].tags_reprinted), (2, 3));
+
+        assert_eq!(report.tags.iter().map(|tag| (tag.tag_id, tag.reprints)).collect::<Vec<_>>(), vec![(tags[0].id, 2), (tags[1].id, 1)]);
+        assert_eq!(report.tags[0].invoice_no, "INV900");
+        assert_eq!(report.tags[0].customer_name, "Reprint Test");
+        let mut reasons: Vec<&str> = report.tags[0].reasons.as_deref().unwrap().split(',').collect();
+        reasons.sort();
+        assert_eq!(reasons, vec!["lost", "torn"]);
+
+        // Only tags reprinted often enough are listed; the totals still cover everything
+        let frequent = tag_reprint_report(&db, all_time(), Some(2)).await.unwrap();
+        assert_eq!(frequent.tags.len(), 1);
+        assert_eq!(frequent.tags_reprinted, 3);
+
+        let before = ReportDateRange { start_date: "2000-01-01".to_string(), end_date: "2000-12-31".to_string() };
+        let before = tag_reprint_report(&db, before, None).await.unwrap();
+        assert_eq!((before.total_print_jobs, before.tags.len()), (0, 0));
+    }
+}
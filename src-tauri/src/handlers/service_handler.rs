@@ -98,15 +98,19 @@ pub async fn create_service(
     // Create addons if provided
     if let Some(addons) = &request.addons {
         for addon in addons {
+            let pricing_mode = validate_addon_pricing(addon)?;
+
             sqlx::query(
-                "INSERT INTO service_addons (service_id, name, description, price, unit, is_active, created_at, updated_at)
-                 VALUES (?, ?, ?, ?, ?, ?, datetime('now'), datetime('now'))"
+                "INSERT INTO service_addons (service_id, name, description, price, unit, pricing_mode, gst_rate, is_active, created_at, updated_at)
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?, datetime('now'), datetime('now'))"
             )
             .bind(service_id)
             .bind(&addon.name)
             .bind(&addon.description)
             .bind(addon.price)
             .bind(&addon.unit)
+            .bind(pricing_mode)
+            .bind(addon.gst_rate)
             .bind(if addon.is_active.unwrap_or(true) { 1 } else { 0 })
             .execute(&mut *tx)
             .await
@@ -431,7 +435,7 @@ pub async fn update_service(
         code: Some("DATABASE_ERROR".to_string()),
     })?;
 
-    // Delete existing variants and addons
+    // Replace variants; addons are updated in place below
     sqlx::query("DELETE FROM service_variants WHERE service_id = ?")
         .bind(service_id)
         .execute(&mut *tx)
@@ -441,15 +445,6 @@ pub async fn update_service(
             code: Some("DATABASE_ERROR".to_string()),
         })?;
 
-    sqlx::query("DELETE FROM service_addons WHERE service_id = ?")
-        .bind(service_id)
-        .execute(&mut *tx)
-        .await
-        .map_err(|e| ApiError {
-            message: format!("Failed to delete existing addons: {}", e),
-            code: Some("DATABASE_ERROR".to_string()),
-        })?;
-
     // Create new variants if provided
     if let Some(variants) = &request.variants {
         for variant in variants {
@@ -471,25 +466,82 @@ pub async fn update_service(
         }
     }
 
-    // Create new addons if provided
+    // Addons keep their ids so invoice lines and links to other services
+    // still point at them. Owned addons dropped from the request are
+    // deactivated rather than deleted; shared addons are edited on their
+    // owning service.
     if let Some(addons) = &request.addons {
-        for addon in addons {
-            sqlx::query(
-                "INSERT INTO service_addons (service_id, name, description, price, unit, is_active, created_at, updated_at)
-                 VALUES (?, ?, ?, ?, ?, ?, datetime('now'), datetime('now'))"
-            )
+        let owned_ids: Vec<i64> = sqlx::query_scalar("SELECT id FROM service_addons WHERE service_id = ?")
             .bind(service_id)
-            .bind(&addon.name)
-            .bind(&addon.description)
-            .bind(addon.price)
-            .bind(&addon.unit)
-            .bind(if addon.is_active.unwrap_or(true) { 1 } else { 0 })
-            .execute(&mut *tx)
+            .fetch_all(&mut *tx)
             .await
             .map_err(|e| ApiError {
-                message: format!("Failed to create service addon: {}", e),
+                message: format!("Failed to get existing addons: {}", e),
                 code: Some("DATABASE_ERROR".to_string()),
             })?;
+        let mut kept_ids = Vec::new();
+
+        for addon in addons {
+            let pricing_mode = validate_addon_pricing(addon)?;
+            let is_active = if addon.is_active.unwrap_or(true) { 1 } else { 0 };
+
+            match addon.id {
+                Some(addon_id) if owned_ids.contains(&addon_id) => {
+                    sqlx::query(
+                        "UPDATE service_addons
+                         SET name = ?, description = ?, price = ?, unit = ?, pricing_mode = ?, gst_rate = ?,
+                             is_active = ?, updated_at = datetime('now')
+                         WHERE id = ?"
+                    )
+                    .bind(&addon.name)
+                    .bind(&addon.description)
+                    .bind(addon.price)
+                    .bind(&addon.unit)
+                    .bind(pricing_mode)
+                    .bind(addon.gst_rate)
+                    .bind(is_active)
+                    .bind(addon_id)
+                    .execute(&mut *tx)
+                    .await
+                    .map_err(|e| ApiError {
+                        message: format!("Failed to update service addon: {}", e),
+                        code: Some("DATABASE_ERROR".to_string()),
+                    })?;
+                    kept_ids.push(addon_id);
+                }
+                Some(_) => {}
+                None => {
+                    sqlx::query(
+                        "INSERT INTO service_addons (service_id, name, description, price, unit, pricing_mode, gst_rate, is_active, created_at, updated_at)
+                         VALUES (?, ?, ?, ?, ?, ?, ?, ?, datetime('now'), datetime('now'))"
+                    )
+                    .bind(service_id)
+                    .bind(&addon.name)
+                    .bind(&addon.description)
+                    .bind(addon.price)
+                    .bind(&addon.unit)
+                    .bind(pricing_mode)
+                    .bind(addon.gst_rate)
+                    .bind(is_active)
+                    .execute(&mut *tx)
+                    .await
+                    .map_err(|e| ApiError {
+                        message: format!("Failed to create service addon: {}", e),
+                        code: Some("DATABASE_ERROR".to_string()),
+                    })?;
+                }
+            }
+        }
+
+        for addon_id in owned_ids.iter().filter(|id| !kept_ids.contains(id)) {
+            sqlx::query("UPDATE service_addons SET is_active = 0, updated_at = datetime('now') WHERE id = ?")
+                .bind(addon_id)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| ApiError {
+                    message: format!("Failed to deactivate service addon: {}", e),
+                    code: Some("DATABASE_ERROR".to_string()),
+                })?;
         }
     }
 
@@ -565,7 +617,11 @@ pub async fn get_service_addons(
     let pool = state.db.get_pool_cloned();
 
     let addons = sqlx::query_as::<_, ServiceAddon>(
-        "SELECT * FROM service_addons WHERE service_id = ? ORDER BY name ASC"
+        r#"
+        SELECT * FROM service_addons
+        WHERE service_id = ?1 OR id IN (SELECT addon_id FROM service_addon_links WHERE service_id = ?1)
+        ORDER BY name ASC
+        "#
     )
     .bind(service_id)
     .fetch_all(&pool)
@@ -578,6 +634,71 @@ pub async fn get_service_addons(
     Ok(addons)
 }
 
+/// Share an addon with a service other than the one that owns it
+#[tauri::command]
+pub async fn link_addon_to_service(
+    state: State<'_, crate::AppState>,
+    addon_id: i64,
+    service_id: i64,
+) -> ApiResult<String> {
+    let pool = state.db.get_pool_cloned();
+
+    let owner_id: Option<i64> = sqlx::query_scalar("SELECT service_id FROM service_addons WHERE id = ?")
+        .bind(addon_id)
+        .fetch_optional(&pool)
+        .await
+        .map_err(|e| ApiError {
+            message: format!("Database error: {}", e),
+            code: Some("DATABASE_ERROR".to_string()),
+        })?;
+
+    match owner_id {
+        None => {
+            return Err(ApiError {
+                message: "Addon not found".to_string(),
+                code: Some("ADDON_NOT_FOUND".to_string()),
+            });
+        }
+        Some(owner_id) if owner_id == service_id => {
+            return Ok("Addon already belongs to this service".to_string());
+        }
+        Some(_) => {}
+    }
+
+    sqlx::query("INSERT OR IGNORE INTO service_addon_links (addon_id, service_id) VALUES (?, ?)")
+        .bind(addon_id)
+        .bind(service_id)
+        .execute(&pool)
+        .await
+        .map_err(|e| ApiError {
+            message: format!("Failed to link addon: {}", e),
+            code: Some("DATABASE_ERROR".to_string()),
+        })?;
+
+    Ok("Addon linked to service".to_string())
+}
+
+#[tauri::command]
+pub async fn unlink_addon_from_service(
+    state: State<'_, crate::AppState>,
+    addon_id: i64,
+    service_id: i64,
+) -> ApiResult<String> {
+    let pool = state.db.get_pool_cloned();
+
+    sqlx::query("DELETE FROM service_addon_links WHERE addon_id = ? AND service_id = ?")
+        .bind(addon_id)
+        .bind(service_id)
+        .execute(&pool)
+        .await
+        .map_err(|e| ApiError {
+            message: format!("Failed to unlink addon: {}", e),
+            code: Some("DATABASE_ERROR".to_string()),
+        })?;
+
+    Ok("Addon unlinked from service".to_string())
+}
+
 #[tauri::command]
 pub async fn get_services_by_category(
     state: State<'_, crate::AppState>,
@@ -636,4 +757,34 @@ pub async fn get_service_categories(
     })?;
 
     Ok(categories)
-}
\ No newline at end of file
+}
+
+/// Validate an addon's pricing mode and GST rate, returning the mode to store
+fn validate_addon_pricing(addon: &CreateServiceAddonRequest) -> ApiResult<&str> {
+    let pricing_mode = addon.pricing_mode.as_deref().unwrap_or("per_unit");
+
+    if !matches!(pricing_mode, "per_unit" | "percent_of_line" | "per_order" | "per_piece") {
+        return Err(ApiError {
+            message: format!("Unknown addon pricing mode: {}", pricing_mode),
+            code: Some("VALIDATION_ERROR".to_string()),
+        });
+    }
+
+    if pricing_mode == "percent_of_line" && !(0.0..=100.0).contains(&addon.price) {
+        return Err(ApiError {
+            message: "Percentage addons must be between 0 and 100".to_string(),
+            code: Some("VALIDATION_ERROR".to_string()),
+        });
+    }
+
+    if let Some(gst_rate) = addon.gst_rate {
+        if !(0.0..=100.0).contains(&gst_rate) {
+            return Err(ApiError {
+                message: "Addon GST rate must be between 0% and 100%".to_string(),
+                code: Some("VALIDATION_ERROR".to_string()),
+            });
+        }
+    }
+
+    Ok(pricing_mode)
+}
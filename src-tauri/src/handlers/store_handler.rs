@@ -1,7 +1,12 @@
 use crate::database::DatabaseManager;
-use crate::models::{Store, CreateStoreRequest, UpdateStoreRequest, StoreWithStats, ApiResult, ApiError};
+use crate::models::{
+    Store, CreateStoreRequest, UpdateStoreRequest, StoreWithStats, StoreBranding, SaveStoreBrandingRequest,
+    TermsCondition, SaveTermsRequest, ApiResult, ApiError,
+};
+use crate::services::{Locale, StoreBrandingService};
 use sqlx::Row;
-use tauri::State;
+use std::path::Path;
+use tauri::{AppHandle, State};
 
 #[tauri::command]
 pub async fn create_store(
@@ -46,11 +51,14 @@ pub async fn create_store(
         }
     }
 
+    let locale = Locale::validate(request.locale.as_deref())?;
+    let secondary_locale = Locale::validate(request.secondary_locale.as_deref())?;
+
     // Insert new store
     let result = sqlx::query(
-        "INSERT INTO stores (name, address, city, state, pincode, phone, email, gstin, pan_number, owner_name, is_active, created_at, updated_at)
-         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, datetime('now'), datetime('now'))
-         RETURNING id, name, address, city, state, pincode, phone, email, gstin, pan_number, owner_name, is_active, created_at, updated_at"
+        "INSERT INTO stores (name, address, city, state, pincode, phone, email, gstin, pan_number, owner_name, locale, secondary_locale, is_active, created_at, updated_at)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, datetime('now'), datetime('now'))
+         RETURNING id, name, address, city, state, pincode, phone, email, gstin, pan_number, owner_name, locale, secondary_locale, is_active, created_at, updated_at"
     )
     .bind(&request.name)
     .bind(&request.address)
@@ -62,6 +70,8 @@ pub async fn create_store(
     .bind(&request.gstin)
     .bind(&request.pan_number)
     .bind(&request.owner_name)
+    .bind(&locale)
+    .bind(&secondary_locale)
     .bind(if request.is_active.unwrap_or(true) { 1 } else { 0 })
     .fetch_one(&pool)
     .await
@@ -82,6 +92,8 @@ pub async fn create_store(
         gstin: result.get("gstin"),
         pan_number: result.get("pan_number"),
         owner_name: result.get("owner_name"),
+        locale: result.get("locale"),
+        secondary_locale: result.get("secondary_locale"),
         is_active: result.get("is_active"),
         created_at: result.get("created_at"),
         updated_at: result.get("updated_at"),
@@ -206,13 +218,16 @@ pub async fn update_store(
         }
     }
 
+    let locale = Locale::validate(request.locale.as_deref())?;
+    let secondary_locale = Locale::validate(request.secondary_locale.as_deref())?;
+
     // Update store
     let result = sqlx::query(
         "UPDATE stores
          SET name = ?, address = ?, city = ?, state = ?, pincode = ?, phone = ?, email = ?,
-             gstin = ?, pan_number = ?, owner_name = ?, is_active = ?, updated_at = datetime('now')
+             gstin = ?, pan_number = ?, owner_name = ?, locale = ?, secondary_locale = ?, is_active = ?, updated_at = datetime('now')
          WHERE id = ?
-         RETURNING id, name, address, city, state, pincode, phone, email, gstin, pan_number, owner_name, is_active, created_at, updated_at"
+         RETURNING id, name, address, city, state, pincode, phone, email, gstin, pan_number, owner_name, locale, secondary_locale, is_active, created_at, updated_at"
     )
     .bind(&request.name)
     .bind(&request.address)
@@ -224,6 +239,8 @@ pub async fn update_store(
     .bind(&request.gstin)
     .bind(&request.pan_number)
     .bind(&request.owner_name)
+    .bind(&locale)
+    .bind(&secondary_locale)
     .bind(if request.is_active.unwrap_or(true) { 1 } else { 0 })
     .bind(store_id)
     .fetch_one(&pool)
@@ -245,6 +262,8 @@ pub async fn update_store(
         gstin: result.get("gstin"),
         pan_number: result.get("pan_number"),
         owner_name: result.get("owner_name"),
+        locale: result.get("locale"),
+        secondary_locale: result.get("secondary_locale"),
         is_active: result.get("is_active"),
         created_at: result.get("created_at"),
         updated_at: result.get("updated_at"),
@@ -347,4 +366,105 @@ pub async fn get_active_stores(
     })?;
 
     Ok(stores)
-}
\ No newline at end of file
+}
+
+/// Saved branding for a store; None until the store saves its own
+#[tauri::command]
+pub async fn get_store_branding(
+    state: State<'_, crate::AppState>,
+    store_id: i64,
+) -> ApiResult<Option<StoreBranding>> {
+    StoreBrandingService::get(&state.db, store_id).await
+}
+
+#[tauri::command]
+pub async fn save_store_branding(
+    state: State<'_, crate::AppState>,
+    store_id: i64,
+    request: SaveStoreBrandingRequest,
+) -> ApiResult<StoreBranding> {
+    StoreBrandingService::save(&state.db, store_id, request).await
+}
+
+/// Copy a logo image into the app data folder and use it on the store's
+/// invoices
+#[tauri::command]
+pub async fn set_store_logo(
+    app_handle: AppHandle,
+    state: State<'_, crate::AppState>,
+    store_id: i64,
+    source_path: String,
+) -> ApiResult<StoreBranding> {
+    let source = Path::new(&source_path);
+    // Checks the type and size before anything is copied
+    StoreBrandingService::logo_data_uri(source)?;
+
+    let branding_dir = app_handle
+        .path_resolver()
+        .app_data_dir()
+        .ok_or_else(|| ApiError {
+            message: "Failed to get app data directory".to_string(),
+            code: Some("APP_DATA_DIR_ERROR".to_string()),
+        })?
+        .join("UCLEAN")
+        .join("Branding");
+    std::fs::create_dir_all(&branding_dir).map_err(|e| ApiError {
+        message: format!("Failed to create branding directory: {}", e),
+        code: Some("DIRECTORY_CREATE_ERROR".to_string()),
+    })?;
+
+    let extension = source.extension().and_then(|ext| ext.to_str()).unwrap_or("png").to_ascii_lowercase();
+    let logo_path = branding_dir.join(format!("store_{}_logo.{}", store_id, extension));
+    std::fs::copy(source, &logo_path).map_err(|e| ApiError {
+        message: format!("Failed to copy logo: {}", e),
+        code: Some("FILE_COPY_ERROR".to_string()),
+    })?;
+
+    StoreBrandingService::set_logo(&state.db, store_id, Some(&logo_path.to_string_lossy())).await
+}
+
+#[tauri::command]
+pub async fn remove_store_logo(
+    state: State<'_, crate::AppState>,
+    store_id: i64,
+) -> ApiResult<StoreBranding> {
+    if let Some(logo_path) = StoreBrandingService::get(&state.db, store_id).await?.and_then(|branding| branding.logo_path) {
+        let _ = std::fs::remove_file(logo_path);
+    }
+    StoreBrandingService::set_logo(&state.db, store_id, None).await
+}
+
+/// All of a store's terms, active or not
+#[tauri::command]
+pub async fn get_store_terms(
+    state: State<'_, crate::AppState>,
+    store_id: i64,
+) -> ApiResult<Vec<TermsCondition>> {
+    StoreBrandingService::list_terms(&state.db, store_id).await
+}
+
+#[tauri::command]
+pub async fn create_store_terms(
+    state: State<'_, crate::AppState>,
+    store_id: i64,
+    request: SaveTermsRequest,
+) -> ApiResult<TermsCondition> {
+    StoreBrandingService::create_terms(&state.db, store_id, request).await
+}
+
+#[tauri::command]
+pub async fn update_store_terms(
+    state: State<'_, crate::AppState>,
+    terms_id: i64,
+    request: SaveTermsRequest,
+) -> ApiResult<TermsCondition> {
+    StoreBrandingService::update_terms(&state.db, terms_id, request).await
+}
+
+#[tauri::command]
+pub async fn delete_store_terms(
+    state: State<'_, crate::AppState>,
+    terms_id: i64,
+) -> ApiResult<()> {
+    StoreBrandingService::delete_terms(&state.db, terms_id).await
+}
@@ -1,9 +1,14 @@
 use crate::database::DatabaseManager;
 use crate::models::{
     ClothingTag, TagSettings, CreateTagSettingsRequest, UpdateTagSettingsRequest,
-    TagPrintRequest, TagPrintResponse, InvoiceTagSummary, TagData
+    TagPrintRequest, TagPrintResponse, InvoiceTagSummary, TagData,
+    ScanTagRequest, TagScanResult, TagScanEvent, TagLookupResult, TagReconciliation,
+    UpdateTagAttributesRequest, TagTemplate, SaveTagTemplateRequest, PrintJob, CreatePrintJobRequest
+};
+use crate::services::{
+    DocumentLanguage, DocumentPrinterService, GarmentTrackingService, LabelPrinterService, PrinterLanguage,
+    TagGeneratorService, TemplateEngine,
 };
-use crate::services::{TagGeneratorService, TemplateEngine};
 use tauri::{State, AppHandle, Manager};
 use anyhow::{Result, Context};
 use sqlx::Row;
@@ -24,41 +29,69 @@ pub async fn generate_invoice_tags(
         })
 }
 
+#[tauri::command]
+pub async fn reconcile_invoice_tags(
+    app_handle: AppHandle,
+    invoice_id: i64,
+) -> Result<TagReconciliation, String> {
+    let state = app_handle.state::<crate::AppState>();
+
+    TagGeneratorService::reconcile_tags_for_invoice(&state.db, invoice_id)
+        .await
+        .map_err(|e| {
+            log::error!("Failed to reconcile tags for invoice {}: {}", invoice_id, e);
+            format!("Failed to reconcile tags: {}", e)
+        })
+}
+
 #[tauri::command]
 pub async fn print_invoice_tags(
     app_handle: AppHandle,
     request: TagPrintRequest,
+) -> Result<TagPrintResponse, String> {
+    print_tags_for_invoice(&app_handle, request, false).await
+}
+
+/// Print the selected tags of an invoice and log the print job. Used by the
+/// print command and the print queue worker. Unattended prints go only to a
+/// label printer; HTML tags would open a browser nobody is watching.
+pub async fn print_tags_for_invoice(
+    app_handle: &AppHandle,
+    request: TagPrintRequest,
+    unattended: bool,
 ) -> Result<TagPrintResponse, String> {
     let state = app_handle.state::<crate::AppState>();
 
-    // Get tag data for printing
-    let mut tag_data = TagGeneratorService::get_tag_data_for_printing(
+    // Get the tags selected for printing
+    let mut tags = TagGeneratorService::get_tags_for_printing(
         &state.db,
         request.invoice_id,
-        request.item_ids.clone(),
+        request.item_ids.as_deref(),
+        request.tag_ids.as_deref(),
     )
     .await
     .map_err(|e| {
-        log::error!("Failed to get tag data for invoice {}: {}", request.invoice_id, e);
-        format!("Failed to get tag data: {}", e)
+        log::error!("Failed to get tags for invoice {}: {}", request.invoice_id, e);
+        format!("Failed to get tags: {}", e)
     })?;
 
     // If no tags exist, try to generate them first
-    if tag_data.is_empty() {
+    if tags.is_empty() && request.tag_ids.is_none() {
         log::info!("No tags found for invoice {}, attempting to generate them", request.invoice_id);
 
         match TagGeneratorService::generate_tags_for_invoice(&state.db, request.invoice_id).await {
             Ok(_) => {
-                // Try to get tag data again after generation
-                tag_data = TagGeneratorService::get_tag_data_for_printing(
+                // Try to get the tags again after generation
+                tags = TagGeneratorService::get_tags_for_printing(
                     &state.db,
                     request.invoice_id,
-                    request.item_ids.clone(),
+                    request.item_ids.as_deref(),
+                    None,
                 )
                 .await
                 .map_err(|e| {
-                    log::error!("Failed to get tag data after generation for invoice {}: {}", request.invoice_id, e);
-                    format!("Failed to get tag data after generation: {}", e)
+                    log::error!("Failed to get tags after generation for invoice {}: {}", request.invoice_id, e);
+                    format!("Failed to get tags after generation: {}", e)
                 })?;
             },
             Err(e) => {
@@ -67,65 +100,139 @@ pub async fn print_invoice_tags(
                     success: false,
                     message: format!("No tags exist and generation failed: {}", e),
                     tags_printed: 0,
+                    print_job_id: None,
                 });
             }
         }
     }
 
     // If still no tags after generation attempt
-    if tag_data.is_empty() {
+    if tags.is_empty() {
         return Ok(TagPrintResponse {
             success: false,
             message: "No tags found for printing and none could be generated".to_string(),
             tags_printed: 0,
+            print_job_id: None,
         });
     }
 
-    // Get tag settings
-    let settings = get_tag_settings_internal(&state.db, None).await.map_err(|e| {
-        log::error!("Failed to get tag settings: {}", e);
-        format!("Failed to get tag settings: {}", e)
-    })?;
+    // Tags that were printed before need a reason so reprints can be audited
+    let reprint_reason = request
+        .reprint_reason
+        .as_deref()
+        .map(str::trim)
+        .filter(|reason| !reason.is_empty())
+        .map(str::to_string);
+    let reprints = tags.iter().filter(|tag| tag.printed_at.is_some()).count();
+    if reprints > 0 && reprint_reason.is_none() {
+        return Ok(TagPrintResponse {
+            success: false,
+            message: format!("{} of these tags were printed before; give a reason to reprint them", reprints),
+            tags_printed: 0,
+            print_job_id: None,
+        });
+    }
 
-    // Generate HTML for printing
-    let html_content = generate_tags_html(&tag_data, &settings.roll_width).await.map_err(|e| {
-        log::error!("Failed to generate tags HTML: {}", e);
-        format!("Failed to generate tags HTML: {}", e)
+    let tag_ids: Vec<i64> = tags.iter().map(|tag| tag.id).collect();
+    let tag_data = TagGeneratorService::get_tag_data_for_printing(
+        &state.db,
+        request.invoice_id,
+        None,
+        Some(tag_ids),
+    )
+    .await
+    .map_err(|e| {
+        log::error!("Failed to get tag data for invoice {}: {}", request.invoice_id, e);
+        format!("Failed to get tag data: {}", e)
     })?;
 
-    // Print the tags (using the existing HTML print mechanism)
-    match print_tags_html(&app_handle, &html_content).await {
-        Ok(_) => {
-            // Mark tags as printed
-            let printed_count = TagGeneratorService::mark_tags_as_printed(
-                &state.db,
-                request.invoice_id,
-                request.item_ids,
-                Some("system".to_string()),
-            )
-            .await
-            .map_err(|e| {
-                log::error!("Failed to mark tags as printed: {}", e);
-                format!("Failed to mark tags as printed: {}", e)
+    // Get the tag settings of the invoice's store
+    let store_id = get_invoice_store_id(&state.db, request.invoice_id).await.map_err(|e| {
+        log::error!("Failed to get store for invoice {}: {}", request.invoice_id, e);
+        format!("Failed to get invoice store: {}", e)
+    })?;
+    let settings = TagGeneratorService::get_tag_settings(&state.db, Some(store_id)).await.map_err(|e| {
+        log::error!("Failed to get tag settings: {}", e);
+        format!("Failed to get tag settings: {}", e)
+    })?;
+    let language = PrinterLanguage::parse(&settings.printer_language);
+    if unattended && matches!(language, Some(PrinterLanguage::Html) | None) {
+        return Ok(TagPrintResponse {
+            success: false,
+            message: "No label printer configured".to_string(),
+            tags_printed: 0,
+            print_job_id: None,
+        });
+    }
+    let print_result = match language {
+        Some(PrinterLanguage::Html) | None => {
+            let html_content = generate_tags_html(app_handle, request.invoice_id, Some(store_id), &tag_data, &settings).await.map_err(|e| {
+                log::error!("Failed to generate tags HTML: {}", e);
+                format!("Failed to generate tags HTML: {}", e)
             })?;
 
-            Ok(TagPrintResponse {
-                success: true,
-                message: format!("Successfully printed {} tags", printed_count),
-                tags_printed: printed_count,
-            })
+            print_tags_html(app_handle, &html_content).await
         }
+        // Thermal label printers take the raw command stream directly
+        Some(language) => LabelPrinterService::print_tags(&settings, language, &tag_data)
+            .await
+            .map(|_| ()),
+    };
+
+    // Log the job whether or not the printer took it
+    let job_request = CreatePrintJobRequest {
+        invoice_id: request.invoice_id,
+        printed_by: request.printed_by.clone(),
+        printer_name: settings.printer_name.clone(),
+        printer_language: settings.printer_language.clone(),
+        reprint_reason,
+        error_message: print_result.as_ref().err().map(|e| e.to_string()),
+    };
+    let job = TagGeneratorService::record_print_job(&state.db, &job_request, &tags)
+        .await
+        .map_err(|e| {
+            log::error!("Failed to record print job: {}", e);
+            format!("Failed to record print job: {}", e)
+        })?;
+
+    match print_result {
+        Ok(_) => Ok(TagPrintResponse {
+            success: true,
+            message: if reprints > 0 {
+                format!("Printed {} tags ({} reprinted)", tags.len(), reprints)
+            } else {
+                format!("Successfully printed {} tags", tags.len())
+            },
+            tags_printed: tags.len() as i64,
+            print_job_id: Some(job.id),
+        }),
         Err(e) => {
             log::error!("Failed to print tags: {}", e);
             Ok(TagPrintResponse {
                 success: false,
                 message: format!("Failed to print tags: {}", e),
                 tags_printed: 0,
+                print_job_id: Some(job.id),
             })
         }
     }
 }
 
+#[tauri::command]
+pub async fn get_tag_print_jobs(
+    app_handle: AppHandle,
+    invoice_id: i64,
+) -> Result<Vec<PrintJob>, String> {
+    let state = app_handle.state::<crate::AppState>();
+
+    TagGeneratorService::get_print_jobs(&state.db, invoice_id)
+        .await
+        .map_err(|e| {
+            log::error!("Failed to get print jobs for invoice {}: {}", invoice_id, e);
+            format!("Failed to get print jobs: {}", e)
+        })
+}
+
 #[tauri::command]
 pub async fn get_invoice_tags(
     app_handle: AppHandle,
@@ -156,6 +263,90 @@ pub async fn get_invoice_tag_summary(
         })
 }
 
+/// Find garments by a scanned or typed tag code or invoice number
+#[tauri::command]
+pub async fn lookup_tag(
+    app_handle: AppHandle,
+    query: String,
+) -> Result<Vec<TagLookupResult>, String> {
+    let state = app_handle.state::<crate::AppState>();
+
+    GarmentTrackingService::lookup_tags(&state.db, &query)
+        .await
+        .map_err(|e| {
+            log::error!("Failed to look up tag {}: {}", query, e);
+            format!("Failed to look up tag: {}", e)
+        })
+}
+
+#[tauri::command]
+pub async fn search_tags(
+    app_handle: AppHandle,
+    query: String,
+) -> Result<Vec<TagLookupResult>, String> {
+    let state = app_handle.state::<crate::AppState>();
+
+    GarmentTrackingService::search_tags(&state.db, &query)
+        .await
+        .map_err(|e| {
+            log::error!("Failed to search tags for {}: {}", query, e);
+            format!("Failed to search tags: {}", e)
+        })
+}
+
+#[tauri::command]
+pub async fn update_tag_attributes(
+    app_handle: AppHandle,
+    request: UpdateTagAttributesRequest,
+) -> Result<ClothingTag, String> {
+    let state = app_handle.state::<crate::AppState>();
+
+    GarmentTrackingService::update_tag_attributes(&state.db, &request)
+        .await
+        .map_err(|e| {
+            log::error!("Failed to update attributes of tag {}: {}", request.tag_id, e);
+            format!("Failed to update tag attributes: {}", e)
+        })
+}
+
+/// Record a scan of a garment's tag, advancing it to the next stage or to
+/// the stage given in the request
+#[tauri::command]
+pub async fn scan_tag(
+    app_handle: AppHandle,
+    request: ScanTagRequest,
+) -> Result<TagScanResult, String> {
+    let state = app_handle.state::<crate::AppState>();
+
+    GarmentTrackingService::scan_tag(
+        &state.db,
+        &request.tag_code,
+        request.stage.as_deref(),
+        request.scanned_by.as_deref(),
+        request.notes.as_deref(),
+    )
+    .await
+    .map_err(|e| {
+        log::error!("Failed to scan tag {}: {}", request.tag_code, e);
+        format!("Failed to scan tag: {}", e)
+    })
+}
+
+#[tauri::command]
+pub async fn get_tag_scan_history(
+    app_handle: AppHandle,
+    tag_id: i64,
+) -> Result<Vec<TagScanEvent>, String> {
+    let state = app_handle.state::<crate::AppState>();
+
+    GarmentTrackingService::get_scan_history(&state.db, tag_id)
+        .await
+        .map_err(|e| {
+            log::error!("Failed to get scan history for tag {}: {}", tag_id, e);
+            format!("Failed to get scan history: {}", e)
+        })
+}
+
 #[tauri::command]
 pub async fn get_tag_settings(
     app_handle: AppHandle,
@@ -163,7 +354,7 @@ pub async fn get_tag_settings(
 ) -> Result<TagSettings, String> {
     let state = app_handle.state::<crate::AppState>();
 
-    get_tag_settings_internal(&state.db, store_id)
+    TagGeneratorService::get_tag_settings(&state.db, store_id)
         .await
         .map_err(|e| {
             log::error!("Failed to get tag settings: {}", e);
@@ -175,10 +366,28 @@ pub async fn get_tag_settings(
 pub async fn save_tag_settings(
     app_handle: AppHandle,
     store_id: Option<i64>,
-    request: UpdateTagSettingsRequest,
+    mut request: UpdateTagSettingsRequest,
 ) -> Result<TagSettings, String> {
     let state = app_handle.state::<crate::AppState>();
 
+    if let Some(language) = &request.printer_language {
+        let parsed = PrinterLanguage::parse(language)
+            .ok_or_else(|| format!("Unsupported printer language: {}", language))?;
+        request.printer_language = Some(parsed.as_str().to_string());
+    }
+    if !matches!(request.template_style.as_str(), "standard" | "compact") {
+        return Err(format!("Unsupported template style: {}", request.template_style));
+    }
+    if let Some(font_scale) = request.font_scale {
+        if !(TagSettings::MIN_FONT_SCALE..=TagSettings::MAX_FONT_SCALE).contains(&font_scale) {
+            return Err(format!(
+                "Font scale must be between {} and {}",
+                TagSettings::MIN_FONT_SCALE,
+                TagSettings::MAX_FONT_SCALE
+            ));
+        }
+    }
+
     // Check if settings actually exist in the database
     let settings_exist = check_tag_settings_exist(&state.db, store_id).await.map_err(|e| {
         log::error!("Failed to check tag settings existence: {}", e);
@@ -187,7 +396,7 @@ pub async fn save_tag_settings(
 
     if settings_exist {
         // Get existing settings to get the ID for update
-        let existing = get_tag_settings_internal(&state.db, store_id).await.map_err(|e| {
+        let existing = TagGeneratorService::get_tag_settings(&state.db, store_id).await.map_err(|e| {
             log::error!("Failed to get existing tag settings: {}", e);
             format!("Failed to get existing tag settings: {}", e)
         })?;
@@ -210,7 +419,7 @@ pub async fn save_tag_settings(
     }
 
     // Return updated settings
-    get_tag_settings_internal(&state.db, store_id)
+    TagGeneratorService::get_tag_settings(&state.db, store_id)
         .await
         .map_err(|e| {
             log::error!("Failed to get updated tag settings: {}", e);
@@ -231,6 +440,7 @@ pub async fn get_tag_preview(
         &state.db,
         invoice_id,
         None,
+        None,
     )
     .await
     .map_err(|e| {
@@ -238,9 +448,19 @@ pub async fn get_tag_preview(
         format!("Failed to get tag data: {}", e)
     })?;
 
-    let width = roll_width.unwrap_or_else(|| "40mm".to_string());
+    let store_id = get_invoice_store_id(&state.db, invoice_id).await.map_err(|e| {
+        log::error!("Failed to get store for invoice {}: {}", invoice_id, e);
+        format!("Failed to get invoice store: {}", e)
+    })?;
+    let mut settings = TagGeneratorService::get_tag_settings(&state.db, Some(store_id)).await.map_err(|e| {
+        log::error!("Failed to get tag settings: {}", e);
+        format!("Failed to get tag settings: {}", e)
+    })?;
+    if let Some(width) = roll_width {
+        settings.roll_width = width;
+    }
 
-    generate_tags_html(&tag_data, &width)
+    generate_tags_html(&app_handle, invoice_id, Some(store_id), &tag_data, &settings)
         .await
         .map_err(|e| {
             log::error!("Failed to generate preview HTML: {}", e);
@@ -248,6 +468,78 @@ pub async fn get_tag_preview(
         })
 }
 
+#[tauri::command]
+pub async fn get_tag_template(
+    app_handle: AppHandle,
+    store_id: Option<i64>,
+    roll_width: String,
+) -> Result<TagTemplate, String> {
+    let builtin = TemplateEngine::builtin_tag_template(&roll_width).map_err(|e| e.message)?;
+
+    let custom = load_custom_tag_template(&app_handle, store_id, &roll_width)
+        .await
+        .map_err(|e| {
+            log::error!("Failed to load {} tag template: {}", roll_width, e);
+            format!("Failed to load tag template: {}", e)
+        })?;
+
+    Ok(TagTemplate {
+        store_id,
+        roll_width,
+        is_custom: custom.is_some(),
+        content: custom.unwrap_or_else(|| builtin.to_string()),
+    })
+}
+
+#[tauri::command]
+pub async fn save_tag_template(
+    app_handle: AppHandle,
+    request: SaveTagTemplateRequest,
+) -> Result<TagTemplate, String> {
+    // Refuse templates that would fail at print time
+    let engine = TemplateEngine::new().map_err(|e| e.message)?;
+    engine
+        .validate_tag_template(&request.content, &request.roll_width)
+        .map_err(|e| e.message)?;
+
+    let path = tag_template_path(&app_handle, request.store_id, &request.roll_width).map_err(|e| e.to_string())?;
+    if let Some(dir) = path.parent() {
+        tokio::fs::create_dir_all(dir).await.map_err(|e| {
+            log::error!("Failed to create tag template directory: {}", e);
+            format!("Failed to save tag template: {}", e)
+        })?;
+    }
+    tokio::fs::write(&path, &request.content).await.map_err(|e| {
+        log::error!("Failed to write tag template {}: {}", path.display(), e);
+        format!("Failed to save tag template: {}", e)
+    })?;
+
+    Ok(TagTemplate {
+        store_id: request.store_id,
+        roll_width: request.roll_width,
+        content: request.content,
+        is_custom: true,
+    })
+}
+
+/// Drop a customised template so the shared or built-in one applies again
+#[tauri::command]
+pub async fn reset_tag_template(
+    app_handle: AppHandle,
+    store_id: Option<i64>,
+    roll_width: String,
+) -> Result<TagTemplate, String> {
+    let path = tag_template_path(&app_handle, store_id, &roll_width).map_err(|e| e.to_string())?;
+    if path.exists() {
+        tokio::fs::remove_file(&path).await.map_err(|e| {
+            log::error!("Failed to remove tag template {}: {}", path.display(), e);
+            format!("Failed to reset tag template: {}", e)
+        })?;
+    }
+
+    get_tag_template(app_handle, store_id, roll_width).await
+}
+
 // Internal helper functions
 
 async fn check_tag_settings_exist(
@@ -278,51 +570,6 @@ async fn check_tag_settings_exist(
     }
 }
 
-async fn get_tag_settings_internal(
-    db: &DatabaseManager,
-    store_id: Option<i64>,
-) -> Result<TagSettings> {
-    let query = if let Some(store_id) = store_id {
-        "SELECT * FROM tag_settings WHERE store_id = ? OR store_id IS NULL ORDER BY store_id DESC LIMIT 1"
-    } else {
-        "SELECT * FROM tag_settings WHERE store_id IS NULL LIMIT 1"
-    };
-
-    let result = if let Some(store_id) = store_id {
-        sqlx::query(query).bind(store_id).fetch_optional(db.get_pool()).await
-    } else {
-        sqlx::query(query).fetch_optional(db.get_pool()).await
-    };
-
-    match result? {
-        Some(row) => Ok(TagSettings {
-            id: row.get("id"),
-            store_id: row.get("store_id"),
-            roll_width: row.get("roll_width"),
-            auto_print: row.get("auto_print"),
-            printer_name: row.get("printer_name"),
-            template_style: row.get("template_style"),
-            include_barcode: row.get("include_barcode"),
-            created_at: row.get("created_at"),
-            updated_at: row.get("updated_at"),
-        }),
-        None => {
-            // Return default settings
-            Ok(TagSettings {
-                id: 0,
-                store_id,
-                roll_width: "40mm".to_string(),
-                auto_print: 0,
-                printer_name: None,
-                template_style: "standard".to_string(),
-                include_barcode: 1,
-                created_at: "".to_string(),
-                updated_at: "".to_string(),
-            })
-        }
-    }
-}
-
 async fn create_tag_settings_internal(
     db: &DatabaseManager,
     store_id: Option<i64>,
@@ -331,8 +578,9 @@ async fn create_tag_settings_internal(
     let query = r#"
         INSERT INTO tag_settings (
             store_id, roll_width, auto_print, printer_name,
-            template_style, include_barcode
-        ) VALUES (?, ?, ?, ?, ?, ?)
+            template_style, include_barcode, printer_language,
+            show_customer_phone, show_store_code, font_scale
+        ) VALUES (?, ?, ?, ?, ?, ?, COALESCE(?, 'html'), COALESCE(?, 0), COALESCE(?, 0), COALESCE(?, 1.0))
     "#;
 
     sqlx::query(query)
@@ -342,6 +590,10 @@ async fn create_tag_settings_internal(
         .bind(&request.printer_name)
         .bind(&request.template_style)
         .bind(if request.include_barcode { 1 } else { 0 })
+        .bind(&request.printer_language)
+        .bind(request.show_customer_phone)
+        .bind(request.show_store_code)
+        .bind(request.font_scale)
         .execute(db.get_pool())
         .await
         .context("Failed to create tag settings")?;
@@ -361,6 +613,10 @@ async fn update_tag_settings_internal(
             printer_name = ?,
             template_style = ?,
             include_barcode = ?,
+            printer_language = COALESCE(?, printer_language),
+            show_customer_phone = COALESCE(?, show_customer_phone),
+            show_store_code = COALESCE(?, show_store_code),
+            font_scale = COALESCE(?, font_scale),
             updated_at = CURRENT_TIMESTAMP
         WHERE id = ?
     "#;
@@ -371,6 +627,10 @@ async fn update_tag_settings_internal(
         .bind(&request.printer_name)
         .bind(&request.template_style)
         .bind(if request.include_barcode { 1 } else { 0 })
+        .bind(&request.printer_language)
+        .bind(request.show_customer_phone)
+        .bind(request.show_store_code)
+        .bind(request.font_scale)
         .bind(settings_id)
         .execute(db.get_pool())
         .await
@@ -379,85 +639,95 @@ async fn update_tag_settings_internal(
     Ok(())
 }
 
-async fn generate_tags_html(tag_data: &[TagData], roll_width: &str) -> Result<String> {
-    let template_path = match roll_width {
-        "32mm" => "src/templates/tags/tag_32mm.html",
-        "40mm" => "src/templates/tags/tag_40mm.html",
-        "50mm" => "src/templates/tags/tag_50mm.html",
-        _ => "src/templates/tags/tag_40mm.html", // Default
-    };
+async fn generate_tags_html(
+    app_handle: &AppHandle,
+    invoice_id: i64,
+    store_id: Option<i64>,
+    tag_data: &[TagData],
+    settings: &TagSettings,
+) -> Result<String> {
+    let custom_template = load_custom_tag_template(app_handle, store_id, &settings.roll_width).await?;
+    let engine = TemplateEngine::new()?;
+
+    let state = app_handle.state::<crate::AppState>();
+    let (store_locale, customer_locale) = sqlx::query_as::<_, (Option<String>, Option<String>)>(
+        r#"
+        SELECT s.locale, c.locale
+        FROM invoices i
+        JOIN stores s ON s.id = i.store_id
+        JOIN customers c ON c.id = i.customer_id
+        WHERE i.id = ?
+        "#,
+    )
+    .bind(invoice_id)
+    .fetch_optional(state.db.get_pool())
+    .await
+    .context("Failed to fetch tag language")?
+    .unwrap_or_default();
+    let locale = DocumentLanguage::for_tags(store_locale.as_deref(), customer_locale.as_deref());
 
-    let mut html_content = String::new();
+    Ok(engine.render_tags(tag_data, settings, locale, custom_template.as_deref())?)
+}
 
-    // Read template
-    let template_content = tokio::fs::read_to_string(template_path)
+async fn get_invoice_store_id(db: &DatabaseManager, invoice_id: i64) -> Result<i64> {
+    sqlx::query_scalar("SELECT store_id FROM invoices WHERE id = ?")
+        .bind(invoice_id)
+        .fetch_optional(db.get_pool())
         .await
-        .context("Failed to read tag template")?;
-
-    // Generate HTML for each tag
-    for (index, tag) in tag_data.iter().enumerate() {
-        let mut tag_html = template_content.clone();
-
-        // Replace template variables
-        tag_html = tag_html.replace("{{invoice_no}}", &tag.invoice_no);
-        tag_html = tag_html.replace("{{customer_name}}", &tag.customer_name);
-        tag_html = tag_html.replace("{{service_name}}", &tag.service_name);
-        tag_html = tag_html.replace("{{tag_number}}", &tag.tag_number.to_string());
-        tag_html = tag_html.replace("{{total_quantity}}", &tag.total_quantity.to_string());
-        tag_html = tag_html.replace("{{overall_piece_number}}", &tag.overall_piece_number.to_string());
-        tag_html = tag_html.replace("{{total_invoice_pieces}}", &tag.total_invoice_pieces.to_string());
-        tag_html = tag_html.replace("{{tag_code}}", &tag.tag_code);
-
-        if let Some(delivery_date) = &tag.delivery_date {
-            tag_html = tag_html.replace("{{delivery_date}}", delivery_date);
-            // For 32mm, create shorter version
-            let short_date = delivery_date.split_whitespace().take(2).collect::<Vec<_>>().join(" ");
-            tag_html = tag_html.replace("{{delivery_date_short}}", &short_date);
-        } else {
-            tag_html = tag_html.replace("{{delivery_date}}", "");
-            tag_html = tag_html.replace("{{delivery_date_short}}", "");
-        }
+        .context("Failed to fetch invoice store")?
+        .ok_or_else(|| anyhow::anyhow!("Invoice {} not found", invoice_id))
+}
 
-        // For 32mm, create shorter service name
-        let service_name_short = if tag.service_name.len() > 15 {
-            format!("{}...", &tag.service_name[..12])
-        } else {
-            tag.service_name.clone()
-        };
-        tag_html = tag_html.replace("{{service_name_short}}", &service_name_short);
-
-        // Handle addons section
-        if let Some(addons) = &tag.addons {
-            tag_html = tag_html.replace("{{#if addons}}", "");
-            tag_html = tag_html.replace("{{addons}}", addons);
-            tag_html = tag_html.replace("{{/if}}", "");
-        } else {
-            // Remove addons section
-            if let Some(start) = tag_html.find("{{#if addons}}") {
-                if let Some(end) = tag_html.find("{{/if}}") {
-                    let end_pos = end + 7; // Length of "{{/if}}"
-                    tag_html.replace_range(start..end_pos, "");
-                }
-            }
-        }
+/// Customised tag templates live in the app data directory:
+/// `templates/tags/store_<id>/tag_<width>.html` for one store and
+/// `templates/tags/default/tag_<width>.html` for all stores
+fn tag_template_path(app_handle: &AppHandle, store_id: Option<i64>, roll_width: &str) -> Result<std::path::PathBuf> {
+    use tauri::api::path;
 
-        // Handle barcode inclusion
-        if tag.include_barcode {
-            tag_html = tag_html.replace("{{#if include_barcode}}", "");
-            tag_html = tag_html.replace("{{/if}}", "");
-        } else {
-            // Remove barcode section
-            if let Some(start) = tag_html.find("{{#if include_barcode}}") {
-                if let Some(end) = tag_html.find("{{/if}}") {
-                    tag_html.replace_range(start..end + 7, "");
-                }
-            }
-        }
+    TemplateEngine::builtin_tag_template(roll_width)?;
+
+    let app_data_dir = path::app_data_dir(&app_handle.config()).ok_or_else(|| {
+        anyhow::anyhow!("Failed to get app data directory")
+    })?;
+    let scope = match store_id {
+        Some(store_id) => format!("store_{}", store_id),
+        None => "default".to_string(),
+    };
+
+    Ok(app_data_dir
+        .join("templates")
+        .join("tags")
+        .join(scope)
+        .join(format!("tag_{}.html", roll_width)))
+}
+
+/// The store's own template, else the customised one shared by all stores,
+/// else None for the built-in template
+async fn load_custom_tag_template(
+    app_handle: &AppHandle,
+    store_id: Option<i64>,
+    roll_width: &str,
+) -> Result<Option<String>> {
+    if TemplateEngine::builtin_tag_template(roll_width).is_err() {
+        return Ok(None);
+    }
+
+    let scopes = match store_id {
+        Some(store_id) => vec![Some(store_id), None],
+        None => vec![None],
+    };
 
-        html_content.push_str(&tag_html);
+    for scope in scopes {
+        let path = tag_template_path(app_handle, scope, roll_width)?;
+        if path.exists() {
+            let template = tokio::fs::read_to_string(&path)
+                .await
+                .with_context(|| format!("Failed to read tag template {}", path.display()))?;
+            return Ok(Some(template));
+        }
     }
 
-    Ok(html_content)
+    Ok(None)
 }
 
 async fn print_tags_html(app_handle: &AppHandle, html_content: &str) -> Result<()> {
@@ -481,13 +751,10 @@ async fn print_tags_html(app_handle: &AppHandle, html_content: &str) -> Result<(
     // Write HTML content to file
     fs::write(&file_path, html_content)?;
 
-    // Open the file in browser for printing using system command
+    // Open the file in the default browser for printing
     let file_path_str = file_path.to_string_lossy().to_string();
 
-    std::process::Command::new("open")
-        .arg(&file_path_str)
-        .spawn()
-        .map_err(|e| anyhow::anyhow!("Failed to open tags HTML for printing: {}", e))?;
+    DocumentPrinterService::open(&app_handle.shell_scope(), &file_path).context("Failed to open tags HTML for printing")?;
 
     log::info!("Successfully opened tags HTML for printing: {}", file_path_str);
 
@@ -0,0 +1,110 @@
+use crate::models::{TurnaroundTier, SaveTurnaroundTierRequest, ApiResult, ApiError};
+use crate::services::TurnaroundService;
+use tauri::State;
+
+#[tauri::command]
+pub async fn get_turnaround_tiers(
+    state: State<'_, crate::AppState>,
+    store_id: Option<i64>,
+) -> ApiResult<Vec<TurnaroundTier>> {
+    let mut conn = state.db.get_pool().acquire().await.map_err(|e| ApiError {
+        message: format!("Database error: {}", e),
+        code: Some("DATABASE_ERROR".to_string()),
+    })?;
+
+    TurnaroundService::get_tiers_for_store(&mut conn, store_id).await
+}
+
+#[tauri::command]
+pub async fn save_turnaround_tier(
+    state: State<'_, crate::AppState>,
+    request: SaveTurnaroundTierRequest,
+) -> ApiResult<TurnaroundTier> {
+    let pool = state.db.get_pool_cloned();
+
+    // Validate input
+    if request.code.trim().is_empty() || request.name.trim().is_empty() {
+        return Err(ApiError {
+            message: "Tier code and name are required".to_string(),
+            code: Some("VALIDATION_ERROR".to_string()),
+        });
+    }
+
+    if request.surcharge_type != "percent" && request.surcharge_type != "flat" {
+        return Err(ApiError {
+            message: "Surcharge type must be 'percent' or 'flat'".to_string(),
+            code: Some("VALIDATION_ERROR".to_string()),
+        });
+    }
+
+    if request.surcharge_value < 0.0 || request.min_surcharge.unwrap_or(0.0) < 0.0 {
+        return Err(ApiError {
+            message: "Surcharge amounts cannot be negative".to_string(),
+            code: Some("VALIDATION_ERROR".to_string()),
+        });
+    }
+
+    if request.turnaround_hours <= 0 {
+        return Err(ApiError {
+            message: "Turnaround hours must be greater than 0".to_string(),
+            code: Some("VALIDATION_ERROR".to_string()),
+        });
+    }
+
+    let excluded = serde_json::to_string(&request.excluded_category_ids.clone().unwrap_or_default())
+        .map_err(|e| ApiError {
+            message: format!("Invalid category list: {}", e),
+            code: Some("VALIDATION_ERROR".to_string()),
+        })?;
+
+    // NULL store ids never collide in a UNIQUE index, so look up the existing row explicitly
+    let existing: Option<i64> = sqlx::query_scalar(
+        "SELECT id FROM turnaround_tiers WHERE code = ? AND store_id IS ?"
+    )
+    .bind(request.code.trim())
+    .bind(request.store_id)
+    .fetch_optional(&pool)
+    .await
+    .map_err(|e| ApiError {
+        message: format!("Database error: {}", e),
+        code: Some("DATABASE_ERROR".to_string()),
+    })?;
+
+    let query = if existing.is_some() {
+        r#"
+        UPDATE turnaround_tiers SET
+            name = ?, surcharge_type = ?, surcharge_value = ?, min_surcharge = ?,
+            turnaround_hours = ?, excluded_category_ids = ?, is_active = ?,
+            updated_at = datetime('now')
+        WHERE id = ?
+        RETURNING *
+        "#
+    } else {
+        r#"
+        INSERT INTO turnaround_tiers (
+            name, surcharge_type, surcharge_value, min_surcharge,
+            turnaround_hours, excluded_category_ids, is_active, store_id, code
+        ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+        RETURNING *
+        "#
+    };
+
+    let mut statement = sqlx::query_as::<_, TurnaroundTier>(query)
+        .bind(request.name.trim())
+        .bind(&request.surcharge_type)
+        .bind(request.surcharge_value)
+        .bind(request.min_surcharge.unwrap_or(0.0))
+        .bind(request.turnaround_hours)
+        .bind(&excluded)
+        .bind(if request.is_active.unwrap_or(true) { 1 } else { 0 });
+
+    statement = match existing {
+        Some(id) => statement.bind(id),
+        None => statement.bind(request.store_id).bind(request.code.trim()),
+    };
+
+    statement.fetch_one(&pool).await.map_err(|e| ApiError {
+        message: format!("Failed to save turnaround tier: {}", e),
+        code: Some("SAVE_TIER_ERROR".to_string()),
+    })
+}
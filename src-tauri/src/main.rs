@@ -20,6 +20,14 @@ use handlers::{
     pricing_handler,
     html_handler,
     tag_handler,
+    turnaround_handler,
+    price_list_handler,
+    rate_card_handler,
+    print_queue_handler,
+    invoice_template_handler,
+    receipt_handler,
+    invoice_export_handler,
+    invoice_archive_handler,
 };
 
 // Application state
@@ -47,6 +55,8 @@ async fn main() {
                             if let Err(e) = state.db.initialize_schema().await {
                                 log::error!("Failed to initialize database schema: {}", e);
                             }
+
+                            print_queue_handler::start_print_worker(app_handle.clone());
                         }
                     },
                     Err(e) => {
@@ -75,6 +85,14 @@ async fn main() {
             store_handler::update_store_status,
             store_handler::delete_store,
             store_handler::get_active_stores,
+            store_handler::get_store_branding,
+            store_handler::save_store_branding,
+            store_handler::set_store_logo,
+            store_handler::remove_store_logo,
+            store_handler::get_store_terms,
+            store_handler::create_store_terms,
+            store_handler::update_store_terms,
+            store_handler::delete_store_terms,
 
             // Service operations
             service_handler::create_service,
@@ -84,18 +102,27 @@ async fn main() {
             service_handler::update_service_status,
             service_handler::get_service_variants,
             service_handler::get_service_addons,
+            service_handler::link_addon_to_service,
+            service_handler::unlink_addon_from_service,
             service_handler::get_service_categories,
             service_handler::get_services_by_category,
             service_handler::get_addons,
 
             // Invoice operations
             invoice_handler::create_invoice,
+            invoice_handler::preview_invoice,
             invoice_handler::get_invoice_by_id,
             invoice_handler::search_invoices,
             invoice_handler::update_invoice,
             invoice_handler::update_invoice_status,
             invoice_handler::update_invoice_details,
             invoice_handler::delete_invoice,
+            invoice_export_handler::start_invoice_export,
+            invoice_export_handler::cancel_invoice_export,
+            invoice_archive_handler::get_invoice_archive,
+            invoice_archive_handler::verify_invoice_archive,
+            invoice_archive_handler::verify_invoice_archives,
+            invoice_archive_handler::save_invoice_duplicate,
 
             // Pricing operations
             pricing_handler::calculate_service_pricing,
@@ -105,15 +132,52 @@ async fn main() {
             pricing_handler::get_service_price_preview,
             pricing_handler::validate_pricing_request_api,
 
+            // Turnaround tier operations
+            turnaround_handler::get_turnaround_tiers,
+            turnaround_handler::save_turnaround_tier,
+
+            // Price list operations
+            price_list_handler::get_price_lists,
+            price_list_handler::create_price_list,
+            price_list_handler::assign_price_list,
+            price_list_handler::get_price_list_assignments,
+            price_list_handler::remove_price_list_assignment,
+            price_list_handler::get_price_list_items,
+            price_list_handler::schedule_price_change,
+            price_list_handler::get_effective_price,
+
+            // Rate card operations
+            rate_card_handler::get_customer_rate_cards,
+            rate_card_handler::save_rate_card,
+            rate_card_handler::deactivate_rate_card,
+
             // HTML operations
             html_handler::save_invoice_html_a5,
             html_handler::save_invoice_html_a4,
             html_handler::save_invoice_html_thermal,
             html_handler::save_and_open_invoice_html,
+            html_handler::save_invoice_pdf,
             html_handler::preview_invoice_html,
+            html_handler::get_invoice_formats,
             html_handler::convert_amount_to_words,
+            html_handler::get_invoice_locales,
             html_handler::validate_html_output_path,
             html_handler::open_html_file,
+            html_handler::print_invoice_silent,
+            html_handler::get_system_printers,
+            // Invoice template operations
+            invoice_template_handler::get_invoice_templates,
+            invoice_template_handler::get_invoice_template,
+            invoice_template_handler::get_builtin_invoice_template,
+            invoice_template_handler::create_invoice_template,
+            invoice_template_handler::update_invoice_template,
+            invoice_template_handler::clone_invoice_template,
+            invoice_template_handler::delete_invoice_template,
+            invoice_template_handler::set_default_invoice_template,
+            invoice_template_handler::clear_default_invoice_template,
+            invoice_template_handler::validate_invoice_template,
+            invoice_template_handler::export_invoice_template,
+            invoice_template_handler::import_invoice_template,
 
             // Report operations
             report_handler::get_sales_summary,
@@ -121,15 +185,37 @@ async fn main() {
             report_handler::get_customer_summary,
             report_handler::get_service_popularity,
             report_handler::get_express_delivery_summary,
+            report_handler::get_tag_reprint_report,
 
             // Tag operations
             tag_handler::generate_invoice_tags,
+            tag_handler::reconcile_invoice_tags,
             tag_handler::print_invoice_tags,
+            tag_handler::get_tag_print_jobs,
             tag_handler::get_invoice_tags,
             tag_handler::get_invoice_tag_summary,
             tag_handler::get_tag_settings,
             tag_handler::save_tag_settings,
             tag_handler::get_tag_preview,
+            tag_handler::get_tag_template,
+            tag_handler::save_tag_template,
+            tag_handler::reset_tag_template,
+            tag_handler::lookup_tag,
+            tag_handler::search_tags,
+            tag_handler::update_tag_attributes,
+            tag_handler::scan_tag,
+            tag_handler::get_tag_scan_history,
+
+            // Print queue operations
+            print_queue_handler::get_print_queue,
+            print_queue_handler::queue_invoice_print,
+            print_queue_handler::retry_print_queue_item,
+            print_queue_handler::cancel_print_queue_item,
+
+            // Receipt printer operations
+            receipt_handler::get_receipt_settings,
+            receipt_handler::save_receipt_settings,
+            receipt_handler::print_invoice_receipt,
 
             // Utility operations
             initialize_database,
@@ -143,20 +229,10 @@ async fn main() {
 
 #[tauri::command]
 async fn initialize_database(state: State<'_, AppState>) -> Result<String, String> {
-    let pool = state.db.get_pool_cloned();
-
-    // Execute initialization directly on the pool
-    let schema = include_str!("database/schema.sql");
-    match sqlx::query(schema).execute(&pool).await {
-        Ok(_) => {
-            // Execute seed data
-            let seed_sql = include_str!("database/seed.sql");
-            match sqlx::query(seed_sql).execute(&pool).await {
-                Ok(_) => Ok("Database initialized successfully".to_string()),
-                Err(e) => Err(format!("Failed to seed database: {}", e)),
-            }
-        },
-        Err(e) => Err(format!("Failed to initialize database schema: {}", e)),
+    // Schema, migrations and seed data run through the database manager
+    match state.db.initialize_schema().await {
+        Ok(_) => Ok("Database initialized successfully".to_string()),
+        Err(e) => Err(format!("Failed to initialize database: {:#}", e)),
     }
 }
 
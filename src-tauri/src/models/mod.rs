@@ -9,6 +9,8 @@ pub struct Customer {
     pub email: Option<String>,
     pub address: Option<String>,
     pub notes: Option<String>,
+    pub customer_group: Option<String>,
+    pub locale: Option<String>, // None prints in the store's language
     pub is_active: Option<i64>,
     pub created_at: String,
     pub updated_at: String,
@@ -21,6 +23,8 @@ pub struct CreateCustomerRequest {
     pub email: Option<String>,
     pub address: Option<String>,
     pub notes: Option<String>,
+    pub customer_group: Option<String>,
+    pub locale: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -30,6 +34,8 @@ pub struct UpdateCustomerRequest {
     pub email: Option<String>,
     pub address: Option<String>,
     pub notes: Option<String>,
+    pub customer_group: Option<String>,
+    pub locale: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -61,6 +67,8 @@ pub struct Store {
     pub gstin: Option<String>,
     pub pan_number: Option<String>,
     pub owner_name: Option<String>,
+    pub locale: Option<String>, // None prints in English
+    pub secondary_locale: Option<String>, // printed alongside for bilingual invoices
     pub is_active: Option<i64>,
     pub created_at: String,
     pub updated_at: String,
@@ -78,6 +86,8 @@ pub struct CreateStoreRequest {
     pub gstin: Option<String>,
     pub pan_number: Option<String>,
     pub owner_name: Option<String>,
+    pub locale: Option<String>,
+    pub secondary_locale: Option<String>,
     pub is_active: Option<bool>,
 }
 
@@ -93,6 +103,8 @@ pub struct UpdateStoreRequest {
     pub gstin: Option<String>,
     pub pan_number: Option<String>,
     pub owner_name: Option<String>,
+    pub locale: Option<String>,
+    pub secondary_locale: Option<String>,
     pub is_active: Option<bool>,
 }
 
@@ -196,6 +208,8 @@ pub struct ServiceAddon {
     pub description: Option<String>,
     pub price: f64,
     pub unit: String,
+    pub pricing_mode: String, // per_unit, percent_of_line, per_order, per_piece
+    pub gst_rate: Option<f64>,
     pub is_active: Option<i64>,
     pub created_at: String,
     pub updated_at: String,
@@ -203,10 +217,14 @@ pub struct ServiceAddon {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CreateServiceAddonRequest {
+    #[serde(default)]
+    pub id: Option<i64>, // existing addon to update; ignored on create
     pub name: String,
     pub description: Option<String>,
     pub price: f64,
     pub unit: String,
+    pub pricing_mode: Option<String>,
+    pub gst_rate: Option<f64>,
     pub is_active: Option<bool>,
 }
 
@@ -251,6 +269,7 @@ pub struct Invoice {
     pub payment_method: Option<String>,
     pub payment_amount: Option<f64>,
     pub total_pieces: i32,
+    pub turnaround_tier: String,
     pub status: String,
     pub notes: Option<String>,
     pub created_at: String,
@@ -267,7 +286,8 @@ pub struct CreateInvoiceRequest {
     pub items: Vec<CreateInvoiceItemRequest>,
     pub discount: Option<f64>,
     pub discount_type: Option<String>,
-    pub express_charge: Option<f64>,
+    pub order_datetime: Option<String>, // defaults to now; also selects the effective price
+    pub turnaround_tier: Option<String>, // standard, next_day, same_day
     pub gst_inclusive: Option<bool>,
     pub payment_method: Option<String>,
     pub payment_amount: Option<f64>,
@@ -306,8 +326,13 @@ pub struct InvoiceItem {
     pub rate: f64,
     pub amount: f64,
     pub gst_rate: f64,
+    pub discount_amount: f64,
+    pub taxable_value: f64,
     pub sgst: f64,
     pub cgst: f64,
+    pub price_list_id: Option<i64>,
+    pub rate_source: String,
+    pub rate_card_id: Option<i64>,
     pub created_at: String,
 }
 
@@ -319,6 +344,15 @@ pub struct InvoiceItemAddon {
     pub qty: f64,
     pub rate: f64,
     pub amount: f64,
+    pub pricing_mode: String,
+    pub gst_rate: f64,
+    pub discount_amount: f64,
+    pub taxable_value: f64,
+    pub sgst: f64,
+    pub cgst: f64,
+    pub price_list_id: Option<i64>,
+    pub rate_source: String,
+    pub rate_card_id: Option<i64>,
     pub created_at: String,
 }
 
@@ -391,9 +425,96 @@ pub struct ClothingTag {
     pub printed_at: Option<String>,
     pub printed_by: Option<String>,
     pub reprint_count: i64,
+    pub status: String,
+    pub status_updated_at: Option<String>,
+    pub is_void: bool,
+    pub voided_at: Option<String>,
+    pub garment_type: Option<String>,
+    pub colour: Option<String>,
+    pub brand: Option<String>,
+    pub damage_notes: Option<String>,
+    pub photo_paths: String, // JSON array of file paths
     pub created_at: String,
 }
 
+impl ClothingTag {
+    /// Garment type, colour and brand as one line, e.g. "Shirt, Blue, Arrow"
+    pub fn garment_description(&self) -> Option<String> {
+        let parts: Vec<&str> = [&self.garment_type, &self.colour, &self.brand]
+            .into_iter()
+            .filter_map(|part| part.as_deref())
+            .collect();
+        if parts.is_empty() {
+            None
+        } else {
+            Some(parts.join(", "))
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct TagScanEvent {
+    pub id: i64,
+    pub tag_id: i64,
+    pub from_status: Option<String>,
+    pub to_status: String,
+    pub scanned_by: Option<String>,
+    pub notes: Option<String>,
+    pub scanned_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScanTagRequest {
+    pub tag_code: String,
+    pub stage: Option<String>, // None advances to the next stage
+    pub scanned_by: Option<String>,
+    pub notes: Option<String>,
+}
+
+/// Replaces all of a tag's garment attributes; omitted fields are cleared
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateTagAttributesRequest {
+    pub tag_id: i64,
+    pub garment_type: Option<String>,
+    pub colour: Option<String>,
+    pub brand: Option<String>,
+    pub damage_notes: Option<String>,
+    pub photo_paths: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TagScanResult {
+    pub tag: ClothingTag,
+    pub event: TagScanEvent,
+    pub invoice_status: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TagLookupResult {
+    pub tag: ClothingTag,
+    pub invoice_no: String,
+    pub invoice_status: String,
+    pub customer_name: String,
+    pub customer_phone: Option<String>,
+    pub service_name: String,
+    pub addons: Vec<String>,
+    pub delivery_datetime: Option<String>,
+}
+
+/// Outcome of reconciling an invoice's tags with its current piece counts
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TagReconciliation {
+    pub invoice_id: i64,
+    /// Active tags in piece order
+    pub tags: Vec<ClothingTag>,
+    /// New or restored tags that have to be printed
+    pub added: Vec<ClothingTag>,
+    /// Tags whose pieces are gone; printed ones should be pulled off garments
+    pub voided: Vec<ClothingTag>,
+    /// Printed tags whose piece numbers changed
+    pub reprint_required: Vec<ClothingTag>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CreateClothingTagRequest {
     pub invoice_id: i64,
@@ -414,10 +535,38 @@ pub struct TagSettings {
     pub printer_name: Option<String>,
     pub template_style: String,
     pub include_barcode: i64,
+    pub printer_language: String,
+    pub show_customer_phone: i64,
+    pub show_store_code: i64,
+    pub font_scale: f64,
     pub created_at: String,
     pub updated_at: String,
 }
 
+impl TagSettings {
+    pub const MIN_FONT_SCALE: f64 = 0.7;
+    pub const MAX_FONT_SCALE: f64 = 1.5;
+
+    /// Settings used when neither the store nor the global defaults are saved
+    pub fn defaults(store_id: Option<i64>) -> Self {
+        Self {
+            id: 0,
+            store_id,
+            roll_width: "40mm".to_string(),
+            auto_print: 0,
+            printer_name: None,
+            template_style: "standard".to_string(),
+            include_barcode: 1,
+            printer_language: "html".to_string(),
+            show_customer_phone: 0,
+            show_store_code: 0,
+            font_scale: 1.0,
+            created_at: "".to_string(),
+            updated_at: "".to_string(),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CreateTagSettingsRequest {
     pub store_id: Option<i64>,
@@ -426,6 +575,10 @@ pub struct CreateTagSettingsRequest {
     pub printer_name: Option<String>,
     pub template_style: String,
     pub include_barcode: bool,
+    pub printer_language: Option<String>,
+    pub show_customer_phone: Option<bool>,
+    pub show_store_code: Option<bool>,
+    pub font_scale: Option<f64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -435,12 +588,204 @@ pub struct UpdateTagSettingsRequest {
     pub printer_name: Option<String>,
     pub template_style: String,
     pub include_barcode: bool,
+    pub printer_language: Option<String>,
+    pub show_customer_phone: Option<bool>,
+    pub show_store_code: Option<bool>,
+    pub font_scale: Option<f64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TagPrintRequest {
     pub invoice_id: i64,
     pub item_ids: Option<Vec<i64>>,
+    pub tag_ids: Option<Vec<i64>>,
+    pub printed_by: Option<String>,
+    /// Required when any selected tag has been printed before
+    pub reprint_reason: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct PrintJob {
+    pub id: i64,
+    pub invoice_id: i64,
+    pub printed_by: Option<String>,
+    pub printer_name: Option<String>,
+    pub printer_language: String,
+    pub is_reprint: bool,
+    pub reprint_reason: Option<String>,
+    pub tag_count: i64,
+    pub status: String, // printed, failed
+    pub error_message: Option<String>,
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct PrintQueueItem {
+    pub id: i64,
+    pub invoice_id: i64,
+    pub kind: String, // receipt, tags
+    pub status: String, // pending, printing, printed, failed, cancelled
+    pub attempts: i64,
+    pub max_attempts: i64,
+    pub next_attempt_at: String,
+    pub last_error: Option<String>,
+    pub print_job_id: Option<i64>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreatePrintJobRequest {
+    pub invoice_id: i64,
+    pub printed_by: Option<String>,
+    pub printer_name: Option<String>,
+    pub printer_language: String,
+    pub reprint_reason: Option<String>,
+    pub error_message: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TagTemplate {
+    pub store_id: Option<i64>,
+    pub roll_width: String,
+    pub content: String,
+    pub is_custom: bool, // false when the built-in template is in use
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SaveTagTemplateRequest {
+    pub store_id: Option<i64>, // None saves the template shared by all stores
+    pub roll_width: String,
+    pub content: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct StoreBranding {
+    pub id: i64,
+    pub store_id: i64,
+    pub business_name: Option<String>, // None prints the store name
+    pub tagline: Option<String>,
+    pub logo_path: Option<String>,
+    pub primary_color: Option<String>,
+    pub secondary_color: Option<String>,
+    pub website: Option<String>,
+    pub support_phone: Option<String>,
+    pub support_email: Option<String>,
+    pub thank_you_message: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SaveStoreBrandingRequest {
+    pub business_name: Option<String>,
+    pub tagline: Option<String>,
+    pub primary_color: Option<String>,
+    pub secondary_color: Option<String>,
+    pub website: Option<String>,
+    pub support_phone: Option<String>,
+    pub support_email: Option<String>,
+    pub thank_you_message: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct TermsCondition {
+    pub id: i64,
+    pub store_id: i64,
+    pub content: String, // one term per line
+    pub is_active: Option<i64>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SaveTermsRequest {
+    pub content: String,
+    pub is_active: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ReceiptSettings {
+    pub id: i64,
+    pub store_id: Option<i64>, // None = shared by all stores
+    pub printer_name: Option<String>, // None prints receipts as thermal HTML
+    pub line_chars: i64, // 42 or 48
+    pub include_qr: bool,
+    pub qr_url: Option<String>, // "{invoice_no}" is replaced; None encodes the invoice number
+    pub cut_paper: bool,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl ReceiptSettings {
+    pub const LINE_CHARS: [i64; 2] = [42, 48];
+
+    /// Settings used when neither the store nor the shared defaults are saved
+    pub fn defaults(store_id: Option<i64>) -> Self {
+        Self {
+            id: 0,
+            store_id,
+            printer_name: None,
+            line_chars: 48,
+            include_qr: true,
+            qr_url: None,
+            cut_paper: true,
+            created_at: "".to_string(),
+            updated_at: "".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SaveReceiptSettingsRequest {
+    pub store_id: Option<i64>, // None saves the settings shared by all stores
+    pub printer_name: Option<String>,
+    pub line_chars: Option<i64>,
+    pub include_qr: Option<bool>,
+    pub qr_url: Option<String>,
+    pub cut_paper: Option<bool>,
+}
+
+/// An invoice as it was issued: the rendered document and the data behind it
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct InvoiceArchive {
+    pub id: i64,
+    pub invoice_id: i64,
+    pub invoice_no: String,
+    pub format: String, // format of the stored document
+    pub document: String, // rendered HTML
+    pub data_json: String, // InvoiceHtmlData as JSON
+    pub settings_json: String, // HtmlSettings per format as JSON
+    pub content_hash: String, // HMAC-SHA256 hex, keyed by the app's archive key
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InvoiceArchiveCheck {
+    pub invoice_id: i64,
+    pub invoice_no: String,
+    pub archived_at: String,
+    pub valid: bool,
+    pub problem: Option<String>, // why the archive failed verification
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InvoiceArchiveReport {
+    pub checked: usize,
+    pub failed: Vec<InvoiceArchiveCheck>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct InvoiceTemplate {
+    pub id: i64,
+    pub store_id: Option<i64>, // None = shared by all stores
+    pub name: String,
+    pub format: String, // a4, a5, thermal
+    pub content: String,
+    pub layout: String, // LayoutSettings as JSON
+    pub is_default: bool,
+    pub created_at: String,
+    pub updated_at: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -456,6 +801,11 @@ pub struct TagData {
     pub delivery_date: Option<String>,
     pub tag_code: String,
     pub include_barcode: bool,
+    pub garment: Option<String>,
+    pub damage_notes: Option<String>,
+    /// Only filled when the store's tag settings show them
+    pub customer_phone: Option<String>,
+    pub store_code: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -463,6 +813,7 @@ pub struct TagPrintResponse {
     pub success: bool,
     pub message: String,
     pub tags_printed: i64,
+    pub print_job_id: Option<i64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -481,6 +832,149 @@ pub struct InvoiceItemAddonWithDetails {
     pub addon: ServiceAddon,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct TurnaroundTier {
+    pub id: i64,
+    pub store_id: Option<i64>,
+    pub code: String,
+    pub name: String,
+    pub surcharge_type: String, // percent, flat
+    pub surcharge_value: f64,
+    pub min_surcharge: f64,
+    pub turnaround_hours: i64,
+    pub excluded_category_ids: String, // JSON array of category ids
+    pub is_active: Option<i64>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SaveTurnaroundTierRequest {
+    pub store_id: Option<i64>,
+    pub code: String,
+    pub name: String,
+    pub surcharge_type: String,
+    pub surcharge_value: f64,
+    pub min_surcharge: Option<f64>,
+    pub turnaround_hours: i64,
+    pub excluded_category_ids: Option<Vec<i64>>,
+    pub is_active: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct PriceList {
+    pub id: i64,
+    pub name: String,
+    pub description: Option<String>,
+    pub is_active: Option<i64>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreatePriceListRequest {
+    pub name: String,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct PriceListAssignment {
+    pub id: i64,
+    pub price_list_id: i64,
+    pub store_id: Option<i64>,
+    pub customer_group: Option<String>,
+    pub priority: i64,
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssignPriceListRequest {
+    pub price_list_id: i64,
+    pub store_id: Option<i64>,
+    pub customer_group: Option<String>,
+    pub priority: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct PriceListItem {
+    pub id: i64,
+    pub price_list_id: i64,
+    pub service_id: Option<i64>,
+    pub variant_id: Option<i64>,
+    pub addon_id: Option<i64>,
+    pub price: f64,
+    pub effective_from: String,
+    /// First day the price no longer applies; None while open-ended
+    pub effective_to: Option<String>,
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SchedulePriceChangeRequest {
+    pub price_list_id: i64,
+    pub service_id: Option<i64>,
+    pub variant_id: Option<i64>,
+    pub addon_id: Option<i64>,
+    pub price: f64,
+    pub effective_from: String, // YYYY-MM-DD
+    pub effective_to: Option<String>, // YYYY-MM-DD, exclusive
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct RateCard {
+    pub id: i64,
+    pub customer_id: i64,
+    pub name: String,
+    pub valid_from: String,
+    /// First day the card no longer applies, like a price's effective_to;
+    /// None until further notice
+    pub valid_to: Option<String>,
+    pub rates_include_gst: i64,
+    pub notes: Option<String>,
+    pub is_active: Option<i64>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct RateCardItem {
+    pub id: i64,
+    pub rate_card_id: i64,
+    pub service_id: Option<i64>,
+    pub variant_id: Option<i64>,
+    pub addon_id: Option<i64>,
+    pub rate_type: String, // fixed, percent_off
+    pub value: f64,
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RateCardWithItems {
+    pub rate_card: RateCard,
+    pub items: Vec<RateCardItem>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SaveRateCardRequest {
+    pub customer_id: i64,
+    pub name: String,
+    pub valid_from: String, // YYYY-MM-DD
+    pub valid_to: Option<String>, // YYYY-MM-DD, exclusive
+    pub rates_include_gst: bool,
+    pub notes: Option<String>,
+    pub is_active: Option<bool>,
+    pub items: Vec<CreateRateCardItemRequest>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateRateCardItemRequest {
+    pub service_id: Option<i64>,
+    pub variant_id: Option<i64>,
+    pub addon_id: Option<i64>,
+    pub rate_type: String,
+    pub value: f64,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PricingCalculation {
     pub base_amount: f64,
@@ -539,5 +1033,15 @@ impl std::fmt::Display for ApiError {
 
 impl std::error::Error for ApiError {}
 
+impl ApiError {
+    /// A failed query, for services that don't need a more specific code
+    pub fn database(e: sqlx::Error) -> Self {
+        ApiError {
+            message: format!("Database error: {}", e),
+            code: Some("DATABASE_ERROR".to_string()),
+        }
+    }
+}
+
 // Result type alias
 pub type ApiResult<T> = Result<T, ApiError>;
\ No newline at end of file
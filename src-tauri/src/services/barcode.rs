@@ -0,0 +1,300 @@
+use serde::{Deserialize, Serialize};
+use qrcode::{QrCode, EcLevel, Color};
+use crate::models::{ApiResult, ApiError};
+use regex::Regex;
+
+/// Code 128 bar/space widths for symbol values 0-105
+const CODE128_PATTERNS: [&str; 106] = [
+    "212222", "222122", "222221", "121223", "121322", "131222", "122213", "122312", "132212", "221213",
+    "221312", "231212", "112232", "122132", "122231", "113222", "123122", "123221", "223211", "221132",
+    "221231", "213212", "223112", "312131", "311222", "321122", "321221", "312212", "322112", "322211",
+    "212123", "212321", "232121", "111323", "131123", "131321", "112313", "132113", "132311", "211313",
+    "231113", "231311", "112133", "112331", "132131", "113123", "113321", "133121", "313121", "211331",
+    "231131", "213113", "213311", "213131", "311123", "311321", "331121", "312113", "312311", "332111",
+    "314111", "221411", "431111", "111224", "111422", "121124", "121421", "141122", "141221", "112214",
+    "112412", "122114", "122411", "142112", "142211", "241211", "221114", "413111", "241112", "134111",
+    "111242", "121142", "121241", "114212", "124112", "124211", "411212", "421112", "421211", "212141",
+    "214121", "412121", "111143", "111341", "131141", "114113", "114311", "411113", "411311", "113141",
+    "114131", "311141", "411131", "211412", "211214", "211232",
+];
+const CODE128_STOP: &str = "2331112";
+const CODE128_CODE_C: u8 = 99;
+const CODE128_CODE_B: u8 = 100;
+const CODE128_START_B: u8 = 104;
+const CODE128_START_C: u8 = 105;
+const CODE128_QUIET_ZONE: usize = 10;
+const QR_QUIET_ZONE: usize = 4;
+
+/// One printer dot on a 203 dpi thermal head. Module widths are snapped to
+/// whole dots, otherwise the printer rounds bars unevenly.
+const DOT_MM: f64 = 25.4 / 203.0;
+/// Narrowest Code 128 bar handheld scanners read reliably off thermal labels
+const MIN_BAR_MODULE_MM: f64 = 2.0 * DOT_MM;
+/// Smallest QR module phone cameras and 2D scanners read reliably
+const MIN_QR_MODULE_MM: f64 = 3.0 * DOT_MM;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BarcodeFormat {
+    Code128,
+    Qr,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Barcode {
+    pub format: BarcodeFormat,
+    pub svg: String,
+    pub width_mm: f64,
+    pub height_mm: f64,
+    pub module_mm: f64,
+}
+
+pub struct BarcodeService;
+
+impl BarcodeService {
+    /// Barcode for a clothing tag. Code 128 is preferred; codes too long to
+    /// scan at the roll's printable width fall back to a QR code.
+    pub fn tag_barcode(tag_code: &str, roll_width: &str) -> ApiResult<Barcode> {
+        let (printable_mm, bar_height_mm, max_qr_mm): (f64, f64, f64) = match roll_width {
+            "32mm" => (28.0, 6.0, 14.0),
+            "50mm" => (46.0, 10.0, 22.0),
+            _ => (36.0, 8.0, 18.0), // 40mm
+        };
+
+        Self::code128(tag_code, printable_mm, bar_height_mm)
+            .or_else(|_| Self::qr(tag_code, max_qr_mm.min(printable_mm)))
+            .map_err(|_| ApiError {
+                message: format!("Tag code {} cannot be printed scannably on a {} roll", tag_code, roll_width),
+                code: Some("BARCODE_NOT_SCANNABLE".to_string()),
+            })
+    }
+
+    /// Barcode of the invoice number for quick lookup at the counter
+    pub fn invoice_barcode(invoice_no: &str, max_width_mm: f64) -> ApiResult<Barcode> {
+        Self::code128(invoice_no, max_width_mm, 10.0)
+            .or_else(|_| Self::qr(invoice_no, max_width_mm.min(20.0)))
+    }
+
+    /// Code 128 as inline SVG, failing if its bars would be too narrow to scan
+    pub fn code128(data: &str, max_width_mm: f64, height_mm: f64) -> ApiResult<Barcode> {
+        let bars = Self::code128_modules(data)?;
+        let total_modules = bars.len() + 2 * CODE128_QUIET_ZONE;
+        let module_mm = Self::fit_module(max_width_mm, total_modules, MIN_BAR_MODULE_MM, data)?;
+
+        let mut rects = String::new();
+        let mut x = 0;
+        while x < bars.len() {
+            if bars[x] {
+                let start = x;
+                while x < bars.len() && bars[x] {
+                    x += 1;
+                }
+                rects.push_str(&format!(
+                    r#"<rect x="{}" y="0" width="{}" height="1"/>"#,
+                    start + CODE128_QUIET_ZONE,
+                    x - start
+                ));
+            } else {
+                x += 1;
+            }
+        }
+
+        let width_mm = total_modules as f64 * module_mm;
+        Ok(Barcode {
+            format: BarcodeFormat::Code128,
+            svg: format!(
+                r##"<svg xmlns="http://www.w3.org/2000/svg" width="{:.3}mm" height="{:.3}mm" viewBox="0 0 {} 1" preserveAspectRatio="none" shape-rendering="crispEdges"><rect width="100%" height="100%" fill="#fff"/><g fill="#000">{}</g></svg>"##,
+                width_mm, height_mm, total_modules, rects
+            ),
+            width_mm,
+            height_mm,
+            module_mm,
+        })
+    }
+
+    /// QR code as inline SVG, failing if its modules would be too small to scan
+    pub fn qr(data: &str, max_side_mm: f64) -> ApiResult<Barcode> {
+        let code = QrCode::with_error_correction_level(data.as_bytes(), EcLevel::M).map_err(|e| ApiError {
+            message: format!("Cannot encode {} as a QR code: {}", data, e),
+            code: Some("BARCODE_ERROR".to_string()),
+        })?;
+
+        let width = code.width();
+        let total_modules = width + 2 * QR_QUIET_ZONE;
+        let module_mm = Self::fit_module(max_side_mm, total_modules, MIN_QR_MODULE_MM, data)?;
+
+        let mut rects = String::new();
+        for (index, color) in code.to_colors().into_iter().enumerate() {
+            if color == Color::Dark {
+                rects.push_str(&format!(
+                    r#"<rect x="{}" y="{}" width="1" height="1"/>"#,
+                    index % width + QR_QUIET_ZONE,
+                    index / width + QR_QUIET_ZONE
+                ));
+            }
+        }
+
+        let side_mm = total_modules as f64 * module_mm;
+        Ok(Barcode {
+            format: BarcodeFormat::Qr,
+            svg: format!(
+                r##"<svg xmlns="http://www.w3.org/2000/svg" width="{:.3}mm" height="{:.3}mm" viewBox="0 0 {} {}" shape-rendering="crispEdges"><rect width="100%" height="100%" fill="#fff"/><g fill="#000">{}</g></svg>"##,
+                side_mm, side_mm, total_modules, total_modules, rects
+            ),
+            width_mm: side_mm,
+            height_mm: side_mm,
+            module_mm,
+        })
+    }
+
+    /// Whether markup has the shape of an SVG made by this service: only
+    /// svg, g and rect elements with plain presentation attributes. Templates
+    /// may output such markup unescaped; anything else must be escaped.
+    pub fn is_barcode_svg(markup: &str) -> bool {
+        let attrs = r#"(?: (?:xmlns|width|height|viewBox|preserveAspectRatio|shape-rendering|fill|x|y)="[0-9A-Za-z.%#:/ ]*")*"#;
+        Regex::new(&format!(r"^<svg{a}>(?:<g{a}>|</g>|<rect{a}/>)*</svg>$", a = attrs))
+            .map(|shape| shape.is_match(markup))
+            .unwrap_or(false)
+    }
+
+    /// Bars (true) and spaces (false) of a Code 128 symbol, one entry per module
+    pub fn code128_modules(data: &str) -> ApiResult<Vec<bool>> {
+        let mut modules = Vec::new();
+        let patterns = Self::code128_symbols(data)?
+            .into_iter()
+            .map(|value| CODE128_PATTERNS[value as usize])
+            .chain(std::iter::once(CODE128_STOP));
+
+        for pattern in patterns {
+            for (index, width) in pattern.bytes().enumerate() {
+                let is_bar = index % 2 == 0;
+                modules.extend(std::iter::repeat(is_bar).take((width - b'0') as usize));
+            }
+        }
+
+        Ok(modules)
+    }
+
+    /// Symbol values from the start code through the check symbol. Runs of
+    /// digits are packed two to a symbol in code set C to keep codes short.
+    pub fn code128_symbols(data: &str) -> ApiResult<Vec<u8>> {
+        if data.is_empty() || !data.bytes().all(|b| (32..=126).contains(&b)) {
+            return Err(ApiError {
+                message: format!("Code 128 supports printable ASCII only: {:?}", data),
+                code: Some("BARCODE_ERROR".to_string()),
+            });
+        }
+
+        let bytes = data.as_bytes();
+        let digit_run = |from: usize| bytes[from..].iter().take_while(|b| b.is_ascii_digit()).count();
+
+        let leading = digit_run(0);
+        let mut in_code_c = leading >= 4 && leading % 2 == 0;
+        let mut symbols = vec![if in_code_c { CODE128_START_C } else { CODE128_START_B }];
+
+        let mut i = 0;
+        while i < bytes.len() {
+            if in_code_c {
+                if digit_run(i) >= 2 {
+                    symbols.push((bytes[i] - b'0') * 10 + (bytes[i + 1] - b'0'));
+                    i += 2;
+                } else {
+                    symbols.push(CODE128_CODE_B);
+                    in_code_c = false;
+                }
+                continue;
+            }
+
+            let run = digit_run(i);
+            if run >= 6 || (run >= 4 && i + run == bytes.len()) {
+                if run % 2 == 1 {
+                    symbols.push(bytes[i] - 32);
+                    i += 1;
+                }
+                symbols.push(CODE128_CODE_C);
+                in_code_c = true;
+            } else {
+                symbols.push(bytes[i] - 32);
+                i += 1;
+            }
+        }
+
+        let checksum = symbols
+            .iter()
+            .enumerate()
+            .map(|(position, value)| position.max(1) as u32 * *value as u32)
+            .sum::<u32>()
+            % 103;
+        symbols.push(checksum as u8);
+
+        Ok(symbols)
+    }
+
+    /// Widest whole-dot module that fits, if it is still scannable
+    fn fit_module(available_mm: f64, modules: usize, min_module_mm: f64, data: &str) -> ApiResult<f64> {
+        let module_mm = (available_mm / modules as f64 / DOT_MM).floor() * DOT_MM;
+        if module_mm + 1e-9 < min_module_mm {
+            return Err(ApiError {
+                message: format!(
+                    "{} needs {} modules; {:.1}mm only allows {:.3}mm per module (minimum {:.3}mm)",
+                    data, modules, available_mm, module_mm, min_module_mm
+                ),
+                code: Some("BARCODE_NOT_SCANNABLE".to_string()),
+            });
+        }
+        Ok(module_mm)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_code128_symbols_and_checksum() {
+        // (104 + 33*1 + 34*2 + 35*3) % 103 = 1
+        assert_eq!(BarcodeService::code128_symbols("ABC").unwrap(), vec![104, 33, 34, 35, 1]);
+        // Pure digits use code set C: (105 + 12 + 34*2 + 56*3 + 78*4) % 103 = 47
+        assert_eq!(BarcodeService::code128_symbols("12345678").unwrap(), vec![105, 12, 34, 56, 78, 47]);
+        // Digit runs inside a tag code switch to set C and back
+        let symbols = BarcodeService::code128_symbols("INV202510__001").unwrap();
+        assert_eq!(&symbols[..8], &[104, 41, 46, 54, 99, 20, 25, 10]);
+        assert_eq!(symbols[8], 100);
+
+        assert!(BarcodeService::code128_symbols("").is_err());
+        assert!(BarcodeService::code128_symbols("naïve").is_err());
+    }
+
+    #[test]
+    fn test_is_barcode_svg() {
+        assert!(BarcodeService::is_barcode_svg(&BarcodeService::invoice_barcode("INV202510001", 60.0).unwrap().svg));
+        assert!(BarcodeService::is_barcode_svg(&BarcodeService::qr("INV202510001-1-1", 20.0).unwrap().svg));
+
+        assert!(!BarcodeService::is_barcode_svg(r#"<svg onload="alert(1)"></svg>"#));
+        assert!(!BarcodeService::is_barcode_svg(r#"<svg><script>alert(1)</script></svg>"#));
+        assert!(!BarcodeService::is_barcode_svg(r#"<svg><rect x="0"/></svg><img src=x onerror=alert(1)>"#));
+        assert!(!BarcodeService::is_barcode_svg(r#"<svg fill="x" onclick="y"></svg>"#));
+    }
+
+    #[test]
+    fn test_code128_modules_are_well_formed() {
+        let symbols = BarcodeService::code128_symbols("INV202510__001-17-3").unwrap();
+        let modules = BarcodeService::code128_modules("INV202510__001-17-3").unwrap();
+        assert_eq!(modules.len(), symbols.len() * 11 + 13);
+        assert!(modules[0] && *modules.last().unwrap());
+    }
+
+    #[test]
+    fn test_tag_barcode_falls_back_to_qr_when_too_narrow() {
+        let tag = BarcodeService::tag_barcode("INV202510__001-17-3", "32mm").unwrap();
+        assert_eq!(tag.format, BarcodeFormat::Qr);
+        assert!(tag.width_mm <= 14.0 && tag.module_mm >= MIN_QR_MODULE_MM);
+        assert!(tag.svg.starts_with("<svg"));
+
+        let invoice = BarcodeService::invoice_barcode("INV202510__001", 60.0).unwrap();
+        assert_eq!(invoice.format, BarcodeFormat::Code128);
+        assert!(invoice.width_mm <= 60.0 && invoice.module_mm >= MIN_BAR_MODULE_MM);
+
+        assert!(BarcodeService::code128("INV202510__001-17-3", 28.0, 6.0).is_err());
+    }
+}
@@ -0,0 +1,182 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::process::Command;
+use tauri::ShellScope;
+
+/// Most copies one silent print may ask for
+pub const MAX_COPIES: u32 = 20;
+
+/// How to send a document straight to a printer, without a print dialog
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SilentPrintOptions {
+    /// System printer (CUPS queue or Windows printer name)
+    pub printer_name: String,
+    pub copies: Option<u32>,
+    /// Paper the printer should use, e.g. "A4", "A5" or "Custom.80x200mm";
+    /// None lets the printer use the document's own page size
+    pub paper_size: Option<String>,
+}
+
+/// A program and its arguments, kept apart so nothing goes through a shell
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrintCommand {
+    pub program: String,
+    pub args: Vec<String>,
+}
+
+pub struct DocumentPrinterService;
+
+impl DocumentPrinterService {
+    /// Open a generated document with the system's default app (browser for
+    /// HTML, PDF viewer for PDFs), through the shell scope's open allowlist
+    pub fn open(scope: &ShellScope, path: &Path) -> Result<()> {
+        if !path.is_file() {
+            return Err(anyhow::anyhow!("{} does not exist", path.display()));
+        }
+        tauri::api::shell::open(scope, path.to_string_lossy(), None)
+            .with_context(|| format!("Failed to open {}", path.display()))
+    }
+
+    /// Send a PDF to a named printer through the platform print service:
+    /// CUPS `lp` on Linux and macOS, the PDF app's PrintTo verb on Windows.
+    /// Blocking; call from `spawn_blocking`.
+    pub fn print_pdf(path: &Path, options: &SilentPrintOptions) -> Result<()> {
+        if !path.is_file() {
+            return Err(anyhow::anyhow!("{} does not exist", path.display()));
+        }
+
+        for command in Self::print_commands(path, options, cfg!(windows))? {
+            let output = Command::new(&command.program)
+                .args(&command.args)
+                .output()
+                .with_context(|| format!("Failed to run {}", command.program))?;
+            if !output.status.success() {
+                return Err(anyhow::anyhow!(
+                    "Printing to {} failed: {}",
+                    options.printer_name,
+                    String::from_utf8_lossy(&output.stderr).trim()
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Printers the system knows about
+    pub fn list_printers() -> Result<Vec<String>> {
+        let (program, args): (&str, &[&str]) = if cfg!(windows) {
+            ("powershell", &["-NoProfile", "-Command", "Get-Printer | Select-Object -ExpandProperty Name"])
+        } else {
+            ("lpstat", &["-e"])
+        };
+
+        let output = Command::new(program)
+            .args(args)
+            .output()
+            .with_context(|| format!("Failed to run {}", program))?;
+        if !output.status.success() {
+            return Err(anyhow::anyhow!(
+                "{} failed: {}",
+                program,
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(str::to_string)
+            .collect())
+    }
+
+    /// The commands that print the file. Windows' PrintTo has no copies
+    /// option, so it runs once per copy; paper size there comes from the
+    /// PDF's own page size.
+    pub fn print_commands(path: &Path, options: &SilentPrintOptions, windows: bool) -> Result<Vec<PrintCommand>> {
+        let printer = options.printer_name.trim();
+        if printer.is_empty() {
+            return Err(anyhow::anyhow!("No printer selected"));
+        }
+        let copies = options.copies.unwrap_or(1);
+        if !(1..=MAX_COPIES).contains(&copies) {
+            return Err(anyhow::anyhow!("Copies must be between 1 and {}", MAX_COPIES));
+        }
+        let paper_size = options.paper_size.as_deref().map(str::trim).filter(|size| !size.is_empty());
+        if let Some(size) = paper_size {
+            if !size.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-')) {
+                return Err(anyhow::anyhow!("Invalid paper size: {}", size));
+            }
+        }
+        let file = path.to_string_lossy().to_string();
+
+        if windows {
+            // PowerShell single-quoted strings only need ' doubled
+            let quote = |value: &str| format!("'{}'", value.replace('\'', "''"));
+            let script = format!(
+                "Start-Process -FilePath {} -Verb PrintTo -ArgumentList {} -WindowStyle Hidden -Wait",
+                quote(&file),
+                quote(&format!("\"{}\"", printer))
+            );
+            let command = PrintCommand {
+                program: "powershell".to_string(),
+                args: vec!["-NoProfile".to_string(), "-Command".to_string(), script],
+            };
+            return Ok(vec![command; copies as usize]);
+        }
+
+        let mut args = vec!["-d".to_string(), printer.to_string(), "-n".to_string(), copies.to_string()];
+        if let Some(size) = paper_size {
+            args.extend(["-o".to_string(), format!("media={}", size)]);
+        }
+        args.extend(["--".to_string(), file]);
+
+        Ok(vec![PrintCommand { program: "lp".to_string(), args }])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options(copies: Option<u32>, paper_size: Option<&str>) -> SilentPrintOptions {
+        SilentPrintOptions {
+            printer_name: "Counter Laser".to_string(),
+            copies,
+            paper_size: paper_size.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_print_commands_per_platform() {
+        let path = Path::new("/data/invoice_INV1_a5.pdf");
+
+        let lp = DocumentPrinterService::print_commands(path, &options(Some(2), Some("A5")), false).unwrap();
+        assert_eq!(lp.len(), 1);
+        assert_eq!(lp[0].program, "lp");
+        assert_eq!(
+            lp[0].args,
+            ["-d", "Counter Laser", "-n", "2", "-o", "media=A5", "--", "/data/invoice_INV1_a5.pdf"]
+        );
+
+        let path = Path::new(r"C:\Users\O'Neil\invoice.pdf");
+        let windows = DocumentPrinterService::print_commands(path, &options(Some(3), None), true).unwrap();
+        assert_eq!(windows.len(), 3);
+        assert_eq!(
+            windows[0].args[2],
+            r#"Start-Process -FilePath 'C:\Users\O''Neil\invoice.pdf' -Verb PrintTo -ArgumentList '"Counter Laser"' -WindowStyle Hidden -Wait"#
+        );
+    }
+
+    #[test]
+    fn test_print_commands_reject_bad_options() {
+        let path = Path::new("/data/invoice.pdf");
+
+        assert!(DocumentPrinterService::print_commands(path, &options(Some(0), None), false).is_err());
+        assert!(DocumentPrinterService::print_commands(path, &options(Some(MAX_COPIES + 1), None), false).is_err());
+        assert!(DocumentPrinterService::print_commands(path, &options(None, Some("A4 -o x")), false).is_err());
+        let unnamed = SilentPrintOptions { printer_name: " ".to_string(), ..options(None, None) };
+        assert!(DocumentPrinterService::print_commands(path, &unnamed, false).is_err());
+    }
+}
@@ -0,0 +1,371 @@
+use crate::models::{ApiError, ApiResult};
+use crate::services::localization::Locale;
+use crate::services::{ShapedGlyph, TextShaper};
+use ab_glyph_rasterizer::{point, Point, Rasterizer};
+use std::path::{Path, PathBuf};
+use ttf_parser::{Face, GlyphId, OutlineBuilder};
+
+// DejaVu Sans covers Latin text and the rupee sign. See assets/fonts/LICENSE.
+pub(crate) const REGULAR_FONT: &[u8] = include_bytes!("../templates/assets/fonts/DejaVuSans.ttf");
+pub(crate) const BOLD_FONT: &[u8] = include_bytes!("../templates/assets/fonts/DejaVuSans-Bold.ttf");
+
+/// DejaVu has no Devanagari, so Hindi text is set in the first of these
+/// fonts found on the machine (after any font in the app's Fonts folder).
+const DEVANAGARI_FONT_PATHS: &[&str] = &[
+    "C:\\Windows\\Fonts\\Nirmala.ttf",
+    "C:\\Windows\\Fonts\\mangal.ttf",
+    "/usr/share/fonts/truetype/noto/NotoSansDevanagari-Regular.ttf",
+    "/usr/share/fonts/opentype/noto/NotoSansDevanagari-Regular.ttf",
+    "/usr/share/fonts/truetype/lohit-devanagari/Lohit-Devanagari.ttf",
+    "/Library/Fonts/NotoSansDevanagari-Regular.ttf",
+];
+
+pub(crate) const PT_TO_MM: f32 = 25.4 / 72.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum FontKind {
+    Regular,
+    Bold,
+    Devanagari,
+}
+
+/// Metrics for the fonts a document is set in. Text the main font has no
+/// glyphs for falls back to the Devanagari font when one is available.
+pub(crate) struct Fonts<'a> {
+    regular: Face<'a>,
+    bold: Face<'a>,
+    devanagari: Option<rustybuzz::Face<'a>>,
+}
+
+impl<'a> Fonts<'a> {
+    pub(crate) fn load(devanagari: Option<&'a [u8]>) -> ApiResult<Self> {
+        let parse = |data: &'a [u8]| {
+            Face::parse(data, 0).map_err(|e| ApiError {
+                message: format!("Failed to read font: {}", e),
+                code: Some("PDF_FONT_ERROR".to_string()),
+            })
+        };
+
+        Ok(Self {
+            regular: parse(REGULAR_FONT)?,
+            bold: parse(BOLD_FONT)?,
+            devanagari: devanagari.map(|data| parse(data).map(rustybuzz::Face::from_face)).transpose()?,
+        })
+    }
+
+    /// First readable font with Devanagari glyphs, checking `font_dir`
+    /// before the usual system locations
+    pub(crate) fn find_devanagari(font_dir: Option<&Path>) -> Option<Vec<u8>> {
+        let mut candidates: Vec<PathBuf> = font_dir
+            .and_then(|dir| std::fs::read_dir(dir).ok())
+            .map(|entries| {
+                entries
+                    .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                    .filter(|path| {
+                        path.extension()
+                            .and_then(|ext| ext.to_str())
+                            .is_some_and(|ext| ext.eq_ignore_ascii_case("ttf") || ext.eq_ignore_ascii_case("otf"))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        candidates.sort();
+        candidates.extend(DEVANAGARI_FONT_PATHS.iter().map(PathBuf::from));
+
+        candidates.into_iter().find_map(|path| {
+            let data = std::fs::read(&path).ok()?;
+            let has_glyphs = Face::parse(&data, 0).ok()?.glyph_index('क').is_some();
+            if has_glyphs {
+                log::info!("Using Devanagari font {}", path.display());
+                Some(data)
+            } else {
+                None
+            }
+        })
+    }
+
+    fn face(&self, kind: FontKind) -> &Face<'a> {
+        match kind {
+            FontKind::Regular => &self.regular,
+            FontKind::Bold => &self.bold,
+            FontKind::Devanagari => self.devanagari.as_deref().unwrap_or(&self.regular),
+        }
+    }
+
+    pub(crate) fn units_per_em(&self, kind: FontKind) -> f32 {
+        self.face(kind).units_per_em() as f32
+    }
+
+    /// Whether every letter of `text` has a glyph in one of the fonts
+    pub(crate) fn can_draw(&self, text: &str) -> bool {
+        text.chars().filter(|c| !c.is_whitespace()).all(|c| {
+            self.regular.glyph_index(c).is_some()
+                || self.devanagari.as_ref().is_some_and(|face| face.glyph_index(c).is_some())
+        })
+    }
+
+    /// Whether every label and the amount in words can be set in `locale`
+    pub(crate) fn can_set(&self, locale: Locale) -> bool {
+        locale.catalog().values().all(|label| self.can_draw(label)) && self.can_draw(&locale.amount_in_words(1234.5))
+    }
+
+    /// Split text into runs that are each set in a single font
+    pub(crate) fn runs(&self, text: &str, bold: bool) -> Vec<(FontKind, String)> {
+        let primary = if bold { FontKind::Bold } else { FontKind::Regular };
+        let mut runs: Vec<(FontKind, String)> = Vec::new();
+
+        for c in text.chars() {
+            let kind = match (&runs.last(), &self.devanagari) {
+                (Some((current, _)), _) if c.is_whitespace() => *current,
+                (_, Some(devanagari))
+                    if self.face(primary).glyph_index(c).is_none() && devanagari.glyph_index(c).is_some() =>
+                {
+                    FontKind::Devanagari
+                }
+                _ => primary,
+            };
+
+            match runs.last_mut() {
+                Some((current, run)) if *current == kind => run.push(c),
+                _ => runs.push((kind, c.to_string())),
+            }
+        }
+
+        runs
+    }
+
+    /// Glyphs for a run, shaped when it is set in the Devanagari font
+    pub(crate) fn glyphs(&self, kind: FontKind, text: &str) -> Vec<ShapedGlyph> {
+        match (kind, &self.devanagari) {
+            (FontKind::Devanagari, Some(face)) => TextShaper::shape(face, text),
+            _ => {
+                let face = self.face(kind);
+                text.chars()
+                    .map(|c| {
+                        let id = face.glyph_index(c).unwrap_or(GlyphId(0));
+                        ShapedGlyph {
+                            id,
+                            x_advance: face.glyph_hor_advance(id).unwrap_or(0) as i32,
+                            x_offset: 0,
+                            y_offset: 0,
+                        }
+                    })
+                    .collect()
+            }
+        }
+    }
+
+    /// Width of a run with an em of `em`, in the em's own unit
+    fn run_advance(&self, kind: FontKind, text: &str, em: f32) -> f32 {
+        let face = self.face(kind);
+        let units: i32 = self.glyphs(kind, text).iter().map(|glyph| glyph.x_advance).sum();
+
+        units as f32 / face.units_per_em() as f32 * em
+    }
+
+    /// Width of a run in mm at the given point size
+    pub(crate) fn run_width(&self, kind: FontKind, text: &str, size: f32) -> f32 {
+        self.run_advance(kind, text, size * PT_TO_MM)
+    }
+
+    /// Width of text with an em of `em`, in the em's own unit
+    pub(crate) fn advance(&self, text: &str, em: f32, bold: bool) -> f32 {
+        self.runs(text, bold)
+            .iter()
+            .map(|(kind, run)| self.run_advance(*kind, run, em))
+            .sum()
+    }
+
+    pub(crate) fn width(&self, text: &str, size: f32, bold: bool) -> f32 {
+        self.advance(text, size * PT_TO_MM, bold)
+    }
+
+    /// Break text into lines no wider than `width` mm, splitting on spaces
+    /// and only inside a word when the word alone is too wide
+    pub(crate) fn wrap(&self, text: &str, width: f32, size: f32, bold: bool) -> Vec<String> {
+        self.wrap_em(text, width, size * PT_TO_MM, bold)
+    }
+
+    /// Break text into lines no wider than `width`, in the unit of `em`
+    pub(crate) fn wrap_em(&self, text: &str, width: f32, em: f32, bold: bool) -> Vec<String> {
+        let mut lines = Vec::new();
+        let mut line = String::new();
+
+        for word in text.split_whitespace() {
+            let candidate = if line.is_empty() { word.to_string() } else { format!("{} {}", line, word) };
+            if self.advance(&candidate, em, bold) <= width {
+                line = candidate;
+                continue;
+            }
+
+            if !line.is_empty() {
+                lines.push(std::mem::take(&mut line));
+            }
+
+            for c in word.chars() {
+                line.push(c);
+                if self.advance(&line, em, bold) > width && line.chars().count() > 1 {
+                    line.pop();
+                    lines.push(std::mem::replace(&mut line, c.to_string()));
+                }
+            }
+        }
+
+        if !line.is_empty() || lines.is_empty() {
+            lines.push(line);
+        }
+
+        lines
+    }
+
+    /// Draw one line of text as a black and white bitmap `width` pixels
+    /// wide, for printers whose own fonts can't set it. Each piece of text
+    /// starts the given number of pixels in; `em` is the font size in pixels.
+    pub(crate) fn rasterize(&self, pieces: &[(f32, &str)], width: usize, em: f32, bold: bool) -> Bitmap {
+        // Room for the tallest font in use, so matras above the headline fit
+        let faces = std::iter::once(&self.regular).chain(self.devanagari.as_deref());
+        let ascender = faces.clone().map(|face| face.ascender() as f32 / face.units_per_em() as f32).fold(0.0, f32::max);
+        let descender = faces.map(|face| -face.descender() as f32 / face.units_per_em() as f32).fold(0.0, f32::max);
+        let baseline = (ascender * em).ceil();
+        let height = (baseline + descender * em).ceil() as usize;
+
+        let mut raster = Rasterizer::new(width, height);
+        for &(x, text) in pieces {
+            let mut x = x;
+            for (kind, run) in self.runs(text, bold) {
+                let face = self.face(kind);
+                let scale = em / face.units_per_em() as f32;
+                for glyph in self.glyphs(kind, &run) {
+                    let mut pen = Pen {
+                        raster: &mut raster,
+                        x: x + glyph.x_offset as f32 * scale,
+                        baseline: baseline - glyph.y_offset as f32 * scale,
+                        scale,
+                        width,
+                        height,
+                        start: point(0.0, 0.0),
+                        last: point(0.0, 0.0),
+                    };
+                    face.outline_glyph(glyph.id, &mut pen);
+                    x += glyph.x_advance as f32 * scale;
+                }
+            }
+        }
+
+        let mut pixels = vec![false; width * height];
+        raster.for_each_pixel(|index, coverage| pixels[index] = coverage >= 0.5);
+        Bitmap { width, height, pixels }
+    }
+}
+
+/// Feeds glyph outlines, in font units with y up, to the rasterizer in
+/// pixels with y down
+struct Pen<'r> {
+    raster: &'r mut Rasterizer,
+    x: f32,
+    baseline: f32,
+    scale: f32,
+    width: usize,
+    height: usize,
+    start: Point,
+    last: Point,
+}
+
+impl Pen<'_> {
+    /// Outlines past the bitmap's edges are clipped to them
+    fn point(&self, x: f32, y: f32) -> Point {
+        point(
+            (self.x + x * self.scale).clamp(0.0, self.width as f32 - 1.0),
+            (self.baseline - y * self.scale).clamp(0.0, self.height as f32),
+        )
+    }
+}
+
+impl OutlineBuilder for Pen<'_> {
+    fn move_to(&mut self, x: f32, y: f32) {
+        self.start = self.point(x, y);
+        self.last = self.start;
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        let to = self.point(x, y);
+        self.raster.draw_line(self.last, to);
+        self.last = to;
+    }
+
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        let to = self.point(x, y);
+        self.raster.draw_quad(self.last, self.point(x1, y1), to);
+        self.last = to;
+    }
+
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        let to = self.point(x, y);
+        self.raster.draw_cubic(self.last, self.point(x1, y1), self.point(x2, y2), to);
+        self.last = to;
+    }
+
+    fn close(&mut self) {
+        if self.last != self.start {
+            self.raster.draw_line(self.last, self.start);
+        }
+        self.last = self.start;
+    }
+}
+
+/// A black and white image, row by row from the top left
+pub struct Bitmap {
+    pub width: usize,
+    pub height: usize,
+    pixels: Vec<bool>,
+}
+
+impl Bitmap {
+    pub fn is_set(&self, x: usize, y: usize) -> bool {
+        self.pixels[y * self.width + x]
+    }
+
+    /// Rows packed eight pixels to a byte, leftmost pixel in the high bit
+    pub fn packed_rows(&self) -> Vec<u8> {
+        let row_bytes = (self.width + 7) / 8;
+        let mut bytes = vec![0u8; row_bytes * self.height];
+        for y in 0..self.height {
+            for x in (0..self.width).filter(|&x| self.is_set(x, y)) {
+                bytes[y * row_bytes + x / 8] |= 0x80 >> (x % 8);
+            }
+        }
+        bytes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wrap_keeps_lines_within_width() {
+        let fonts = Fonts::load(None).unwrap();
+        let text = "Dry Clean - Silk Saree with Heavy Zari Border ₹1,250.00 INV202510001-1-1-ABCDEFGHIJ";
+        let lines = fonts.wrap(text, 25.0, 9.0, false);
+
+        assert!(lines.len() > 2);
+        assert!(lines.iter().all(|line| fonts.width(line, 9.0, false) <= 25.0));
+        assert_eq!(lines.join(" ").replace(' ', ""), text.replace(' ', ""));
+        assert!(fonts.can_draw("₹"));
+        assert!(!fonts.can_draw("कुल राशि"));
+    }
+
+    #[test]
+    fn test_rasterize_draws_where_the_text_is() {
+        let fonts = Fonts::load(None).unwrap();
+        let bitmap = fonts.rasterize(&[(0.0, "Total"), (300.0, "Rs. 354.00")], 400, 24.0, true);
+
+        assert_eq!(bitmap.width, 400);
+        assert!(bitmap.height >= 24);
+        let inked = |from: usize, to: usize| (0..bitmap.height).any(|y| (from..to).any(|x| bitmap.is_set(x, y)));
+        assert!(inked(0, 60));
+        assert!(!inked(100, 290));
+        assert!(inked(300, 400));
+        assert_eq!(bitmap.packed_rows().len(), 50 * bitmap.height);
+    }
+}
@@ -0,0 +1,572 @@
+use crate::models::{ClothingTag, TagScanEvent, TagScanResult, TagLookupResult, UpdateTagAttributesRequest};
+use sqlx::Row;
+use crate::database::DatabaseManager;
+use anyhow::{Result, Context};
+
+/// Where a garment is in the store's workflow
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GarmentStage {
+    Received,
+    Sorted,
+    Washing,
+    Drying,
+    Ironing,
+    Qc,
+    Packed,
+    Delivered,
+    Missing,
+}
+
+impl GarmentStage {
+    /// Regular workflow order; `Missing` sits outside it
+    const WORKFLOW: [GarmentStage; 8] = [
+        GarmentStage::Received,
+        GarmentStage::Sorted,
+        GarmentStage::Washing,
+        GarmentStage::Drying,
+        GarmentStage::Ironing,
+        GarmentStage::Qc,
+        GarmentStage::Packed,
+        GarmentStage::Delivered,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GarmentStage::Received => "received",
+            GarmentStage::Sorted => "sorted",
+            GarmentStage::Washing => "washing",
+            GarmentStage::Drying => "drying",
+            GarmentStage::Ironing => "ironing",
+            GarmentStage::Qc => "qc",
+            GarmentStage::Packed => "packed",
+            GarmentStage::Delivered => "delivered",
+            GarmentStage::Missing => "missing",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<GarmentStage> {
+        let value = value.trim().to_lowercase();
+        if value == GarmentStage::Missing.as_str() {
+            return Some(GarmentStage::Missing);
+        }
+        Self::WORKFLOW.iter().copied().find(|stage| stage.as_str() == value)
+    }
+
+    /// Stage a plain scan moves the garment to. Delivered garments have
+    /// nowhere to go, and a missing garment needs an explicit stage when found.
+    pub fn next(&self) -> Option<GarmentStage> {
+        let position = Self::WORKFLOW.iter().position(|stage| stage == self)?;
+        Self::WORKFLOW.get(position + 1).copied()
+    }
+
+    fn is_packed(&self) -> bool {
+        matches!(self, GarmentStage::Packed | GarmentStage::Delivered)
+    }
+
+    /// Invoice status implied by the stages of all its tags: ready once every
+    /// garment is packed, in progress once any has moved past intake
+    pub fn invoice_status(stages: &[GarmentStage]) -> &'static str {
+        if stages.is_empty() || stages.iter().all(|stage| *stage == GarmentStage::Received) {
+            "pending"
+        } else if stages.iter().all(|stage| stage.is_packed()) {
+            "ready"
+        } else {
+            "in-progress"
+        }
+    }
+}
+
+/// Cap on partial-match lookups so a short query doesn't return every tag
+const MAX_LOOKUP_RESULTS: i64 = 50;
+
+pub struct GarmentTrackingService;
+
+impl GarmentTrackingService {
+    /// Record a scan of a tag: move it to `stage`, or to the next workflow
+    /// stage when none is given, then roll the change up to the invoice
+    pub async fn scan_tag(
+        db: &DatabaseManager,
+        tag_code: &str,
+        stage: Option<&str>,
+        scanned_by: Option<&str>,
+        notes: Option<&str>,
+    ) -> Result<TagScanResult> {
+        let mut tx = db.get_pool().begin().await.context("Failed to start transaction")?;
+
+        let tag_code = Self::normalize_scanned_code(tag_code);
+        let tag = sqlx::query_as::<_, ClothingTag>("SELECT * FROM clothing_tags WHERE tag_code = ?")
+            .bind(&tag_code)
+            .fetch_optional(&mut *tx)
+            .await
+            .context("Failed to look up tag")?
+            .ok_or_else(|| anyhow::anyhow!("No tag found with code {}", tag_code))?;
+
+        if tag.is_void {
+            return Err(anyhow::anyhow!("Tag {} was voided; the piece is no longer on its invoice", tag.tag_code));
+        }
+
+        let current = GarmentStage::parse(&tag.status)
+            .ok_or_else(|| anyhow::anyhow!("Tag {} has unknown status {}", tag.tag_code, tag.status))?;
+
+        let target = match stage {
+            Some(stage) => GarmentStage::parse(stage)
+                .ok_or_else(|| anyhow::anyhow!("Unknown garment stage: {}", stage))?,
+            None => current.next().ok_or_else(|| match current {
+                GarmentStage::Missing => anyhow::anyhow!("Tag {} is marked missing; scan it with the stage it was found at", tag.tag_code),
+                _ => anyhow::anyhow!("Tag {} has already been delivered", tag.tag_code),
+            })?,
+        };
+
+        let event = sqlx::query_as::<_, TagScanEvent>(
+            r#"
+            INSERT INTO tag_scan_events (tag_id, from_status, to_status, scanned_by, notes)
+            VALUES (?, ?, ?, ?, ?)
+            RETURNING *
+            "#
+        )
+        .bind(tag.id)
+        .bind(current.as_str())
+        .bind(target.as_str())
+        .bind(scanned_by)
+        .bind(notes)
+        .fetch_one(&mut *tx)
+        .await
+        .context("Failed to record scan event")?;
+
+        let tag = sqlx::query_as::<_, ClothingTag>(
+            "UPDATE clothing_tags SET status = ?, status_updated_at = ? WHERE id = ? RETURNING *"
+        )
+        .bind(target.as_str())
+        .bind(&event.scanned_at)
+        .bind(tag.id)
+        .fetch_one(&mut *tx)
+        .await
+        .context("Failed to update tag status")?;
+
+        let invoice_status = Self::roll_up_invoice_status(&mut tx, tag.invoice_id).await?;
+
+        tx.commit().await.context("Failed to commit scan")?;
+
+        Ok(TagScanResult { tag, event, invoice_status })
+    }
+
+    pub async fn get_scan_history(db: &DatabaseManager, tag_id: i64) -> Result<Vec<TagScanEvent>> {
+        sqlx::query_as::<_, TagScanEvent>(
+            "SELECT * FROM tag_scan_events WHERE tag_id = ? ORDER BY scanned_at, id"
+        )
+        .bind(tag_id)
+        .fetch_all(db.get_pool())
+        .await
+        .context("Failed to fetch scan history")
+    }
+
+    /// Clean up scanner input: surrounding whitespace and control characters,
+    /// AIM symbology prefixes like `]C0`, stray framing characters and case
+    pub fn normalize_scanned_code(raw: &str) -> String {
+        let mut code = raw.trim().to_uppercase();
+        if let Some(rest) = code.strip_prefix(']') {
+            code = rest.chars().skip(2).collect();
+        }
+        code.trim_matches(|c: char| !c.is_ascii_alphanumeric()).to_string()
+    }
+
+    /// Find tags by scanned or typed code. Tries an exact tag code, then an
+    /// invoice number (all of its tags), then tag codes starting with the
+    /// query, then tag codes or invoice numbers containing it.
+    pub async fn lookup_tags(db: &DatabaseManager, query: &str) -> Result<Vec<TagLookupResult>> {
+        let code = Self::normalize_scanned_code(query);
+        if code.len() < 3 {
+            return Err(anyhow::anyhow!("Enter at least 3 characters of a tag code or invoice number"));
+        }
+
+        let mut tag_ids: Vec<i64> = sqlx::query_scalar("SELECT id FROM clothing_tags WHERE tag_code = ?")
+            .bind(&code)
+            .fetch_all(db.get_pool())
+            .await
+            .context("Failed to look up tag code")?;
+
+        if tag_ids.is_empty() {
+            tag_ids = sqlx::query_scalar(
+                r#"
+                SELECT ct.id FROM clothing_tags ct
+                JOIN invoices i ON i.id = ct.invoice_id
+                WHERE i.invoice_no = ? COLLATE NOCASE
+                ORDER BY ct.overall_piece_number
+                "#
+            )
+            .bind(&code)
+            .fetch_all(db.get_pool())
+            .await
+            .context("Failed to look up invoice tags")?;
+        }
+
+        if tag_ids.is_empty() {
+            // Range scan so the tag code index is used; codes are ASCII, so
+            // every code with this prefix sorts below prefix + DEL
+            tag_ids = sqlx::query_scalar(
+                "SELECT id FROM clothing_tags WHERE tag_code >= ? AND tag_code < ? ORDER BY tag_code LIMIT ?"
+            )
+            .bind(&code)
+            .bind(format!("{}\u{7f}", code))
+            .bind(MAX_LOOKUP_RESULTS)
+            .fetch_all(db.get_pool())
+            .await
+            .context("Failed to search tag codes")?;
+        }
+
+        if tag_ids.is_empty() {
+            let pattern = Self::like_pattern(&code);
+            tag_ids = sqlx::query_scalar(
+                r#"
+                SELECT ct.id FROM clothing_tags ct
+                JOIN invoices i ON i.id = ct.invoice_id
+                WHERE ct.tag_code LIKE ?1 ESCAPE '\' OR i.invoice_no LIKE ?1 ESCAPE '\'
+                ORDER BY ct.created_at DESC, ct.id DESC
+                LIMIT ?2
+                "#
+            )
+            .bind(&pattern)
+            .bind(MAX_LOOKUP_RESULTS)
+            .fetch_all(db.get_pool())
+            .await
+            .context("Failed to search tags")?;
+        }
+
+        let mut results = Vec::with_capacity(tag_ids.len());
+        for tag_id in tag_ids {
+            results.push(Self::get_tag_details(db, tag_id).await?);
+        }
+
+        Ok(results)
+    }
+
+    /// Search tags by garment attributes, e.g. "blue arrow" or "stain". Every
+    /// word has to match the type, colour, brand, damage notes or tag code.
+    pub async fn search_tags(db: &DatabaseManager, query: &str) -> Result<Vec<TagLookupResult>> {
+        let words: Vec<&str> = query.split_whitespace().collect();
+        if words.is_empty() {
+            return Err(anyhow::anyhow!("Enter something to search for"));
+        }
+
+        let conditions = vec![
+            r#"(COALESCE(garment_type, '') || ' ' || COALESCE(colour, '') || ' ' || COALESCE(brand, '') || ' ' ||
+                COALESCE(damage_notes, '') || ' ' || tag_code) LIKE ? ESCAPE '\'"#;
+            words.len()
+        ];
+        let sql = format!(
+            "SELECT id FROM clothing_tags WHERE {} ORDER BY created_at DESC, id DESC LIMIT ?",
+            conditions.join(" AND ")
+        );
+
+        let mut search = sqlx::query_scalar(&sql);
+        for word in &words {
+            search = search.bind(Self::like_pattern(word));
+        }
+        let tag_ids: Vec<i64> = search
+            .bind(MAX_LOOKUP_RESULTS)
+            .fetch_all(db.get_pool())
+            .await
+            .context("Failed to search tags")?;
+
+        let mut results = Vec::with_capacity(tag_ids.len());
+        for tag_id in tag_ids {
+            results.push(Self::get_tag_details(db, tag_id).await?);
+        }
+
+        Ok(results)
+    }
+
+    /// Record the garment's type, colour, brand and intake condition on its tag
+    pub async fn update_tag_attributes(
+        db: &DatabaseManager,
+        request: &UpdateTagAttributesRequest,
+    ) -> Result<ClothingTag> {
+        let clean = |value: &Option<String>| {
+            value.as_deref().map(str::trim).filter(|value| !value.is_empty()).map(str::to_string)
+        };
+        let photo_paths: Vec<String> = request.photo_paths
+            .iter()
+            .flatten()
+            .map(|path| path.trim().to_string())
+            .filter(|path| !path.is_empty())
+            .collect();
+
+        sqlx::query_as::<_, ClothingTag>(
+            r#"
+            UPDATE clothing_tags
+            SET garment_type = ?, colour = ?, brand = ?, damage_notes = ?, photo_paths = ?
+            WHERE id = ?
+            RETURNING *
+            "#
+        )
+        .bind(clean(&request.garment_type))
+        .bind(clean(&request.colour))
+        .bind(clean(&request.brand))
+        .bind(clean(&request.damage_notes))
+        .bind(serde_json::to_string(&photo_paths)?)
+        .bind(request.tag_id)
+        .fetch_optional(db.get_pool())
+        .await
+        .context("Failed to update tag attributes")?
+        .ok_or_else(|| anyhow::anyhow!("Tag {} not found", request.tag_id))
+    }
+
+    /// `%term%` with LIKE wildcards in the term escaped (tag codes contain `_`)
+    fn like_pattern(term: &str) -> String {
+        format!("%{}%", term.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_"))
+    }
+
+    async fn get_tag_details(db: &DatabaseManager, tag_id: i64) -> Result<TagLookupResult> {
+        let tag = sqlx::query_as::<_, ClothingTag>("SELECT * FROM clothing_tags WHERE id = ?")
+            .bind(tag_id)
+            .fetch_one(db.get_pool())
+            .await
+            .context("Failed to fetch tag")?;
+
+        let row = sqlx::query(
+            r#"
+            SELECT i.invoice_no, i.status, i.delivery_datetime,
+                   c.name AS customer_name, c.phone AS customer_phone,
+                   s.name AS service_name, sv.name AS variant_name
+            FROM invoice_items ii
+            JOIN invoices i ON i.id = ii.invoice_id
+            JOIN customers c ON c.id = i.customer_id
+            JOIN services s ON s.id = ii.service_id
+            LEFT JOIN service_variants sv ON sv.id = ii.variant_id
+            WHERE ii.id = ?
+            "#
+        )
+        .bind(tag.invoice_item_id)
+        .fetch_one(db.get_pool())
+        .await
+        .context("Failed to fetch tag invoice details")?;
+
+        let addons: Vec<String> = sqlx::query_scalar(
+            r#"
+            SELECT sa.name FROM invoice_item_addons iia
+            JOIN service_addons sa ON sa.id = iia.addon_id
+            WHERE iia.invoice_item_id = ?
+            ORDER BY iia.id
+            "#
+        )
+        .bind(tag.invoice_item_id)
+        .fetch_all(db.get_pool())
+        .await
+        .context("Failed to fetch tag addons")?;
+
+        let service_name: String = row.get("service_name");
+        let service_name = match row.get::<Option<String>, _>("variant_name") {
+            Some(variant) => format!("{} - {}", service_name, variant),
+            None => service_name,
+        };
+
+        Ok(TagLookupResult {
+            tag,
+            invoice_no: row.get("invoice_no"),
+            invoice_status: row.get("status"),
+            customer_name: row.get("customer_name"),
+            customer_phone: row.get("customer_phone"),
+            service_name,
+            addons,
+            delivery_datetime: row.get("delivery_datetime"),
+        })
+    }
+
+    /// Update the invoice's workflow status from its tags. Invoices that have
+    /// moved on to completed, paid or cancelled are left alone.
+    async fn roll_up_invoice_status(
+        conn: &mut sqlx::SqliteConnection,
+        invoice_id: i64,
+    ) -> Result<String> {
+        let statuses: Vec<String> = sqlx::query_scalar("SELECT status FROM clothing_tags WHERE invoice_id = ? AND is_void = 0")
+            .bind(invoice_id)
+            .fetch_all(&mut *conn)
+            .await
+            .context("Failed to fetch invoice tag statuses")?;
+
+        let stages: Vec<GarmentStage> = statuses
+            .iter()
+            .map(|status| GarmentStage::parse(status).unwrap_or(GarmentStage::Received))
+            .collect();
+        let rolled_up = GarmentStage::invoice_status(&stages);
+
+        sqlx::query(
+            r#"
+            UPDATE invoices SET status = ?, updated_at = CURRENT_TIMESTAMP
+            WHERE id = ? AND status IN ('pending', 'in-progress', 'ready') AND status != ?
+            "#
+        )
+        .bind(rolled_up)
+        .bind(invoice_id)
+        .bind(rolled_up)
+        .execute(&mut *conn)
+        .await
+        .context("Failed to update invoice status")?;
+
+        let status: String = sqlx::query_scalar("SELECT status FROM invoices WHERE id = ?")
+            .bind(invoice_id)
+            .fetch_one(&mut *conn)
+            .await
+            .context("Failed to fetch invoice status")?;
+
+        Ok(status)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::test_fixtures::{add_invoice_item, invoice_db};
+    use crate::models::TagSettings;
+    use crate::services::html_generator::{test_data::sample_invoice, HtmlGenerator};
+    use crate::services::localization::Locale;
+    use crate::services::tag_generator::TagGeneratorService;
+    use crate::services::template_engine::TemplateEngine;
+
+    /// An invoice with one shirt and two sarees, tagged
+    async fn tagged_invoice() -> (DatabaseManager, Vec<ClothingTag>) {
+        let db = invoice_db("INV9_00", "Garment Test").await;
+        for pieces in [1, 2] {
+            add_invoice_item(&db, pieces).await;
+        }
+
+        let tags = TagGeneratorService::generate_tags_for_invoice(&db, 900).await.unwrap();
+        (db, tags)
+    }
+
+    fn attributes(tag_id: i64, garment_type: &str, colour: &str, brand: &str, damage_notes: &str) -> UpdateTagAttributesRequest {
+        let value = |value: &str| Some(value.to_string());
+        UpdateTagAttributesRequest {
+            tag_id,
+            garment_type: value(garment_type),
+            colour: value(colour),
+            brand: value(brand),
+            damage_notes: value(damage_notes),
+            photo_paths: None,
+        }
+    }
+
+    #[test]
+    fn test_stage_progression() {
+        assert_eq!(GarmentStage::Received.next(), Some(GarmentStage::Sorted));
+        assert_eq!(GarmentStage::Qc.next(), Some(GarmentStage::Packed));
+        assert_eq!(GarmentStage::Delivered.next(), None);
+        assert_eq!(GarmentStage::Missing.next(), None);
+        assert_eq!(GarmentStage::parse(" QC "), Some(GarmentStage::Qc));
+        assert_eq!(GarmentStage::parse("lost"), None);
+    }
+
+    #[test]
+    fn test_invoice_status_roll_up() {
+        use GarmentStage::*;
+
+        assert_eq!(GarmentStage::invoice_status(&[Received, Received]), "pending");
+        assert_eq!(GarmentStage::invoice_status(&[Packed, Washing]), "in-progress");
+        assert_eq!(GarmentStage::invoice_status(&[Packed, Missing]), "in-progress");
+        assert_eq!(GarmentStage::invoice_status(&[Packed, Delivered]), "ready");
+    }
+
+    #[test]
+    fn test_normalize_scanned_code() {
+        assert_eq!(GarmentTrackingService::normalize_scanned_code(" inv202510__001-17-3\r\n"), "INV202510__001-17-3");
+        assert_eq!(GarmentTrackingService::normalize_scanned_code("]C0INV202510__001-17-3"), "INV202510__001-17-3");
+        assert_eq!(GarmentTrackingService::normalize_scanned_code("*INV202510__001*"), "INV202510__001");
+        assert_eq!(GarmentTrackingService::normalize_scanned_code("\u{2}17-3\u{3}"), "17-3");
+    }
+
+    #[tokio::test]
+    async fn test_update_tag_attributes() {
+        let (db, tags) = tagged_invoice().await;
+
+        let request = UpdateTagAttributesRequest {
+            photo_paths: Some(vec![" photos/1.jpg ".to_string(), "  ".to_string()]),
+            ..attributes(tags[0].id, " Shirt ", "Blue", "", "Collar stain")
+        };
+        let tag = GarmentTrackingService::update_tag_attributes(&db, &request).await.unwrap();
+        assert_eq!(tag.garment_type.as_deref(), Some("Shirt"));
+        assert_eq!(tag.colour.as_deref(), Some("Blue"));
+        assert_eq!(tag.brand, None);
+        assert_eq!(tag.damage_notes.as_deref(), Some("Collar stain"));
+        assert_eq!(tag.photo_paths, r#"["photos/1.jpg"]"#);
+
+        // Attributes are replaced, not merged
+        let cleared = UpdateTagAttributesRequest {
+            tag_id: tags[0].id,
+            garment_type: Some("Shirt".to_string()),
+            colour: None,
+            brand: None,
+            damage_notes: None,
+            photo_paths: None,
+        };
+        let tag = GarmentTrackingService::update_tag_attributes(&db, &cleared).await.unwrap();
+        assert_eq!((tag.colour, tag.damage_notes, tag.photo_paths.as_str()), (None, None, "[]"));
+
+        let missing = attributes(999, "Shirt", "", "", "");
+        assert!(GarmentTrackingService::update_tag_attributes(&db, &missing).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_search_tags_by_attributes() {
+        let (db, tags) = tagged_invoice().await;
+        for (tag, request) in tags.iter().zip([
+            attributes(0, "Shirt", "Blue", "Arrow", "Collar stain"),
+            attributes(0, "Saree", "Blue", "Nalli", ""),
+            attributes(0, "Saree", "Red", "", "100% silk, torn pallu"),
+        ]) {
+            let request = UpdateTagAttributesRequest { tag_id: tag.id, ..request };
+            GarmentTrackingService::update_tag_attributes(&db, &request).await.unwrap();
+        }
+
+        let codes = |results: Vec<TagLookupResult>| {
+            let mut codes: Vec<String> = results.into_iter().map(|result| result.tag.tag_code).collect();
+            codes.sort();
+            codes
+        };
+
+        // Every word has to match, in any attribute and any case
+        let blue = codes(GarmentTrackingService::search_tags(&db, "blue").await.unwrap());
+        assert_eq!(blue, vec![tags[0].tag_code.clone(), tags[1].tag_code.clone()]);
+        let blue_arrow = GarmentTrackingService::search_tags(&db, " BLUE  arrow ").await.unwrap();
+        assert_eq!(codes(blue_arrow.clone()), vec![tags[0].tag_code.clone()]);
+        assert_eq!(blue_arrow[0].customer_name, "Garment Test");
+        assert_eq!(blue_arrow[0].invoice_no, "INV9_00");
+
+        let stain = codes(GarmentTrackingService::search_tags(&db, "stain").await.unwrap());
+        assert_eq!(stain, vec![tags[0].tag_code.clone()]);
+
+        // LIKE wildcards in the query are literal
+        let percent = codes(GarmentTrackingService::search_tags(&db, "100%").await.unwrap());
+        assert_eq!(percent, vec![tags[2].tag_code.clone()]);
+        assert_eq!(GarmentTrackingService::search_tags(&db, "%").await.unwrap().len(), 1);
+        assert_eq!(GarmentTrackingService::search_tags(&db, "9_0").await.unwrap().len(), 3);
+        assert!(GarmentTrackingService::search_tags(&db, "9_1").await.unwrap().is_empty());
+
+        assert!(GarmentTrackingService::search_tags(&db, "green").await.unwrap().is_empty());
+        assert!(GarmentTrackingService::search_tags(&db, "   ").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_garment_notes_are_escaped_when_rendered() {
+        let (db, tags) = tagged_invoice().await;
+        let hostile = r#"<script>alert(1)</script>"#;
+        let escaped = "&lt;script&gt;alert(1)&lt;&#x2F;script&gt;";
+
+        let request = attributes(tags[0].id, "Shirt", "", "", hostile);
+        let tag = GarmentTrackingService::update_tag_attributes(&db, &request).await.unwrap();
+        assert_eq!(tag.damage_notes.as_deref(), Some(hostile));
+
+        let engine = TemplateEngine::new().unwrap();
+        let tag_data = TagGeneratorService::get_tag_data_for_printing(&db, 900, None, Some(vec![tag.id])).await.unwrap();
+        let settings = TagSettings { roll_width: "50mm".to_string(), ..TagSettings::defaults(None) };
+        let tag_html = engine.render_tags(&tag_data, &settings, Locale::En, None).unwrap();
+        assert!(tag_html.contains(escaped));
+        assert!(!tag_html.contains(hostile));
+
+        let mut invoice = sample_invoice(1);
+        invoice.items[0].garments.push(tag);
+        let invoice_settings = HtmlGenerator::get_settings("a4", &invoice.store).unwrap();
+        let invoice_html = HtmlGenerator::render_invoice_html(&invoice, &invoice_settings).unwrap();
+        assert!(invoice_html.contains(&format!("Pc 1: Shirt; Damage: {}", escaped)));
+        assert!(!invoice_html.contains(hostile));
+    }
+}
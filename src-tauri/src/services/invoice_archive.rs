@@ -0,0 +1,399 @@
+use crate::database::DatabaseManager;
+use crate::models::{ApiError, ApiResult, InvoiceArchive, InvoiceArchiveCheck, InvoiceArchiveReport, Store};
+use crate::services::html_generator::{HtmlGenerator, HtmlSettings, InvoiceHtmlData};
+use crate::services::{InvoiceTemplateService, TemplateConfig};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use sqlx::{Sqlite, SqliteConnection};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+
+/// Format of the document stored with each archive. Other formats are
+/// rendered from the archived data and settings.
+pub const ARCHIVE_FORMAT: &str = "a4";
+
+/// File holding the key archives are signed with, in the app data folder.
+/// It stays out of the database so an edit there can't be re-signed.
+pub const ARCHIVE_KEY_FILE: &str = "archive.key";
+
+const ARCHIVE_KEY_LEN: usize = 32;
+
+/// Archives are checked in batches so a full verification never loads every
+/// document at once
+const VERIFY_BATCH: i64 = 200;
+
+/// Each format's settings as the invoice was issued, keyed by format
+pub type ArchivedSettings = BTreeMap<String, HtmlSettings>;
+
+pub struct InvoiceArchiveService;
+
+impl InvoiceArchiveService {
+    pub async fn get(db: &DatabaseManager, invoice_id: i64) -> ApiResult<Option<InvoiceArchive>> {
+        Self::fetch(db.get_pool(), invoice_id).await
+    }
+
+    async fn fetch<'e, E>(executor: E, invoice_id: i64) -> ApiResult<Option<InvoiceArchive>>
+    where
+        E: sqlx::Executor<'e, Database = Sqlite>,
+    {
+        sqlx::query_as::<_, InvoiceArchive>("SELECT * FROM invoice_archives WHERE invoice_id = ?")
+            .bind(invoice_id)
+            .fetch_optional(executor)
+            .await
+            .map_err(ApiError::database)
+    }
+
+    /// The signing key, created on first use
+    pub fn load_or_create_key(path: &Path) -> ApiResult<Vec<u8>> {
+        let key_error = |e: std::io::Error| ApiError {
+            message: format!("Failed to read the invoice archive key {}: {}", path.display(), e),
+            code: Some("ARCHIVE_KEY_ERROR".to_string()),
+        };
+
+        if !path.exists() {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent).map_err(key_error)?;
+            }
+            let mut key = [0u8; ARCHIVE_KEY_LEN];
+            getrandom::getrandom(&mut key).map_err(|e| ApiError {
+                message: format!("Failed to generate the invoice archive key: {}", e),
+                code: Some("ARCHIVE_KEY_ERROR".to_string()),
+            })?;
+            // create_new so a key written meanwhile is never replaced
+            match fs::OpenOptions::new().write(true).create_new(true).open(path) {
+                Ok(mut file) => file.write_all(&key).map_err(key_error)?,
+                Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {}
+                Err(e) => return Err(key_error(e)),
+            }
+        }
+
+        let key = fs::read(path).map_err(key_error)?;
+        if key.len() != ARCHIVE_KEY_LEN {
+            return Err(ApiError {
+                message: format!("The invoice archive key {} is damaged", path.display()),
+                code: Some("ARCHIVE_KEY_ERROR".to_string()),
+            });
+        }
+        Ok(key)
+    }
+
+    /// Settings for every format, as they stand when the invoice is issued.
+    /// Read before the issuing transaction so it holds the only connection
+    /// it needs.
+    pub async fn current_settings(db: &DatabaseManager, store: &Store) -> ApiResult<ArchivedSettings> {
+        let mut settings = ArchivedSettings::new();
+        for format in TemplateConfig::FORMATS {
+            settings.insert(format.to_string(), InvoiceTemplateService::get_settings(db, store, format).await?);
+        }
+        Ok(settings)
+    }
+
+    /// Snapshot an invoice as issued, on the connection (usually the
+    /// transaction) that created it. An invoice is archived once; archiving
+    /// it again returns the first snapshot.
+    pub async fn archive(
+        conn: &mut SqliteConnection,
+        key: &[u8],
+        data: &InvoiceHtmlData,
+        settings: &ArchivedSettings,
+    ) -> ApiResult<InvoiceArchive> {
+        if let Some(archive) = Self::fetch(&mut *conn, data.invoice.id).await? {
+            return Ok(archive);
+        }
+
+        let document_settings = settings.get(ARCHIVE_FORMAT).ok_or_else(|| ApiError {
+            message: format!("No {} settings to archive invoice {} with", ARCHIVE_FORMAT, data.invoice.invoice_no),
+            code: Some("ARCHIVE_ERROR".to_string()),
+        })?;
+        let document = HtmlGenerator::render_invoice_html(data, document_settings)?;
+        let serialize_error = |e: serde_json::Error| ApiError {
+            message: format!("Failed to serialize invoice: {}", e),
+            code: Some("ARCHIVE_ERROR".to_string()),
+        };
+        let data_json = serde_json::to_string(data).map_err(serialize_error)?;
+        let settings_json = serde_json::to_string(settings).map_err(serialize_error)?;
+        let content_hash = Self::content_hash(
+            key,
+            data.invoice.id,
+            &data.invoice.invoice_no,
+            ARCHIVE_FORMAT,
+            &data_json,
+            &settings_json,
+            &document,
+        );
+
+        sqlx::query(
+            r#"
+            INSERT INTO invoice_archives (invoice_id, invoice_no, format, document, data_json, settings_json, content_hash)
+            VALUES (?, ?, ?, ?, ?, ?, ?)
+            ON CONFLICT(invoice_id) DO NOTHING
+            "#,
+        )
+        .bind(data.invoice.id)
+        .bind(&data.invoice.invoice_no)
+        .bind(ARCHIVE_FORMAT)
+        .bind(&document)
+        .bind(&data_json)
+        .bind(&settings_json)
+        .bind(&content_hash)
+        .execute(&mut *conn)
+        .await
+        .map_err(ApiError::database)?;
+
+        // A concurrent archive may have won the insert; either way the stored row is the snapshot
+        Self::fetch(&mut *conn, data.invoice.id).await?.ok_or_else(|| ApiError {
+            message: format!("Invoice {} was not archived", data.invoice.invoice_no),
+            code: Some("ARCHIVE_ERROR".to_string()),
+        })
+    }
+
+    /// The archive an invoice is reprinted from. Invoices without one can
+    /// only be printed as a duplicate copy.
+    pub async fn get_issued(db: &DatabaseManager, invoice_id: i64) -> ApiResult<InvoiceArchive> {
+        Self::get(db, invoice_id).await?.ok_or_else(|| ApiError {
+            message: "This invoice has no archived copy; print a duplicate copy instead".to_string(),
+            code: Some("ARCHIVE_NOT_FOUND".to_string()),
+        })
+    }
+
+    /// An invoice's data and a format's settings as issued
+    pub async fn issued(
+        db: &DatabaseManager,
+        key: &[u8],
+        invoice_id: i64,
+        format: &str,
+    ) -> ApiResult<(InvoiceHtmlData, HtmlSettings)> {
+        Self::snapshot(&Self::get_issued(db, invoice_id).await?, key, format)
+    }
+
+    /// The archived invoice data and a format's settings, once the archive
+    /// passes verification
+    pub fn snapshot(archive: &InvoiceArchive, key: &[u8], format: &str) -> ApiResult<(InvoiceHtmlData, HtmlSettings)> {
+        let check = Self::check(archive, key);
+        if let Some(problem) = check.problem {
+            log::error!("Archive of invoice {} failed verification: {}", archive.invoice_no, problem);
+            return Err(ApiError {
+                message: format!(
+                    "The archived copy of invoice {} has been altered ({}); print a duplicate copy instead",
+                    archive.invoice_no, problem
+                ),
+                code: Some("ARCHIVE_TAMPERED".to_string()),
+            });
+        }
+
+        let read_error = |e: serde_json::Error| ApiError {
+            message: format!("Failed to read archived invoice {}: {}", archive.invoice_no, e),
+            code: Some("ARCHIVE_ERROR".to_string()),
+        };
+        let data = serde_json::from_str(&archive.data_json).map_err(read_error)?;
+        let mut settings: ArchivedSettings = serde_json::from_str(&archive.settings_json).map_err(read_error)?;
+        let settings = settings.remove(format).ok_or_else(|| ApiError {
+            message: format!("Invoice {} was not archived in the {} format", archive.invoice_no, format),
+            code: Some("ARCHIVE_NOT_FOUND".to_string()),
+        })?;
+
+        Ok((data, settings))
+    }
+
+    /// Recompute an archive's signature and check the snapshot belongs to
+    /// its invoice
+    pub fn check(archive: &InvoiceArchive, key: &[u8]) -> InvoiceArchiveCheck {
+        let expected = Self::content_hash(
+            key,
+            archive.invoice_id,
+            &archive.invoice_no,
+            &archive.format,
+            &archive.data_json,
+            &archive.settings_json,
+            &archive.document,
+        );
+
+        let problem = if expected != archive.content_hash {
+            Some("content hash does not match".to_string())
+        } else if let Err(e) = serde_json::from_str::<ArchivedSettings>(&archive.settings_json) {
+            Some(format!("settings are unreadable: {}", e))
+        } else {
+            match serde_json::from_str::<InvoiceHtmlData>(&archive.data_json) {
+                Err(e) => Some(format!("snapshot is unreadable: {}", e)),
+                Ok(data) if data.invoice.id != archive.invoice_id || data.invoice.invoice_no != archive.invoice_no => {
+                    Some(format!("snapshot belongs to invoice {}", data.invoice.invoice_no))
+                }
+                Ok(_) => None,
+            }
+        };
+
+        InvoiceArchiveCheck {
+            invoice_id: archive.invoice_id,
+            invoice_no: archive.invoice_no.clone(),
+            archived_at: archive.created_at.clone(),
+            valid: problem.is_none(),
+            problem,
+        }
+    }
+
+    /// Verify every archive, returning the ones that fail
+    pub async fn verify_all(db: &DatabaseManager, key: &[u8]) -> ApiResult<InvoiceArchiveReport> {
+        let mut report = InvoiceArchiveReport { checked: 0, failed: Vec::new() };
+        let mut last_id = 0;
+
+        loop {
+            let batch = sqlx::query_as::<_, InvoiceArchive>(
+                "SELECT * FROM invoice_archives WHERE id > ? ORDER BY id LIMIT ?",
+            )
+            .bind(last_id)
+            .bind(VERIFY_BATCH)
+            .fetch_all(db.get_pool())
+            .await
+            .map_err(ApiError::database)?;
+
+            last_id = match batch.last() {
+                Some(last) => last.id,
+                None => break,
+            };
+            report.checked += batch.len();
+            report.failed.extend(batch.iter().map(|archive| Self::check(archive, key)).filter(|check| !check.valid));
+        }
+
+        Ok(report)
+    }
+
+    /// HMAC-SHA256 over everything stored for the invoice, so the database
+    /// alone isn't enough to re-sign an edit. Fields are separated by NUL,
+    /// which never appears in the JSON or the escaped HTML.
+    pub fn content_hash(
+        key: &[u8],
+        invoice_id: i64,
+        invoice_no: &str,
+        format: &str,
+        data_json: &str,
+        settings_json: &str,
+        document: &str,
+    ) -> String {
+        let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any length");
+        for field in [invoice_id.to_string().as_str(), invoice_no, format, data_json, settings_json, document] {
+            mac.update(field.as_bytes());
+            mac.update(&[0u8]);
+        }
+        mac.finalize()
+            .into_bytes()
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::html_generator::test_data;
+
+    const KEY: &[u8] = &[7u8; ARCHIVE_KEY_LEN];
+
+    async fn archive_invoice(db: &DatabaseManager, data: &InvoiceHtmlData, settings: &ArchivedSettings) -> InvoiceArchive {
+        let mut conn = db.get_pool().acquire().await.unwrap();
+        InvoiceArchiveService::archive(&mut conn, KEY, data, settings).await.unwrap()
+    }
+
+    fn builtin_settings(store: &Store) -> ArchivedSettings {
+        TemplateConfig::FORMATS
+            .iter()
+            .map(|format| (format.to_string(), HtmlGenerator::get_settings(format, store).unwrap()))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_archive_is_immutable_and_verified() {
+        let db = DatabaseManager::new_with_url("sqlite::memory:").await.unwrap();
+        db.initialize_schema().await.unwrap();
+
+        let data = test_data::sample_invoice(2);
+        let settings = builtin_settings(&data.store);
+        let archive = archive_invoice(&db, &data, &settings).await;
+        assert!(archive.document.contains(&data.invoice.invoice_no));
+        assert!(InvoiceArchiveService::check(&archive, KEY).valid);
+
+        // Later renames don't reach the snapshot, and archiving again keeps the first one
+        let mut renamed = data.clone();
+        renamed.store.name = "Renamed Store".to_string();
+        let again = archive_invoice(&db, &renamed, &settings).await;
+        assert_eq!(again.content_hash, archive.content_hash);
+        let (snapshot, _) = InvoiceArchiveService::snapshot(&again, KEY, "a5").unwrap();
+        assert_eq!(snapshot.store.name, data.store.name);
+
+        // Re-rendering from live data is marked as a duplicate
+        let duplicate = HtmlSettings { duplicate_copy: true, ..settings[ARCHIVE_FORMAT].clone() };
+        assert!(!archive.document.contains("DUPLICATE COPY"));
+        assert!(HtmlGenerator::render_invoice_html(&renamed, &duplicate).unwrap().contains("DUPLICATE COPY"));
+
+        // The triggers refuse edits
+        let update = sqlx::query("UPDATE invoice_archives SET document = 'edited'").execute(db.get_pool()).await;
+        assert!(update.unwrap_err().to_string().contains("read-only"));
+        assert!(sqlx::query("DELETE FROM invoice_archives").execute(db.get_pool()).await.is_err());
+
+        let report = InvoiceArchiveService::verify_all(&db, KEY).await.unwrap();
+        assert_eq!((report.checked, report.failed.len()), (1, 0));
+    }
+
+    #[tokio::test]
+    async fn test_archive_detects_tampering() {
+        let db = DatabaseManager::new_with_url("sqlite::memory:").await.unwrap();
+        db.initialize_schema().await.unwrap();
+
+        let data = test_data::sample_invoice(1);
+        let archive = archive_invoice(&db, &data, &builtin_settings(&data.store)).await;
+
+        // An edit made around the triggers is caught by the signature
+        let mut tampered = archive.clone();
+        tampered.data_json = tampered.data_json.replace(&data.customer.name, "Someone Else");
+        let check = InvoiceArchiveService::check(&tampered, KEY);
+        assert!(!check.valid);
+        assert_eq!(check.problem.as_deref(), Some("content hash does not match"));
+        let error = InvoiceArchiveService::snapshot(&tampered, KEY, ARCHIVE_FORMAT).unwrap_err();
+        assert_eq!(error.code.as_deref(), Some("ARCHIVE_TAMPERED"));
+
+        let mut restyled = archive.clone();
+        restyled.settings_json = restyled.settings_json.replacen("\"duplicate_copy\":false", "\"duplicate_copy\":true", 1);
+        assert_ne!(restyled.settings_json, archive.settings_json);
+        assert!(!InvoiceArchiveService::check(&restyled, KEY).valid);
+
+        // Re-hashing an edit without the key doesn't make it pass
+        let mut rehashed = tampered.clone();
+        rehashed.content_hash = InvoiceArchiveService::content_hash(
+            b"guessed key",
+            rehashed.invoice_id,
+            &rehashed.invoice_no,
+            &rehashed.format,
+            &rehashed.data_json,
+            &rehashed.settings_json,
+            &rehashed.document,
+        );
+        assert!(!InvoiceArchiveService::check(&rehashed, KEY).valid);
+        assert!(!InvoiceArchiveService::check(&archive, b"another install").valid);
+        assert_eq!(InvoiceArchiveService::verify_all(&db, b"another install").await.unwrap().failed.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_unarchived_invoice_is_not_reprinted() {
+        let db = DatabaseManager::new_with_url("sqlite::memory:").await.unwrap();
+        db.initialize_schema().await.unwrap();
+
+        let error = InvoiceArchiveService::issued(&db, KEY, 404, ARCHIVE_FORMAT).await.unwrap_err();
+        assert_eq!(error.code.as_deref(), Some("ARCHIVE_NOT_FOUND"));
+    }
+
+    #[test]
+    fn test_archive_key_is_created_once() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("UCLEAN").join(ARCHIVE_KEY_FILE);
+
+        let key = InvoiceArchiveService::load_or_create_key(&path).unwrap();
+        assert_eq!(key.len(), ARCHIVE_KEY_LEN);
+        assert_eq!(InvoiceArchiveService::load_or_create_key(&path).unwrap(), key);
+
+        fs::write(&path, b"short").unwrap();
+        let error = InvoiceArchiveService::load_or_create_key(&path).unwrap_err();
+        assert_eq!(error.code.as_deref(), Some("ARCHIVE_KEY_ERROR"));
+    }
+}
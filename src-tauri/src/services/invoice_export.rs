@@ -0,0 +1,352 @@
+use crate::models::{ApiError, ApiResult, Invoice};
+use crate::services::html_generator::{HtmlGenerator, HtmlSettings, InvoiceHtmlData};
+use crate::services::pdf_generator::PdfGenerator;
+use chrono::{Datelike, Timelike};
+use flate2::write::DeflateEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use std::io::{self, Write};
+use std::path::Path;
+
+/// Emitted with an `InvoiceExportProgress` after each exported invoice and
+/// when the export ends
+pub const INVOICE_EXPORT_EVENT: &str = "invoice-export-progress";
+
+// Starts with a byte order mark so Excel reads Hindi and Tamil names as UTF-8
+const INDEX_HEADER: &str =
+    "\u{feff}Invoice No,Date,Customer,Phone,Subtotal,Discount,Express Charge,Taxable Value,SGST,CGST,IGST,Total GST,Total,Paid,Payment Method,Status\r\n";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFileType {
+    Html,
+    Pdf,
+}
+
+impl ExportFileType {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFileType::Html => "html",
+            ExportFileType::Pdf => "pdf",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InvoiceExportProgress {
+    pub status: String, // running, completed, cancelled, failed
+    pub done: usize,
+    pub total: usize,
+    pub invoice_no: Option<String>,
+    /// The finished archive, once completed
+    pub path: Option<String>,
+    pub error: Option<String>,
+}
+
+pub struct InvoiceExportService;
+
+impl InvoiceExportService {
+    /// Render one invoice as an HTML page or a PDF
+    pub fn render(
+        data: &InvoiceHtmlData,
+        settings: &HtmlSettings,
+        file_type: ExportFileType,
+        font_dir: Option<&Path>,
+    ) -> ApiResult<Vec<u8>> {
+        match file_type {
+            ExportFileType::Html => {
+                // An accountant opening the file shouldn't get a print dialog
+                let settings = HtmlSettings { auto_print: false, ..settings.clone() };
+                HtmlGenerator::render_invoice_html(data, &settings).map(String::into_bytes)
+            }
+            ExportFileType::Pdf => {
+                let font = PdfGenerator::devanagari_font_for(data, font_dir);
+                PdfGenerator::generate_invoice_pdf(data, settings, font.as_deref())
+            }
+        }
+    }
+
+    /// Path of an invoice inside the archive. Invoice numbers are made safe
+    /// for file names, so the id keeps e.g. "INV/1" and "INV_1" apart.
+    pub fn entry_name(data: &InvoiceHtmlData, format: &str, file_type: ExportFileType) -> String {
+        let invoice_no: String = data
+            .invoice
+            .invoice_no
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+            .collect();
+        format!("invoices/invoice_{}_{}_{}.{}", invoice_no, data.invoice.id, format, file_type.extension())
+    }
+
+    pub fn index_header() -> &'static str {
+        INDEX_HEADER
+    }
+
+    /// One index.csv line: number, date, customer, totals and GST as
+    /// issued, with payment and status from the live invoice row (`current`),
+    /// since the archived snapshot still shows them as at creation
+    pub fn index_row(data: &InvoiceHtmlData, current: &Invoice) -> String {
+        let invoice = &data.invoice;
+        let totals = &data.totals;
+        let total_gst = totals.sgst_amount + totals.cgst_amount + invoice.igst_amount;
+
+        let text = [
+            invoice.invoice_no.as_str(),
+            invoice.order_datetime.as_str(),
+            data.customer.name.as_str(),
+            data.customer.phone.as_deref().unwrap_or(""),
+        ]
+        .map(csv_text);
+        let amounts = [
+            totals.subtotal,
+            totals.discount_amount,
+            totals.express_charge,
+            totals.base_amount,
+            totals.sgst_amount,
+            totals.cgst_amount,
+            invoice.igst_amount,
+            total_gst,
+            totals.total_amount,
+            current.payment_amount.unwrap_or(0.0),
+        ]
+        .map(|amount| format!("{:.2}", amount));
+
+        format!(
+            "{},{},{},{}\r\n",
+            text.join(","),
+            amounts.join(","),
+            csv_text(current.payment_method.as_deref().unwrap_or("")),
+            csv_text(&current.status)
+        )
+    }
+}
+
+/// Quote a CSV field when needed. Text that a spreadsheet would run as a
+/// formula gets a leading apostrophe.
+fn csv_text(value: &str) -> String {
+    let value = if value.starts_with(&['=', '+', '-', '@'][..]) {
+        format!("'{}", value)
+    } else {
+        value.to_string()
+    };
+
+    if value.contains(&[',', '"', '\r', '\n'][..]) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value
+    }
+}
+
+struct ZipEntry {
+    name: String,
+    crc: u32,
+    compressed_size: u32,
+    size: u32,
+    offset: u32,
+}
+
+/// Streams a deflate-compressed ZIP archive. Entries are written as they are
+/// added, so a large export never sits in memory. No ZIP64: an archive is
+/// limited to 65535 entries and 4 GB.
+pub struct ZipWriter<W: Write> {
+    out: W,
+    offset: u64,
+    entries: Vec<ZipEntry>,
+    dos_time: u16,
+    dos_date: u16,
+}
+
+impl<W: Write> ZipWriter<W> {
+    pub fn new(out: W) -> Self {
+        let now = chrono::Local::now();
+        let dos_time = ((now.hour() << 11) | (now.minute() << 5) | (now.second() / 2)) as u16;
+        let dos_date = (((now.year().max(1980) - 1980) << 9) as u32 | (now.month() << 5) | now.day()) as u16;
+
+        Self { out, offset: 0, entries: Vec::new(), dos_time, dos_date }
+    }
+
+    pub fn add_file(&mut self, name: &str, data: &[u8]) -> io::Result<()> {
+        let mut encoder = DeflateEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(data)?;
+        let compressed = encoder.finish()?;
+
+        let too_large = || io::Error::new(io::ErrorKind::Other, "ZIP archive is larger than 4 GB");
+        if self.entries.len() >= u16::MAX as usize {
+            return Err(io::Error::new(io::ErrorKind::Other, "ZIP archive has too many files"));
+        }
+        let entry = ZipEntry {
+            name: name.to_string(),
+            crc: crc32fast::hash(data),
+            compressed_size: u32::try_from(compressed.len()).map_err(|_| too_large())?,
+            size: u32::try_from(data.len()).map_err(|_| too_large())?,
+            offset: u32::try_from(self.offset).map_err(|_| too_large())?,
+        };
+
+        let mut header = Vec::with_capacity(30 + name.len());
+        header.extend(0x04034b50u32.to_le_bytes());
+        self.common_fields(&mut header, &entry);
+        header.extend(0u16.to_le_bytes()); // extra field length
+        header.extend(name.as_bytes());
+
+        self.write(&header)?;
+        self.write(&compressed)?;
+        self.entries.push(entry);
+        Ok(())
+    }
+
+    /// Write the central directory and return the underlying writer
+    pub fn finish(mut self) -> io::Result<W> {
+        let directory_offset = self.offset;
+        let mut directory = Vec::new();
+        for entry in &self.entries {
+            directory.extend(0x02014b50u32.to_le_bytes());
+            directory.extend(20u16.to_le_bytes()); // version made by
+            self.common_fields(&mut directory, entry);
+            directory.extend(0u16.to_le_bytes()); // extra field length
+            directory.extend(0u16.to_le_bytes()); // comment length
+            directory.extend(0u16.to_le_bytes()); // disk number
+            directory.extend(0u16.to_le_bytes()); // internal attributes
+            directory.extend(0u32.to_le_bytes()); // external attributes
+            directory.extend(entry.offset.to_le_bytes());
+            directory.extend(entry.name.as_bytes());
+        }
+
+        let count = self.entries.len() as u16;
+        let too_large = || io::Error::new(io::ErrorKind::Other, "ZIP archive is larger than 4 GB");
+        let mut end = Vec::with_capacity(22);
+        end.extend(0x06054b50u32.to_le_bytes());
+        end.extend(0u16.to_le_bytes()); // this disk
+        end.extend(0u16.to_le_bytes()); // disk with the directory
+        end.extend(count.to_le_bytes());
+        end.extend(count.to_le_bytes());
+        end.extend(u32::try_from(directory.len()).map_err(|_| too_large())?.to_le_bytes());
+        end.extend(u32::try_from(directory_offset).map_err(|_| too_large())?.to_le_bytes());
+        end.extend(0u16.to_le_bytes()); // comment length
+
+        self.write(&directory)?;
+        self.write(&end)?;
+        self.out.flush()?;
+        Ok(self.out)
+    }
+
+    /// Fields shared by the local header and the central directory, from
+    /// "version needed" through "file name length"
+    fn common_fields(&self, bytes: &mut Vec<u8>, entry: &ZipEntry) {
+        bytes.extend(20u16.to_le_bytes()); // version needed: deflate
+        bytes.extend(0x0800u16.to_le_bytes()); // names are UTF-8
+        bytes.extend(8u16.to_le_bytes()); // deflate
+        bytes.extend(self.dos_time.to_le_bytes());
+        bytes.extend(self.dos_date.to_le_bytes());
+        bytes.extend(entry.crc.to_le_bytes());
+        bytes.extend(entry.compressed_size.to_le_bytes());
+        bytes.extend(entry.size.to_le_bytes());
+        bytes.extend((entry.name.len() as u16).to_le_bytes());
+    }
+
+    fn write(&mut self, bytes: &[u8]) -> io::Result<()> {
+        self.out.write_all(bytes)?;
+        self.offset += bytes.len() as u64;
+        Ok(())
+    }
+}
+
+/// Map a ZIP write failure to the API error the export reports
+pub fn archive_error(e: io::Error) -> ApiError {
+    ApiError {
+        message: format!("Failed to write export archive: {}", e),
+        code: Some("EXPORT_WRITE_ERROR".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::html_generator::test_data;
+    use std::io::Read;
+
+    /// Read every entry back through the central directory
+    fn read_zip(bytes: &[u8]) -> Vec<(String, Vec<u8>)> {
+        let u16_at = |at: usize| u16::from_le_bytes([bytes[at], bytes[at + 1]]) as usize;
+        let u32_at = |at: usize| u32::from_le_bytes([bytes[at], bytes[at + 1], bytes[at + 2], bytes[at + 3]]) as usize;
+
+        let end = bytes.len() - 22;
+        assert_eq!(u32_at(end), 0x06054b50);
+        let mut at = u32_at(end + 16);
+        let mut entries = Vec::new();
+        for _ in 0..u16_at(end + 10) {
+            assert_eq!(u32_at(at), 0x02014b50);
+            let (crc, compressed_size, size) = (u32_at(at + 16), u32_at(at + 20), u32_at(at + 24));
+            let name_len = u16_at(at + 28);
+            let name = String::from_utf8(bytes[at + 46..at + 46 + name_len].to_vec()).unwrap();
+
+            let local = u32_at(at + 42);
+            assert_eq!(u32_at(local), 0x04034b50);
+            let data_start = local + 30 + u16_at(local + 26) + u16_at(local + 28);
+            let mut data = Vec::new();
+            flate2::read::DeflateDecoder::new(&bytes[data_start..data_start + compressed_size])
+                .read_to_end(&mut data)
+                .unwrap();
+            assert_eq!((data.len(), crc32fast::hash(&data) as usize), (size, crc));
+
+            entries.push((name, data));
+            at += 46 + name_len + u16_at(at + 30) + u16_at(at + 32);
+        }
+        entries
+    }
+
+    #[test]
+    fn test_zip_round_trip() {
+        let mut zip = ZipWriter::new(Vec::new());
+        zip.add_file("index.csv", b"a,b\r\n").unwrap();
+        zip.add_file("invoices/बिल.html", &[b'x'; 10_000]).unwrap();
+        zip.add_file("empty.txt", b"").unwrap();
+        let bytes = zip.finish().unwrap();
+
+        let entries = read_zip(&bytes);
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0], ("index.csv".to_string(), b"a,b\r\n".to_vec()));
+        assert_eq!(entries[1].0, "invoices/बिल.html");
+        assert_eq!(entries[1].1.len(), 10_000);
+        assert!(bytes.len() < 1_000);
+        assert!(entries[2].1.is_empty());
+    }
+
+    #[test]
+    fn test_index_rows_and_entries() {
+        let mut data = test_data::sample_invoice(1);
+        data.customer.name = "=HYPERLINK(\"x\"), Sharma".to_string();
+        data.invoice.invoice_no = "INV/2025/001".to_string();
+
+        let row = InvoiceExportService::index_row(&data, &data.invoice);
+        assert!(row.starts_with("INV/2025/001,"));
+        assert!(row.contains(",\"'=HYPERLINK(\"\"x\"\"), Sharma\","));
+        assert!(row.ends_with(",300.00,0.00,0.00,300.00,27.00,27.00,0.00,54.00,354.00,0.00,,pending\r\n"), "{}", row);
+
+        // Paid since it was issued: the index follows the live row, not the snapshot
+        let mut paid = data.invoice.clone();
+        paid.status = "paid".to_string();
+        paid.payment_method = Some("upi".to_string());
+        paid.payment_amount = Some(354.0);
+        let row = InvoiceExportService::index_row(&data, &paid);
+        assert!(row.ends_with(",354.00,354.00,upi,paid\r\n"), "{}", row);
+        assert_eq!(InvoiceExportService::index_header().matches(',').count(), row.matches(',').count() - 1);
+
+        assert_eq!(
+            InvoiceExportService::entry_name(&data, "a4", ExportFileType::Pdf),
+            "invoices/invoice_INV_2025_001_1_a4.pdf"
+        );
+        let mut lookalike = data.clone();
+        lookalike.invoice.id = 2;
+        lookalike.invoice.invoice_no = "INV_2025_001".to_string();
+        assert_ne!(
+            InvoiceExportService::entry_name(&lookalike, "a4", ExportFileType::Pdf),
+            InvoiceExportService::entry_name(&data, "a4", ExportFileType::Pdf)
+        );
+
+        let settings = HtmlGenerator::get_settings("a4", &data.store).unwrap();
+        let html = InvoiceExportService::render(&data, &settings, ExportFileType::Html, None).unwrap();
+        assert!(!String::from_utf8(html).unwrap().contains("window.print()"));
+        let pdf = InvoiceExportService::render(&data, &settings, ExportFileType::Pdf, None).unwrap();
+        assert!(pdf.starts_with(b"%PDF"));
+    }
+}
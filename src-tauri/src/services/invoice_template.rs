@@ -0,0 +1,379 @@
+use crate::database::DatabaseManager;
+use crate::models::{ApiError, ApiResult, InvoiceTemplate, Store};
+use crate::services::html_generator::{HtmlGenerator, HtmlSettings};
+use crate::services::store_branding::StoreBrandingService;
+use crate::services::template_engine::{LayoutSettings, TemplateConfig, TemplateEngine};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateInvoiceTemplateRequest {
+    pub store_id: Option<i64>, // None creates a template shared by all stores
+    pub name: String,
+    pub format: String,
+    /// None starts from the format's built-in template
+    pub content: Option<String>,
+    /// None starts from the format's built-in layout
+    pub layout: Option<LayoutSettings>,
+    pub is_default: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateInvoiceTemplateRequest {
+    pub name: String,
+    pub content: String,
+    pub layout: LayoutSettings,
+}
+
+/// File format for moving a template between installs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InvoiceTemplateExport {
+    pub name: String,
+    pub format: String,
+    pub layout: LayoutSettings,
+    pub content: String,
+}
+
+pub struct InvoiceTemplateService;
+
+impl InvoiceTemplateService {
+    /// Templates a store can use (its own and the shared ones), or every
+    /// template when no store is given
+    pub async fn list(db: &DatabaseManager, store_id: Option<i64>, format: Option<&str>) -> ApiResult<Vec<InvoiceTemplate>> {
+        sqlx::query_as::<_, InvoiceTemplate>(
+            r#"
+            SELECT * FROM invoice_templates
+            WHERE (?1 IS NULL OR store_id = ?1 OR store_id IS NULL)
+              AND (?2 IS NULL OR format = ?2)
+            ORDER BY format, store_id IS NULL, name
+            "#,
+        )
+        .bind(store_id)
+        .bind(format)
+        .fetch_all(db.get_pool())
+        .await
+        .map_err(ApiError::database)
+    }
+
+    pub async fn get(db: &DatabaseManager, template_id: i64) -> ApiResult<InvoiceTemplate> {
+        sqlx::query_as::<_, InvoiceTemplate>("SELECT * FROM invoice_templates WHERE id = ?")
+            .bind(template_id)
+            .fetch_optional(db.get_pool())
+            .await
+            .map_err(ApiError::database)?
+            .ok_or_else(|| ApiError {
+                message: format!("Invoice template {} not found", template_id),
+                code: Some("NOT_FOUND".to_string()),
+            })
+    }
+
+    /// Save a new template once it renders the sample invoice
+    pub async fn create(db: &DatabaseManager, request: CreateInvoiceTemplateRequest) -> ApiResult<InvoiceTemplate> {
+        let builtin = TemplateConfig::for_format(&request.format)?;
+        let content = match request.content {
+            Some(content) => content,
+            None => TemplateEngine::builtin_invoice_template(&request.format)?.to_string(),
+        };
+        let layout = request.layout.unwrap_or(builtin.layout);
+        let name = Self::validate(&request.name, &content, &request.format, &layout)?;
+
+        let template = sqlx::query_as::<_, InvoiceTemplate>(
+            "INSERT INTO invoice_templates (store_id, name, format, content, layout) VALUES (?, ?, ?, ?, ?) RETURNING *",
+        )
+        .bind(request.store_id)
+        .bind(name)
+        .bind(&request.format)
+        .bind(content)
+        .bind(Self::layout_json(&layout)?)
+        .fetch_one(db.get_pool())
+        .await
+        .map_err(ApiError::database)?;
+
+        if request.is_default.unwrap_or(false) {
+            Self::set_default(db, template.id).await
+        } else {
+            Ok(template)
+        }
+    }
+
+    /// Replace a template's name, source and layout once it renders the
+    /// sample invoice
+    pub async fn update(db: &DatabaseManager, template_id: i64, request: UpdateInvoiceTemplateRequest) -> ApiResult<InvoiceTemplate> {
+        let existing = Self::get(db, template_id).await?;
+        let name = Self::validate(&request.name, &request.content, &existing.format, &request.layout)?;
+
+        sqlx::query_as::<_, InvoiceTemplate>(
+            r#"
+            UPDATE invoice_templates
+            SET name = ?, content = ?, layout = ?, updated_at = CURRENT_TIMESTAMP
+            WHERE id = ?
+            RETURNING *
+            "#,
+        )
+        .bind(name)
+        .bind(&request.content)
+        .bind(Self::layout_json(&request.layout)?)
+        .bind(template_id)
+        .fetch_one(db.get_pool())
+        .await
+        .map_err(ApiError::database)
+    }
+
+    /// Copy a template, optionally into another store. The copy is never
+    /// the default.
+    pub async fn clone_template(
+        db: &DatabaseManager,
+        template_id: i64,
+        name: Option<String>,
+        store_id: Option<i64>,
+    ) -> ApiResult<InvoiceTemplate> {
+        let source = Self::get(db, template_id).await?;
+
+        sqlx::query_as::<_, InvoiceTemplate>(
+            "INSERT INTO invoice_templates (store_id, name, format, content, layout) VALUES (?, ?, ?, ?, ?) RETURNING *",
+        )
+        .bind(store_id)
+        .bind(name.unwrap_or_else(|| format!("Copy of {}", source.name)))
+        .bind(&source.format)
+        .bind(&source.content)
+        .bind(&source.layout)
+        .fetch_one(db.get_pool())
+        .await
+        .map_err(ApiError::database)
+    }
+
+    pub async fn delete(db: &DatabaseManager, template_id: i64) -> ApiResult<()> {
+        let result = sqlx::query("DELETE FROM invoice_templates WHERE id = ?")
+            .bind(template_id)
+            .execute(db.get_pool())
+            .await
+            .map_err(ApiError::database)?;
+
+        if result.rows_affected() == 0 {
+            return Err(ApiError {
+                message: format!("Invoice template {} not found", template_id),
+                code: Some("NOT_FOUND".to_string()),
+            });
+        }
+        Ok(())
+    }
+
+    /// Make a template the one its store prints its format with
+    pub async fn set_default(db: &DatabaseManager, template_id: i64) -> ApiResult<InvoiceTemplate> {
+        let template = Self::get(db, template_id).await?;
+        let mut tx = db.get_pool().begin().await.map_err(ApiError::database)?;
+
+        sqlx::query("UPDATE invoice_templates SET is_default = 0 WHERE store_id IS ? AND format = ? AND is_default = 1")
+            .bind(template.store_id)
+            .bind(&template.format)
+            .execute(&mut *tx)
+            .await
+            .map_err(ApiError::database)?;
+
+        let template = sqlx::query_as::<_, InvoiceTemplate>(
+            "UPDATE invoice_templates SET is_default = 1, updated_at = CURRENT_TIMESTAMP WHERE id = ? RETURNING *",
+        )
+        .bind(template_id)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(ApiError::database)?;
+
+        tx.commit().await.map_err(ApiError::database)?;
+        Ok(template)
+    }
+
+    /// Go back to the shared default (or the built-in template) for a
+    /// store's format
+    pub async fn clear_default(db: &DatabaseManager, store_id: Option<i64>, format: &str) -> ApiResult<()> {
+        sqlx::query("UPDATE invoice_templates SET is_default = 0 WHERE store_id IS ? AND format = ? AND is_default = 1")
+            .bind(store_id)
+            .bind(format)
+            .execute(db.get_pool())
+            .await
+            .map_err(ApiError::database)?;
+        Ok(())
+    }
+
+    /// The template a store prints a format with: its own default, else
+    /// the shared default. None means the built-in template.
+    pub async fn default_for(db: &DatabaseManager, store_id: i64, format: &str) -> ApiResult<Option<InvoiceTemplate>> {
+        sqlx::query_as::<_, InvoiceTemplate>(
+            r#"
+            SELECT * FROM invoice_templates
+            WHERE format = ? AND is_default = 1 AND (store_id = ? OR store_id IS NULL)
+            ORDER BY store_id IS NULL
+            LIMIT 1
+            "#,
+        )
+        .bind(format)
+        .bind(store_id)
+        .fetch_optional(db.get_pool())
+        .await
+        .map_err(ApiError::database)
+    }
+
+    /// Invoice settings for a store's format: its branding, plus its
+    /// default template when one is saved
+    pub async fn get_settings(db: &DatabaseManager, store: &Store, format: &str) -> ApiResult<HtmlSettings> {
+        let mut settings = StoreBrandingService::get_settings(db, store, format).await?;
+        if let Some(template) = Self::default_for(db, store.id, format).await? {
+            settings.template = Self::config(&template)?;
+        }
+        Ok(settings)
+    }
+
+    /// The format config a saved template renders with
+    pub fn config(template: &InvoiceTemplate) -> ApiResult<TemplateConfig> {
+        let builtin = TemplateConfig::for_format(&template.format)?;
+        Ok(TemplateConfig {
+            display_name: template.name.clone(),
+            content: Some(template.content.clone()),
+            layout: Self::parse_layout(&template.layout)?,
+            ..builtin
+        })
+    }
+
+    pub fn export(template: &InvoiceTemplate) -> ApiResult<InvoiceTemplateExport> {
+        Ok(InvoiceTemplateExport {
+            name: template.name.clone(),
+            format: template.format.clone(),
+            layout: Self::parse_layout(&template.layout)?,
+            content: template.content.clone(),
+        })
+    }
+
+    /// Save an exported template for a store; it is checked like any other
+    pub async fn import(db: &DatabaseManager, export: InvoiceTemplateExport, store_id: Option<i64>) -> ApiResult<InvoiceTemplate> {
+        Self::create(
+            db,
+            CreateInvoiceTemplateRequest {
+                store_id,
+                name: export.name,
+                format: export.format,
+                content: Some(export.content),
+                layout: Some(export.layout),
+                is_default: Some(false),
+            },
+        )
+        .await
+    }
+
+    /// Check the name, then render the sample invoice with the template
+    fn validate(name: &str, content: &str, format: &str, layout: &LayoutSettings) -> ApiResult<String> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(ApiError {
+                message: "Template name is required".to_string(),
+                code: Some("VALIDATION_ERROR".to_string()),
+            });
+        }
+
+        TemplateEngine::new()?.validate_invoice_template(content, format, layout)?;
+        Ok(name.to_string())
+    }
+
+    fn parse_layout(layout: &str) -> ApiResult<LayoutSettings> {
+        serde_json::from_str(layout).map_err(|e| ApiError {
+            message: format!("Invalid template layout: {}", e),
+            code: Some("INVALID_TEMPLATE".to_string()),
+        })
+    }
+
+    fn layout_json(layout: &LayoutSettings) -> ApiResult<String> {
+        serde_json::to_string(layout).map_err(|e| ApiError {
+            message: format!("Failed to serialize template layout: {}", e),
+            code: Some("SERIALIZATION_ERROR".to_string()),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(store_id: Option<i64>, name: &str) -> CreateInvoiceTemplateRequest {
+        CreateInvoiceTemplateRequest {
+            store_id,
+            name: name.to_string(),
+            format: "a5".to_string(),
+            content: None,
+            layout: None,
+            is_default: Some(true),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_default_template_per_store_and_format() {
+        let db = DatabaseManager::new_with_url("sqlite::memory:").await.unwrap();
+        db.initialize_schema().await.unwrap();
+        let store: Store = sqlx::query_as("SELECT * FROM stores WHERE id = 1")
+            .fetch_one(db.get_pool())
+            .await
+            .unwrap();
+
+        // No saved templates: the built-in renders
+        assert!(InvoiceTemplateService::get_settings(&db, &store, "a5").await.unwrap().template.content.is_none());
+
+        let shared = InvoiceTemplateService::create(&db, request(None, "Shared")).await.unwrap();
+        let own = InvoiceTemplateService::create(&db, request(Some(1), "Koramangala")).await.unwrap();
+        assert!(shared.is_default && own.is_default);
+        assert_eq!(InvoiceTemplateService::default_for(&db, 1, "a5").await.unwrap().unwrap().id, own.id);
+        assert_eq!(InvoiceTemplateService::default_for(&db, 2, "a5").await.unwrap().unwrap().id, shared.id);
+        assert!(InvoiceTemplateService::default_for(&db, 1, "a4").await.unwrap().is_none());
+
+        // A store has one default per format
+        let copy = InvoiceTemplateService::clone_template(&db, own.id, None, Some(1)).await.unwrap();
+        assert_eq!((copy.name.as_str(), copy.is_default), ("Copy of Koramangala", false));
+        let edited = InvoiceTemplateService::update(
+            &db,
+            copy.id,
+            UpdateInvoiceTemplateRequest {
+                name: "Plain".to_string(),
+                content: "{{ invoice.invoice_no }} {{ layout.margin_top }}".to_string(),
+                layout: LayoutSettings { margin_top: 12.0, ..InvoiceTemplateService::export(&copy).unwrap().layout },
+            },
+        )
+        .await
+        .unwrap();
+        InvoiceTemplateService::set_default(&db, edited.id).await.unwrap();
+        assert!(!InvoiceTemplateService::get(&db, own.id).await.unwrap().is_default);
+
+        let settings = InvoiceTemplateService::get_settings(&db, &store, "a5").await.unwrap();
+        assert_eq!((settings.template.display_name.as_str(), settings.template.layout.margin_top), ("Plain", 12.0));
+        let mut data = crate::services::InvoiceHtmlData::sample();
+        data.store = store.clone();
+        assert_eq!(HtmlGenerator::render_invoice_html(&data, &settings).unwrap(), "INV202510001 12");
+
+        InvoiceTemplateService::clear_default(&db, Some(1), "a5").await.unwrap();
+        assert_eq!(InvoiceTemplateService::default_for(&db, 1, "a5").await.unwrap().unwrap().id, shared.id);
+        assert_eq!(InvoiceTemplateService::list(&db, Some(1), Some("a5")).await.unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_invalid_templates_are_not_saved() {
+        let db = DatabaseManager::new_with_url("sqlite::memory:").await.unwrap();
+        db.initialize_schema().await.unwrap();
+        let builtin = TemplateConfig::for_format("a4").unwrap();
+
+        let broken = InvoiceTemplateExport {
+            name: "Broken".to_string(),
+            format: "a4".to_string(),
+            layout: builtin.layout.clone(),
+            content: "{% block items %}{{ items.0.no_such_field }}{% endblock items %}".to_string(),
+        };
+        assert!(InvoiceTemplateService::import(&db, broken.clone(), None).await.is_err());
+
+        let unclosed = InvoiceTemplateExport { content: "{% if invoice %}".to_string(), ..broken.clone() };
+        assert!(InvoiceTemplateService::import(&db, unclosed, None).await.is_err());
+
+        let unreadable = InvoiceTemplateExport {
+            content: "{% extends \"invoice/base.html\" %}".to_string(),
+            layout: LayoutSettings { font_size_normal: 1.0, ..builtin.layout.clone() },
+            ..broken.clone()
+        };
+        assert!(InvoiceTemplateService::import(&db, unreadable, None).await.is_err());
+
+        let letter = InvoiceTemplateExport { format: "letter".to_string(), content: "{{ invoice.invoice_no }}".to_string(), ..broken };
+        assert!(InvoiceTemplateService::import(&db, letter, None).await.is_err());
+        assert!(InvoiceTemplateService::list(&db, None, None).await.unwrap().is_empty());
+    }
+}
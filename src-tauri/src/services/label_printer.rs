@@ -0,0 +1,731 @@
+use crate::models::{TagData, TagSettings};
+use crate::services::barcode::{BarcodeService, BarcodeFormat};
+use crate::services::fonts::Bitmap;
+use anyhow::{Result, Context};
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Print head resolution assumed for all label printers
+const DPI: f64 = 203.0;
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+const DEFAULT_RAW_PORT: u16 = 9100;
+/// TSPL built-in fonts: (name, character width, height) in dots
+const TSPL_FONTS: [(&str, i64, i64); 5] = [("1", 8, 12), ("2", 12, 20), ("3", 16, 24), ("4", 24, 32), ("5", 32, 48)];
+
+/// How tags are sent to the printer: an HTML page for the system print
+/// dialog, or a raw command stream for thermal label printers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PrinterLanguage {
+    Html,
+    Zpl,
+    Tspl,
+    EscPos,
+}
+
+impl PrinterLanguage {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PrinterLanguage::Html => "html",
+            PrinterLanguage::Zpl => "zpl",
+            PrinterLanguage::Tspl => "tspl",
+            PrinterLanguage::EscPos => "escpos",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<PrinterLanguage> {
+        match value.trim().to_lowercase().as_str() {
+            "html" => Some(PrinterLanguage::Html),
+            "zpl" => Some(PrinterLanguage::Zpl),
+            "tspl" => Some(PrinterLanguage::Tspl),
+            "escpos" | "esc/pos" | "esc-pos" => Some(PrinterLanguage::EscPos),
+            _ => None,
+        }
+    }
+}
+
+/// Where raw print data goes, parsed from `TagSettings.printer_name`:
+///
+/// - `tcp://host:port` or `host:port` - network printer raw port (9100 if omitted)
+/// - `/dev/usb/lp0`, `COM3`, `\\host\share` - device or shared printer path
+/// - `file:///path/labels.prn` - writes the stream to a file instead of printing
+/// - `cups://queue` or any other name - CUPS queue, printed with `lp -o raw`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PrinterTarget {
+    Tcp(String),
+    Device(PathBuf),
+    Cups(String),
+    File(PathBuf),
+}
+
+impl PrinterTarget {
+    pub fn parse(printer_name: &str) -> Result<PrinterTarget> {
+        let name = printer_name.trim();
+        if name.is_empty() {
+            return Err(anyhow::anyhow!("No printer configured in tag settings"));
+        }
+
+        if let Some(path) = name.strip_prefix("file://").or_else(|| name.strip_prefix("file:")) {
+            return Ok(PrinterTarget::File(PathBuf::from(path)));
+        }
+        if let Some(queue) = name.strip_prefix("cups://") {
+            return Ok(PrinterTarget::Cups(queue.to_string()));
+        }
+        if let Some(address) = name.strip_prefix("tcp://") {
+            return Ok(PrinterTarget::Tcp(Self::with_default_port(address)));
+        }
+
+        let upper = name.to_uppercase();
+        let is_port_name = ["COM", "LPT"].iter().any(|prefix| {
+            upper.strip_prefix(prefix).is_some_and(|n| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()))
+        });
+        if name.starts_with("/dev/") || name.starts_with("\\\\") || is_port_name {
+            return Ok(PrinterTarget::Device(PathBuf::from(name)));
+        }
+
+        if let Some((host, port)) = name.rsplit_once(':') {
+            if !host.is_empty() && !host.contains(char::is_whitespace) && port.parse::<u16>().is_ok() {
+                return Ok(PrinterTarget::Tcp(name.to_string()));
+            }
+        }
+
+        Ok(PrinterTarget::Cups(name.to_string()))
+    }
+
+    fn with_default_port(address: &str) -> String {
+        if address.rsplit_once(':').is_some_and(|(_, port)| port.parse::<u16>().is_ok()) {
+            address.to_string()
+        } else {
+            format!("{}:{}", address, DEFAULT_RAW_PORT)
+        }
+    }
+
+    /// Send raw bytes to the printer. Blocking; call from `spawn_blocking`.
+    pub fn send(&self, data: &[u8]) -> Result<()> {
+        match self {
+            PrinterTarget::Tcp(address) => {
+                use std::net::{TcpStream, ToSocketAddrs};
+
+                let socket_address = address
+                    .to_socket_addrs()
+                    .with_context(|| format!("Cannot resolve printer address {}", address))?
+                    .next()
+                    .ok_or_else(|| anyhow::anyhow!("Cannot resolve printer address {}", address))?;
+                let mut stream = TcpStream::connect_timeout(&socket_address, CONNECT_TIMEOUT)
+                    .with_context(|| format!("Cannot connect to printer at {}", address))?;
+                stream.set_write_timeout(Some(CONNECT_TIMEOUT))?;
+                stream.write_all(data).with_context(|| format!("Failed to send to printer at {}", address))?;
+                stream.flush()?;
+            }
+            PrinterTarget::Device(path) => {
+                let mut device = std::fs::OpenOptions::new()
+                    .write(true)
+                    .open(path)
+                    .with_context(|| format!("Cannot open printer device {}", path.display()))?;
+                device.write_all(data).with_context(|| format!("Failed to write to {}", path.display()))?;
+                device.flush()?;
+            }
+            PrinterTarget::Cups(queue) => {
+                if cfg!(windows) {
+                    return Err(anyhow::anyhow!(
+                        "Printer queue {} can't take raw data on Windows; share the printer and use \\\\computer\\share",
+                        queue
+                    ));
+                }
+
+                let mut child = std::process::Command::new("lp")
+                    .args(["-d", queue, "-o", "raw"])
+                    .stdin(std::process::Stdio::piped())
+                    .stdout(std::process::Stdio::null())
+                    .stderr(std::process::Stdio::piped())
+                    .spawn()
+                    .context("Failed to run lp")?;
+                child
+                    .stdin
+                    .take()
+                    .ok_or_else(|| anyhow::anyhow!("Failed to open lp input"))?
+                    .write_all(data)
+                    .context("Failed to send data to lp")?;
+
+                let output = child.wait_with_output().context("Failed to wait for lp")?;
+                if !output.status.success() {
+                    return Err(anyhow::anyhow!(
+                        "lp failed for queue {}: {}",
+                        queue,
+                        String::from_utf8_lossy(&output.stderr).trim()
+                    ));
+                }
+            }
+            PrinterTarget::File(path) => {
+                if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+                    std::fs::create_dir_all(dir)?;
+                }
+                std::fs::write(path, data).with_context(|| format!("Failed to write {}", path.display()))?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// One line of label text, centred
+struct LabelLine {
+    text: String,
+    large: bool,
+}
+
+/// How the tag settings shape a raw label: the roll, a text scale clamped
+/// like the HTML templates', and the compact style, which drops add-ons and
+/// tightens line spacing
+struct LabelLayout<'a> {
+    roll_width: &'a str,
+    font_scale: f64,
+    compact: bool,
+}
+
+impl<'a> LabelLayout<'a> {
+    fn new(settings: &'a TagSettings) -> Self {
+        Self {
+            roll_width: &settings.roll_width,
+            font_scale: settings.font_scale.clamp(TagSettings::MIN_FONT_SCALE, TagSettings::MAX_FONT_SCALE),
+            compact: settings.template_style == "compact",
+        }
+    }
+
+    /// A font height in dots at this scale
+    fn scaled(&self, dots: i64) -> i64 {
+        (dots as f64 * self.font_scale).round() as i64
+    }
+
+    fn line_gap(&self) -> i64 {
+        if self.compact { 2 } else { 6 }
+    }
+}
+
+/// Physical label stock for a roll width: (width, printable width, length) in mm
+fn label_size(roll_width: &str) -> (f64, f64, f64) {
+    match roll_width {
+        "32mm" => (32.0, 28.0, 40.0),
+        "50mm" => (50.0, 46.0, 60.0),
+        _ => (40.0, 36.0, 50.0),
+    }
+}
+
+fn dots(mm: f64) -> i64 {
+    (mm * DPI / 25.4).round() as i64
+}
+
+/// The same content as the HTML tag templates: short names on 32mm rolls,
+/// garment details from 40mm and damage notes on 50mm
+fn label_lines(tag: &TagData, layout: &LabelLayout) -> Vec<LabelLine> {
+    let roll_width = layout.roll_width;
+    let narrow = roll_width == "32mm";
+    let header = format!("{} ({}/{})", tag.invoice_no, tag.overall_piece_number, tag.total_invoice_pieces);
+    let mut lines = vec![
+        LabelLine {
+            text: match &tag.store_code {
+                Some(store_code) => format!("{} {}", store_code, header),
+                None => header,
+            },
+            large: true,
+        },
+        LabelLine { text: tag.customer_name.clone(), large: true },
+    ];
+
+    if let Some(phone) = &tag.customer_phone {
+        lines.push(LabelLine { text: phone.clone(), large: false });
+    }
+    lines.push(LabelLine { text: tag.service_name.clone(), large: false });
+
+    if let Some(addons) = tag.addons.as_ref().filter(|_| !layout.compact) {
+        lines.push(LabelLine { text: format!("+ {}", addons), large: false });
+    }
+    if !narrow {
+        if let Some(garment) = &tag.garment {
+            lines.push(LabelLine { text: garment.clone(), large: false });
+        }
+    }
+    if roll_width == "50mm" {
+        if let Some(damage_notes) = &tag.damage_notes {
+            lines.push(LabelLine { text: format!("Noted: {}", damage_notes), large: false });
+        }
+    }
+
+    lines.push(LabelLine { text: format!("{} of {}", tag.tag_number, tag.total_quantity), large: true });
+    if let Some(delivery_date) = &tag.delivery_date {
+        let text = if narrow {
+            delivery_date.split_whitespace().take(2).collect::<Vec<_>>().join(" ")
+        } else {
+            delivery_date.clone()
+        };
+        lines.push(LabelLine { text, large: false });
+    }
+
+    lines
+}
+
+/// Printer fonts here only cover ASCII; anything else prints as `?`
+fn ascii_only(text: &str) -> String {
+    text.chars().map(|c| if c.is_ascii() && !c.is_ascii_control() { c } else { '?' }).collect()
+}
+
+pub(crate) fn truncate(text: &str, max_chars: usize) -> String {
+    if text.chars().count() <= max_chars {
+        text.to_string()
+    } else {
+        let mut truncated: String = text.chars().take(max_chars.saturating_sub(1)).collect();
+        truncated.push('.');
+        truncated
+    }
+}
+
+pub struct LabelRenderer;
+
+impl LabelRenderer {
+    /// Raw printer commands for the tags, one label per tag, laid out by the
+    /// store's roll width, font scale and template style
+    pub fn render(language: PrinterLanguage, tags: &[TagData], settings: &TagSettings) -> Result<Vec<u8>> {
+        let layout = LabelLayout::new(settings);
+        let mut output = Vec::new();
+        for tag in tags {
+            match language {
+                PrinterLanguage::Zpl => output.extend(Self::render_zpl(tag, &layout)?),
+                PrinterLanguage::Tspl => output.extend(Self::render_tspl(tag, &layout)?),
+                PrinterLanguage::EscPos => output.extend(Self::render_escpos(tag, &layout)?),
+                PrinterLanguage::Html => {
+                    return Err(anyhow::anyhow!("HTML tags are printed through the tag templates, not raw"));
+                }
+            }
+        }
+        Ok(output)
+    }
+
+    fn render_zpl(tag: &TagData, layout: &LabelLayout) -> Result<Vec<u8>> {
+        let roll_width = layout.roll_width;
+        let (width_mm, printable_mm, length_mm) = label_size(roll_width);
+        let printable = dots(printable_mm);
+        // Field data goes through ^FH so ^ and ~ can't start commands
+        let field = |text: &str| ascii_only(text).replace('\\', "\\5C").replace('^', "\\5E").replace('~', "\\7E");
+
+        let mut zpl = format!(
+            "^XA\n^PW{}\n^LL{}\n^LH{},{}\n",
+            dots(width_mm),
+            dots(length_mm),
+            dots((width_mm - printable_mm) / 2.0),
+            dots(1.5)
+        );
+
+        let mut y = 0;
+        for line in label_lines(tag, layout) {
+            // Font 0 averages about half its height per character; long
+            // lines shrink to two thirds of their size before they are cut short
+            let preferred = layout.scaled(if line.large { 28 } else { 22 });
+            let fitting = (printable as f64 / (line.text.chars().count() as f64 * 0.5)) as i64;
+            let height = preferred.min(fitting).max(layout.scaled(16).min(preferred));
+            let max_chars = (printable as f64 / (height as f64 * 0.5)) as usize;
+            zpl.push_str(&format!(
+                "^FO0,{}^A0N,{},{}^FB{},1,0,C^FH\\^FD{}^FS\n",
+                y,
+                height,
+                height,
+                printable,
+                field(&truncate(&line.text, max_chars))
+            ));
+            y += height + layout.line_gap();
+        }
+
+        if tag.include_barcode {
+            y += 4;
+            let barcode = BarcodeService::tag_barcode(&tag.tag_code, roll_width)?;
+            let module = dots(barcode.module_mm);
+            let width = dots(barcode.width_mm);
+            match barcode.format {
+                BarcodeFormat::Code128 => {
+                    zpl.push_str(&format!(
+                        "^FO{},{}^BY{}^BCN,{},N,N,N,A^FD{}^FS\n",
+                        (printable - width) / 2 + 10 * module,
+                        y,
+                        module,
+                        dots(barcode.height_mm),
+                        field(&tag.tag_code)
+                    ));
+                    y += dots(barcode.height_mm) + 6;
+                }
+                BarcodeFormat::Qr => {
+                    zpl.push_str(&format!(
+                        "^FO{},{}^BQN,2,{}^FDMA,{}^FS\n",
+                        (printable - width) / 2,
+                        y,
+                        module,
+                        field(&tag.tag_code)
+                    ));
+                    y += width + 6;
+                }
+            }
+            zpl.push_str(&format!("^FO0,{}^A0N,18,18^FB{},1,0,C^FH\\^FD{}^FS\n", y, printable, field(&tag.tag_code)));
+        }
+
+        zpl.push_str("^PQ1\n^XZ\n");
+        Ok(zpl.into_bytes())
+    }
+
+    fn render_tspl(tag: &TagData, layout: &LabelLayout) -> Result<Vec<u8>> {
+        let roll_width = layout.roll_width;
+        let (width_mm, printable_mm, length_mm) = label_size(roll_width);
+        let centre = dots(width_mm / 2.0);
+        let quote = |text: &str| ascii_only(text).replace('"', "\\[\"]");
+
+        let mut tspl = format!(
+            "SIZE {} mm,{} mm\r\nGAP 2 mm,0 mm\r\nDIRECTION 1\r\nCLS\r\n",
+            width_mm, length_mm
+        );
+
+        let mut y = dots(1.5);
+        for line in label_lines(tag, layout) {
+            // Fonts come in fixed sizes: take the largest one that fits the
+            // scaled height of font 3 (16x24 dots) or font 2 (12x20)
+            let target = layout.scaled(if line.large { 24 } else { 20 });
+            let (font, char_width, height) = TSPL_FONTS
+                .iter()
+                .rev()
+                .find(|(_, _, height)| *height <= target)
+                .copied()
+                .unwrap_or(TSPL_FONTS[0]);
+            let max_chars = (dots(printable_mm) / char_width) as usize;
+            tspl.push_str(&format!(
+                "TEXT {},{},\"{}\",0,1,1,2,\"{}\"\r\n",
+                centre,
+                y,
+                font,
+                quote(&truncate(&line.text, max_chars))
+            ));
+            y += height + layout.line_gap();
+        }
+
+        if tag.include_barcode {
+            y += 4;
+            let barcode = BarcodeService::tag_barcode(&tag.tag_code, roll_width)?;
+            let module = dots(barcode.module_mm);
+            let width = dots(barcode.width_mm);
+            let left = (dots(width_mm) - width) / 2;
+            match barcode.format {
+                BarcodeFormat::Code128 => {
+                    tspl.push_str(&format!(
+                        "BARCODE {},{},\"128M\",{},0,0,{},{},\"{}\"\r\n",
+                        left + 10 * module,
+                        y,
+                        dots(barcode.height_mm),
+                        module,
+                        module,
+                        quote(&tag.tag_code)
+                    ));
+                    y += dots(barcode.height_mm) + 6;
+                }
+                BarcodeFormat::Qr => {
+                    tspl.push_str(&format!(
+                        "QRCODE {},{},M,{},A,0,\"{}\"\r\n",
+                        left,
+                        y,
+                        module,
+                        quote(&tag.tag_code)
+                    ));
+                    y += width + 6;
+                }
+            }
+            tspl.push_str(&format!("TEXT {},{},\"1\",0,1,1,2,\"{}\"\r\n", centre, y, quote(&tag.tag_code)));
+        }
+
+        tspl.push_str("PRINT 1,1\r\n");
+        Ok(tspl.into_bytes())
+    }
+
+    fn render_escpos(tag: &TagData, layout: &LabelLayout) -> Result<Vec<u8>> {
+        let roll_width = layout.roll_width;
+        let (_, printable_mm, _) = label_size(roll_width);
+        // ESC/POS only has fixed sizes: font B (9x17 dots) below full scale,
+        // font A (12x24), and double height for large lines from 1.25x
+        let small = layout.font_scale < 1.0;
+        let max_chars = (dots(printable_mm) / if small { 9 } else { 12 }) as usize;
+
+        let mut escpos = EscPosBuilder::new();
+        escpos.align_centre();
+        escpos.small_font(small);
+        for line in label_lines(tag, layout) {
+            escpos.bold(line.large);
+            escpos.double_height(line.large && layout.font_scale >= 1.25);
+            escpos.line(&truncate(&line.text, max_chars));
+        }
+        escpos.bold(false);
+        escpos.double_height(false);
+        escpos.small_font(false);
+
+        if tag.include_barcode {
+            let barcode = BarcodeService::tag_barcode(&tag.tag_code, roll_width)?;
+            let module = dots(barcode.module_mm) as u8;
+            match barcode.format {
+                BarcodeFormat::Code128 => escpos.code128(&tag.tag_code, module, dots(barcode.height_mm) as u8),
+                BarcodeFormat::Qr => escpos.qr_code(&tag.tag_code, module),
+            };
+            escpos.line(&tag.tag_code);
+        }
+
+        escpos.feed(3);
+        escpos.cut();
+        Ok(escpos.finish())
+    }
+}
+
+/// Minimal ESC/POS command builder shared by tag and receipt printing
+pub struct EscPosBuilder {
+    bytes: Vec<u8>,
+}
+
+impl Default for EscPosBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EscPosBuilder {
+    pub fn new() -> Self {
+        // ESC @ resets the printer to its defaults
+        Self { bytes: vec![0x1B, 0x40] }
+    }
+
+    pub fn align_left(&mut self) -> &mut Self {
+        self.bytes.extend([0x1B, 0x61, 0x00]);
+        self
+    }
+
+    pub fn align_centre(&mut self) -> &mut Self {
+        self.bytes.extend([0x1B, 0x61, 0x01]);
+        self
+    }
+
+    /// Double width and height; a line then holds half as many characters
+    pub fn double_size(&mut self, on: bool) -> &mut Self {
+        self.bytes.extend([0x1D, 0x21, if on { 0x11 } else { 0x00 }]);
+        self
+    }
+
+    /// Double height only, so a line still holds as many characters
+    pub fn double_height(&mut self, on: bool) -> &mut Self {
+        self.bytes.extend([0x1D, 0x21, if on { 0x01 } else { 0x00 }]);
+        self
+    }
+
+    /// Font B, smaller than the default font A
+    pub fn small_font(&mut self, on: bool) -> &mut Self {
+        self.bytes.extend([0x1B, 0x4D, on as u8]);
+        self
+    }
+
+    pub fn bold(&mut self, on: bool) -> &mut Self {
+        self.bytes.extend([0x1B, 0x45, on as u8]);
+        self
+    }
+
+    pub fn text(&mut self, text: &str) -> &mut Self {
+        self.bytes.extend(ascii_only(text).into_bytes());
+        self
+    }
+
+    pub fn line(&mut self, text: &str) -> &mut Self {
+        self.text(text);
+        self.bytes.push(b'\n');
+        self
+    }
+
+    pub fn feed(&mut self, lines: u8) -> &mut Self {
+        self.bytes.extend([0x1B, 0x64, lines]);
+        self
+    }
+
+    /// Code 128 (GS k 73) in code set B, without printer-drawn text
+    pub fn code128(&mut self, data: &str, module_dots: u8, height_dots: u8) -> &mut Self {
+        let data = ascii_only(data);
+        self.bytes.extend([0x1D, 0x68, height_dots.max(1)]);
+        self.bytes.extend([0x1D, 0x77, module_dots.clamp(2, 6)]);
+        self.bytes.extend([0x1D, 0x48, 0x00]);
+        self.bytes.extend([0x1D, 0x6B, 73, (data.len() + 2).min(255) as u8, b'{', b'B']);
+        self.bytes.extend(data.bytes().take(253));
+        self.bytes.push(b'\n');
+        self
+    }
+
+    /// QR code (GS ( k, model 2, error correction M)
+    pub fn qr_code(&mut self, data: &str, module_dots: u8) -> &mut Self {
+        let store_len = data.len() + 3;
+        self.bytes.extend([0x1D, 0x28, 0x6B, 0x04, 0x00, 0x31, 0x41, 0x32, 0x00]);
+        self.bytes.extend([0x1D, 0x28, 0x6B, 0x03, 0x00, 0x31, 0x43, module_dots.clamp(1, 16)]);
+        self.bytes.extend([0x1D, 0x28, 0x6B, 0x03, 0x00, 0x31, 0x45, 0x31]);
+        self.bytes.extend([0x1D, 0x28, 0x6B, (store_len % 256) as u8, (store_len / 256) as u8, 0x31, 0x50, 0x30]);
+        self.bytes.extend(data.bytes());
+        self.bytes.extend([0x1D, 0x28, 0x6B, 0x03, 0x00, 0x31, 0x51, 0x30]);
+        self.bytes.push(b'\n');
+        self
+    }
+
+    /// A black and white image (GS v 0), e.g. text in a script the
+    /// printer's font lacks
+    pub fn raster(&mut self, bitmap: &Bitmap) -> &mut Self {
+        let row_bytes = (bitmap.width + 7) / 8;
+        self.bytes.extend([0x1D, 0x76, 0x30, 0x00]);
+        self.bytes.extend([(row_bytes % 256) as u8, (row_bytes / 256) as u8]);
+        self.bytes.extend([(bitmap.height % 256) as u8, (bitmap.height / 256) as u8]);
+        self.bytes.extend(bitmap.packed_rows());
+        self
+    }
+
+    /// Feed past the cutter and partial-cut
+    pub fn cut(&mut self) -> &mut Self {
+        self.bytes.extend([0x1D, 0x56, 0x42, 0x00]);
+        self
+    }
+
+    pub fn finish(self) -> Vec<u8> {
+        self.bytes
+    }
+}
+
+pub struct LabelPrinterService;
+
+impl LabelPrinterService {
+    /// Render tags in the printer's language and send them to the printer
+    /// named in the tag settings. Returns the number of bytes sent.
+    pub async fn print_tags(
+        settings: &TagSettings,
+        language: PrinterLanguage,
+        tags: &[TagData],
+    ) -> Result<usize> {
+        let target = PrinterTarget::parse(settings.printer_name.as_deref().unwrap_or(""))?;
+        let data = LabelRenderer::render(language, tags, settings)?;
+        let sent = data.len();
+
+        tokio::task::spawn_blocking(move || target.send(&data))
+            .await
+            .context("Printer task failed")??;
+
+        Ok(sent)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_tag() -> TagData {
+        TagData {
+            invoice_no: "INV202510__001".to_string(),
+            customer_name: "Rao ^ Sons".to_string(),
+            service_name: "Dry Clean - Saree".to_string(),
+            addons: Some("Starch".to_string()),
+            tag_number: 1,
+            total_quantity: 2,
+            overall_piece_number: 1,
+            total_invoice_pieces: 2,
+            delivery_date: Some("24/10/2025 07:30 PM".to_string()),
+            tag_code: "INV202510__001-17-1".to_string(),
+            include_barcode: true,
+            garment: Some("Saree, Red".to_string()),
+            damage_notes: None,
+            customer_phone: Some("9876543210".to_string()),
+            store_code: None,
+        }
+    }
+
+    fn settings(roll_width: &str) -> TagSettings {
+        TagSettings {
+            roll_width: roll_width.to_string(),
+            ..TagSettings::defaults(None)
+        }
+    }
+
+    #[test]
+    fn test_printer_target_parse() {
+        assert_eq!(PrinterTarget::parse("tcp://10.0.0.5").unwrap(), PrinterTarget::Tcp("10.0.0.5:9100".to_string()));
+        assert_eq!(PrinterTarget::parse("10.0.0.5:6101").unwrap(), PrinterTarget::Tcp("10.0.0.5:6101".to_string()));
+        assert_eq!(PrinterTarget::parse("/dev/usb/lp0").unwrap(), PrinterTarget::Device(PathBuf::from("/dev/usb/lp0")));
+        assert_eq!(PrinterTarget::parse("com3").unwrap(), PrinterTarget::Device(PathBuf::from("com3")));
+        assert_eq!(PrinterTarget::parse("file:///tmp/tags.prn").unwrap(), PrinterTarget::File(PathBuf::from("/tmp/tags.prn")));
+        assert_eq!(PrinterTarget::parse("Zebra_ZD220").unwrap(), PrinterTarget::Cups("Zebra_ZD220".to_string()));
+        assert!(PrinterTarget::parse("  ").is_err());
+    }
+
+    #[test]
+    fn test_render_label_languages() {
+        let tags = [sample_tag(), sample_tag()];
+
+        let zpl = String::from_utf8(LabelRenderer::render(PrinterLanguage::Zpl, &tags, &settings("40mm")).unwrap()).unwrap();
+        assert_eq!(zpl.matches("^XA").count(), 2);
+        assert!(zpl.contains("^PW320"));
+        assert!(zpl.contains("Rao \\5E Sons"));
+        assert!(zpl.contains("INV202510__001 (1/2)"));
+        assert!(zpl.contains("^FD9876543210^FS"));
+        assert!(zpl.contains("^BQN,2,4^FDMA,INV202510__001-17-1^FS"));
+
+        // Label fonts only cover ASCII, so ZPL folds text like the other languages
+        let accented = TagData { customer_name: "Zoë Rao".to_string(), ..sample_tag() };
+        let zpl = LabelRenderer::render(PrinterLanguage::Zpl, &[accented], &settings("40mm")).unwrap();
+        assert!(zpl.is_ascii());
+        assert!(String::from_utf8(zpl).unwrap().contains("^FDZo? Rao^FS"));
+
+        let tspl = String::from_utf8(LabelRenderer::render(PrinterLanguage::Tspl, &tags, &settings("50mm")).unwrap()).unwrap();
+        assert!(tspl.starts_with("SIZE 50 mm,60 mm"));
+        assert_eq!(tspl.matches("PRINT 1,1").count(), 2);
+        assert!(tspl.contains("QRCODE"));
+
+        let escpos = LabelRenderer::render(PrinterLanguage::EscPos, &tags[..1], &settings("32mm")).unwrap();
+        assert_eq!(&escpos[..2], &[0x1B, 0x40]);
+        assert_eq!(&escpos[escpos.len() - 4..], &[0x1D, 0x56, 0x42, 0x00]);
+
+        assert!(LabelRenderer::render(PrinterLanguage::Html, &tags, &settings("40mm")).is_err());
+    }
+
+    #[test]
+    fn test_render_label_settings() {
+        let tags = [sample_tag()];
+        let render = |language, settings: &TagSettings| {
+            String::from_utf8(LabelRenderer::render(language, &tags, settings).unwrap()).unwrap()
+        };
+        let large = TagSettings { font_scale: 1.5, ..settings("50mm") };
+        let small = TagSettings { font_scale: 0.7, ..settings("50mm") };
+        let compact = TagSettings { template_style: "compact".to_string(), ..settings("50mm") };
+
+        // Font heights follow the scale
+        assert!(render(PrinterLanguage::Zpl, &settings("50mm")).contains("^A0N,28,28^FB368,1,0,C^FH\\^FD1 of 2^FS"));
+        assert!(render(PrinterLanguage::Zpl, &large).contains("^A0N,42,42^FB368,1,0,C^FH\\^FD1 of 2^FS"));
+        assert!(render(PrinterLanguage::Zpl, &small).contains("^A0N,20,20^FB368,1,0,C^FH\\^FD1 of 2^FS"));
+        assert!(render(PrinterLanguage::Tspl, &settings("50mm")).contains("\"3\",0,1,1,2,\"1 of 2\""));
+        assert!(render(PrinterLanguage::Tspl, &large).contains("\"4\",0,1,1,2,\"1 of 2\""));
+        assert!(render(PrinterLanguage::Tspl, &small).contains("\"1\",0,1,1,2,\"1 of 2\""));
+
+        let escpos = LabelRenderer::render(PrinterLanguage::EscPos, &tags, &small).unwrap();
+        assert!(escpos.windows(3).any(|command| command == [0x1B, 0x4D, 1]));
+        let escpos = LabelRenderer::render(PrinterLanguage::EscPos, &tags, &large).unwrap();
+        assert!(escpos.windows(3).any(|command| command == [0x1D, 0x21, 0x01]));
+
+        // The compact style drops add-ons like the HTML template
+        assert!(render(PrinterLanguage::Zpl, &settings("50mm")).contains("+ Starch"));
+        assert!(!render(PrinterLanguage::Zpl, &compact).contains("+ Starch"));
+        assert!(!render(PrinterLanguage::Tspl, &compact).contains("+ Starch"));
+    }
+
+    #[tokio::test]
+    async fn test_print_tags_to_file_sink() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("labels").join("tags.prn");
+        let file_printer = TagSettings {
+            printer_name: Some(format!("file://{}", path.display())),
+            ..settings("40mm")
+        };
+
+        let sent = LabelPrinterService::print_tags(&file_printer, PrinterLanguage::Zpl, &[sample_tag()])
+            .await
+            .unwrap();
+
+        let written = std::fs::read(&path).unwrap();
+        assert_eq!(written.len(), sent);
+        assert_eq!(written, LabelRenderer::render(PrinterLanguage::Zpl, &[sample_tag()], &file_printer).unwrap());
+    }
+}
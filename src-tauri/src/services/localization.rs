@@ -0,0 +1,395 @@
+use crate::models::{ApiError, ApiResult, Customer, Store};
+use crate::services::html_generator::HtmlGenerator;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// String catalogs for invoice and tag templates, one JSON object per locale.
+/// English is complete; other locales fall back to it for missing keys.
+const CATALOGS: [(Locale, &str); 3] = [
+    (Locale::En, include_str!("../locales/en.json")),
+    (Locale::Hi, include_str!("../locales/hi.json")),
+    (Locale::Ta, include_str!("../locales/ta.json")),
+];
+
+/// Languages invoices and tags can print in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Locale {
+    En,
+    Hi,
+    Ta,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LocaleInfo {
+    pub code: String,
+    pub name: String,
+}
+
+impl Locale {
+    pub const ALL: [Locale; 3] = [Locale::En, Locale::Hi, Locale::Ta];
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            Locale::En => "en",
+            Locale::Hi => "hi",
+            Locale::Ta => "ta",
+        }
+    }
+
+    /// Name of the language in that language, for pickers
+    pub fn name(&self) -> &'static str {
+        match self {
+            Locale::En => "English",
+            Locale::Hi => "हिन्दी",
+            Locale::Ta => "தமிழ்",
+        }
+    }
+
+    pub fn parse(code: &str) -> Option<Locale> {
+        Self::ALL.into_iter().find(|locale| locale.code() == code.trim().to_lowercase())
+    }
+
+    /// Check a locale code from a store or customer form. Blank clears it.
+    pub fn validate(code: Option<&str>) -> ApiResult<Option<String>> {
+        match code.map(str::trim).filter(|code| !code.is_empty()) {
+            None => Ok(None),
+            Some(code) => Self::parse(code).map(|locale| Some(locale.code().to_string())).ok_or_else(|| ApiError {
+                message: format!("Unsupported language: {}", code),
+                code: Some("VALIDATION_ERROR".to_string()),
+            }),
+        }
+    }
+
+    pub fn all() -> Vec<LocaleInfo> {
+        Self::ALL
+            .iter()
+            .map(|locale| LocaleInfo { code: locale.code().to_string(), name: locale.name().to_string() })
+            .collect()
+    }
+
+    /// Template labels for this locale, with English filling any gaps
+    pub fn catalog(&self) -> HashMap<String, String> {
+        let parse = |locale: Locale| -> HashMap<String, String> {
+            CATALOGS
+                .iter()
+                .find(|(catalog_locale, _)| *catalog_locale == locale)
+                .and_then(|(_, json)| serde_json::from_str(json).ok())
+                .unwrap_or_default()
+        };
+
+        let mut catalog = parse(Locale::En);
+        if *self != Locale::En {
+            catalog.extend(parse(*self));
+        }
+        catalog
+    }
+
+    pub fn amount_in_words(&self, amount: f64) -> String {
+        match self {
+            Locale::En => HtmlGenerator::amount_to_words(amount),
+            Locale::Hi => hindi_amount_in_words(amount),
+            Locale::Ta => tamil_amount_in_words(amount),
+        }
+    }
+}
+
+/// The language a document prints in, with an optional second language
+/// printed alongside each label for bilingual layouts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DocumentLanguage {
+    pub primary: Locale,
+    pub secondary: Option<Locale>,
+}
+
+impl DocumentLanguage {
+    pub fn new(primary: Locale, secondary: Option<Locale>) -> Self {
+        Self { primary, secondary: secondary.filter(|locale| *locale != primary) }
+    }
+
+    /// The store's language and its bilingual partner, English by default
+    pub fn for_store(store: &Store) -> Self {
+        let primary = store.locale.as_deref().and_then(Locale::parse).unwrap_or(Locale::En);
+        Self::new(primary, store.secondary_locale.as_deref().and_then(Locale::parse))
+    }
+
+    /// Invoices print in the customer's language when they have one. The
+    /// store's own language then goes alongside, so the counter can still
+    /// read the invoice.
+    pub fn for_invoice(store: &Store, customer: &Customer) -> Self {
+        let store_language = Self::for_store(store);
+        match customer.locale.as_deref().and_then(Locale::parse) {
+            Some(locale) if locale != store_language.primary => {
+                Self::new(locale, store_language.secondary.or(Some(store_language.primary)))
+            }
+            _ => store_language,
+        }
+    }
+
+    /// Tags are too small for two languages. They print in the store's
+    /// language, which the staff handling the garments read, and fall back
+    /// to the customer's when the store hasn't set one.
+    pub fn for_tags(store_locale: Option<&str>, customer_locale: Option<&str>) -> Locale {
+        store_locale
+            .and_then(Locale::parse)
+            .or_else(|| customer_locale.and_then(Locale::parse))
+            .unwrap_or(Locale::En)
+    }
+
+    /// Drop the languages a renderer can't print, e.g. a script its fonts
+    /// don't cover. English is left when neither language can print.
+    pub fn restricted_to(&self, printable: impl Fn(Locale) -> bool) -> Self {
+        let mut languages = std::iter::once(self.primary).chain(self.secondary).filter(|locale| printable(*locale));
+        match languages.next() {
+            Some(primary) => Self::new(primary, languages.next()),
+            None => Self::new(Locale::En, None),
+        }
+    }
+
+    /// Template labels, as "Primary / Secondary" when bilingual
+    pub fn labels(&self) -> HashMap<String, String> {
+        let mut labels = self.primary.catalog();
+        if let Some(secondary) = self.secondary.map(|locale| locale.catalog()) {
+            for (key, label) in labels.iter_mut() {
+                if let Some(other) = secondary.get(key).filter(|other| *other != label) {
+                    *label = format!("{} / {}", label, other);
+                }
+            }
+        }
+        labels
+    }
+
+    /// The amount in words in each language, primary first
+    pub fn amount_in_words(&self, amount: f64) -> Vec<String> {
+        std::iter::once(self.primary)
+            .chain(self.secondary)
+            .map(|locale| locale.amount_in_words(amount))
+            .collect()
+    }
+}
+
+/// Split whole rupees into Indian groups: crores, lakhs, thousands and the
+/// last three digits
+fn indian_groups(rupees: u64) -> (u64, u64, u64, u64) {
+    (rupees / 10_000_000, (rupees % 10_000_000) / 100_000, (rupees % 100_000) / 1_000, rupees % 1_000)
+}
+
+/// Round to whole paise first, then split, so 1.999 is two rupees rather
+/// than one rupee and a hundred paise
+pub(crate) fn rupees_and_paise(amount: f64) -> (u64, u64) {
+    let paise = (amount.max(0.0) * 100.0).round() as u64;
+    (paise / 100, paise % 100)
+}
+
+/// Hindi has its own word for every number below a hundred
+const HINDI_BELOW_HUNDRED: [&str; 100] = [
+    "", "एक", "दो", "तीन", "चार", "पाँच", "छह", "सात", "आठ", "नौ",
+    "दस", "ग्यारह", "बारह", "तेरह", "चौदह", "पंद्रह", "सोलह", "सत्रह", "अठारह", "उन्नीस",
+    "बीस", "इक्कीस", "बाईस", "तेईस", "चौबीस", "पच्चीस", "छब्बीस", "सत्ताईस", "अट्ठाईस", "उनतीस",
+    "तीस", "इकतीस", "बत्तीस", "तैंतीस", "चौंतीस", "पैंतीस", "छत्तीस", "सैंतीस", "अड़तीस", "उनतालीस",
+    "चालीस", "इकतालीस", "बयालीस", "तैंतालीस", "चौवालीस", "पैंतालीस", "छियालीस", "सैंतालीस", "अड़तालीस", "उनचास",
+    "पचास", "इक्यावन", "बावन", "तिरेपन", "चौवन", "पचपन", "छप्पन", "सत्तावन", "अट्ठावन", "उनसठ",
+    "साठ", "इकसठ", "बासठ", "तिरसठ", "चौंसठ", "पैंसठ", "छियासठ", "सड़सठ", "अड़सठ", "उनहत्तर",
+    "सत्तर", "इकहत्तर", "बहत्तर", "तिहत्तर", "चौहत्तर", "पचहत्तर", "छिहत्तर", "सतहत्तर", "अठहत्तर", "उन्यासी",
+    "अस्सी", "इक्यासी", "बयासी", "तिरासी", "चौरासी", "पचासी", "छियासी", "सत्तासी", "अट्ठासी", "नवासी",
+    "नब्बे", "इक्यानवे", "बानवे", "तिरानवे", "चौरानवे", "पचानवे", "छियानवे", "सत्तानवे", "अट्ठानवे", "निन्यानवे",
+];
+
+fn hindi_group(num: u64) -> String {
+    let mut words = Vec::new();
+    if num >= 100 {
+        words.push(format!("{} सौ", HINDI_BELOW_HUNDRED[(num / 100 % 10) as usize]));
+    }
+    if num % 100 > 0 {
+        words.push(HINDI_BELOW_HUNDRED[(num % 100) as usize].to_string());
+    }
+    words.join(" ")
+}
+
+/// "एक हज़ार दो सौ पचास रुपये और पचास पैसे मात्र"
+fn hindi_amount_in_words(amount: f64) -> String {
+    let (rupees, paise) = rupees_and_paise(amount);
+    if rupees == 0 && paise == 0 {
+        return "शून्य रुपये मात्र".to_string();
+    }
+
+    let (crores, lakhs, thousands, remainder) = indian_groups(rupees);
+    let mut words = Vec::new();
+    for (count, scale) in [(crores, "करोड़"), (lakhs, "लाख"), (thousands, "हज़ार")] {
+        if count > 0 {
+            words.push(format!("{} {}", hindi_group(count), scale));
+        }
+    }
+    if remainder > 0 {
+        words.push(hindi_group(remainder));
+    }
+    if rupees > 0 {
+        words.push("रुपये".to_string());
+    }
+
+    if paise > 0 {
+        if rupees > 0 {
+            words.push("और".to_string());
+        }
+        words.push(format!("{} पैसे", hindi_group(paise)));
+    }
+    words.push("मात्र".to_string());
+
+    words.join(" ")
+}
+
+const TAMIL_UNITS: [&str; 10] = ["", "ஒன்று", "இரண்டு", "மூன்று", "நான்கு", "ஐந்து", "ஆறு", "ஏழு", "எட்டு", "ஒன்பது"];
+const TAMIL_TEENS: [&str; 10] = [
+    "பத்து", "பதினொன்று", "பன்னிரண்டு", "பதின்மூன்று", "பதினான்கு",
+    "பதினைந்து", "பதினாறு", "பதினேழு", "பதினெட்டு", "பத்தொன்பது",
+];
+/// Tens and hundreds, alone and in the joining form used when more follows
+const TAMIL_TENS: [(&str, &str); 10] = [
+    ("", ""), ("", ""), ("இருபது", "இருபத்து"), ("முப்பது", "முப்பத்து"), ("நாற்பது", "நாற்பத்து"),
+    ("ஐம்பது", "ஐம்பத்து"), ("அறுபது", "அறுபத்து"), ("எழுபது", "எழுபத்து"), ("எண்பது", "எண்பத்து"),
+    ("தொண்ணூறு", "தொண்ணூற்று"),
+];
+const TAMIL_HUNDREDS: [(&str, &str); 10] = [
+    ("", ""), ("நூறு", "நூற்று"), ("இருநூறு", "இருநூற்று"), ("முந்நூறு", "முந்நூற்று"), ("நானூறு", "நானூற்று"),
+    ("ஐந்நூறு", "ஐந்நூற்று"), ("அறுநூறு", "அறுநூற்று"), ("எழுநூறு", "எழுநூற்று"), ("எண்ணூறு", "எண்ணூற்று"),
+    ("தொள்ளாயிரம்", "தொள்ளாயிரத்து"),
+];
+
+fn tamil_group(num: u64) -> String {
+    let mut words = Vec::new();
+    let rest = num % 100;
+    if num >= 100 {
+        let (alone, joining) = TAMIL_HUNDREDS[(num / 100 % 10) as usize];
+        words.push(if rest > 0 { joining } else { alone });
+    }
+    if rest >= 20 {
+        let (alone, joining) = TAMIL_TENS[(rest / 10) as usize];
+        if rest % 10 > 0 {
+            words.push(joining);
+            words.push(TAMIL_UNITS[(rest % 10) as usize]);
+        } else {
+            words.push(alone);
+        }
+    } else if rest >= 10 {
+        words.push(TAMIL_TEENS[(rest - 10) as usize]);
+    } else if rest > 0 {
+        words.push(TAMIL_UNITS[rest as usize]);
+    }
+    words.join(" ")
+}
+
+/// "ஆயிரத்து இருநூற்று ஐம்பது ரூபாய் ஐம்பது பைசா மட்டும்"
+fn tamil_amount_in_words(amount: f64) -> String {
+    let (rupees, paise) = rupees_and_paise(amount);
+    if rupees == 0 && paise == 0 {
+        return "பூஜ்யம் ரூபாய் மட்டும்".to_string();
+    }
+
+    let (crores, lakhs, thousands, remainder) = indian_groups(rupees);
+    let scales = [
+        (crores, ("கோடி", "கோடியே"), lakhs + thousands + remainder > 0),
+        (lakhs, ("லட்சம்", "லட்சத்து"), thousands + remainder > 0),
+        (thousands, ("ஆயிரம்", "ஆயிரத்து"), remainder > 0),
+    ];
+
+    let mut words = Vec::new();
+    for (count, (alone, joining), more_follows) in scales {
+        let scale = if more_follows { joining } else { alone };
+        match count {
+            0 => {}
+            // A thousand on its own; one lakh or crore takes "ஒரு"
+            1 if alone == "ஆயிரம்" => words.push(scale.to_string()),
+            1 => words.push(format!("ஒரு {}", scale)),
+            _ => words.push(format!("{} {}", tamil_group(count), scale)),
+        }
+    }
+    if remainder > 0 {
+        words.push(tamil_group(remainder));
+    }
+    if rupees > 0 {
+        words.push("ரூபாய்".to_string());
+    }
+
+    if paise > 0 {
+        words.push(format!("{} பைசா", tamil_group(paise)));
+    }
+    words.push("மட்டும்".to_string());
+
+    words.join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::html_generator::test_data;
+
+    #[test]
+    fn test_catalogs_cover_every_label() {
+        let english: HashMap<String, String> = serde_json::from_str(CATALOGS[0].1).unwrap();
+        for (locale, json) in CATALOGS {
+            let catalog: HashMap<String, String> = serde_json::from_str(json).unwrap();
+            let missing: Vec<&String> = english.keys().filter(|key| !catalog.contains_key(*key)).collect();
+            assert!(missing.is_empty(), "{} is missing {:?}", locale.code(), missing);
+        }
+        assert_eq!(Locale::Hi.catalog()["invoice_no"], "बिल संख्या");
+        assert_eq!(Locale::validate(Some(" HI ")).unwrap().as_deref(), Some("hi"));
+        assert_eq!(Locale::validate(Some("")).unwrap(), None);
+        assert!(Locale::validate(Some("fr")).is_err());
+    }
+
+    #[test]
+    fn test_amount_in_words() {
+        assert_eq!(Locale::Hi.amount_in_words(1250.50), "एक हज़ार दो सौ पचास रुपये और पचास पैसे मात्र");
+        assert_eq!(Locale::Hi.amount_in_words(2_599_099.0), "पच्चीस लाख निन्यानवे हज़ार निन्यानवे रुपये मात्र");
+        assert_eq!(Locale::Hi.amount_in_words(0.0), "शून्य रुपये मात्र");
+
+        assert_eq!(Locale::Ta.amount_in_words(1250.50), "ஆயிரத்து இருநூற்று ஐம்பது ரூபாய் ஐம்பது பைசா மட்டும்");
+        assert_eq!(Locale::Ta.amount_in_words(100_000.0), "ஒரு லட்சம் ரூபாய் மட்டும்");
+        assert_eq!(Locale::Ta.amount_in_words(354.0), "முந்நூற்று ஐம்பத்து நான்கு ரூபாய் மட்டும்");
+        assert_eq!(Locale::Ta.amount_in_words(12_000_015.0), "ஒரு கோடியே இருபது லட்சத்து பதினைந்து ரூபாய் மட்டும்");
+
+        // Paise alone, and amounts that round up to the next rupee
+        assert_eq!(Locale::Hi.amount_in_words(0.5), "पचास पैसे मात्र");
+        assert_eq!(Locale::Ta.amount_in_words(0.5), "ஐம்பது பைசா மட்டும்");
+        assert_eq!(Locale::En.amount_in_words(0.5), "Fifty Paise Only");
+        assert_eq!(Locale::Hi.amount_in_words(1.999), "दो रुपये मात्र");
+        assert_eq!(Locale::Ta.amount_in_words(1.999), "இரண்டு ரூபாய் மட்டும்");
+        assert_eq!(Locale::En.amount_in_words(1.999), "Two Rupees Only");
+        assert_eq!(Locale::Hi.amount_in_words(0.001), "शून्य रुपये मात्र");
+
+        assert_eq!(Locale::En.amount_in_words(354.0), HtmlGenerator::amount_to_words(354.0));
+    }
+
+    #[test]
+    fn test_document_language_per_store_and_customer() {
+        let mut data = test_data::sample_invoice(1);
+        assert_eq!(DocumentLanguage::for_invoice(&data.store, &data.customer), DocumentLanguage::new(Locale::En, None));
+
+        // A Hindi-speaking customer gets Hindi first, with the store's English alongside
+        data.customer.locale = Some("hi".to_string());
+        let language = DocumentLanguage::for_invoice(&data.store, &data.customer);
+        assert_eq!(language, DocumentLanguage::new(Locale::Hi, Some(Locale::En)));
+        let labels = language.labels();
+        assert_eq!(labels["invoice_no"], "बिल संख्या / Invoice No");
+        assert_eq!(labels["gstin"], "GSTIN");
+        assert_eq!(language.amount_in_words(2.0), ["दो रुपये मात्र", "Two Rupees Only"]);
+
+        // A bilingual store keeps its second language
+        data.store.locale = Some("ta".to_string());
+        data.store.secondary_locale = Some("en".to_string());
+        data.customer.locale = Some("ta".to_string());
+        assert_eq!(DocumentLanguage::for_invoice(&data.store, &data.customer), DocumentLanguage::new(Locale::Ta, Some(Locale::En)));
+        assert_eq!(DocumentLanguage::new(Locale::Hi, Some(Locale::Hi)).secondary, None);
+
+        // Renderers drop what they can't print and keep the rest in order
+        let bilingual = DocumentLanguage::new(Locale::Ta, Some(Locale::Hi));
+        assert_eq!(bilingual.restricted_to(|locale| locale != Locale::Ta), DocumentLanguage::new(Locale::Hi, None));
+        assert_eq!(bilingual.restricted_to(|locale| locale == Locale::En), DocumentLanguage::new(Locale::En, None));
+        assert_eq!(bilingual.restricted_to(|_| true), bilingual);
+
+        // Tags keep the store's language and fall back to the customer's
+        assert_eq!(DocumentLanguage::for_tags(Some("ta"), Some("hi")), Locale::Ta);
+        assert_eq!(DocumentLanguage::for_tags(None, Some("hi")), Locale::Hi);
+        assert_eq!(DocumentLanguage::for_tags(None, None), Locale::En);
+    }
+}
@@ -1,9 +1,40 @@
 pub mod pricing_engine;
 pub mod html_generator;
+pub mod pdf_generator;
 pub mod template_engine;
 pub mod tag_generator;
+pub mod turnaround_service;
+pub mod price_resolver;
+pub mod garment_tracking;
+pub mod barcode;
+pub mod label_printer;
+pub mod print_queue;
+pub mod invoice_template;
+pub mod store_branding;
+pub mod document_printer;
+pub mod receipt_printer;
+pub mod localization;
+pub mod invoice_export;
+pub mod invoice_archive;
+pub mod text_shaping;
+pub mod fonts;
 
 pub use pricing_engine::*;
 pub use html_generator::*;
+pub use pdf_generator::*;
 pub use template_engine::*;
-pub use tag_generator::*;
\ No newline at end of file
+pub use tag_generator::*;
+pub use turnaround_service::*;
+pub use price_resolver::*;
+pub use garment_tracking::*;
+pub use barcode::*;
+pub use label_printer::*;
+pub use print_queue::*;
+pub use invoice_template::*;
+pub use store_branding::*;
+pub use document_printer::*;
+pub use receipt_printer::*;
+pub use localization::*;
+pub use invoice_export::*;
+pub use invoice_archive::*;
+pub use text_shaping::*;
\ No newline at end of file
@@ -0,0 +1,800 @@
+use crate::models::{ApiResult, ApiError};
+use crate::services::barcode::BarcodeService;
+use crate::services::fonts::{FontKind, Fonts, BOLD_FONT, PT_TO_MM, REGULAR_FONT};
+use crate::services::html_generator::{HtmlGenerator, HtmlSettings, InvoiceHtmlData, InvoiceItemWithDetails};
+use crate::services::localization::{DocumentLanguage, Locale};
+use crate::services::template_engine::PaperSize;
+use printpdf::{IndirectFontRef, Line, Mm, PdfDocument, PdfLayerReference, Point, Rect};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+const LINE_SPACING: f32 = 1.3;
+const RULE_THICKNESS_PT: f32 = 0.5;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Align {
+    Left,
+    Right,
+    Centre,
+}
+
+/// Something drawn on a page. Positions are in mm from the top left.
+#[derive(Debug, Clone)]
+enum Op {
+    Text { x: f32, baseline: f32, size: f32, bold: bool, text: String },
+    Rule { x1: f32, x2: f32, y: f32 },
+    Bar { x: f32, y: f32, width: f32, height: f32 },
+}
+
+/// A block of text placed `x` mm into the content area and `width` mm wide
+struct Cell {
+    text: String,
+    x: f32,
+    width: f32,
+    align: Align,
+    bold: bool,
+}
+
+impl Cell {
+    fn new(text: impl Into<String>, x: f32, width: f32, align: Align) -> Self {
+        Self { text: text.into(), x, width, align, bold: false }
+    }
+
+    fn bold(mut self) -> Self {
+        self.bold = true;
+        self
+    }
+}
+
+/// Lays content out top to bottom, starting a new page when a sheet is full.
+/// Receipt rolls have no fixed height and grow with the content instead.
+struct PageLayout<'f> {
+    fonts: &'f Fonts<'f>,
+    width: f32,
+    height: Option<f32>,
+    margin_top: f32,
+    margin_bottom: f32,
+    margin_left: f32,
+    margin_right: f32,
+    y: f32,
+    pages: Vec<Vec<Op>>,
+}
+
+impl<'f> PageLayout<'f> {
+    fn new(fonts: &'f Fonts<'f>, settings: &HtmlSettings) -> Self {
+        let (width, height) = PdfGenerator::page_size(&settings.template.paper_size);
+        let layout = &settings.template.layout;
+
+        Self {
+            fonts,
+            width,
+            height,
+            margin_top: layout.margin_top as f32,
+            margin_bottom: layout.margin_bottom as f32,
+            margin_left: layout.margin_left as f32,
+            margin_right: layout.margin_right as f32,
+            y: layout.margin_top as f32,
+            pages: vec![Vec::new()],
+        }
+    }
+
+    fn content_width(&self) -> f32 {
+        self.width - self.margin_left - self.margin_right
+    }
+
+    /// Page height once laid out; a roll is cut just below the content
+    fn page_height(&self) -> f32 {
+        self.height.unwrap_or(self.y + self.margin_bottom)
+    }
+
+    /// Move to a new page if `height` mm does not fit on the current one
+    fn reserve(&mut self, height: f32) {
+        if let Some(page_height) = self.height {
+            if self.y + height > page_height - self.margin_bottom && self.y > self.margin_top {
+                self.pages.push(Vec::new());
+                self.y = self.margin_top;
+            }
+        }
+    }
+
+    fn push(&mut self, op: Op) {
+        if let Some(page) = self.pages.last_mut() {
+            page.push(op);
+        }
+    }
+
+    fn gap(&mut self, height: f32) {
+        self.y += height;
+    }
+
+    /// Set cells side by side, wrapping each within its width. The row is
+    /// as tall as its longest cell.
+    fn row(&mut self, cells: &[Cell], size: f32) {
+        let line_height = size * PT_TO_MM * LINE_SPACING;
+        let wrapped: Vec<Vec<String>> = cells
+            .iter()
+            .map(|cell| self.fonts.wrap(&cell.text, cell.width, size, cell.bold))
+            .collect();
+        let line_count = wrapped.iter().map(Vec::len).max().unwrap_or(1);
+
+        self.reserve(line_count as f32 * line_height);
+
+        for (cell, lines) in cells.iter().zip(wrapped) {
+            for (index, line) in lines.into_iter().enumerate() {
+                if line.is_empty() {
+                    continue;
+                }
+
+                let text_width = self.fonts.width(&line, size, cell.bold);
+                let offset = match cell.align {
+                    Align::Left => 0.0,
+                    Align::Right => cell.width - text_width,
+                    Align::Centre => (cell.width - text_width) / 2.0,
+                };
+
+                self.push(Op::Text {
+                    x: self.margin_left + cell.x + offset.max(0.0),
+                    baseline: self.y + index as f32 * line_height + size * PT_TO_MM,
+                    size,
+                    bold: cell.bold,
+                    text: line,
+                });
+            }
+        }
+
+        self.y += line_count as f32 * line_height;
+    }
+
+    fn text(&mut self, text: &str, size: f32, bold: bool, align: Align) {
+        let cell = Cell { text: text.to_string(), x: 0.0, width: self.content_width(), align, bold };
+        self.row(&[cell], size);
+    }
+
+    /// Label on the left and value flush right, starting `x` mm in
+    fn pair(&mut self, label: &str, value: &str, x: f32, size: f32, bold: bool) {
+        let width = self.content_width() - x;
+        let label_width = width * 0.6;
+        let mut label = Cell::new(label, x, label_width, Align::Left);
+        let mut value = Cell::new(value, x + label_width, width - label_width, Align::Right);
+        label.bold = bold;
+        value.bold = bold;
+        self.row(&[label, value], size);
+    }
+
+    /// Two independent columns of lines, e.g. store details beside invoice details
+    fn columns(&mut self, left: &[String], right: &[String], size: f32) {
+        let half = self.content_width() / 2.0;
+        for index in 0..left.len().max(right.len()) {
+            self.row(
+                &[
+                    Cell::new(left.get(index).cloned().unwrap_or_default(), 0.0, half - 2.0, Align::Left),
+                    Cell::new(right.get(index).cloned().unwrap_or_default(), half, half, Align::Right),
+                ],
+                size,
+            );
+        }
+    }
+
+    fn rule(&mut self) {
+        self.reserve(2.0);
+        self.push(Op::Rule {
+            x1: self.margin_left,
+            x2: self.width - self.margin_right,
+            y: self.y + 1.0,
+        });
+        self.y += 2.0;
+    }
+
+    /// Code 128 bars scaled to fit `max_width` mm
+    fn barcode(&mut self, data: &str, max_width: f64, align: Align) {
+        let (barcode, modules) = match (
+            BarcodeService::invoice_barcode(data, max_width),
+            BarcodeService::code128_modules(data),
+        ) {
+            (Ok(barcode), Ok(modules)) => (barcode, modules),
+            _ => return,
+        };
+
+        let module = barcode.module_mm as f32;
+        let height = barcode.height_mm as f32;
+        let total_width = modules.len() as f32 * module;
+        let left = self.margin_left
+            + match align {
+                Align::Left => 0.0,
+                Align::Right => self.content_width() - total_width,
+                Align::Centre => (self.content_width() - total_width) / 2.0,
+            };
+
+        self.reserve(height + 1.0);
+
+        let mut index = 0;
+        while index < modules.len() {
+            if !modules[index] {
+                index += 1;
+                continue;
+            }
+            let start = index;
+            while index < modules.len() && modules[index] {
+                index += 1;
+            }
+            self.push(Op::Bar {
+                x: left + start as f32 * module,
+                y: self.y,
+                width: (index - start) as f32 * module,
+                height,
+            });
+        }
+
+        self.y += height + 1.0;
+    }
+}
+
+pub struct PdfGenerator;
+
+impl PdfGenerator {
+    /// Render the invoice as a PDF. Text is set in the bundled DejaVu Sans;
+    /// Devanagari falls back to `devanagari_font` when given and is shaped
+    /// with that font's OpenType tables.
+    pub fn generate_invoice_pdf(
+        data: &InvoiceHtmlData,
+        settings: &HtmlSettings,
+        devanagari_font: Option<&[u8]>,
+    ) -> ApiResult<Vec<u8>> {
+        let fonts = Fonts::load(devanagari_font)?;
+        let language = Self::document_language(data, &fonts);
+        let mut layout = PageLayout::new(&fonts, settings);
+
+        match settings.template.paper_size {
+            PaperSize::Thermal80mm => Self::layout_receipt(&mut layout, data, settings, &language),
+            _ => Self::layout_sheet(&mut layout, data, settings, &language),
+        }
+
+        Self::write_pdf(&layout, &format!("Invoice {}", data.invoice.invoice_no), devanagari_font)
+    }
+
+    /// Save the invoice PDF and return its file path
+    pub fn save_invoice_pdf(
+        data: &InvoiceHtmlData,
+        settings: &HtmlSettings,
+        output_dir: &PathBuf,
+        format: &str,
+        font_dir: Option<&Path>,
+    ) -> ApiResult<String> {
+        let devanagari_font = Self::devanagari_font_for(data, font_dir);
+        let pdf = Self::generate_invoice_pdf(data, settings, devanagari_font.as_deref())?;
+
+        let filename = format!("invoice_{}_{}.pdf", data.invoice.invoice_no, format);
+        let file_path = output_dir.join(&filename);
+
+        std::fs::create_dir_all(output_dir).map_err(|e| ApiError {
+            message: format!("Failed to create output directory: {}", e),
+            code: Some("DIRECTORY_CREATE_ERROR".to_string()),
+        })?;
+
+        std::fs::write(&file_path, &pdf).map_err(|e| ApiError {
+            message: format!("Failed to save PDF file: {}", e),
+            code: Some("PDF_SAVE_ERROR".to_string()),
+        })?;
+
+        log::info!("PDF file saved at: {}", file_path.display());
+
+        Ok(file_path.to_string_lossy().to_string())
+    }
+
+    /// Width and height in mm; rolls have no fixed height
+    fn page_size(paper_size: &PaperSize) -> (f32, Option<f32>) {
+        match paper_size {
+            PaperSize::A4 => (210.0, Some(297.0)),
+            PaperSize::A5 => (148.0, Some(210.0)),
+            PaperSize::Thermal80mm => (80.0, None),
+            PaperSize::Custom { width_mm, height_mm } => (*width_mm as f32, Some(*height_mm as f32)),
+        }
+    }
+
+    /// Hindi text, or labels in a language DejaVu can't set. Fonts found
+    /// for Devanagari (Nirmala on Windows) often cover Tamil as well.
+    fn needs_script_font(data: &InvoiceHtmlData) -> bool {
+        let is_devanagari = |text: &str| text.chars().any(|c| ('\u{0900}'..='\u{097F}').contains(&c));
+        let language = DocumentLanguage::for_invoice(&data.store, &data.customer);
+
+        std::iter::once(language.primary).chain(language.secondary).any(|locale| locale != Locale::En)
+            || is_devanagari(&data.customer.name)
+            || data.customer.address.as_deref().is_some_and(is_devanagari)
+            || is_devanagari(&data.store.name)
+            || is_devanagari(&data.store.address)
+            || data.items.iter().any(|item| {
+                is_devanagari(&item.service_name)
+                    || item.variant_name.as_deref().is_some_and(is_devanagari)
+                    || item.addons.iter().any(|addon| is_devanagari(&addon.addon_name))
+                    || HtmlGenerator::garment_note_lines(&item.garments).iter().any(|line| is_devanagari(line))
+            })
+    }
+
+    /// A Devanagari font when the invoice has Hindi text or prints in an
+    /// Indian language, else None
+    pub fn devanagari_font_for(data: &InvoiceHtmlData, font_dir: Option<&Path>) -> Option<Vec<u8>> {
+        if !Self::needs_script_font(data) {
+            return None;
+        }
+
+        let font = Fonts::find_devanagari(font_dir);
+        if font.is_none() {
+            log::warn!("Invoice {} needs a Devanagari font but none was found", data.invoice.invoice_no);
+        }
+        font
+    }
+
+    /// The invoice's language, less any the loaded fonts can't set
+    pub(crate) fn document_language(data: &InvoiceHtmlData, fonts: &Fonts) -> DocumentLanguage {
+        let language = DocumentLanguage::for_invoice(&data.store, &data.customer);
+        let printable = language.restricted_to(|locale| fonts.can_set(locale));
+        if printable != language {
+            log::warn!("Invoice {} prints in {:?}; no font was found for the rest", data.invoice.invoice_no, printable);
+        }
+        printable
+    }
+
+    fn money(amount: f64) -> String {
+        format!("₹{:.2}", amount)
+    }
+
+    fn service_display(item: &InvoiceItemWithDetails) -> String {
+        match item.variant_name.as_deref() {
+            Some(variant) if !variant.is_empty() => format!("{} ({})", item.service_name, variant),
+            _ => item.service_name.clone(),
+        }
+    }
+
+    /// A4, A5 and custom sheets, following the HTML invoice section by section
+    fn layout_sheet(layout: &mut PageLayout, data: &InvoiceHtmlData, settings: &HtmlSettings, language: &DocumentLanguage) {
+        let t = language.labels();
+        let normal = settings.template.layout.font_size_normal as f32;
+        let small = settings.template.layout.font_size_small as f32;
+        let large = settings.template.layout.font_size_large as f32;
+        let width = layout.content_width();
+        let half = width / 2.0;
+        let spacing = settings.template.layout.section_spacing as f32;
+
+        // Header
+        let title = if settings.duplicate_copy {
+            format!("{} - {}", t["invoice_title"], t["duplicate_copy"])
+        } else {
+            t["invoice_title"].clone()
+        };
+        layout.row(
+            &[
+                Cell::new(settings.branding.business_name.clone(), 0.0, half, Align::Left).bold(),
+                Cell::new(title, half, half, Align::Right).bold(),
+            ],
+            large,
+        );
+
+        let mut store_lines = Vec::new();
+        store_lines.extend(settings.branding.business_tagline.clone());
+        store_lines.push(data.store.address.clone());
+        store_lines.extend(data.store.city.clone());
+        store_lines.extend(data.store.phone.as_ref().map(|phone| format!("{}: {}", t["phone"], phone)));
+        store_lines.extend(data.store.email.as_ref().map(|email| format!("{}: {}", t["email"], email)));
+        store_lines.extend(data.store.gstin.as_ref().map(|gstin| format!("{}: {}", t["gstin"], gstin)));
+
+        let invoice_lines = vec![
+            format!("{}: {}", t["invoice_no"], data.invoice.invoice_no),
+            format!("{}: {}", t["date"], data.invoice.order_datetime),
+            format!("{}: {}", t["order_source"], data.invoice.order_source),
+        ];
+
+        layout.columns(&store_lines, &invoice_lines, small);
+        layout.barcode(&data.invoice.invoice_no, 60.0_f64.min(half as f64), Align::Right);
+        layout.rule();
+
+        // Customer and delivery
+        layout.row(
+            &[
+                Cell::new(format!("{}:", t["bill_to"]), 0.0, half, Align::Left).bold(),
+                Cell::new(format!("{}:", t["delivery_info"]), half, half, Align::Right).bold(),
+            ],
+            normal,
+        );
+
+        let mut customer_lines = vec![data.customer.name.clone()];
+        customer_lines.extend(data.customer.address.clone());
+        customer_lines.extend(data.customer.phone.as_ref().map(|phone| format!("{}: {}", t["phone"], phone)));
+        customer_lines.extend(data.customer.email.as_ref().map(|email| format!("{}: {}", t["email"], email)));
+
+        let not_available = t["not_available"].as_str();
+        let delivery_lines = vec![
+            format!("{}: {}", t["pickup_date"], data.invoice.pickup_datetime.as_deref().unwrap_or(not_available)),
+            format!("{}: {}", t["delivery_date"], data.invoice.delivery_datetime.as_deref().unwrap_or(not_available)),
+            format!("{}: {}", t["status"], data.invoice.status),
+        ];
+
+        layout.columns(&customer_lines, &delivery_lines, normal);
+        layout.gap(spacing);
+
+        // Items
+        let fractions = [0.31, 0.08, 0.09, 0.13, 0.13, 0.12, 0.14];
+        let mut columns = Vec::with_capacity(fractions.len());
+        let mut x = 0.0;
+        for fraction in fractions {
+            columns.push((x, width * fraction));
+            x += width * fraction;
+        }
+
+        let table_row = |values: [String; 7], bold: bool| -> Vec<Cell> {
+            values
+                .into_iter()
+                .zip(&columns)
+                .enumerate()
+                .map(|(index, (value, &(x, width)))| {
+                    let align = if index == 0 || index == 2 { Align::Left } else { Align::Right };
+                    let cell = Cell::new(value, x, width - 1.0, align);
+                    if bold { cell.bold() } else { cell }
+                })
+                .collect()
+        };
+
+        layout.rule();
+        layout.row(
+            &table_row(
+                ["service", "qty", "unit", "rate", "amount", "discount", "taxable_value"].map(|key| t[key].clone()),
+                true,
+            ),
+            normal,
+        );
+        layout.rule();
+
+        for item in &data.items {
+            layout.row(
+                &table_row(
+                    [
+                        Self::service_display(item),
+                        format!("{:.2}", item.item.qty),
+                        item.unit.clone(),
+                        Self::money(item.item.rate),
+                        Self::money(item.item.amount),
+                        HtmlGenerator::format_line_discount(item.item.discount_amount),
+                        Self::money(item.item.taxable_value),
+                    ],
+                    false,
+                ),
+                normal,
+            );
+
+            for note in HtmlGenerator::garment_note_lines(&item.garments) {
+                layout.row(&[Cell::new(note, 5.0, width - 5.0, Align::Left)], small);
+            }
+
+            for addon in &item.addons {
+                let rate = if addon.pricing_mode == "percent_of_line" {
+                    format!("{:.2}%", addon.rate)
+                } else {
+                    Self::money(addon.rate)
+                };
+
+                layout.row(
+                    &table_row(
+                        [
+                            format!("+ {}", addon.addon_name),
+                            format!("{:.2}", addon.quantity),
+                            t["addon"].clone(),
+                            rate,
+                            Self::money(addon.amount),
+                            HtmlGenerator::format_line_discount(addon.discount_amount),
+                            Self::money(addon.taxable_value),
+                        ],
+                        false,
+                    ),
+                    normal,
+                );
+            }
+        }
+
+        layout.rule();
+        Self::layout_totals(layout, data, &t, half, normal);
+        layout.gap(spacing);
+        for (index, words) in language.amount_in_words(data.totals.total_amount).iter().enumerate() {
+            let label = if index == 0 { format!("{}: ", t["amount_in_words"]) } else { String::new() };
+            layout.text(&format!("{}{}", label, words), normal, false, Align::Left);
+        }
+        layout.gap(spacing);
+        Self::layout_footer(layout, settings, &t, small, normal);
+    }
+
+    /// 80mm receipt roll: one column, lines as long as the paper allows
+    fn layout_receipt(layout: &mut PageLayout, data: &InvoiceHtmlData, settings: &HtmlSettings, language: &DocumentLanguage) {
+        let t = language.labels();
+        let normal = settings.template.layout.font_size_normal as f32;
+        let small = settings.template.layout.font_size_small as f32;
+        let large = settings.template.layout.font_size_large as f32;
+
+        layout.text(&settings.branding.business_name, large, true, Align::Centre);
+        if let Some(tagline) = &settings.branding.business_tagline {
+            layout.text(tagline, small, false, Align::Centre);
+        }
+        layout.text(&data.store.address, small, false, Align::Centre);
+        if let Some(city) = &data.store.city {
+            layout.text(city, small, false, Align::Centre);
+        }
+        if let Some(phone) = &data.store.phone {
+            layout.text(&format!("{}: {}", t["phone"], phone), small, false, Align::Centre);
+        }
+        if let Some(gstin) = &data.store.gstin {
+            layout.text(&format!("{}: {}", t["gstin"], gstin), small, false, Align::Centre);
+        }
+        layout.rule();
+
+        if settings.duplicate_copy {
+            layout.text(&t["duplicate_copy"], normal, true, Align::Centre);
+        }
+        layout.pair(&format!("{}:", t["invoice_no"]), &data.invoice.invoice_no, 0.0, normal, true);
+        layout.pair(&format!("{}:", t["date"]), &data.invoice.order_datetime, 0.0, normal, false);
+        layout.pair(&format!("{}:", t["customer"]), &data.customer.name, 0.0, normal, false);
+        if let Some(phone) = &data.customer.phone {
+            layout.pair(&format!("{}:", t["phone"]), phone, 0.0, normal, false);
+        }
+        if let Some(delivery) = &data.invoice.delivery_datetime {
+            layout.pair(&format!("{}:", t["delivery_date"]), delivery, 0.0, normal, false);
+        }
+        layout.rule();
+
+        for item in &data.items {
+            layout.text(&Self::service_display(item), normal, true, Align::Left);
+            layout.pair(
+                &format!("{:.2} {} x {}", item.item.qty, item.unit, Self::money(item.item.rate)),
+                &Self::money(item.item.amount),
+                2.0,
+                normal,
+                false,
+            );
+            if item.item.discount_amount > 0.0 {
+                layout.pair(&t["discount"], &HtmlGenerator::format_line_discount(item.item.discount_amount), 2.0, normal, false);
+            }
+            for addon in &item.addons {
+                layout.pair(&format!("+ {}", addon.addon_name), &Self::money(addon.amount), 2.0, normal, false);
+            }
+            for note in HtmlGenerator::garment_note_lines(&item.garments) {
+                layout.row(&[Cell::new(note, 2.0, layout.content_width() - 2.0, Align::Left)], small);
+            }
+        }
+        layout.rule();
+
+        Self::layout_totals(layout, data, &t, 0.0, normal);
+        for words in language.amount_in_words(data.totals.total_amount) {
+            layout.text(&words, small, false, Align::Left);
+        }
+        layout.gap(1.0);
+        layout.barcode(&data.invoice.invoice_no, 40.0, Align::Centre);
+        layout.rule();
+        Self::layout_footer(layout, settings, &t, small, normal);
+    }
+
+    fn layout_totals(layout: &mut PageLayout, data: &InvoiceHtmlData, t: &HashMap<String, String>, x: f32, size: f32) {
+        let totals = &data.totals;
+
+        layout.pair(&format!("{}:", t["subtotal"]), &Self::money(totals.subtotal), x, size, false);
+        if totals.discount_amount > 0.0 {
+            let discount = format!("-{}", Self::money(totals.discount_amount));
+            layout.pair(&format!("{}:", t["discount"]), &discount, x, size, false);
+        }
+        if totals.express_charge > 0.0 {
+            layout.pair(&format!("{}:", t["express_charge"]), &Self::money(totals.express_charge), x, size, false);
+        }
+        layout.pair(&format!("{}:", t["base_amount"]), &Self::money(totals.base_amount), x, size, false);
+        let (sgst, cgst) = HtmlGenerator::gst_labels(data, t);
+        layout.pair(&sgst, &Self::money(totals.sgst_amount), x, size, false);
+        layout.pair(&cgst, &Self::money(totals.cgst_amount), x, size, false);
+        layout.pair(&format!("{}:", t["total_amount"]), &Self::money(totals.total_amount), x, size, true);
+    }
+
+    fn layout_footer(layout: &mut PageLayout, settings: &HtmlSettings, t: &HashMap<String, String>, small: f32, normal: f32) {
+        let footer = &settings.footer;
+
+        if !footer.terms_conditions.is_empty() {
+            layout.text(&format!("{}:", t["terms_conditions"]), small, true, Align::Left);
+            for (index, term) in footer.terms_conditions.iter().enumerate() {
+                let term = crate::utils::terms_plain_text(term);
+                layout.text(&format!("{}. {}", index + 1, term), small, false, Align::Left);
+            }
+            layout.gap(2.0);
+        }
+
+        if let Some(thank_you) = &footer.thank_you_message {
+            layout.text(thank_you, normal, true, Align::Centre);
+        }
+
+        for info in &footer.contact_info {
+            layout.text(info, small, false, Align::Centre);
+        }
+    }
+
+    fn write_pdf(layout: &PageLayout, title: &str, devanagari_font: Option<&[u8]>) -> ApiResult<Vec<u8>> {
+        let pdf_error = |e: printpdf::Error| ApiError {
+            message: format!("Failed to generate PDF: {}", e),
+            code: Some("PDF_GENERATION_ERROR".to_string()),
+        };
+
+        let width = layout.width;
+        let height = layout.page_height();
+        let (doc, first_page, first_layer) = PdfDocument::new(title, Mm(width), Mm(height), "Invoice");
+
+        let regular = doc.add_external_font(REGULAR_FONT).map_err(pdf_error)?;
+        let bold = doc.add_external_font(BOLD_FONT).map_err(pdf_error)?;
+
+        // Only embed the Devanagari font when some text is actually set in it
+        let uses_devanagari = layout.pages.iter().flatten().any(|op| match op {
+            Op::Text { text, bold, .. } => layout
+                .fonts
+                .runs(text, *bold)
+                .iter()
+                .any(|(kind, _)| *kind == FontKind::Devanagari),
+            _ => false,
+        });
+        let devanagari = match devanagari_font {
+            Some(font) if uses_devanagari => Some(doc.add_external_font(font).map_err(pdf_error)?),
+            _ => None,
+        };
+
+        let font_ref = |kind: FontKind| -> &IndirectFontRef {
+            match kind {
+                FontKind::Regular => &regular,
+                FontKind::Bold => &bold,
+                FontKind::Devanagari => devanagari.as_ref().unwrap_or(&regular),
+            }
+        };
+
+        for (index, ops) in layout.pages.iter().enumerate() {
+            let layer: PdfLayerReference = if index == 0 {
+                doc.get_page(first_page).get_layer(first_layer)
+            } else {
+                let (page, page_layer) = doc.add_page(Mm(width), Mm(height), "Invoice");
+                doc.get_page(page).get_layer(page_layer)
+            };
+            layer.set_outline_thickness(RULE_THICKNESS_PT);
+
+            for op in ops {
+                match op {
+                    Op::Text { x, baseline, size, bold, text } => {
+                        let mut x = *x;
+                        for (kind, run) in layout.fonts.runs(text, *bold) {
+                            // Glyph ids rather than characters, so shaped
+                            // Devanagari keeps its conjuncts and reordering
+                            let glyphs = layout.fonts.glyphs(kind, &run);
+                            if kind == FontKind::Devanagari {
+                                // Marks are positioned by the shaper, so each
+                                // glyph goes where it was placed
+                                let scale = *size * PT_TO_MM / layout.fonts.units_per_em(kind);
+                                let mut pen = x;
+                                for glyph in glyphs {
+                                    layer.begin_text_section();
+                                    layer.set_font(font_ref(kind), *size);
+                                    layer.set_text_cursor(
+                                        Mm(pen + glyph.x_offset as f32 * scale),
+                                        Mm(height - baseline + glyph.y_offset as f32 * scale),
+                                    );
+                                    layer.write_codepoints([glyph.id.0]);
+                                    layer.end_text_section();
+                                    pen += glyph.x_advance as f32 * scale;
+                                }
+                            } else {
+                                layer.begin_text_section();
+                                layer.set_font(font_ref(kind), *size);
+                                layer.set_text_cursor(Mm(x), Mm(height - baseline));
+                                layer.write_codepoints(glyphs.into_iter().map(|glyph| glyph.id.0));
+                                layer.end_text_section();
+                            }
+                            x += layout.fonts.run_width(kind, &run, *size);
+                        }
+                    }
+                    Op::Rule { x1, x2, y } => {
+                        layer.add_line(Line {
+                            points: vec![
+                                (Point::new(Mm(*x1), Mm(height - y)), false),
+                                (Point::new(Mm(*x2), Mm(height - y)), false),
+                            ],
+                            is_closed: false,
+                        });
+                    }
+                    Op::Bar { x, y, width, height: bar_height } => {
+                        layer.add_rect(Rect::new(
+                            Mm(*x),
+                            Mm(height - y - bar_height),
+                            Mm(x + width),
+                            Mm(height - y),
+                        ));
+                    }
+                }
+            }
+        }
+
+        doc.save_to_bytes().map_err(pdf_error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::html_generator::test_data::sample_invoice;
+    use printpdf::lopdf::{Document, Object};
+
+    /// Page count and the first page's size in mm
+    fn page_sizes(pdf: &[u8]) -> (usize, f32, f32) {
+        let doc = Document::load_mem(pdf).unwrap();
+        let pages = doc.get_pages();
+        let first = doc.get_object(*pages.values().next().unwrap()).unwrap().as_dict().unwrap();
+        let media_box: Vec<f32> = first
+            .get(b"MediaBox")
+            .unwrap()
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|value| match value {
+                Object::Integer(value) => *value as f32,
+                Object::Real(value) => *value,
+                other => panic!("unexpected MediaBox value {:?}", other),
+            })
+            .collect();
+
+        let to_mm = |points: f32| points * PT_TO_MM;
+        (pages.len(), to_mm(media_box[2] - media_box[0]), to_mm(media_box[3] - media_box[1]))
+    }
+
+    #[test]
+    fn test_invoice_pdf_sizes_per_paper() {
+        let data = sample_invoice(3);
+
+        let a4 = PdfGenerator::generate_invoice_pdf(&data, &HtmlGenerator::get_settings("a4", &data.store).unwrap(), None).unwrap();
+        assert!(a4.starts_with(b"%PDF"));
+        let (pages, width, height) = page_sizes(&a4);
+        assert_eq!(pages, 1);
+        assert!((width - 210.0).abs() < 0.5 && (height - 297.0).abs() < 0.5);
+
+        // A receipt roll is cut to the content, however long
+        let thermal = HtmlGenerator::get_settings("thermal", &data.store).unwrap();
+        let (pages, width, short) =
+            page_sizes(&PdfGenerator::generate_invoice_pdf(&data, &thermal, None).unwrap());
+        assert_eq!(pages, 1);
+        assert!((width - 80.0).abs() < 0.5);
+        let long_receipt = sample_invoice(40);
+        let (_, _, long) =
+            page_sizes(&PdfGenerator::generate_invoice_pdf(&long_receipt, &thermal, None).unwrap());
+        assert!(short > 60.0 && long > short * 2.0);
+
+        // Sheets flow onto extra pages
+        let a5 = HtmlGenerator::get_settings("a5", &long_receipt.store).unwrap();
+        let (pages, width, _) = page_sizes(&PdfGenerator::generate_invoice_pdf(&long_receipt, &a5, None).unwrap());
+        assert!(pages > 1);
+        assert!((width - 148.0).abs() < 0.5);
+    }
+
+    #[test]
+    fn test_gst_labels_follow_line_rates() {
+        let mut data = sample_invoice(2);
+        let english = Locale::En.catalog();
+        assert_eq!(HtmlGenerator::gst_labels(&data, &english), ("SGST (9.0%):".to_string(), "CGST (9.0%):".to_string()));
+
+        data.items[0].item.gst_rate = 5.0;
+        data.items[1].item.gst_rate = 5.0;
+        assert_eq!(HtmlGenerator::gst_labels(&data, &english).0, "SGST (2.5%):");
+        let bilingual = DocumentLanguage::new(Locale::Hi, Some(Locale::En)).labels();
+        assert_eq!(HtmlGenerator::gst_labels(&data, &bilingual).1, format!("{} (2.5%):", bilingual["cgst"]));
+
+        // Mixed rates have no single split to print
+        data.items[1].item.gst_rate = 12.0;
+        assert_eq!(HtmlGenerator::gst_labels(&data, &english), ("SGST:".to_string(), "CGST:".to_string()));
+    }
+
+    #[test]
+    fn test_document_language_needs_a_font() {
+        let mut data = sample_invoice(1);
+        let fonts = Fonts::load(None).unwrap();
+        assert!(!PdfGenerator::needs_script_font(&data));
+
+        // A Hindi customer's invoice looks for a Devanagari font, and prints
+        // in English rather than empty boxes when none is found
+        data.customer.locale = Some("hi".to_string());
+        assert!(PdfGenerator::needs_script_font(&data));
+        assert_eq!(PdfGenerator::document_language(&data, &fonts), DocumentLanguage::new(Locale::En, None));
+        let thermal = HtmlGenerator::get_settings("thermal", &data.store).unwrap();
+        assert!(PdfGenerator::generate_invoice_pdf(&data, &thermal, None).unwrap().starts_with(b"%PDF"));
+    }
+}
@@ -0,0 +1,482 @@
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+use sqlx::SqliteConnection;
+use crate::models::{RateCard, RateCardItem, ApiResult, ApiError};
+
+pub const RATE_SOURCE_BASE_PRICE: &str = "base_price";
+pub const RATE_SOURCE_PRICE_LIST: &str = "price_list";
+pub const RATE_SOURCE_RATE_CARD: &str = "rate_card";
+
+/// Rate applicable to a line and where it came from
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResolvedPrice {
+    pub rate: f64,
+    pub rate_source: String, // base_price, price_list, rate_card
+    pub price_list_id: Option<i64>,
+    pub rate_card_id: Option<i64>,
+}
+
+impl ResolvedPrice {
+    fn from_list(rate: f64, price_list_id: Option<i64>) -> Self {
+        let rate_source = if price_list_id.is_some() { RATE_SOURCE_PRICE_LIST } else { RATE_SOURCE_BASE_PRICE };
+        Self {
+            rate,
+            rate_source: rate_source.to_string(),
+            price_list_id,
+            rate_card_id: None,
+        }
+    }
+}
+
+/// Everything needed to price lines for one store, customer and order date
+#[derive(Debug, Clone)]
+pub struct PricingContext {
+    pub on_date: String,
+    pub price_lists: Vec<i64>,
+    pub rate_card: Option<RateCard>,
+    pub gst_inclusive: bool,
+}
+
+pub struct PriceResolver;
+
+impl PriceResolver {
+    /// Price date (YYYY-MM-DD) for an order timestamp; defaults to today
+    pub fn price_date(order_datetime: Option<&str>) -> ApiResult<String> {
+        Ok(Self::order_datetime(order_datetime)?.format("%Y-%m-%d").to_string())
+    }
+
+    /// A YYYY-MM-DD date from a form, trimmed and zero-padded
+    pub fn parse_date(value: &str) -> ApiResult<String> {
+        NaiveDate::parse_from_str(value.trim(), "%Y-%m-%d")
+            .map(|date| date.format("%Y-%m-%d").to_string())
+            .map_err(|_| ApiError {
+                message: format!("Invalid date '{}', expected YYYY-MM-DD", value),
+                code: Some("INVALID_DATE".to_string()),
+            })
+    }
+
+    /// Parse a validity period for a price or rate card. Periods run from
+    /// their start date up to, not including, their end date, so one
+    /// period's end is the next one's start.
+    pub fn validity_period(from: &str, to: Option<&str>) -> ApiResult<(String, Option<String>)> {
+        let from = Self::parse_date(from)?;
+        let to = to.map(Self::parse_date).transpose()?;
+
+        if to.as_ref().is_some_and(|to| *to <= from) {
+            return Err(ApiError {
+                message: "The end date must be after the start date".to_string(),
+                code: Some("VALIDATION_ERROR".to_string()),
+            });
+        }
+        Ok((from, to))
+    }
+
+    /// Local order time from an RFC 3339 timestamp, a datetime-local value
+    /// or a bare date (midnight); defaults to now
+    pub fn order_datetime(order_datetime: Option<&str>) -> ApiResult<NaiveDateTime> {
+        let Some(value) = order_datetime.map(str::trim).filter(|v| !v.is_empty()) else {
+            return Ok(Local::now().naive_local());
+        };
+
+        DateTime::parse_from_rfc3339(value)
+            .map(|dt| dt.with_timezone(&Local).naive_local())
+            .or_else(|_| NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S"))
+            .or_else(|_| NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M"))
+            .or_else(|_| NaiveDate::parse_from_str(value, "%Y-%m-%d").map(|date| date.and_hms_opt(0, 0, 0).unwrap()))
+            .map_err(|_| ApiError {
+                message: format!("Invalid order date: {}", value),
+                code: Some("INVALID_DATE".to_string()),
+            })
+    }
+
+    /// Load the price lists and the customer's rate card in effect on the order date
+    pub async fn load_context(
+        conn: &mut SqliteConnection,
+        store_id: Option<i64>,
+        customer_id: Option<i64>,
+        order_datetime: Option<&str>,
+        gst_inclusive: bool,
+    ) -> ApiResult<PricingContext> {
+        let on_date = Self::price_date(order_datetime)?;
+
+        let customer_group: Option<String> = match customer_id {
+            Some(customer_id) => sqlx::query_scalar("SELECT customer_group FROM customers WHERE id = ?")
+                .bind(customer_id)
+                .fetch_optional(&mut *conn)
+                .await
+                .map_err(|e| ApiError {
+                    message: format!("Database error: {}", e),
+                    code: Some("DATABASE_ERROR".to_string()),
+                })?
+                .flatten(),
+            None => None,
+        };
+
+        let price_lists = Self::applicable_price_lists(conn, store_id, customer_group.as_deref()).await?;
+
+        let rate_card = match customer_id {
+            Some(customer_id) => Self::active_rate_card(conn, customer_id, &on_date).await?,
+            None => None,
+        };
+
+        Ok(PricingContext {
+            on_date,
+            price_lists,
+            rate_card,
+            gst_inclusive,
+        })
+    }
+
+    /// Rate for a service line: the customer's rate card overrides the price list,
+    /// which overrides the service base price
+    pub async fn service_rate(
+        conn: &mut SqliteConnection,
+        context: &PricingContext,
+        service_id: i64,
+        variant_id: Option<i64>,
+        base_price: f64,
+        price_multiplier: f64,
+        gst_rate: f64,
+    ) -> ApiResult<ResolvedPrice> {
+        let listed = Self::resolve_service_rate(
+            conn,
+            &context.price_lists,
+            service_id,
+            variant_id,
+            base_price,
+            price_multiplier,
+            &context.on_date,
+        ).await?;
+
+        let Some(card) = &context.rate_card else {
+            return Ok(listed);
+        };
+
+        // A variant-specific override beats one for the whole service
+        let item = sqlx::query_as::<_, RateCardItem>(
+            r#"
+            SELECT * FROM rate_card_items
+            WHERE rate_card_id = ? AND service_id = ? AND (variant_id = ? OR variant_id IS NULL)
+            ORDER BY variant_id IS NULL
+            LIMIT 1
+            "#
+        )
+        .bind(card.id)
+        .bind(service_id)
+        .bind(variant_id)
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(|e| ApiError {
+            message: format!("Database error: {}", e),
+            code: Some("DATABASE_ERROR".to_string()),
+        })?;
+
+        Ok(match item {
+            Some(item) => Self::from_rate_card(listed, card, &item, price_multiplier, context.gst_inclusive, gst_rate),
+            None => listed,
+        })
+    }
+
+    /// Rate for an addon line, with the same precedence as services
+    pub async fn addon_rate(
+        conn: &mut SqliteConnection,
+        context: &PricingContext,
+        addon_id: i64,
+        base_price: f64,
+        gst_rate: f64,
+    ) -> ApiResult<ResolvedPrice> {
+        let listed = Self::resolve_addon_rate(
+            conn,
+            &context.price_lists,
+            addon_id,
+            base_price,
+            &context.on_date,
+        ).await?;
+
+        let Some(card) = &context.rate_card else {
+            return Ok(listed);
+        };
+
+        let item = sqlx::query_as::<_, RateCardItem>(
+            "SELECT * FROM rate_card_items WHERE rate_card_id = ? AND addon_id = ? LIMIT 1"
+        )
+        .bind(card.id)
+        .bind(addon_id)
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(|e| ApiError {
+            message: format!("Database error: {}", e),
+            code: Some("DATABASE_ERROR".to_string()),
+        })?;
+
+        Ok(match item {
+            Some(item) => Self::from_rate_card(listed, card, &item, 1.0, context.gst_inclusive, gst_rate),
+            None => listed,
+        })
+    }
+
+    /// Most recently started active rate card valid on the date
+    pub async fn active_rate_card(
+        conn: &mut SqliteConnection,
+        customer_id: i64,
+        on_date: &str,
+    ) -> ApiResult<Option<RateCard>> {
+        sqlx::query_as::<_, RateCard>(
+            r#"
+            SELECT * FROM rate_cards
+            WHERE customer_id = ? AND is_active = 1
+              AND valid_from <= ? AND (valid_to IS NULL OR valid_to > ?)
+            ORDER BY valid_from DESC, id DESC
+            LIMIT 1
+            "#
+        )
+        .bind(customer_id)
+        .bind(on_date)
+        .bind(on_date)
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(|e| ApiError {
+            message: format!("Database error: {}", e),
+            code: Some("DATABASE_ERROR".to_string()),
+        })
+    }
+
+    /// Apply a rate card override to the listed rate. Fixed rates are converted
+    /// to the invoice's GST treatment; percent-off applies to the listed rate.
+    pub fn apply_rate_card_item(
+        listed_rate: f64,
+        item: &RateCardItem,
+        price_multiplier: f64,
+        rates_include_gst: bool,
+        invoice_gst_inclusive: bool,
+        gst_rate: f64,
+    ) -> f64 {
+        let rate = match item.rate_type.as_str() {
+            "percent_off" => listed_rate * (1.0 - item.value.clamp(0.0, 100.0) / 100.0),
+            _ => {
+                let fixed = if item.variant_id.is_some() { item.value } else { item.value * price_multiplier };
+                match (rates_include_gst, invoice_gst_inclusive) {
+                    (true, false) => fixed / (1.0 + gst_rate / 100.0),
+                    (false, true) => fixed * (1.0 + gst_rate / 100.0),
+                    _ => fixed,
+                }
+            }
+        };
+
+        crate::utils::round_to_paisa(rate)
+    }
+
+    fn from_rate_card(
+        listed: ResolvedPrice,
+        card: &RateCard,
+        item: &RateCardItem,
+        price_multiplier: f64,
+        invoice_gst_inclusive: bool,
+        gst_rate: f64,
+    ) -> ResolvedPrice {
+        let rate = Self::apply_rate_card_item(
+            listed.rate,
+            item,
+            price_multiplier,
+            card.rates_include_gst == 1,
+            invoice_gst_inclusive,
+            gst_rate,
+        );
+
+        ResolvedPrice {
+            rate,
+            rate_source: RATE_SOURCE_RATE_CARD.to_string(),
+            // percent-off is still based on the listed price
+            price_list_id: if item.rate_type == "percent_off" { listed.price_list_id } else { None },
+            rate_card_id: Some(card.id),
+        }
+    }
+
+    /// Price lists that apply to a store and customer group, most specific first.
+    /// Group-specific assignments beat store-specific ones, which beat global ones.
+    pub async fn applicable_price_lists(
+        conn: &mut SqliteConnection,
+        store_id: Option<i64>,
+        customer_group: Option<&str>,
+    ) -> ApiResult<Vec<i64>> {
+        sqlx::query_scalar::<_, i64>(
+            r#"
+            SELECT pla.price_list_id
+            FROM price_list_assignments pla
+            JOIN price_lists pl ON pla.price_list_id = pl.id
+            WHERE pl.is_active = 1
+              AND (pla.store_id = ? OR pla.store_id IS NULL)
+              AND (pla.customer_group = ? OR pla.customer_group IS NULL)
+            ORDER BY pla.customer_group IS NULL, pla.store_id IS NULL, pla.priority DESC, pla.id
+            "#
+        )
+        .bind(store_id)
+        .bind(customer_group)
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| ApiError {
+            message: format!("Database error: {}", e),
+            code: Some("DATABASE_ERROR".to_string()),
+        })
+    }
+
+    /// Service rate on a date. A variant-specific price is used as-is; a
+    /// service-level price gets the variant multiplier like the base price does.
+    pub async fn resolve_service_rate(
+        conn: &mut SqliteConnection,
+        price_lists: &[i64],
+        service_id: i64,
+        variant_id: Option<i64>,
+        base_price: f64,
+        price_multiplier: f64,
+        on_date: &str,
+    ) -> ApiResult<ResolvedPrice> {
+        for &price_list_id in price_lists {
+            if variant_id.is_some() {
+                let variant_price = Self::find_price(
+                    conn,
+                    "service_id = ? AND variant_id = ?",
+                    price_list_id,
+                    service_id,
+                    variant_id,
+                    on_date,
+                ).await?;
+
+                if let Some(price) = variant_price {
+                    return Ok(ResolvedPrice::from_list(price, Some(price_list_id)));
+                }
+            }
+
+            let service_price = Self::find_price(
+                conn,
+                "service_id = ? AND variant_id IS ?",
+                price_list_id,
+                service_id,
+                None,
+                on_date,
+            ).await?;
+
+            if let Some(price) = service_price {
+                return Ok(ResolvedPrice::from_list(price * price_multiplier, Some(price_list_id)));
+            }
+        }
+
+        Ok(ResolvedPrice::from_list(base_price * price_multiplier, None))
+    }
+
+    /// Addon rate on a date, falling back to the addon's own price
+    pub async fn resolve_addon_rate(
+        conn: &mut SqliteConnection,
+        price_lists: &[i64],
+        addon_id: i64,
+        base_price: f64,
+        on_date: &str,
+    ) -> ApiResult<ResolvedPrice> {
+        for &price_list_id in price_lists {
+            let addon_price = Self::find_price(
+                conn,
+                "addon_id = ? AND service_id IS ?",
+                price_list_id,
+                addon_id,
+                None,
+                on_date,
+            ).await?;
+
+            if let Some(price) = addon_price {
+                return Ok(ResolvedPrice::from_list(price, Some(price_list_id)));
+            }
+        }
+
+        Ok(ResolvedPrice::from_list(base_price, None))
+    }
+
+    async fn find_price(
+        conn: &mut SqliteConnection,
+        target_filter: &str,
+        price_list_id: i64,
+        target_id: i64,
+        secondary_id: Option<i64>,
+        on_date: &str,
+    ) -> ApiResult<Option<f64>> {
+        let query = format!(
+            r#"
+            SELECT price FROM price_list_items
+            WHERE price_list_id = ? AND {}
+              AND effective_from <= ?
+              AND (effective_to IS NULL OR effective_to > ?)
+            ORDER BY effective_from DESC
+            LIMIT 1
+            "#,
+            target_filter
+        );
+
+        sqlx::query_scalar::<_, f64>(&query)
+            .bind(price_list_id)
+            .bind(target_id)
+            .bind(secondary_id)
+            .bind(on_date)
+            .bind(on_date)
+            .fetch_optional(&mut *conn)
+            .await
+            .map_err(|e| ApiError {
+                message: format!("Database error: {}", e),
+                code: Some("DATABASE_ERROR".to_string()),
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_price_date_formats() {
+        assert_eq!(PriceResolver::price_date(Some("2025-03-31T19:30:00")).unwrap(), "2025-03-31");
+        assert_eq!(PriceResolver::price_date(Some("2025-03-31T19:30")).unwrap(), "2025-03-31");
+        assert_eq!(PriceResolver::price_date(Some("2025-03-31")).unwrap(), "2025-03-31");
+        assert!(PriceResolver::price_date(Some("31/03/2025")).is_err());
+        assert_eq!(PriceResolver::price_date(None).unwrap().len(), 10);
+
+        let order = |value| PriceResolver::order_datetime(Some(value)).unwrap().format("%Y-%m-%d %H:%M").to_string();
+        assert_eq!(order("2025-03-31T19:30"), "2025-03-31 19:30");
+        assert_eq!(order("2025-03-31"), "2025-03-31 00:00");
+        assert!(PriceResolver::order_datetime(Some("31/03/2025 19:30")).is_err());
+
+        assert_eq!(PriceResolver::parse_date(" 2025-3-1 ").unwrap(), "2025-03-01");
+        assert_eq!(PriceResolver::parse_date("2025-02-30").unwrap_err().code.as_deref(), Some("INVALID_DATE"));
+        let period = PriceResolver::validity_period("2025-04-01", Some("2025-05-01")).unwrap();
+        assert_eq!(period, ("2025-04-01".to_string(), Some("2025-05-01".to_string())));
+        let same_day = PriceResolver::validity_period("2025-04-01", Some("2025-04-01")).unwrap_err();
+        assert_eq!(same_day.code.as_deref(), Some("VALIDATION_ERROR"));
+    }
+
+    fn card_item(rate_type: &str, value: f64, variant_id: Option<i64>) -> RateCardItem {
+        RateCardItem {
+            id: 1,
+            rate_card_id: 1,
+            service_id: Some(20),
+            variant_id,
+            addon_id: None,
+            rate_type: rate_type.to_string(),
+            value,
+            created_at: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_apply_rate_card_item() {
+        // 10% off the listed rate
+        let rate = PriceResolver::apply_rate_card_item(150.0, &card_item("percent_off", 10.0, None), 1.0, false, false, 18.0);
+        assert_eq!(rate, 135.0);
+
+        // Service-level fixed rate follows the variant multiplier, variant-level does not
+        let rate = PriceResolver::apply_rate_card_item(150.0, &card_item("fixed", 100.0, None), 1.5, false, false, 18.0);
+        assert_eq!(rate, 150.0);
+        let rate = PriceResolver::apply_rate_card_item(150.0, &card_item("fixed", 100.0, Some(2)), 1.5, false, false, 18.0);
+        assert_eq!(rate, 100.0);
+
+        // GST-inclusive contract rate on a GST-exclusive invoice
+        let rate = PriceResolver::apply_rate_card_item(150.0, &card_item("fixed", 118.0, None), 1.0, true, false, 18.0);
+        assert_eq!(rate, 100.0);
+    }
+}
@@ -1,11 +1,13 @@
 use serde::{Deserialize, Serialize};
-use crate::models::{Service, ServiceVariant, ServiceAddon, ApiResult, ApiError};
+use crate::models::{Service, ServiceVariant, ServiceAddon, TurnaroundTier, ApiResult, ApiError};
+use crate::services::price_resolver::{ResolvedPrice, RATE_SOURCE_BASE_PRICE};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PricingRequest {
     pub service_id: i64,
     pub variant_id: Option<i64>,
     pub quantity: f64,
+    pub piece_count: Option<i32>, // defaults to quantity; used by per-piece addons
     pub weight_kg: Option<f64>,
     pub area_sqft: Option<f64>,
     pub addons: Vec<AddonPricingRequest>,
@@ -13,6 +15,8 @@ pub struct PricingRequest {
     pub discount_type: Option<String>, // "flat" or "percent"
     pub express_charge: Option<f64>,
     pub gst_inclusive: bool,
+    pub store_id: Option<i64>,
+    pub customer_id: Option<i64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -37,6 +41,7 @@ pub struct ServicePricing {
     pub base_price: f64,
     pub price_multiplier: f64,
     pub effective_rate: f64,
+    pub rate_source: String,
     pub quantity: f64,
     pub unit: String,
     pub amount: f64,
@@ -51,9 +56,26 @@ pub struct AddonPricing {
     pub addon_id: i64,
     pub addon_name: String,
     pub unit_price: f64,
+    pub pricing_mode: String,
+    pub rate_source: String,
     pub quantity: f64,
     pub unit: String,
     pub amount: f64,
+    pub gst_rate: f64,
+}
+
+/// Quantity and amount charged for an addon under its pricing mode
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AddonCharge {
+    pub quantity: f64,
+    pub amount: f64,
+}
+
+/// Rates resolved for a pricing request (rate card, price list or base price)
+#[derive(Debug, Clone)]
+pub struct ItemRates {
+    pub service: ResolvedPrice,
+    pub addons: Vec<(i64, ResolvedPrice)>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -82,6 +104,24 @@ pub struct GstCalculation {
     pub is_inclusive: bool,
 }
 
+/// A priced invoice line considered for the turnaround surcharge
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SurchargeLine {
+    pub amount: f64, // line amount as charged (GST-inclusive when the invoice is)
+    pub category_id: Option<i64>,
+    pub gst_rate: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TurnaroundSurcharge {
+    pub tier_code: String,
+    pub eligible_amount: f64,
+    pub surcharge: f64,
+    pub base_amount: f64,
+    pub sgst_amount: f64,
+    pub cgst_amount: f64,
+}
+
 pub struct PricingEngine;
 
 impl PricingEngine {
@@ -91,6 +131,7 @@ impl PricingEngine {
         variant: Option<&ServiceVariant>,
         addons: &[ServiceAddon],
         request: &PricingRequest,
+        rates: &ItemRates,
     ) -> ApiResult<PricingResult> {
         // Validate quantity
         if request.quantity <= 0.0 {
@@ -109,10 +150,16 @@ impl PricingEngine {
         }
 
         // Calculate service pricing
-        let service_pricing = Self::calculate_service_pricing(service, variant, request)?;
+        let service_pricing = Self::calculate_service_pricing(service, variant, request, &rates.service)?;
 
         // Calculate addon pricing
-        let addon_calculations = Self::calculate_addon_pricing(addons, request)?;
+        let addon_calculations = Self::calculate_addon_pricing(
+            addons,
+            request,
+            &rates.addons,
+            service_pricing.amount,
+            service_pricing.gst_rate,
+        )?;
 
         // Calculate totals
         let totals = Self::calculate_totals(&service_pricing, &addon_calculations, request)?;
@@ -129,10 +176,11 @@ impl PricingEngine {
         service: &Service,
         variant: Option<&ServiceVariant>,
         request: &PricingRequest,
+        rate: &ResolvedPrice,
     ) -> ApiResult<ServicePricing> {
         let base_price = service.base_price;
         let price_multiplier = variant.map(|v| v.price_multiplier).unwrap_or(1.0);
-        let effective_rate = base_price * price_multiplier;
+        let effective_rate = rate.rate;
 
         let amount = effective_rate * request.quantity;
 
@@ -147,6 +195,7 @@ impl PricingEngine {
             base_price,
             price_multiplier,
             effective_rate,
+            rate_source: rate.rate_source.clone(),
             quantity: request.quantity,
             unit: service.unit.clone(),
             amount,
@@ -161,8 +210,12 @@ impl PricingEngine {
     fn calculate_addon_pricing(
         addons: &[ServiceAddon],
         request: &PricingRequest,
+        rates: &[(i64, ResolvedPrice)],
+        line_amount: f64,
+        service_gst_rate: f64,
     ) -> ApiResult<Vec<AddonPricing>> {
         let mut addon_calculations = Vec::new();
+        let piece_count = request.piece_count.unwrap_or(request.quantity as i32);
 
         for addon_request in &request.addons {
             if let Some(addon) = addons.iter().find(|a| a.id == addon_request.addon_id) {
@@ -173,15 +226,28 @@ impl PricingEngine {
                     });
                 }
 
-                let amount = addon.price * addon_request.quantity;
+                let rate = rates.iter().find(|(id, _)| *id == addon.id).map(|(_, rate)| rate);
+                let unit_price = rate.map(|r| r.rate).unwrap_or(addon.price);
+                let charge = Self::calculate_addon_charge(
+                    &addon.pricing_mode,
+                    unit_price,
+                    addon_request.quantity,
+                    line_amount,
+                    piece_count,
+                )?;
 
                 addon_calculations.push(AddonPricing {
                     addon_id: addon.id,
                     addon_name: addon.name.clone(),
-                    unit_price: addon.price,
-                    quantity: addon_request.quantity,
+                    unit_price,
+                    pricing_mode: addon.pricing_mode.clone(),
+                    rate_source: rate
+                        .map(|r| r.rate_source.clone())
+                        .unwrap_or_else(|| RATE_SOURCE_BASE_PRICE.to_string()),
+                    quantity: charge.quantity,
                     unit: addon.unit.clone(),
-                    amount,
+                    amount: charge.amount,
+                    gst_rate: addon.gst_rate.unwrap_or(service_gst_rate),
                 });
             } else {
                 return Err(ApiError {
@@ -233,22 +299,68 @@ impl PricingEngine {
             });
         }
 
-        // Calculate GST on the base amount
-        let gst_calculation = Self::calculate_gst(base_amount, service_pricing.gst_rate, request.gst_inclusive)?;
+        // Calculate GST per component, each at its own rate on its share of the base amount
+        let scale = if subtotal > 0.0 { base_amount / subtotal } else { 0.0 };
+        let components = std::iter::once((service_amount, service_pricing.gst_rate))
+            .chain(addon_calculations.iter().map(|a| (a.amount, a.gst_rate)));
+
+        let mut taxable_amount = 0.0;
+        let mut sgst_amount = 0.0;
+        let mut cgst_amount = 0.0;
+        let mut total_amount = 0.0;
+        for (amount, gst_rate) in components {
+            let gst_calculation = Self::calculate_gst(amount * scale, gst_rate, request.gst_inclusive)?;
+            taxable_amount += gst_calculation.base_amount;
+            sgst_amount += gst_calculation.sgst_amount;
+            cgst_amount += gst_calculation.cgst_amount;
+            total_amount += gst_calculation.total_with_gst;
+        }
 
         Ok(PricingTotals {
             subtotal,
             discount_amount,
             express_charge,
-            base_amount: gst_calculation.base_amount,
-            total_gst_amount: gst_calculation.total_gst,
-            sgst_amount: gst_calculation.sgst_amount,
-            cgst_amount: gst_calculation.cgst_amount,
-            total_amount: gst_calculation.total_with_gst,
+            base_amount: taxable_amount,
+            total_gst_amount: sgst_amount + cgst_amount,
+            sgst_amount,
+            cgst_amount,
+            total_amount,
             gst_inclusive: request.gst_inclusive,
         })
     }
 
+    /// Amount for an addon under its pricing mode. `rate` is the per-unit
+    /// amount, or the percentage for percent_of_line.
+    pub fn calculate_addon_charge(
+        pricing_mode: &str,
+        rate: f64,
+        quantity: f64,
+        line_amount: f64,
+        piece_count: i32,
+    ) -> ApiResult<AddonCharge> {
+        let (quantity, amount) = match pricing_mode {
+            "per_unit" => (quantity, rate * quantity),
+            "percent_of_line" => (1.0, line_amount * rate / 100.0),
+            "per_order" => (1.0, rate),
+            "per_piece" => (piece_count as f64, rate * piece_count as f64),
+            other => {
+                return Err(ApiError {
+                    message: format!("Unknown addon pricing mode: {}", other),
+                    code: Some("INVALID_PRICING_MODE".to_string()),
+                });
+            }
+        };
+
+        if quantity <= 0.0 {
+            return Err(ApiError {
+                message: "Addon quantity must be greater than 0".to_string(),
+                code: Some("INVALID_ADDON_QUANTITY".to_string()),
+            });
+        }
+
+        Ok(AddonCharge { quantity, amount })
+    }
+
     /// Calculate GST amounts based on inclusive/exclusive mode
     pub fn calculate_gst(amount: f64, gst_rate: f64, is_inclusive: bool) -> ApiResult<GstCalculation> {
         if gst_rate < 0.0 || gst_rate > 100.0 {
@@ -361,6 +473,128 @@ impl PricingEngine {
         base_amount * (express_rate / 100.0)
     }
 
+    /// Calculate the turnaround (express) surcharge for an invoice.
+    /// Lines in excluded categories don't attract the surcharge; GST on the
+    /// surcharge follows the rates of the lines it was charged on.
+    pub fn calculate_turnaround_surcharge(
+        tier: &TurnaroundTier,
+        lines: &[SurchargeLine],
+        gst_inclusive: bool,
+    ) -> ApiResult<TurnaroundSurcharge> {
+        let excluded: Vec<i64> = serde_json::from_str(&tier.excluded_category_ids).unwrap_or_default();
+        let eligible: Vec<&SurchargeLine> = lines
+            .iter()
+            .filter(|line| line.category_id.map_or(true, |id| !excluded.contains(&id)))
+            .collect();
+        let eligible_amount: f64 = eligible.iter().map(|line| line.amount).sum();
+
+        let raw_surcharge = match tier.surcharge_type.as_str() {
+            "percent" => Self::calculate_express_charge(eligible_amount, tier.surcharge_value),
+            "flat" => tier.surcharge_value,
+            other => {
+                return Err(ApiError {
+                    message: format!("Unknown surcharge type: {}", other),
+                    code: Some("INVALID_SURCHARGE_TYPE".to_string()),
+                });
+            }
+        };
+
+        let surcharge = if eligible_amount <= 0.0 || raw_surcharge <= 0.0 {
+            0.0
+        } else {
+            crate::utils::round_to_paisa(raw_surcharge.max(tier.min_surcharge))
+        };
+
+        let mut base_amount = 0.0;
+        let mut sgst_amount = 0.0;
+        let mut cgst_amount = 0.0;
+
+        if surcharge > 0.0 {
+            for line in &eligible {
+                let share = surcharge * (line.amount / eligible_amount);
+                let gst = Self::calculate_gst(share, line.gst_rate, gst_inclusive)?;
+                base_amount += gst.base_amount;
+                sgst_amount += gst.sgst_amount;
+                cgst_amount += gst.cgst_amount;
+            }
+        }
+
+        Ok(TurnaroundSurcharge {
+            tier_code: tier.code.clone(),
+            eligible_amount,
+            surcharge,
+            base_amount,
+            sgst_amount,
+            cgst_amount,
+        })
+    }
+
+    /// Invoice discount in rupees for a flat or percent discount on the line total
+    pub fn calculate_invoice_discount(
+        discount: f64,
+        discount_type: &str,
+        line_total: f64,
+    ) -> ApiResult<f64> {
+        if discount < 0.0 {
+            return Err(ApiError {
+                message: "Discount cannot be negative".to_string(),
+                code: Some("INVALID_DISCOUNT".to_string()),
+            });
+        }
+
+        let amount = match discount_type {
+            "percent" if discount <= 100.0 => line_total * discount / 100.0,
+            "percent" => {
+                return Err(ApiError {
+                    message: "Discount percentage cannot exceed 100%".to_string(),
+                    code: Some("INVALID_DISCOUNT".to_string()),
+                });
+            }
+            "flat" => discount,
+            other => {
+                return Err(ApiError {
+                    message: format!("Unknown discount type: {}", other),
+                    code: Some("INVALID_DISCOUNT".to_string()),
+                });
+            }
+        };
+
+        let amount = crate::utils::round_to_paisa(amount);
+        if amount > crate::utils::round_to_paisa(line_total) {
+            return Err(ApiError {
+                message: "Discount cannot exceed the invoice amount".to_string(),
+                code: Some("INVALID_DISCOUNT".to_string()),
+            });
+        }
+
+        Ok(amount)
+    }
+
+    /// Split an invoice discount across lines in proportion to their amounts.
+    /// Shares are whole paisa; the rounding residue goes to the largest line
+    /// (the first one on ties) so the shares always add up to the discount.
+    pub fn allocate_discount(discount: f64, line_amounts: &[f64]) -> Vec<f64> {
+        let total: f64 = line_amounts.iter().sum();
+        if discount <= 0.0 || total <= 0.0 {
+            return vec![0.0; line_amounts.len()];
+        }
+
+        let discount_paisa = (discount * 100.0).round() as i64;
+        let mut shares: Vec<i64> = line_amounts
+            .iter()
+            .map(|amount| (discount_paisa as f64 * amount / total).floor() as i64)
+            .collect();
+
+        let residue = discount_paisa - shares.iter().sum::<i64>();
+        let largest = line_amounts
+            .iter()
+            .enumerate()
+            .fold(0, |best, (index, amount)| if *amount > line_amounts[best] { index } else { best });
+        shares[largest] += residue;
+
+        shares.into_iter().map(|paisa| paisa as f64 / 100.0).collect()
+    }
+
     /// Calculate loyalty discount based on customer tier
     pub fn calculate_loyalty_discount(base_amount: f64, customer_tier: &str) -> f64 {
         let discount_rate = match customer_tier {
@@ -491,6 +725,97 @@ mod tests {
         assert_eq!(result, 50.0);
     }
 
+    fn tier(surcharge_type: &str, value: f64, minimum: f64, excluded: &str) -> TurnaroundTier {
+        TurnaroundTier {
+            id: 1,
+            store_id: None,
+            code: "next_day".to_string(),
+            name: "Next Day".to_string(),
+            surcharge_type: surcharge_type.to_string(),
+            surcharge_value: value,
+            min_surcharge: minimum,
+            turnaround_hours: 24,
+            excluded_category_ids: excluded.to_string(),
+            is_active: Some(1),
+            created_at: String::new(),
+            updated_at: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_turnaround_surcharge_percent_with_exclusions() {
+        let lines = vec![
+            SurchargeLine { amount: 400.0, category_id: Some(1), gst_rate: 18.0 },
+            SurchargeLine { amount: 600.0, category_id: Some(9), gst_rate: 18.0 },
+        ];
+        let result = PricingEngine::calculate_turnaround_surcharge(
+            &tier("percent", 50.0, 0.0, "[9]"), &lines, false,
+        ).unwrap();
+
+        assert_eq!(result.eligible_amount, 400.0);
+        assert_eq!(result.surcharge, 200.0);
+        assert_eq!(result.base_amount, 200.0);
+        assert_eq!(result.sgst_amount, 18.0);
+        assert_eq!(result.cgst_amount, 18.0);
+    }
+
+    #[test]
+    fn test_turnaround_surcharge_minimum_and_flat() {
+        let lines = vec![SurchargeLine { amount: 60.0, category_id: Some(1), gst_rate: 18.0 }];
+
+        let result = PricingEngine::calculate_turnaround_surcharge(
+            &tier("percent", 50.0, 50.0, "[]"), &lines, false,
+        ).unwrap();
+        assert_eq!(result.surcharge, 50.0);
+
+        let result = PricingEngine::calculate_turnaround_surcharge(
+            &tier("flat", 75.0, 0.0, "[]"), &lines, true,
+        ).unwrap();
+        assert_eq!(result.surcharge, 75.0);
+        assert!((result.base_amount - 63.56).abs() < 0.01);
+
+        let result = PricingEngine::calculate_turnaround_surcharge(
+            &tier("flat", 75.0, 0.0, "[1]"), &lines, false,
+        ).unwrap();
+        assert_eq!(result.surcharge, 0.0);
+    }
+
+    #[test]
+    fn test_addon_charge_modes() {
+        let charge = PricingEngine::calculate_addon_charge("per_unit", 20.0, 3.0, 500.0, 6).unwrap();
+        assert_eq!((charge.quantity, charge.amount), (3.0, 60.0));
+
+        let charge = PricingEngine::calculate_addon_charge("percent_of_line", 10.0, 3.0, 500.0, 6).unwrap();
+        assert_eq!((charge.quantity, charge.amount), (1.0, 50.0));
+
+        let charge = PricingEngine::calculate_addon_charge("per_order", 99.0, 3.0, 500.0, 6).unwrap();
+        assert_eq!((charge.quantity, charge.amount), (1.0, 99.0));
+
+        let charge = PricingEngine::calculate_addon_charge("per_piece", 15.0, 1.0, 500.0, 6).unwrap();
+        assert_eq!((charge.quantity, charge.amount), (6.0, 90.0));
+
+        assert!(PricingEngine::calculate_addon_charge("per_piece", 15.0, 1.0, 500.0, 0).is_err());
+        assert!(PricingEngine::calculate_addon_charge("per_weight", 15.0, 1.0, 500.0, 6).is_err());
+    }
+
+    #[test]
+    fn test_invoice_discount_allocation() {
+        let discount = PricingEngine::calculate_invoice_discount(10.0, "percent", 350.0).unwrap();
+        assert_eq!(discount, 35.0);
+        assert!(PricingEngine::calculate_invoice_discount(400.0, "flat", 350.0).is_err());
+        assert!(PricingEngine::calculate_invoice_discount(101.0, "percent", 350.0).is_err());
+
+        // 100 split three ways leaves one paisa for the largest (first) line
+        let shares = PricingEngine::allocate_discount(100.0, &[300.0, 300.0, 300.0]);
+        assert_eq!(shares, vec![33.34, 33.33, 33.33]);
+
+        let shares = PricingEngine::allocate_discount(10.0, &[50.0, 0.0, 150.0]);
+        assert_eq!(shares, vec![2.5, 0.0, 7.5]);
+
+        let shares = PricingEngine::allocate_discount(0.05, &[10.0, 20.0, 30.0]);
+        assert_eq!(shares, vec![0.0, 0.01, 0.04]);
+    }
+
     #[test]
     fn test_loyalty_discount_calculation() {
         let result = PricingEngine::calculate_loyalty_discount(100.0, "PREMIUM");
@@ -0,0 +1,290 @@
+use crate::database::DatabaseManager;
+use crate::models::PrintQueueItem;
+use crate::services::{PrinterLanguage, ReceiptPrinterService, TagGeneratorService};
+use anyhow::{Result, Context};
+
+pub const PRINT_KIND_RECEIPT: &str = "receipt";
+pub const PRINT_KIND_TAGS: &str = "tags";
+
+/// First retry waits this long; each further failure doubles it
+const BASE_RETRY_DELAY_SECS: i64 = 15;
+const MAX_RETRY_DELAY_SECS: i64 = 600;
+
+pub struct PrintQueueService;
+
+impl PrintQueueService {
+    /// Queue the receipt and tags of a new invoice when its store has
+    /// auto-print turned on and a printer for them. Returns the queued items
+    /// (none if disabled).
+    pub async fn enqueue_auto_print(db: &DatabaseManager, invoice_id: i64) -> Result<Vec<PrintQueueItem>> {
+        let store_id: i64 = sqlx::query_scalar("SELECT store_id FROM invoices WHERE id = ?")
+            .bind(invoice_id)
+            .fetch_one(db.get_pool())
+            .await
+            .context("Failed to fetch invoice store")?;
+
+        let settings = TagGeneratorService::get_tag_settings(db, Some(store_id)).await?;
+        if settings.auto_print != 1 {
+            return Ok(Vec::new());
+        }
+
+        let mut queued = Vec::new();
+        for kind in [PRINT_KIND_RECEIPT, PRINT_KIND_TAGS] {
+            match Self::missing_printer(db, store_id, kind).await? {
+                Some(reason) => log::info!("Not auto-printing {} for invoice {}: {}", kind, invoice_id, reason),
+                None => queued.push(Self::enqueue(db, invoice_id, kind).await?),
+            }
+        }
+        Ok(queued)
+    }
+
+    /// Why a store has nowhere to send a kind of print unattended, if it
+    /// doesn't. The queue only prints to a configured printer; it never
+    /// falls back to opening the document in a browser.
+    pub async fn missing_printer(db: &DatabaseManager, store_id: i64, kind: &str) -> Result<Option<&'static str>> {
+        match kind {
+            PRINT_KIND_RECEIPT => {
+                let receipt = ReceiptPrinterService::get_settings(db, Some(store_id)).await?;
+                Ok(receipt.printer_name.is_none().then_some("No receipt printer configured"))
+            }
+            PRINT_KIND_TAGS => {
+                let settings = TagGeneratorService::get_tag_settings(db, Some(store_id)).await?;
+                let raw = !matches!(PrinterLanguage::parse(&settings.printer_language), Some(PrinterLanguage::Html) | None);
+                let named = settings.printer_name.as_deref().is_some_and(|name| !name.trim().is_empty());
+                Ok((!(raw && named)).then_some("No label printer configured"))
+            }
+            other => Err(anyhow::anyhow!("Unknown print kind: {}", other)),
+        }
+    }
+
+    /// Add a print to the queue. An invoice already waiting for the same
+    /// kind of print is not queued twice.
+    pub async fn enqueue(db: &DatabaseManager, invoice_id: i64, kind: &str) -> Result<PrintQueueItem> {
+        if kind != PRINT_KIND_RECEIPT && kind != PRINT_KIND_TAGS {
+            return Err(anyhow::anyhow!("Unknown print kind: {}", kind));
+        }
+
+        let waiting = sqlx::query_as::<_, PrintQueueItem>(
+            "SELECT * FROM print_queue WHERE invoice_id = ? AND kind = ? AND status IN ('pending', 'printing') LIMIT 1",
+        )
+        .bind(invoice_id)
+        .bind(kind)
+        .fetch_optional(db.get_pool())
+        .await
+        .context("Failed to check print queue")?;
+
+        if let Some(item) = waiting {
+            return Ok(item);
+        }
+
+        sqlx::query_as::<_, PrintQueueItem>(
+            "INSERT INTO print_queue (invoice_id, kind, next_attempt_at) VALUES (?, ?, ?) RETURNING *",
+        )
+        .bind(invoice_id)
+        .bind(kind)
+        .bind(Self::timestamp(chrono::Utc::now()))
+        .fetch_one(db.get_pool())
+        .await
+        .context("Failed to queue print")
+    }
+
+    /// Take the oldest print that is due and mark it as printing
+    pub async fn claim_next(db: &DatabaseManager) -> Result<Option<PrintQueueItem>> {
+        sqlx::query_as::<_, PrintQueueItem>(
+            r#"
+            UPDATE print_queue
+            SET status = 'printing', attempts = attempts + 1, updated_at = CURRENT_TIMESTAMP
+            WHERE id = (
+                SELECT id FROM print_queue
+                WHERE status = 'pending' AND next_attempt_at <= ?
+                ORDER BY next_attempt_at, id
+                LIMIT 1
+            )
+            RETURNING *
+            "#,
+        )
+        .bind(Self::timestamp(chrono::Utc::now()))
+        .fetch_optional(db.get_pool())
+        .await
+        .context("Failed to claim print from queue")
+    }
+
+    pub async fn complete(db: &DatabaseManager, item_id: i64, print_job_id: Option<i64>) -> Result<PrintQueueItem> {
+        sqlx::query_as::<_, PrintQueueItem>(
+            r#"
+            UPDATE print_queue
+            SET status = 'printed', last_error = NULL, print_job_id = COALESCE(?, print_job_id),
+                updated_at = CURRENT_TIMESTAMP
+            WHERE id = ?
+            RETURNING *
+            "#,
+        )
+        .bind(print_job_id)
+        .bind(item_id)
+        .fetch_one(db.get_pool())
+        .await
+        .context("Failed to complete print")
+    }
+
+    /// Record a failed attempt. The print is retried with backoff until it
+    /// runs out of attempts, then stays failed until retried by hand.
+    pub async fn fail(db: &DatabaseManager, item: &PrintQueueItem, error: &str) -> Result<PrintQueueItem> {
+        let exhausted = item.attempts >= item.max_attempts;
+        let next_attempt_at = chrono::Utc::now() + Self::retry_delay(item.attempts);
+
+        sqlx::query_as::<_, PrintQueueItem>(
+            r#"
+            UPDATE print_queue
+            SET status = ?, last_error = ?, next_attempt_at = ?, updated_at = CURRENT_TIMESTAMP
+            WHERE id = ?
+            RETURNING *
+            "#,
+        )
+        .bind(if exhausted { "failed" } else { "pending" })
+        .bind(error)
+        .bind(Self::timestamp(next_attempt_at))
+        .bind(item.id)
+        .fetch_one(db.get_pool())
+        .await
+        .context("Failed to record print failure")
+    }
+
+    /// Put a failed or cancelled print back in the queue with fresh attempts
+    pub async fn retry(db: &DatabaseManager, item_id: i64) -> Result<PrintQueueItem> {
+        sqlx::query_as::<_, PrintQueueItem>(
+            r#"
+            UPDATE print_queue
+            SET status = 'pending', attempts = 0, next_attempt_at = ?, updated_at = CURRENT_TIMESTAMP
+            WHERE id = ? AND status IN ('failed', 'cancelled')
+            RETURNING *
+            "#,
+        )
+        .bind(Self::timestamp(chrono::Utc::now()))
+        .bind(item_id)
+        .fetch_optional(db.get_pool())
+        .await
+        .context("Failed to retry print")?
+        .ok_or_else(|| anyhow::anyhow!("Print {} is not failed or cancelled", item_id))
+    }
+
+    pub async fn cancel(db: &DatabaseManager, item_id: i64) -> Result<PrintQueueItem> {
+        sqlx::query_as::<_, PrintQueueItem>(
+            r#"
+            UPDATE print_queue
+            SET status = 'cancelled', updated_at = CURRENT_TIMESTAMP
+            WHERE id = ? AND status IN ('pending', 'failed')
+            RETURNING *
+            "#,
+        )
+        .bind(item_id)
+        .fetch_optional(db.get_pool())
+        .await
+        .context("Failed to cancel print")?
+        .ok_or_else(|| anyhow::anyhow!("Print {} is not waiting in the queue", item_id))
+    }
+
+    pub async fn list(db: &DatabaseManager, status: Option<&str>, limit: i64) -> Result<Vec<PrintQueueItem>> {
+        sqlx::query_as::<_, PrintQueueItem>(
+            "SELECT * FROM print_queue WHERE (? IS NULL OR status = ?) ORDER BY id DESC LIMIT ?",
+        )
+        .bind(status)
+        .bind(status)
+        .bind(limit)
+        .fetch_all(db.get_pool())
+        .await
+        .context("Failed to fetch print queue")
+    }
+
+    /// Prints left mid-flight when the app closed go back to pending
+    pub async fn reset_interrupted(db: &DatabaseManager) -> Result<u64> {
+        let result = sqlx::query(
+            "UPDATE print_queue SET status = 'pending', updated_at = CURRENT_TIMESTAMP WHERE status = 'printing'",
+        )
+        .execute(db.get_pool())
+        .await
+        .context("Failed to reset interrupted prints")?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Wait before the next attempt after `attempts` failures
+    pub fn retry_delay(attempts: i64) -> chrono::Duration {
+        let exponent = (attempts.max(1) - 1).min(10) as u32;
+        chrono::Duration::seconds((BASE_RETRY_DELAY_SECS * 2i64.pow(exponent)).min(MAX_RETRY_DELAY_SECS))
+    }
+
+    /// Same format as SQLite's CURRENT_TIMESTAMP, so times compare as text
+    fn timestamp(time: chrono::DateTime<chrono::Utc>) -> String {
+        time.format("%Y-%m-%d %H:%M:%S").to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::test_fixtures::invoice_db;
+
+    #[test]
+    fn test_retry_delay_backs_off() {
+        assert_eq!(PrintQueueService::retry_delay(1).num_seconds(), 15);
+        assert_eq!(PrintQueueService::retry_delay(2).num_seconds(), 30);
+        assert_eq!(PrintQueueService::retry_delay(4).num_seconds(), 120);
+        assert_eq!(PrintQueueService::retry_delay(9).num_seconds(), 600);
+    }
+
+    #[tokio::test]
+    async fn test_queue_retries_then_fails() {
+        let db = invoice_db("INV900", "Queue Test").await;
+
+        let queued = PrintQueueService::enqueue(&db, 900, PRINT_KIND_TAGS).await.unwrap();
+        assert_eq!(PrintQueueService::enqueue(&db, 900, PRINT_KIND_TAGS).await.unwrap().id, queued.id);
+        assert!(PrintQueueService::enqueue(&db, 900, "label").await.is_err());
+
+        let claimed = PrintQueueService::claim_next(&db).await.unwrap().unwrap();
+        assert_eq!((claimed.id, claimed.status.as_str(), claimed.attempts), (queued.id, "printing", 1));
+        assert!(PrintQueueService::claim_next(&db).await.unwrap().is_none());
+
+        // A failed attempt waits for its backoff before it is due again
+        let retrying = PrintQueueService::fail(&db, &claimed, "printer offline").await.unwrap();
+        assert_eq!(retrying.status, "pending");
+        assert!(PrintQueueService::claim_next(&db).await.unwrap().is_none());
+
+        let last_attempt = PrintQueueItem { attempts: claimed.max_attempts, ..claimed };
+        let failed = PrintQueueService::fail(&db, &last_attempt, "printer offline").await.unwrap();
+        assert_eq!((failed.status.as_str(), failed.last_error.as_deref()), ("failed", Some("printer offline")));
+
+        let retried = PrintQueueService::retry(&db, failed.id).await.unwrap();
+        assert_eq!((retried.status.as_str(), retried.attempts), ("pending", 0));
+        let claimed = PrintQueueService::claim_next(&db).await.unwrap().unwrap();
+        let done = PrintQueueService::complete(&db, claimed.id, None).await.unwrap();
+        assert_eq!(done.status, "printed");
+        assert!(PrintQueueService::cancel(&db, done.id).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_auto_print_needs_a_printer() {
+        let db = invoice_db("INV900", "Auto Print Test").await;
+        sqlx::query("INSERT INTO tag_settings (store_id, auto_print, printer_language) VALUES (1, 1, 'html')")
+            .execute(db.get_pool())
+            .await
+            .unwrap();
+
+        // HTML tags and receipts would open a browser, so nothing is queued
+        assert!(PrintQueueService::enqueue_auto_print(&db, 900).await.unwrap().is_empty());
+        assert_eq!(
+            PrintQueueService::missing_printer(&db, 1, PRINT_KIND_TAGS).await.unwrap(),
+            Some("No label printer configured")
+        );
+
+        sqlx::query("UPDATE tag_settings SET printer_language = 'zpl', printer_name = 'tcp://10.0.0.9:9100'")
+            .execute(db.get_pool())
+            .await
+            .unwrap();
+        let queued = PrintQueueService::enqueue_auto_print(&db, 900).await.unwrap();
+        assert_eq!(queued.iter().map(|item| item.kind.as_str()).collect::<Vec<_>>(), vec![PRINT_KIND_TAGS]);
+        assert_eq!(
+            PrintQueueService::missing_printer(&db, 1, PRINT_KIND_RECEIPT).await.unwrap(),
+            Some("No receipt printer configured")
+        );
+    }
+}
@@ -0,0 +1,571 @@
+use crate::database::DatabaseManager;
+use crate::models::{ReceiptSettings, SaveReceiptSettingsRequest};
+use crate::services::fonts::Fonts;
+use crate::services::html_generator::{HtmlGenerator, HtmlSettings, InvoiceHtmlData, InvoiceItemWithDetails};
+use crate::services::label_printer::{truncate, EscPosBuilder, PrinterTarget};
+use crate::services::localization::DocumentLanguage;
+use anyhow::{Context, Result};
+
+/// Item table columns after the description: qty, rate and amount widths
+const QTY_WIDTH: usize = 6;
+const RATE_WIDTH: usize = 9;
+const AMOUNT_WIDTH: usize = 9;
+/// QR module size in dots; about 25mm wide for a typical invoice URL
+const QR_MODULE_DOTS: u8 = 6;
+/// The printer's font is 12 dots to a character
+const CHAR_DOTS: usize = 12;
+/// Size in dots of text printed as an image, close to the printer's font
+const RASTER_EM: f32 = 22.0;
+
+/// Lays an invoice out as an 80mm ESC/POS receipt, with every line fitted to
+/// the printer's 42 or 48 characters
+pub struct ReceiptRenderer;
+
+impl ReceiptRenderer {
+    /// `script_font` sets the lines the printer's ASCII font can't, such as
+    /// Hindi labels; without it the receipt drops to a language it can print.
+    pub fn render(
+        data: &InvoiceHtmlData,
+        settings: &HtmlSettings,
+        receipt: &ReceiptSettings,
+        script_font: Option<&[u8]>,
+    ) -> Result<Vec<u8>> {
+        let width = receipt.line_chars as usize;
+        if !ReceiptSettings::LINE_CHARS.contains(&receipt.line_chars) {
+            return Err(anyhow::anyhow!("Receipts print 42 or 48 characters per line, not {}", width));
+        }
+        let fonts = Fonts::load(script_font).map_err(|e| anyhow::anyhow!(e.message))?;
+        let language = DocumentLanguage::for_invoice(&data.store, &data.customer)
+            .restricted_to(|locale| fonts.can_set(locale));
+        let t = language.labels();
+        let label = |key: &str| format!("{}:", t[key]);
+        let rule = "-".repeat(width);
+        let mut out = ReceiptLines { escpos: EscPosBuilder::new(), fonts: &fonts, width };
+
+        out.escpos.align_centre().bold(true).double_size(true);
+        out.escpos.line(&truncate(&settings.branding.business_name, width / 2));
+        out.escpos.double_size(false).bold(false);
+        let mut header = vec![settings.branding.business_tagline.clone(), Some(data.store.address.clone())];
+        header.push(data.store.city.clone());
+        header.push(data.store.phone.as_ref().map(|phone| format!("{}: {}", t["phone"], phone)));
+        header.push(data.store.gstin.as_ref().map(|gstin| format!("{}: {}", t["gstin"], gstin)));
+        for text in header.into_iter().flatten() {
+            out.wrapped(&text, false);
+        }
+
+        out.escpos.align_left().line(&rule);
+        out.pair(&label("invoice_no"), &data.invoice.invoice_no, true);
+        out.pair(&label("date"), &data.invoice.order_datetime, false);
+        out.pair(&label("customer"), &data.customer.name, false);
+        if let Some(phone) = &data.customer.phone {
+            out.pair(&label("phone"), phone, false);
+        }
+        if let Some(delivery) = &data.invoice.delivery_datetime {
+            out.pair(&label("delivery_date"), delivery, false);
+        }
+        out.escpos.line(&rule);
+
+        out.row([&t["service"], &t["qty"], &t["rate"], &t["amount"]], true);
+        out.escpos.line(&rule);
+        for item in &data.items {
+            out.item(item, &t["discount"]);
+        }
+        out.escpos.line(&rule);
+
+        let totals = &data.totals;
+        out.pair(&label("subtotal"), &money(totals.subtotal), false);
+        if totals.discount_amount > 0.0 {
+            out.pair(&label("discount"), &format!("-{}", money(totals.discount_amount)), false);
+        }
+        if totals.express_charge > 0.0 {
+            out.pair(&label("express_charge"), &money(totals.express_charge), false);
+        }
+        out.pair(&label("base_amount"), &money(totals.base_amount), false);
+        let (sgst, cgst) = HtmlGenerator::gst_labels(data, &t);
+        out.pair(&sgst, &money(totals.sgst_amount), false);
+        out.pair(&cgst, &money(totals.cgst_amount), false);
+        out.pair(&label("total_amount"), &money(totals.total_amount), true);
+        for words in language.amount_in_words(totals.total_amount) {
+            out.wrapped(&words, false);
+        }
+
+        let escpos = &mut out.escpos;
+        if receipt.include_qr {
+            escpos.feed(1).align_centre();
+            escpos.qr_code(&Self::qr_data(receipt, &data.invoice.invoice_no), QR_MODULE_DOTS);
+            escpos.line(&data.invoice.invoice_no).align_left();
+        }
+        escpos.line(&rule);
+
+        let footer = &settings.footer;
+        if !footer.terms_conditions.is_empty() {
+            out.line(&label("terms_conditions"), true);
+            for (index, term) in footer.terms_conditions.iter().enumerate() {
+                let term = crate::utils::terms_plain_text(term);
+                out.wrapped(&format!("{}. {}", index + 1, term), false);
+            }
+            out.escpos.feed(1);
+        }
+        out.escpos.align_centre();
+        if let Some(thank_you) = &footer.thank_you_message {
+            out.wrapped(thank_you, true);
+        }
+        for info in &footer.contact_info {
+            out.wrapped(info, false);
+        }
+
+        let mut escpos = out.escpos;
+        escpos.feed(4);
+        if receipt.cut_paper {
+            escpos.cut();
+        }
+        Ok(escpos.finish())
+    }
+
+    /// What the QR code holds: the store's lookup URL for the invoice, or
+    /// just the invoice number for scanners at the counter
+    fn qr_data(receipt: &ReceiptSettings, invoice_no: &str) -> String {
+        match receipt.qr_url.as_deref().filter(|url| !url.trim().is_empty()) {
+            Some(url) => url.trim().replace("{invoice_no}", &urlencoding::encode(invoice_no)),
+            None => invoice_no.to_string(),
+        }
+    }
+}
+
+/// Receipt lines in the printer's own font, which only covers ASCII. Lines
+/// with any other letters are drawn and sent as images instead, laid out
+/// on the same character grid.
+struct ReceiptLines<'f> {
+    escpos: EscPosBuilder,
+    fonts: &'f Fonts<'f>,
+    width: usize,
+}
+
+impl ReceiptLines<'_> {
+    fn styled(&mut self, bold: bool, print: impl FnOnce(&mut Self)) {
+        if bold {
+            self.escpos.bold(true);
+        }
+        print(self);
+        if bold {
+            self.escpos.bold(false);
+        }
+    }
+
+    /// Word-wrap to `chars` characters, or to as many dots when the text
+    /// will be drawn
+    fn wrap(&self, text: &str, chars: usize, bold: bool) -> Vec<String> {
+        if text.is_ascii() {
+            wrap(text, chars)
+        } else {
+            self.fonts.wrap_em(text, (chars * CHAR_DOTS) as f32, RASTER_EM, bold)
+        }
+    }
+
+    /// Draw pieces of text placed `at` characters in, ending there when
+    /// right aligned and starting there otherwise
+    fn image(&mut self, pieces: &[(&str, usize, bool)], width: usize, bold: bool) {
+        let placed: Vec<(f32, &str)> = pieces
+            .iter()
+            .map(|&(text, at, right)| {
+                let x = (at * CHAR_DOTS) as f32;
+                let x = if right { x - self.fonts.advance(text, RASTER_EM, bold) } else { x };
+                (x.max(0.0), text)
+            })
+            .collect();
+        let bitmap = self.fonts.rasterize(&placed, (width * CHAR_DOTS).max(1), RASTER_EM, bold);
+        self.escpos.raster(&bitmap);
+    }
+
+    fn line(&mut self, text: &str, bold: bool) {
+        if text.is_ascii() {
+            self.styled(bold, |out| {
+                out.escpos.line(text);
+            });
+        } else {
+            // As wide as the text, so centred lines stay centred
+            let chars = (self.fonts.advance(text, RASTER_EM, bold) / CHAR_DOTS as f32).ceil() as usize;
+            self.image(&[(text, 0, false)], chars.min(self.width), bold);
+        }
+    }
+
+    fn wrapped(&mut self, text: &str, bold: bool) {
+        for line in self.wrap(text, self.width, bold) {
+            self.line(&line, bold);
+        }
+    }
+
+    fn pair(&mut self, label: &str, value: &str, bold: bool) {
+        if label.is_ascii() && value.is_ascii() {
+            let line = pair(label, value, self.width);
+            self.styled(bold, |out| {
+                out.escpos.line(&line);
+            });
+        } else {
+            self.image(&[(label, 0, false), (value, self.width, true)], self.width, bold);
+        }
+    }
+
+    /// One row of the item table: description, qty, rate and amount
+    fn row(&mut self, [name, qty, rate, amount]: [&str; 4], bold: bool) {
+        if [name, qty, rate, amount].iter().all(|text| text.is_ascii()) {
+            let line = item_row(name, qty, rate, amount, self.width);
+            self.styled(bold, |out| {
+                out.escpos.line(&line);
+            });
+        } else {
+            let amount_end = self.width;
+            let rate_end = amount_end - AMOUNT_WIDTH;
+            let qty_end = rate_end - RATE_WIDTH;
+            let pieces = [(name, 0, false), (qty, qty_end, true), (rate, rate_end, true), (amount, amount_end, true)];
+            self.image(&pieces, self.width, bold);
+        }
+    }
+
+    fn item(&mut self, item: &InvoiceItemWithDetails, discount_label: &str) {
+        let width = self.width;
+        let name_width = width - QTY_WIDTH - RATE_WIDTH - AMOUNT_WIDTH;
+        let name = match item.variant_name.as_deref() {
+            Some(variant) if !variant.is_empty() => format!("{} ({})", item.service_name, variant),
+            _ => item.service_name.clone(),
+        };
+
+        // The description wraps inside its column; figures go on the first line
+        let names = self.wrap(&name, name_width - 1, false);
+        for (index, line) in names.iter().enumerate() {
+            if index == 0 {
+                let qty = format!("{}{}", quantity(item.item.qty), unit_suffix(&item.unit));
+                let rate = format!("{:.2}", item.item.rate);
+                self.row([line, &qty, &rate, &format!("{:.2}", item.item.amount)], false);
+            } else {
+                self.line(line, false);
+            }
+        }
+        if item.item.discount_amount > 0.0 {
+            let discount = HtmlGenerator::format_line_discount(item.item.discount_amount).replace('₹', "");
+            self.row([&format!("  {}", discount_label), "", "", &discount], false);
+        }
+        for addon in &item.addons {
+            let name = format!("  + {}", addon.addon_name);
+            let name = if name.is_ascii() {
+                truncate(&name, name_width - 1)
+            } else {
+                self.wrap(&name, name_width - 1, false).remove(0)
+            };
+            let rate = format!("{:.2}", addon.rate);
+            self.row([&name, &quantity(addon.quantity), &rate, &format!("{:.2}", addon.amount)], false);
+        }
+        for note in HtmlGenerator::garment_note_lines(&item.garments) {
+            for line in self.wrap(&note, width - 2, false) {
+                self.line(&format!("  {}", line), false);
+            }
+        }
+    }
+}
+
+pub struct ReceiptPrinterService;
+
+impl ReceiptPrinterService {
+    /// The store's receipt settings, else the shared ones, else the defaults
+    pub async fn get_settings(db: &DatabaseManager, store_id: Option<i64>) -> Result<ReceiptSettings> {
+        let settings = sqlx::query_as::<_, ReceiptSettings>(
+            "SELECT * FROM receipt_settings WHERE store_id = ? OR store_id IS NULL ORDER BY store_id IS NULL, id LIMIT 1",
+        )
+        .bind(store_id)
+        .fetch_optional(db.get_pool())
+        .await
+        .context("Failed to fetch receipt settings")?;
+
+        Ok(settings.unwrap_or_else(|| ReceiptSettings::defaults(store_id)))
+    }
+
+    pub async fn save_settings(db: &DatabaseManager, request: SaveReceiptSettingsRequest) -> Result<ReceiptSettings> {
+        let blank_to_none = |value: Option<String>| value.map(|v| v.trim().to_string()).filter(|v| !v.is_empty());
+        let printer_name = blank_to_none(request.printer_name);
+        if let Some(name) = &printer_name {
+            PrinterTarget::parse(name)?;
+        }
+        let line_chars = request.line_chars.unwrap_or(48);
+        if !ReceiptSettings::LINE_CHARS.contains(&line_chars) {
+            return Err(anyhow::anyhow!("Receipts print 42 or 48 characters per line, not {}", line_chars));
+        }
+        let qr_url = blank_to_none(request.qr_url);
+        if let Some(url) = &qr_url {
+            if !url.contains("{invoice_no}") {
+                return Err(anyhow::anyhow!("The QR lookup URL needs {{invoice_no}} where the invoice number goes"));
+            }
+        }
+        let include_qr = request.include_qr.unwrap_or(true);
+        let cut_paper = request.cut_paper.unwrap_or(true);
+
+        let updated = sqlx::query_as::<_, ReceiptSettings>(
+            r#"
+            UPDATE receipt_settings SET
+                printer_name = ?, line_chars = ?, include_qr = ?, qr_url = ?, cut_paper = ?,
+                updated_at = CURRENT_TIMESTAMP
+            WHERE store_id IS ?
+            RETURNING *
+            "#,
+        )
+        .bind(&printer_name)
+        .bind(line_chars)
+        .bind(include_qr)
+        .bind(&qr_url)
+        .bind(cut_paper)
+        .bind(request.store_id)
+        .fetch_optional(db.get_pool())
+        .await
+        .context("Failed to update receipt settings")?;
+
+        if let Some(settings) = updated {
+            return Ok(settings);
+        }
+
+        sqlx::query_as::<_, ReceiptSettings>(
+            r#"
+            INSERT INTO receipt_settings (store_id, printer_name, line_chars, include_qr, qr_url, cut_paper)
+            VALUES (?, ?, ?, ?, ?, ?)
+            RETURNING *
+            "#,
+        )
+        .bind(request.store_id)
+        .bind(printer_name)
+        .bind(line_chars)
+        .bind(include_qr)
+        .bind(qr_url)
+        .bind(cut_paper)
+        .fetch_one(db.get_pool())
+        .await
+        .context("Failed to save receipt settings")
+    }
+
+    /// Render the receipt and send it to the configured printer. Returns the
+    /// number of bytes sent.
+    pub async fn print_receipt(
+        receipt: &ReceiptSettings,
+        data: &InvoiceHtmlData,
+        settings: &HtmlSettings,
+        script_font: Option<&[u8]>,
+    ) -> Result<usize> {
+        let printer_name = receipt
+            .printer_name
+            .as_deref()
+            .ok_or_else(|| anyhow::anyhow!("No receipt printer configured"))?;
+        let target = PrinterTarget::parse(printer_name)?;
+        let bytes = ReceiptRenderer::render(data, settings, receipt, script_font)?;
+        let sent = bytes.len();
+
+        tokio::task::spawn_blocking(move || target.send(&bytes))
+            .await
+            .context("Printer task failed")??;
+
+        Ok(sent)
+    }
+}
+
+fn money(amount: f64) -> String {
+    format!("Rs. {:.2}", amount)
+}
+
+/// 2, 1.5 or 0.75 - no trailing zeros in a narrow column
+fn quantity(qty: f64) -> String {
+    let text = format!("{:.2}", qty);
+    text.trim_end_matches('0').trim_end_matches('.').to_string()
+}
+
+/// Weight and area quantities keep a one-letter hint; pieces need none
+fn unit_suffix(unit: &str) -> &'static str {
+    match unit {
+        "kg" => "k",
+        "sqft" => "s",
+        _ => "",
+    }
+}
+
+/// A label on the left and a value on the right, filling the line
+fn pair(label: &str, value: &str, width: usize) -> String {
+    let value = truncate(value, width.saturating_sub(label.chars().count() + 1).max(width / 2));
+    let label = truncate(label, width - value.chars().count() - 1);
+    format!("{}{}{}", label, " ".repeat(width - label.chars().count() - value.chars().count()), value)
+}
+
+/// One row of the item table, exactly `width` characters wide
+fn item_row(name: &str, qty: &str, rate: &str, amount: &str, width: usize) -> String {
+    let name_width = width - QTY_WIDTH - RATE_WIDTH - AMOUNT_WIDTH;
+    format!(
+        "{:<name_w$}{:>qty_w$}{:>rate_w$}{:>amount_w$}",
+        truncate(name, name_width - 1),
+        truncate(qty, QTY_WIDTH - 1),
+        truncate(rate, RATE_WIDTH - 1),
+        truncate(amount, AMOUNT_WIDTH - 1),
+        name_w = name_width,
+        qty_w = QTY_WIDTH,
+        rate_w = RATE_WIDTH,
+        amount_w = AMOUNT_WIDTH,
+    )
+}
+
+/// Word-wrap to `width` characters, splitting words too long for a line
+fn wrap(text: &str, width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut current = String::new();
+
+    for word in text.split_whitespace() {
+        let mut word: Vec<char> = word.chars().collect();
+        while word.len() > width {
+            if !current.is_empty() {
+                lines.push(std::mem::take(&mut current));
+            }
+            lines.push(word.drain(..width).collect());
+        }
+        if word.is_empty() {
+            continue;
+        }
+
+        let word: String = word.into_iter().collect();
+        let needed = if current.is_empty() { 0 } else { current.chars().count() + 1 };
+        if needed + word.chars().count() > width {
+            lines.push(std::mem::take(&mut current));
+        }
+        if !current.is_empty() {
+            current.push(' ');
+        }
+        current.push_str(&word);
+    }
+    if !current.is_empty() {
+        lines.push(current);
+    }
+
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::html_generator::test_data;
+
+    fn settings() -> HtmlSettings {
+        let data = test_data::sample_invoice(1);
+        HtmlGenerator::get_settings("thermal", &data.store).unwrap()
+    }
+
+    /// The text lines of a receipt, without the formatting commands that
+    /// lead them. Lines holding a QR code or the cut are left out.
+    fn printed_lines(bytes: &[u8]) -> Vec<String> {
+        bytes
+            .split(|&byte| byte == b'\n')
+            .filter_map(|mut line| {
+                while let Some(&first) = line.first().filter(|&&byte| byte == 0x1B || byte == 0x1D) {
+                    let len = if first == 0x1B && line.get(1) == Some(&b'@') { 2 } else { 3 };
+                    line = &line[len.min(line.len())..];
+                }
+                let text = String::from_utf8_lossy(line).to_string();
+                if text.chars().any(|c| c.is_control()) { None } else { Some(text) }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_item_rows_fit_the_line() {
+        for width in [42, 48] {
+            let row = item_row("Dry Clean - Silk Saree (Heavy)", "12.5k", "1250.00", "15625.00", width);
+            assert_eq!(row.chars().count(), width);
+            assert!(row.ends_with(" 12.5k  1250.00 15625.00"));
+            assert_eq!(pair("Total Amount:", "Rs. 354.00", width).chars().count(), width);
+        }
+
+        assert_eq!(wrap("Terms apply to all orders", 10), ["Terms", "apply to", "all orders"]);
+        assert_eq!(wrap("INV2025100000001", 6), ["INV202", "510000", "0001"]);
+        assert_eq!(quantity(2.0), "2");
+        assert_eq!(quantity(1.50), "1.5");
+    }
+
+    #[test]
+    fn test_render_receipt() {
+        let data = test_data::sample_invoice(3);
+        let mut receipt = ReceiptSettings::defaults(Some(1));
+        receipt.line_chars = 42;
+        receipt.qr_url = Some("https://uclean.in/track/{invoice_no}".to_string());
+
+        let bytes = ReceiptRenderer::render(&data, &settings(), &receipt, None).unwrap();
+        assert_eq!(&bytes[..2], &[0x1B, 0x40]);
+        assert_eq!(&bytes[bytes.len() - 4..], &[0x1D, 0x56, 0x42, 0x00]);
+
+        let lines = printed_lines(&bytes);
+        assert!(lines.iter().all(|line| line.chars().count() <= 42), "{:#?}", lines);
+        assert!(lines.contains(&pair("Invoice No:", &data.invoice.invoice_no, 42)));
+        assert!(lines.iter().any(|line| line.starts_with("Total Amount:")));
+        assert!(lines.contains(&pair("SGST (9.0%):", &money(data.totals.sgst_amount), 42)));
+
+        let qr = format!("https://uclean.in/track/{}", urlencoding::encode(&data.invoice.invoice_no));
+        let text = String::from_utf8_lossy(&bytes);
+        assert!(text.contains(&qr));
+
+        receipt.include_qr = false;
+        receipt.cut_paper = false;
+        let bytes = ReceiptRenderer::render(&data, &settings(), &receipt, None).unwrap();
+        assert!(!String::from_utf8_lossy(&bytes).contains("uclean.in/track"));
+        assert_ne!(&bytes[bytes.len() - 4..], &[0x1D, 0x56, 0x42, 0x00]);
+
+        // Lines at different rates have no single split to print
+        let mut mixed = data.clone();
+        mixed.items[0].item.gst_rate = 5.0;
+        let lines = printed_lines(&ReceiptRenderer::render(&mixed, &settings(), &receipt, None).unwrap());
+        assert!(lines.contains(&pair("SGST:", &money(data.totals.sgst_amount), 42)));
+
+        // A Hindi customer's receipt needs a Devanagari font to print its
+        // labels; without one it prints in English, not question marks
+        let mut hindi = data.clone();
+        hindi.customer.locale = Some("hi".to_string());
+        let bytes = ReceiptRenderer::render(&hindi, &settings(), &receipt, None).unwrap();
+        assert!(printed_lines(&bytes).contains(&pair("Invoice No:", &data.invoice.invoice_no, 42)));
+
+        // Text the printer's font lacks is sent as an image instead
+        let raster = [0x1D, 0x76, 0x30, 0x00];
+        assert!(!bytes.windows(4).any(|window| window == raster));
+        hindi.customer.name = "Zoë Müller".to_string();
+        let bytes = ReceiptRenderer::render(&hindi, &settings(), &receipt, None).unwrap();
+        assert!(bytes.windows(4).any(|window| window == raster));
+        assert!(!String::from_utf8_lossy(&bytes).contains("Zo?"));
+
+        receipt.line_chars = 40;
+        assert!(ReceiptRenderer::render(&data, &settings(), &receipt, None).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_settings_and_print_to_file() {
+        let db = DatabaseManager::new_with_url("sqlite::memory:").await.unwrap();
+        db.initialize_schema().await.unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("receipt.prn");
+
+        assert_eq!(ReceiptPrinterService::get_settings(&db, Some(1)).await.unwrap().id, 0);
+
+        let request = |store_id, line_chars| SaveReceiptSettingsRequest {
+            store_id,
+            printer_name: Some(format!("file://{}", path.display())),
+            line_chars: Some(line_chars),
+            include_qr: None,
+            qr_url: Some(" ".to_string()),
+            cut_paper: None,
+        };
+        let shared = ReceiptPrinterService::save_settings(&db, request(None, 42)).await.unwrap();
+        let saved = ReceiptPrinterService::save_settings(&db, request(None, 48)).await.unwrap();
+        assert_eq!((saved.id, saved.line_chars, saved.qr_url), (shared.id, 48, None));
+        assert!(ReceiptPrinterService::save_settings(&db, request(Some(1), 40)).await.is_err());
+
+        // The shared settings apply until the store saves its own
+        let settings = ReceiptPrinterService::get_settings(&db, Some(1)).await.unwrap();
+        assert_eq!(settings.id, shared.id);
+        let own = ReceiptPrinterService::save_settings(&db, request(Some(1), 42)).await.unwrap();
+        let settings = ReceiptPrinterService::get_settings(&db, Some(1)).await.unwrap();
+        assert_eq!((settings.id, settings.line_chars), (own.id, 42));
+
+        let data = test_data::sample_invoice(2);
+        let html_settings = HtmlGenerator::get_settings("thermal", &data.store).unwrap();
+        let sent = ReceiptPrinterService::print_receipt(&settings, &data, &html_settings, None).await.unwrap();
+        let written = std::fs::read(&path).unwrap();
+        assert_eq!(written.len(), sent);
+        assert_eq!(written, ReceiptRenderer::render(&data, &html_settings, &settings, None).unwrap());
+    }
+}
@@ -0,0 +1,360 @@
+use crate::database::DatabaseManager;
+use crate::models::{ApiError, ApiResult, SaveStoreBrandingRequest, SaveTermsRequest, Store, StoreBranding, TermsCondition};
+use crate::services::html_generator::{HtmlGenerator, HtmlSettings};
+use base64::Engine;
+use std::path::Path;
+
+/// Logos are embedded in every saved invoice, so keep them small
+const MAX_LOGO_BYTES: u64 = 512 * 1024;
+
+pub struct StoreBrandingService;
+
+impl StoreBrandingService {
+    pub async fn get(db: &DatabaseManager, store_id: i64) -> ApiResult<Option<StoreBranding>> {
+        sqlx::query_as::<_, StoreBranding>("SELECT * FROM store_branding WHERE store_id = ?")
+            .bind(store_id)
+            .fetch_optional(db.get_pool())
+            .await
+            .map_err(ApiError::database)
+    }
+
+    /// Save a store's branding. Blank fields are stored as NULL so they fall
+    /// back to the built-in look; the logo is set separately.
+    pub async fn save(db: &DatabaseManager, store_id: i64, request: SaveStoreBrandingRequest) -> ApiResult<StoreBranding> {
+        let blank_to_none = |value: Option<String>| value.map(|v| v.trim().to_string()).filter(|v| !v.is_empty());
+        let primary_color = blank_to_none(request.primary_color);
+        let secondary_color = blank_to_none(request.secondary_color);
+
+        for color in [&primary_color, &secondary_color].into_iter().flatten() {
+            if !Self::is_hex_color(color) {
+                return Err(ApiError {
+                    message: format!("Invalid colour {}; use a hex colour such as #2563eb", color),
+                    code: Some("VALIDATION_ERROR".to_string()),
+                });
+            }
+        }
+        let support_email = blank_to_none(request.support_email);
+        if let Some(email) = &support_email {
+            if !crate::utils::validate_email(email) {
+                return Err(ApiError {
+                    message: format!("Invalid support email: {}", email),
+                    code: Some("VALIDATION_ERROR".to_string()),
+                });
+            }
+        }
+
+        sqlx::query_as::<_, StoreBranding>(
+            r#"
+            INSERT INTO store_branding (
+                store_id, business_name, tagline, primary_color, secondary_color,
+                website, support_phone, support_email, thank_you_message
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+            ON CONFLICT(store_id) DO UPDATE SET
+                business_name = excluded.business_name,
+                tagline = excluded.tagline,
+                primary_color = excluded.primary_color,
+                secondary_color = excluded.secondary_color,
+                website = excluded.website,
+                support_phone = excluded.support_phone,
+                support_email = excluded.support_email,
+                thank_you_message = excluded.thank_you_message,
+                updated_at = CURRENT_TIMESTAMP
+            RETURNING *
+            "#,
+        )
+        .bind(store_id)
+        .bind(blank_to_none(request.business_name))
+        .bind(blank_to_none(request.tagline))
+        .bind(primary_color)
+        .bind(secondary_color)
+        .bind(blank_to_none(request.website))
+        .bind(blank_to_none(request.support_phone))
+        .bind(support_email)
+        .bind(blank_to_none(request.thank_you_message))
+        .fetch_one(db.get_pool())
+        .await
+        .map_err(ApiError::database)
+    }
+
+    /// Point a store's branding at a logo file, or clear it with None
+    pub async fn set_logo(db: &DatabaseManager, store_id: i64, logo_path: Option<&str>) -> ApiResult<StoreBranding> {
+        if let Some(path) = logo_path {
+            Self::logo_data_uri(Path::new(path))?;
+        }
+
+        sqlx::query_as::<_, StoreBranding>(
+            r#"
+            INSERT INTO store_branding (store_id, logo_path) VALUES (?, ?)
+            ON CONFLICT(store_id) DO UPDATE SET logo_path = excluded.logo_path, updated_at = CURRENT_TIMESTAMP
+            RETURNING *
+            "#,
+        )
+        .bind(store_id)
+        .bind(logo_path)
+        .fetch_one(db.get_pool())
+        .await
+        .map_err(ApiError::database)
+    }
+
+    /// Invoice settings for a store's format with its saved branding and
+    /// active terms applied over the built-in ones
+    pub async fn get_settings(db: &DatabaseManager, store: &Store, format: &str) -> ApiResult<HtmlSettings> {
+        let mut settings = HtmlGenerator::get_settings(format, store)?;
+        settings.footer.terms_conditions = Self::active_terms(db, store.id).await?;
+
+        let saved = match Self::get(db, store.id).await? {
+            Some(saved) => saved,
+            None => return Ok(settings),
+        };
+
+        let branding = &mut settings.branding;
+        branding.business_name = saved.business_name.unwrap_or_else(|| store.name.clone());
+        if saved.tagline.is_some() {
+            branding.business_tagline = saved.tagline;
+        }
+        if let Some(color) = saved.primary_color {
+            branding.primary_color = color;
+        }
+        if let Some(color) = saved.secondary_color {
+            branding.secondary_color = color;
+        }
+        if let Some(path) = saved.logo_path {
+            // A missing logo shouldn't stop the invoice from printing
+            match Self::logo_data_uri(Path::new(&path)) {
+                Ok(data_uri) => branding.logo_data_uri = Some(data_uri),
+                Err(e) => log::warn!("Skipping logo for store {}: {}", store.id, e.message),
+            }
+            branding.logo_path = Some(path);
+        }
+        branding.website = saved.website;
+        branding.support_phone = saved.support_phone;
+        branding.support_email = saved.support_email;
+
+        let contact_info: Vec<String> = [
+            branding.support_phone.as_ref().map(|phone| format!("Call us: {}", phone)),
+            branding.support_email.as_ref().map(|email| format!("Email: {}", email)),
+            branding.website.clone(),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        if !contact_info.is_empty() {
+            settings.footer.contact_info = contact_info;
+        }
+        if saved.thank_you_message.is_some() {
+            settings.footer.thank_you_message = saved.thank_you_message;
+        }
+
+        Ok(settings)
+    }
+
+    pub async fn list_terms(db: &DatabaseManager, store_id: i64) -> ApiResult<Vec<TermsCondition>> {
+        sqlx::query_as::<_, TermsCondition>("SELECT * FROM terms_conditions WHERE store_id = ? ORDER BY id")
+            .bind(store_id)
+            .fetch_all(db.get_pool())
+            .await
+            .map_err(ApiError::database)
+    }
+
+    /// One entry per printed term, across the store's active terms
+    pub async fn active_terms(db: &DatabaseManager, store_id: i64) -> ApiResult<Vec<String>> {
+        let terms = sqlx::query_as::<_, TermsCondition>(
+            "SELECT * FROM terms_conditions WHERE store_id = ? AND is_active = 1 ORDER BY id",
+        )
+        .bind(store_id)
+        .fetch_all(db.get_pool())
+        .await
+        .map_err(ApiError::database)?;
+
+        Ok(terms.iter().flat_map(|terms| Self::term_lines(&terms.content)).collect())
+    }
+
+    pub async fn create_terms(db: &DatabaseManager, store_id: i64, request: SaveTermsRequest) -> ApiResult<TermsCondition> {
+        Self::validate_terms(&request.content)?;
+
+        sqlx::query_as::<_, TermsCondition>(
+            "INSERT INTO terms_conditions (store_id, content, is_active) VALUES (?, ?, ?) RETURNING *",
+        )
+        .bind(store_id)
+        .bind(request.content.trim())
+        .bind(request.is_active.unwrap_or(true))
+        .fetch_one(db.get_pool())
+        .await
+        .map_err(ApiError::database)
+    }
+
+    pub async fn update_terms(db: &DatabaseManager, terms_id: i64, request: SaveTermsRequest) -> ApiResult<TermsCondition> {
+        Self::validate_terms(&request.content)?;
+
+        sqlx::query_as::<_, TermsCondition>(
+            r#"
+            UPDATE terms_conditions
+            SET content = ?, is_active = COALESCE(?, is_active), updated_at = CURRENT_TIMESTAMP
+            WHERE id = ?
+            RETURNING *
+            "#,
+        )
+        .bind(request.content.trim())
+        .bind(request.is_active)
+        .bind(terms_id)
+        .fetch_optional(db.get_pool())
+        .await
+        .map_err(ApiError::database)?
+        .ok_or_else(|| ApiError {
+            message: format!("Terms {} not found", terms_id),
+            code: Some("NOT_FOUND".to_string()),
+        })
+    }
+
+    pub async fn delete_terms(db: &DatabaseManager, terms_id: i64) -> ApiResult<()> {
+        sqlx::query("DELETE FROM terms_conditions WHERE id = ?")
+            .bind(terms_id)
+            .execute(db.get_pool())
+            .await
+            .map_err(ApiError::database)?;
+        Ok(())
+    }
+
+    /// Split terms text into one entry per line, dropping the "1." style
+    /// numbering people type in; invoices number terms themselves
+    pub fn term_lines(content: &str) -> Vec<String> {
+        content
+            .lines()
+            .map(|line| {
+                let line = line.trim();
+                let digits = line.chars().take_while(|c| c.is_ascii_digit()).count();
+                match line[digits..].strip_prefix(|c| c == '.' || c == ')') {
+                    Some(rest) if digits > 0 => rest.trim_start(),
+                    _ => line,
+                }
+            })
+            .filter(|line| !line.is_empty())
+            .map(str::to_string)
+            .collect()
+    }
+
+    /// Read a logo file as a data URI, checking its type and size
+    pub fn logo_data_uri(path: &Path) -> ApiResult<String> {
+        let extension = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(|ext| ext.to_ascii_lowercase());
+        let mime = match extension.as_deref() {
+            Some("png") => "image/png",
+            Some("jpg") | Some("jpeg") => "image/jpeg",
+            Some("gif") => "image/gif",
+            Some("webp") => "image/webp",
+            _ => {
+                return Err(ApiError {
+                    message: "Logo must be a PNG, JPEG, GIF or WebP image".to_string(),
+                    code: Some("VALIDATION_ERROR".to_string()),
+                })
+            }
+        };
+
+        let read_error = |e: std::io::Error| ApiError {
+            message: format!("Failed to read logo {}: {}", path.display(), e),
+            code: Some("FILE_READ_ERROR".to_string()),
+        };
+        let size = std::fs::metadata(path).map_err(read_error)?.len();
+        if size > MAX_LOGO_BYTES {
+            return Err(ApiError {
+                message: format!("Logo is {} KB; the limit is {} KB", size / 1024, MAX_LOGO_BYTES / 1024),
+                code: Some("VALIDATION_ERROR".to_string()),
+            });
+        }
+        let bytes = std::fs::read(path).map_err(read_error)?;
+
+        Ok(format!("data:{};base64,{}", mime, base64::engine::general_purpose::STANDARD.encode(bytes)))
+    }
+
+    fn is_hex_color(color: &str) -> bool {
+        color
+            .strip_prefix('#')
+            .map(|hex| matches!(hex.len(), 3 | 6) && hex.chars().all(|c| c.is_ascii_hexdigit()))
+            .unwrap_or(false)
+    }
+
+    fn validate_terms(content: &str) -> ApiResult<()> {
+        if Self::term_lines(content).is_empty() {
+            return Err(ApiError {
+                message: "Terms text is required".to_string(),
+                code: Some("VALIDATION_ERROR".to_string()),
+            });
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_term_lines_drop_typed_numbering() {
+        assert_eq!(
+            StoreBrandingService::term_lines("1. Bring your bill.\n\n  2) Express costs 50% extra\n10. Wear & Tear policy\n24 hour service"),
+            vec!["Bring your bill.", "Express costs 50% extra", "Wear & Tear policy", "24 hour service"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_settings_use_saved_branding_and_active_terms() {
+        let db = DatabaseManager::new_with_url("sqlite::memory:").await.unwrap();
+        db.initialize_schema().await.unwrap();
+        let store: Store = sqlx::query_as("SELECT * FROM stores WHERE id = 1")
+            .fetch_one(db.get_pool())
+            .await
+            .unwrap();
+
+        // The seeded terms are one numbered block
+        let seeded = StoreBrandingService::get_settings(&db, &store, "a5").await.unwrap();
+        assert_eq!(seeded.footer.terms_conditions.len(), 20);
+        assert!(seeded.footer.terms_conditions[0].starts_with("Ensure All Original Bill"));
+        assert_eq!(seeded.branding.business_name, store.name);
+
+        let request = |primary_color: &str| SaveStoreBrandingRequest {
+            business_name: Some("UClean Premium".to_string()),
+            tagline: Some("  ".to_string()),
+            primary_color: Some(primary_color.to_string()),
+            secondary_color: None,
+            website: Some("www.example.com".to_string()),
+            support_phone: Some("1800 000 000".to_string()),
+            support_email: None,
+            thank_you_message: Some("See you soon!".to_string()),
+        };
+        assert!(StoreBrandingService::save(&db, 1, request("red; } body { display: none")).await.is_err());
+        let saved = StoreBrandingService::save(&db, 1, request("#0f766e")).await.unwrap();
+        assert_eq!(saved.tagline, None);
+
+        let logo = tempfile::Builder::new().suffix(".png").tempfile().unwrap();
+        std::fs::write(logo.path(), [0x89, b'P', b'N', b'G']).unwrap();
+        StoreBrandingService::set_logo(&db, 1, logo.path().to_str()).await.unwrap();
+        assert!(StoreBrandingService::set_logo(&db, 1, Some("/tmp/logo.exe")).await.is_err());
+
+        for terms in StoreBrandingService::list_terms(&db, 1).await.unwrap() {
+            StoreBrandingService::update_terms(&db, terms.id, SaveTermsRequest { content: terms.content, is_active: Some(false) })
+                .await
+                .unwrap();
+        }
+        StoreBrandingService::create_terms(&db, 1, SaveTermsRequest { content: "Pay on <b>delivery</b>".to_string(), is_active: None })
+            .await
+            .unwrap();
+
+        let settings = StoreBrandingService::get_settings(&db, &store, "a5").await.unwrap();
+        assert_eq!(settings.branding.business_name, "UClean Premium");
+        assert_eq!(settings.branding.primary_color, "#0f766e");
+        assert_eq!(settings.branding.secondary_color, "#64748b");
+        assert_eq!(settings.branding.logo_data_uri.as_deref(), Some("data:image/png;base64,iVBORw=="));
+        assert_eq!(settings.footer.terms_conditions, vec!["Pay on <b>delivery</b>"]);
+        assert_eq!(settings.footer.contact_info, vec!["Call us: 1800 000 000", "www.example.com"]);
+        assert_eq!(settings.footer.thank_you_message.as_deref(), Some("See you soon!"));
+
+        let mut data = crate::services::InvoiceHtmlData::sample();
+        data.store = store;
+        let html = HtmlGenerator::render_invoice_html(&data, &settings).unwrap();
+        assert!(html.contains(r#"<img class="business-logo" src="data:image&#x2F;png;base64,iVBORw==""#));
+        assert!(html.contains("<li>Pay on <b>delivery</b></li>"));
+        assert!(html.contains("border-bottom: 2px solid #0f766e;"));
+    }
+}
@@ -1,4 +1,4 @@
-use crate::models::{ClothingTag, CreateClothingTagRequest, TagData, InvoiceWithDetails, TagSettings};
+use crate::models::{ClothingTag, CreateClothingTagRequest, TagData, InvoiceWithDetails, TagSettings, TagReconciliation, PrintJob, CreatePrintJobRequest};
 use crate::database::DatabaseManager;
 use anyhow::{Result, Context};
 use sqlx::{Row, Arguments, Sqlite};
@@ -7,54 +7,140 @@ use chrono::Timelike;
 pub struct TagGeneratorService;
 
 impl TagGeneratorService {
+    /// Create the tags for an invoice, or bring existing ones in line with
+    /// its current piece counts
     pub async fn generate_tags_for_invoice(
         db: &DatabaseManager,
         invoice_id: i64,
     ) -> Result<Vec<ClothingTag>> {
-        // First check if tags already exist for this invoice
-        let existing_tags = Self::get_tags_by_invoice_id(db, invoice_id).await?;
-        if !existing_tags.is_empty() {
-            return Ok(existing_tags);
-        }
+        Ok(Self::reconcile_tags_for_invoice(db, invoice_id).await?.tags)
+    }
 
-        // Get invoice with details
-        let invoice_details = Self::get_invoice_with_details(db, invoice_id).await?;
+    /// Diff the invoice items' piece counts against existing tags: add tags
+    /// for new pieces, void tags for removed ones and renumber the rest.
+    /// Print history stays on each tag so stale printed labels can be reported.
+    pub async fn reconcile_tags_for_invoice(
+        db: &DatabaseManager,
+        invoice_id: i64,
+    ) -> Result<TagReconciliation> {
+        let mut tx = db.get_pool().begin().await.context("Failed to start transaction")?;
 
-        let mut tags = Vec::new();
+        let invoice_no: String = sqlx::query_scalar("SELECT invoice_no FROM invoices WHERE id = ?")
+            .bind(invoice_id)
+            .fetch_optional(&mut *tx)
+            .await
+            .context("Failed to fetch invoice")?
+            .ok_or_else(|| anyhow::anyhow!("Invoice {} not found", invoice_id))?;
+
+        let items: Vec<(i64, i64)> = sqlx::query_as(
+            "SELECT id, MAX(COALESCE(piece_count, 0), 0) FROM invoice_items WHERE invoice_id = ? ORDER BY id"
+        )
+        .bind(invoice_id)
+        .fetch_all(&mut *tx)
+        .await
+        .context("Failed to fetch invoice items")?;
+
+        let existing = sqlx::query_as::<_, ClothingTag>("SELECT * FROM clothing_tags WHERE invoice_id = ?")
+            .bind(invoice_id)
+            .fetch_all(&mut *tx)
+            .await
+            .context("Failed to fetch clothing tags")?;
+
+        let now = chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string();
+        let mut reconciliation = TagReconciliation {
+            invoice_id,
+            tags: Vec::new(),
+            added: Vec::new(),
+            voided: Vec::new(),
+            reprint_required: Vec::new(),
+        };
+
+        // Void tags whose pieces are no longer on the invoice
+        for tag in existing.iter().filter(|tag| !tag.is_void) {
+            let still_present = items
+                .iter()
+                .any(|(item_id, pieces)| *item_id == tag.invoice_item_id && tag.tag_number <= *pieces);
+            if !still_present {
+                let voided = sqlx::query_as::<_, ClothingTag>(
+                    "UPDATE clothing_tags SET is_void = 1, voided_at = ? WHERE id = ? RETURNING *"
+                )
+                .bind(&now)
+                .bind(tag.id)
+                .fetch_one(&mut *tx)
+                .await
+                .context("Failed to void clothing tag")?;
+                reconciliation.voided.push(voided);
+            }
+        }
+
+        let total_invoice_pieces: i64 = items.iter().map(|(_, pieces)| pieces).sum();
         let mut overall_piece_number = 1i64;
 
-        for item in &invoice_details.items {
-            let total_quantity = item.item.piece_count as i64;
-
-            for tag_number in 1..=total_quantity {
-                let tag_code = Self::format_tag_code(
-                    &invoice_details.invoice.invoice_no,
-                    item.item.id,
-                    tag_number,
-                )?;
-
-                let tag_request = CreateClothingTagRequest {
-                    invoice_id,
-                    invoice_item_id: item.item.id,
-                    tag_number,
-                    total_quantity,
-                    overall_piece_number,
-                    total_invoice_pieces: invoice_details.invoice.total_pieces as i64,
-                    tag_code: tag_code.clone(),
+        for (item_id, total_quantity) in &items {
+            for tag_number in 1..=*total_quantity {
+                let current = existing
+                    .iter()
+                    .find(|tag| tag.invoice_item_id == *item_id && tag.tag_number == tag_number);
+
+                let tag = match current {
+                    None => {
+                        let tag_request = CreateClothingTagRequest {
+                            invoice_id,
+                            invoice_item_id: *item_id,
+                            tag_number,
+                            total_quantity: *total_quantity,
+                            overall_piece_number,
+                            total_invoice_pieces,
+                            tag_code: Self::format_tag_code(&invoice_no, *item_id, tag_number)?,
+                        };
+
+                        let tag = Self::create_clothing_tag(&mut tx, &tag_request).await?;
+                        reconciliation.added.push(tag.clone());
+                        tag
+                    }
+                    Some(tag) if !tag.is_void
+                        && tag.total_quantity == *total_quantity
+                        && tag.overall_piece_number == overall_piece_number
+                        && tag.total_invoice_pieces == total_invoice_pieces => tag.clone(),
+                    Some(tag) => {
+                        let updated = sqlx::query_as::<_, ClothingTag>(
+                            r#"
+                            UPDATE clothing_tags
+                            SET total_quantity = ?, overall_piece_number = ?, total_invoice_pieces = ?,
+                                is_void = 0, voided_at = NULL
+                            WHERE id = ?
+                            RETURNING *
+                            "#
+                        )
+                        .bind(total_quantity)
+                        .bind(overall_piece_number)
+                        .bind(total_invoice_pieces)
+                        .bind(tag.id)
+                        .fetch_one(&mut *tx)
+                        .await
+                        .context("Failed to renumber clothing tag")?;
+
+                        if tag.is_void {
+                            reconciliation.added.push(updated.clone());
+                        } else if tag.printed_at.is_some() {
+                            reconciliation.reprint_required.push(updated.clone());
+                        }
+                        updated
+                    }
                 };
 
-                let tag = Self::create_clothing_tag(db, &tag_request).await?;
-                tags.push(tag);
-
+                reconciliation.tags.push(tag);
                 overall_piece_number += 1;
             }
         }
 
-        Ok(tags)
+        tx.commit().await.context("Failed to commit tag reconciliation")?;
+
+        Ok(reconciliation)
     }
 
     pub async fn create_clothing_tag(
-        db: &DatabaseManager,
+        conn: &mut sqlx::SqliteConnection,
         request: &CreateClothingTagRequest,
     ) -> Result<ClothingTag> {
         let query = r#"
@@ -64,7 +150,8 @@ impl TagGeneratorService {
             ) VALUES (?, ?, ?, ?, ?, ?, ?)
             RETURNING id, invoice_id, invoice_item_id, tag_number, total_quantity,
                      overall_piece_number, total_invoice_pieces, tag_code,
-                     printed_at, printed_by, reprint_count, created_at
+                     printed_at, printed_by, reprint_count, status, status_updated_at, is_void, voided_at,
+                     garment_type, colour, brand, damage_notes, photo_paths, created_at
         "#;
 
         let row = sqlx::query(query)
@@ -75,7 +162,7 @@ impl TagGeneratorService {
             .bind(request.overall_piece_number)
             .bind(request.total_invoice_pieces)
             .bind(&request.tag_code)
-            .fetch_one(db.get_pool())
+            .fetch_one(&mut *conn)
             .await
             .context("Failed to create clothing tag")?;
 
@@ -91,6 +178,15 @@ impl TagGeneratorService {
             printed_at: row.get("printed_at"),
             printed_by: row.get("printed_by"),
             reprint_count: row.get("reprint_count"),
+            status: row.get("status"),
+            status_updated_at: row.get("status_updated_at"),
+            is_void: row.get("is_void"),
+            voided_at: row.get("voided_at"),
+            garment_type: row.get("garment_type"),
+            colour: row.get("colour"),
+            brand: row.get("brand"),
+            damage_notes: row.get("damage_notes"),
+            photo_paths: row.get("photo_paths"),
             created_at: row.get("created_at"),
         })
     }
@@ -102,9 +198,10 @@ impl TagGeneratorService {
         let query = r#"
             SELECT id, invoice_id, invoice_item_id, tag_number, total_quantity,
                    overall_piece_number, total_invoice_pieces, tag_code,
-                   printed_at, printed_by, reprint_count, created_at
+                   printed_at, printed_by, reprint_count, status, status_updated_at, is_void, voided_at,
+                   garment_type, colour, brand, damage_notes, photo_paths, created_at
             FROM clothing_tags
-            WHERE invoice_id = ?
+            WHERE invoice_id = ? AND is_void = 0
             ORDER BY invoice_item_id, tag_number
         "#;
 
@@ -131,6 +228,15 @@ impl TagGeneratorService {
                 printed_at: row.get("printed_at"),
                 printed_by: row.get("printed_by"),
                 reprint_count: row.get("reprint_count"),
+                status: row.get("status"),
+                status_updated_at: row.get("status_updated_at"),
+                is_void: row.get("is_void"),
+                voided_at: row.get("voided_at"),
+                garment_type: row.get("garment_type"),
+                colour: row.get("colour"),
+                brand: row.get("brand"),
+                damage_notes: row.get("damage_notes"),
+                photo_paths: row.get("photo_paths"),
                 created_at: row.get("created_at"),
             });
         }
@@ -138,10 +244,28 @@ impl TagGeneratorService {
         Ok(tags)
     }
 
+    /// Tags of an invoice selected for printing, optionally narrowed to some
+    /// items and/or specific tags
+    pub async fn get_tags_for_printing(
+        db: &DatabaseManager,
+        invoice_id: i64,
+        item_ids: Option<&[i64]>,
+        tag_ids: Option<&[i64]>,
+    ) -> Result<Vec<ClothingTag>> {
+        let tags = Self::get_tags_by_invoice_id(db, invoice_id).await?;
+
+        Ok(tags
+            .into_iter()
+            .filter(|tag| item_ids.map_or(true, |ids| ids.contains(&tag.invoice_item_id)))
+            .filter(|tag| tag_ids.map_or(true, |ids| ids.contains(&tag.id)))
+            .collect())
+    }
+
     pub async fn get_tag_data_for_printing(
         db: &DatabaseManager,
         invoice_id: i64,
         item_ids: Option<Vec<i64>>,
+        tag_ids: Option<Vec<i64>>,
     ) -> Result<Vec<TagData>> {
         let mut tag_data = Vec::new();
 
@@ -151,17 +275,24 @@ impl TagGeneratorService {
         // Get tag settings for formatting
         let settings = Self::get_tag_settings(db, Some(invoice_details.invoice.store_id)).await?;
 
-        // Get tags for this invoice
-        let tags = Self::get_tags_by_invoice_id(db, invoice_id).await?;
+        // Get the selected tags for this invoice
+        let tags = Self::get_tags_for_printing(db, invoice_id, item_ids.as_deref(), tag_ids.as_deref()).await?;
 
-        for tag in tags {
-            // If item_ids specified, filter by those items
-            if let Some(ref filter_items) = item_ids {
-                if !filter_items.contains(&tag.invoice_item_id) {
-                    continue;
-                }
-            }
+        let customer_phone = invoice_details.customer.phone.clone()
+            .filter(|phone| settings.show_customer_phone == 1 && !phone.trim().is_empty());
+        let store_code = if settings.show_store_code == 1 {
+            sqlx::query_scalar::<_, Option<String>>("SELECT code FROM stores WHERE id = ?")
+                .bind(invoice_details.invoice.store_id)
+                .fetch_optional(db.get_pool())
+                .await
+                .context("Failed to fetch store code")?
+                .flatten()
+                .filter(|code| !code.trim().is_empty())
+        } else {
+            None
+        };
 
+        for tag in tags {
             // Find the corresponding invoice item details
             if let Some(item_details) = invoice_details.items.iter()
                 .find(|item| item.item.id == tag.invoice_item_id) {
@@ -201,6 +332,10 @@ impl TagGeneratorService {
                     delivery_date: formatted_delivery_date,
                     tag_code: tag.tag_code.clone(),
                     include_barcode: settings.include_barcode == 1,
+                    garment: tag.garment_description(),
+                    damage_notes: tag.damage_notes.clone(),
+                    customer_phone: customer_phone.clone(),
+                    store_code: store_code.clone(),
                 });
             }
         }
@@ -208,44 +343,75 @@ impl TagGeneratorService {
         Ok(tag_data)
     }
 
-    pub async fn mark_tags_as_printed(
+    /// Log a print job for the given tags. Successful jobs stamp the tags as
+    /// printed; tags that had already been printed count as reprints.
+    pub async fn record_print_job(
         db: &DatabaseManager,
-        invoice_id: i64,
-        item_ids: Option<Vec<i64>>,
-        printed_by: Option<String>,
-    ) -> Result<i64> {
-        let now = chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string();
-
-        let result = if let Some(items) = item_ids {
-            let placeholders = items.iter().map(|_| "?").collect::<Vec<_>>().join(",");
-            let query = format!(
-                "UPDATE clothing_tags SET printed_at = ?, printed_by = ?, reprint_count = reprint_count + 1
-                 WHERE invoice_id = ? AND invoice_item_id IN ({})",
-                placeholders
-            );
-
-            let mut query_builder = sqlx::query(&query)
-                .bind(&now)
-                .bind(&printed_by)
-                .bind(invoice_id);
+        request: &CreatePrintJobRequest,
+        tags: &[ClothingTag],
+    ) -> Result<PrintJob> {
+        let is_reprint = tags.iter().any(|tag| tag.printed_at.is_some());
+        let status = if request.error_message.is_some() { "failed" } else { "printed" };
+
+        let mut tx = db.get_pool().begin().await.context("Failed to start print job")?;
+
+        let job = sqlx::query_as::<_, PrintJob>(
+            r#"
+            INSERT INTO print_jobs (
+                invoice_id, printed_by, printer_name, printer_language,
+                is_reprint, reprint_reason, tag_count, status, error_message
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+            RETURNING *
+            "#,
+        )
+        .bind(request.invoice_id)
+        .bind(&request.printed_by)
+        .bind(&request.printer_name)
+        .bind(&request.printer_language)
+        .bind(is_reprint)
+        .bind(&request.reprint_reason)
+        .bind(tags.len() as i64)
+        .bind(status)
+        .bind(&request.error_message)
+        .fetch_one(&mut *tx)
+        .await
+        .context("Failed to record print job")?;
 
-            for item_id in items {
-                query_builder = query_builder.bind(item_id);
+        for tag in tags {
+            sqlx::query("INSERT INTO print_job_tags (print_job_id, tag_id, is_reprint) VALUES (?, ?, ?)")
+                .bind(job.id)
+                .bind(tag.id)
+                .bind(tag.printed_at.is_some())
+                .execute(&mut *tx)
+                .await
+                .context("Failed to record print job tags")?;
+
+            if request.error_message.is_none() {
+                sqlx::query(
+                    "UPDATE clothing_tags SET printed_at = ?, printed_by = ?,
+                         reprint_count = reprint_count + CASE WHEN printed_at IS NULL THEN 0 ELSE 1 END
+                     WHERE id = ?",
+                )
+                .bind(&job.created_at)
+                .bind(&request.printed_by)
+                .bind(tag.id)
+                .execute(&mut *tx)
+                .await
+                .context("Failed to mark tags as printed")?;
             }
+        }
 
-            query_builder.execute(db.get_pool()).await
-        } else {
-            sqlx::query("UPDATE clothing_tags SET printed_at = ?, printed_by = ?, reprint_count = reprint_count + 1 WHERE invoice_id = ?")
-                .bind(&now)
-                .bind(&printed_by)
-                .bind(invoice_id)
-                .execute(db.get_pool())
-                .await
-        };
+        tx.commit().await.context("Failed to commit print job")?;
 
-        let result = result.context("Failed to mark tags as printed")?;
+        Ok(job)
+    }
 
-        Ok(result.rows_affected() as i64)
+    pub async fn get_print_jobs(db: &DatabaseManager, invoice_id: i64) -> Result<Vec<PrintJob>> {
+        sqlx::query_as::<_, PrintJob>("SELECT * FROM print_jobs WHERE invoice_id = ? ORDER BY created_at DESC, id DESC")
+            .bind(invoice_id)
+            .fetch_all(db.get_pool())
+            .await
+            .context("Failed to fetch print jobs")
     }
 
     fn format_tag_code(invoice_no: &str, item_id: i64, tag_number: i64) -> Result<String> {
@@ -290,7 +456,7 @@ impl TagGeneratorService {
         // This should call the existing invoice handler method
         // For now, we'll implement a basic version
         let query = r#"
-            SELECT i.*, c.name as customer_name, s.name as store_name
+            SELECT i.*, c.name as customer_name, c.phone as customer_phone, s.name as store_name
             FROM invoices i
             JOIN customers c ON i.customer_id = c.id
             JOIN stores s ON i.store_id = s.id
@@ -346,6 +512,7 @@ impl TagGeneratorService {
                 payment_method: row.get("payment_method"),
                 payment_amount: row.get("payment_amount"),
                 total_pieces: row.get("total_pieces"),
+                turnaround_tier: row.get("turnaround_tier"),
                 status: row.get("status"),
                 notes: row.get("notes"),
                 created_at: row.get("created_at"),
@@ -354,10 +521,12 @@ impl TagGeneratorService {
             customer: crate::models::Customer {
                 id: row.get("customer_id"),
                 name: row.get("customer_name"),
-                phone: None,
+                phone: row.get("customer_phone"),
                 email: None,
                 address: None,
                 notes: None,
+                customer_group: None,
+                locale: None,
                 is_active: Some(1),
                 created_at: "".to_string(),
                 updated_at: "".to_string(),
@@ -374,6 +543,8 @@ impl TagGeneratorService {
                 gstin: None,
                 pan_number: None,
                 owner_name: None,
+                locale: None,
+                secondary_locale: None,
                 is_active: Some(1),
                 created_at: "".to_string(),
                 updated_at: "".to_string(),
@@ -395,6 +566,8 @@ impl TagGeneratorService {
                             sa.description as service_addon_description,
                             sa.price as service_addon_price,
                             sa.unit as service_addon_unit,
+                            sa.pricing_mode as service_addon_pricing_mode,
+                            sa.gst_rate as service_addon_gst_rate,
                             sa.is_active as service_addon_is_active,
                             sa.created_at as service_addon_created_at,
                             sa.updated_at as service_addon_updated_at
@@ -421,6 +594,15 @@ impl TagGeneratorService {
                                 qty: addon_row.get("qty"),
                                 rate: addon_row.get("rate"),
                                 amount: addon_row.get("amount"),
+                                pricing_mode: addon_row.get("pricing_mode"),
+                                gst_rate: addon_row.get("gst_rate"),
+                                discount_amount: addon_row.get("discount_amount"),
+                                taxable_value: addon_row.get("taxable_value"),
+                                sgst: addon_row.get("sgst"),
+                                cgst: addon_row.get("cgst"),
+                                price_list_id: addon_row.get("price_list_id"),
+                                rate_source: addon_row.get("rate_source"),
+                                rate_card_id: addon_row.get("rate_card_id"),
                                 created_at: addon_row.get("created_at"),
                             },
                             addon: crate::models::ServiceAddon {
@@ -430,6 +612,8 @@ impl TagGeneratorService {
                                 description: addon_row.get("service_addon_description"),
                                 price: addon_row.get("service_addon_price"),
                                 unit: addon_row.get("service_addon_unit"),
+                                pricing_mode: addon_row.get("service_addon_pricing_mode"),
+                                gst_rate: addon_row.get("service_addon_gst_rate"),
                                 is_active: addon_row.get("service_addon_is_active"),
                                 created_at: addon_row.get("service_addon_created_at"),
                                 updated_at: addon_row.get("service_addon_updated_at"),
@@ -451,8 +635,13 @@ impl TagGeneratorService {
                             rate: item_row.get("rate"),
                             amount: item_row.get("amount"),
                             gst_rate: item_row.get("gst_rate"),
+                            discount_amount: item_row.get("discount_amount"),
+                            taxable_value: item_row.get("taxable_value"),
                             sgst: item_row.get("sgst"),
                             cgst: item_row.get("cgst"),
+                            price_list_id: item_row.get("price_list_id"),
+                            rate_source: item_row.get("rate_source"),
+                            rate_card_id: item_row.get("rate_card_id"),
                             created_at: item_row.get("created_at"),
                         },
                         service: crate::models::Service {
@@ -489,49 +678,21 @@ impl TagGeneratorService {
         })
     }
 
-    async fn get_tag_settings(
+    /// Tag settings for a store, falling back to the global settings and
+    /// then to the built-in defaults
+    pub async fn get_tag_settings(
         db: &DatabaseManager,
         store_id: Option<i64>,
     ) -> Result<TagSettings> {
-        let query = if let Some(store_id) = store_id {
-            "SELECT * FROM tag_settings WHERE store_id = ? OR store_id IS NULL ORDER BY store_id DESC LIMIT 1"
-        } else {
-            "SELECT * FROM tag_settings WHERE store_id IS NULL LIMIT 1"
-        };
-
-        let result = if let Some(store_id) = store_id {
-            sqlx::query(query).bind(store_id).fetch_optional(db.get_pool()).await
-        } else {
-            sqlx::query(query).fetch_optional(db.get_pool()).await
-        };
-
-        match result? {
-            Some(row) => Ok(TagSettings {
-                id: row.get("id"),
-                store_id: row.get("store_id"),
-                roll_width: row.get("roll_width"),
-                auto_print: row.get("auto_print"),
-                printer_name: row.get("printer_name"),
-                template_style: row.get("template_style"),
-                include_barcode: row.get("include_barcode"),
-                created_at: row.get("created_at"),
-                updated_at: row.get("updated_at"),
-            }),
-            None => {
-                // Return default settings
-                Ok(TagSettings {
-                    id: 0,
-                    store_id,
-                    roll_width: "40mm".to_string(),
-                    auto_print: 0,
-                    printer_name: None,
-                    template_style: "standard".to_string(),
-                    include_barcode: 1,
-                    created_at: "".to_string(),
-                    updated_at: "".to_string(),
-                })
-            }
-        }
+        let settings = sqlx::query_as::<_, TagSettings>(
+            "SELECT * FROM tag_settings WHERE store_id = ? OR store_id IS NULL ORDER BY store_id IS NULL, id LIMIT 1",
+        )
+        .bind(store_id)
+        .fetch_optional(db.get_pool())
+        .await
+        .context("Failed to fetch tag settings")?;
+
+        Ok(settings.unwrap_or_else(|| TagSettings::defaults(store_id)))
     }
 
     pub async fn get_invoice_tag_summary(
@@ -547,7 +708,7 @@ impl TagGeneratorService {
                 COUNT(CASE WHEN ct.printed_at IS NULL THEN 1 END) as pending_tags,
                 MAX(ct.printed_at) as last_printed_at
             FROM invoices i
-            LEFT JOIN clothing_tags ct ON i.id = ct.invoice_id
+            LEFT JOIN clothing_tags ct ON i.id = ct.invoice_id AND ct.is_void = 0
             WHERE i.id = ?
             GROUP BY i.id, i.invoice_no
         "#;
@@ -567,4 +728,165 @@ impl TagGeneratorService {
             last_printed_at: row.get("last_printed_at"),
         })
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::test_fixtures::{add_invoice_item, invoice_db};
+
+    async fn set_pieces(db: &DatabaseManager, item_id: i64, pieces: i64) {
+        sqlx::query("UPDATE invoice_items SET piece_count = ? WHERE id = ?")
+            .bind(pieces)
+            .bind(item_id)
+            .execute(db.get_pool())
+            .await
+            .unwrap();
+    }
+
+    fn numbering(tags: &[ClothingTag]) -> Vec<(i64, i64, i64, i64)> {
+        tags.iter()
+            .map(|tag| (tag.tag_number, tag.total_quantity, tag.overall_piece_number, tag.total_invoice_pieces))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_reconcile_follows_piece_counts() {
+        let db = invoice_db("INV900", "Tag Test").await;
+        let shirts = add_invoice_item(&db, 2).await;
+        let sarees = add_invoice_item(&db, 1).await;
+
+        let first = TagGeneratorService::reconcile_tags_for_invoice(&db, 900).await.unwrap();
+        assert_eq!(first.added.len(), 3);
+        assert_eq!(numbering(&first.tags), vec![(1, 2, 1, 3), (2, 2, 2, 3), (1, 1, 3, 3)]);
+        assert_eq!(first.tags[2].tag_code, format!("INV900-{}-1", sarees));
+
+        // Nothing changed, nothing to do
+        let again = TagGeneratorService::reconcile_tags_for_invoice(&db, 900).await.unwrap();
+        assert!(again.added.is_empty() && again.voided.is_empty() && again.reprint_required.is_empty());
+        assert_eq!(again.tags.iter().map(|tag| tag.id).collect::<Vec<_>>(), first.tags.iter().map(|tag| tag.id).collect::<Vec<_>>());
+
+        // Quantity up: the new piece is added and printed tags whose numbers moved need reprinting
+        sqlx::query("UPDATE clothing_tags SET printed_at = '2025-01-01 10:00:00'").execute(db.get_pool()).await.unwrap();
+        set_pieces(&db, shirts, 3).await;
+        let up = TagGeneratorService::reconcile_tags_for_invoice(&db, 900).await.unwrap();
+        assert_eq!(up.added.iter().map(|tag| tag.tag_code.clone()).collect::<Vec<_>>(), vec![format!("INV900-{}-3", shirts)]);
+        assert_eq!(numbering(&up.tags), vec![(1, 3, 1, 4), (2, 3, 2, 4), (3, 3, 3, 4), (1, 1, 4, 4)]);
+        assert_eq!(up.reprint_required.len(), 3);
+        assert!(up.voided.is_empty());
+
+        // Quantity down: the last pieces are voided, not deleted
+        set_pieces(&db, shirts, 1).await;
+        let down = TagGeneratorService::reconcile_tags_for_invoice(&db, 900).await.unwrap();
+        assert_eq!(down.voided.iter().map(|tag| tag.tag_number).collect::<Vec<_>>(), vec![2, 3]);
+        assert!(down.voided.iter().all(|tag| tag.is_void && tag.voided_at.is_some()));
+        assert_eq!(numbering(&down.tags), vec![(1, 1, 1, 2), (1, 1, 2, 2)]);
+        assert_eq!(TagGeneratorService::get_tags_by_invoice_id(&db, 900).await.unwrap().len(), 2);
+
+        // Restore: the voided tag comes back with its id and print history
+        set_pieces(&db, shirts, 2).await;
+        let restored = TagGeneratorService::reconcile_tags_for_invoice(&db, 900).await.unwrap();
+        assert_eq!(restored.added.len(), 1);
+        assert_eq!(restored.added[0].id, down.voided[0].id);
+        assert!(!restored.added[0].is_void && restored.added[0].voided_at.is_none());
+        assert!(restored.added[0].printed_at.is_some());
+        assert_eq!(numbering(&restored.tags), vec![(1, 2, 1, 3), (2, 2, 2, 3), (1, 1, 3, 3)]);
+
+        // Removing an item voids all of its tags and renumbers the rest
+        set_pieces(&db, shirts, 0).await;
+        let removed = TagGeneratorService::reconcile_tags_for_invoice(&db, 900).await.unwrap();
+        assert_eq!(removed.voided.len(), 2);
+        assert_eq!(numbering(&removed.tags), vec![(1, 1, 1, 1)]);
+        assert_eq!(removed.reprint_required.iter().map(|tag| tag.invoice_item_id).collect::<Vec<_>>(), vec![sarees]);
+    }
+
+    #[tokio::test]
+    async fn test_reconcile_unknown_invoice() {
+        let db = invoice_db("INV900", "Tag Test").await;
+        assert!(TagGeneratorService::reconcile_tags_for_invoice(&db, 901).await.is_err());
+    }
+
+    fn print_request(printed_by: &str, reprint_reason: Option<&str>, error_message: Option<&str>) -> CreatePrintJobRequest {
+        CreatePrintJobRequest {
+            invoice_id: 900,
+            printed_by: Some(printed_by.to_string()),
+            printer_name: Some("Zebra_ZD220".to_string()),
+            printer_language: "zpl".to_string(),
+            reprint_reason: reprint_reason.map(str::to_string),
+            error_message: error_message.map(str::to_string),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_get_tags_for_printing() {
+        let db = invoice_db("INV900", "Tag Test").await;
+        let shirts = add_invoice_item(&db, 2).await;
+        let sarees = add_invoice_item(&db, 2).await;
+        let tags = TagGeneratorService::generate_tags_for_invoice(&db, 900).await.unwrap();
+        let ids = |tags: Vec<ClothingTag>| tags.into_iter().map(|tag| tag.id).collect::<Vec<_>>();
+
+        let all = TagGeneratorService::get_tags_for_printing(&db, 900, None, None).await.unwrap();
+        assert_eq!(ids(all), ids(tags.clone()));
+
+        let by_item = TagGeneratorService::get_tags_for_printing(&db, 900, Some(&[sarees]), None).await.unwrap();
+        assert_eq!(ids(by_item), vec![tags[2].id, tags[3].id]);
+
+        let by_tag = TagGeneratorService::get_tags_for_printing(&db, 900, None, Some(&[tags[1].id, tags[3].id])).await.unwrap();
+        assert_eq!(ids(by_tag), vec![tags[1].id, tags[3].id]);
+
+        // Both filters have to match
+        let both = TagGeneratorService::get_tags_for_printing(&db, 900, Some(&[shirts]), Some(&[tags[1].id, tags[3].id])).await.unwrap();
+        assert_eq!(ids(both), vec![tags[1].id]);
+
+        // Voided tags are never printed
+        set_pieces(&db, shirts, 1).await;
+        TagGeneratorService::reconcile_tags_for_invoice(&db, 900).await.unwrap();
+        let after_void = TagGeneratorService::get_tags_for_printing(&db, 900, Some(&[shirts]), None).await.unwrap();
+        assert_eq!(ids(after_void), vec![tags[0].id]);
+    }
+
+    #[tokio::test]
+    async fn test_record_print_job() {
+        let db = invoice_db("INV900", "Tag Test").await;
+        add_invoice_item(&db, 2).await;
+        let tags = TagGeneratorService::generate_tags_for_invoice(&db, 900).await.unwrap();
+
+        // A failed job is logged but leaves the tags unprinted
+        let failed = TagGeneratorService::record_print_job(&db, &print_request("asha", None, Some("printer offline")), &tags)
+            .await
+            .unwrap();
+        assert_eq!((failed.status.as_str(), failed.is_reprint, failed.tag_count), ("failed", false, 2));
+        let tags = TagGeneratorService::get_tags_for_printing(&db, 900, None, None).await.unwrap();
+        assert!(tags.iter().all(|tag| tag.printed_at.is_none()));
+
+        let first = TagGeneratorService::record_print_job(&db, &print_request("asha", None, None), &tags).await.unwrap();
+        assert_eq!((first.status.as_str(), first.is_reprint, first.tag_count), ("printed", false, 2));
+        assert_eq!(first.printer_name.as_deref(), Some("Zebra_ZD220"));
+        let tags = TagGeneratorService::get_tags_for_printing(&db, 900, None, None).await.unwrap();
+        assert!(tags.iter().all(|tag| {
+            tag.printed_at.as_deref() == Some(first.created_at.as_str())
+                && tag.printed_by.as_deref() == Some("asha")
+                && tag.reprint_count == 0
+        }));
+
+        // Printing a tag again is a reprint, counted on the tag and its job
+        let reprint = TagGeneratorService::record_print_job(&db, &print_request("ravi", Some("torn"), None), &tags[..1])
+            .await
+            .unwrap();
+        assert_eq!((reprint.is_reprint, reprint.tag_count), (true, 1));
+        assert_eq!(reprint.reprint_reason.as_deref(), Some("torn"));
+        let tags = TagGeneratorService::get_tags_for_printing(&db, 900, None, None).await.unwrap();
+        assert_eq!((tags[0].reprint_count, tags[0].printed_by.as_deref()), (1, Some("ravi")));
+        assert_eq!((tags[1].reprint_count, tags[1].printed_by.as_deref()), (0, Some("asha")));
+
+        let job_tags: Vec<(i64, i64, bool)> = sqlx::query_as("SELECT print_job_id, tag_id, is_reprint FROM print_job_tags ORDER BY print_job_id, tag_id")
+            .fetch_all(db.get_pool())
+            .await
+            .unwrap();
+        assert_eq!(job_tags.len(), 5);
+        assert_eq!(job_tags[4], (reprint.id, tags[0].id, true));
+
+        let jobs = TagGeneratorService::get_print_jobs(&db, 900).await.unwrap();
+        assert_eq!(jobs.iter().map(|job| job.id).collect::<Vec<_>>(), vec![reprint.id, first.id, failed.id]);
+    }
+}
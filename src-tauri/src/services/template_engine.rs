@@ -1,8 +1,47 @@
-use crate::models::{ApiResult, ApiError};
-use crate::services::html_generator::InvoiceHtmlData;
+use crate::models::{ApiResult, ApiError, TagData, TagSettings};
+use crate::services::html_generator::{HtmlGenerator, HtmlSettings, InvoiceHtmlData, InvoiceItemWithDetails};
+use crate::services::barcode::BarcodeService;
+use crate::services::localization::{DocumentLanguage, Locale};
+use crate::utils::{escape_html, sanitize_terms_html};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
 use tera::{Tera, Context};
 use std::collections::HashMap;
-use serde_json::json;
+
+/// Built-in clothing tag templates, one per roll width
+const TAG_TEMPLATES: [(&str, &str); 3] = [
+    ("32mm", include_str!("../templates/tags/tag_32mm.html")),
+    ("40mm", include_str!("../templates/tags/tag_40mm.html")),
+    ("50mm", include_str!("../templates/tags/tag_50mm.html")),
+];
+
+/// Built-in invoice templates. Each paper size extends the shared base.
+const INVOICE_TEMPLATES: [(&str, &str); 4] = [
+    ("invoice/base.html", include_str!("../templates/invoice/base.html")),
+    ("invoice/a4.html", include_str!("../templates/invoice/a4.html")),
+    ("invoice/a5.html", include_str!("../templates/invoice/a5.html")),
+    ("invoice/thermal.html", include_str!("../templates/invoice/thermal.html")),
+];
+
+/// What a tag template sees for each tag: the tag data plus display helpers
+#[derive(serde::Serialize)]
+struct TagTemplateItem<'a> {
+    #[serde(flatten)]
+    tag: &'a TagData,
+    service_name_short: String,
+    delivery_date_short: Option<String>,
+    /// "2 of 3", in the tag's language
+    count_label: String,
+    barcode: Option<String>,
+}
+
+/// What an invoice template sees for each line: the item plus display helpers
+#[derive(serde::Serialize)]
+struct InvoiceTemplateItem<'a> {
+    #[serde(flatten)]
+    details: &'a InvoiceItemWithDetails,
+    garment_notes: Vec<String>,
+}
 
 pub struct TemplateEngine {
     tera: Tera,
@@ -10,590 +49,249 @@ pub struct TemplateEngine {
 
 impl TemplateEngine {
     pub fn new() -> ApiResult<Self> {
-        // Create Tera instance with embedded template
-        let mut tera = Tera::new("").map_err(|e| ApiError {
-            message: format!("Failed to create Tera instance: {}", e),
-            code: Some("TEMPLATE_INIT_ERROR".to_string()),
-        })?;
-
-        // Add embedded template with inline CSS
-        let template_content = Self::get_embedded_template();
-        tera.add_raw_template("invoice/invoice_template.html", &template_content)
+        // Create Tera instance with embedded templates; Tera::new expects a
+        // glob of template files, which a packaged app doesn't have
+        let mut tera = Tera::default();
+
+        tera.add_raw_templates(INVOICE_TEMPLATES)
             .map_err(|e| ApiError {
-                message: format!("Failed to add template: {}", e),
+                message: format!("Failed to add invoice templates: {}", e),
                 code: Some("TEMPLATE_INIT_ERROR".to_string()),
             })?;
 
+        for (roll_width, template) in TAG_TEMPLATES {
+            tera.add_raw_template(&format!("tags/tag_{}.html", roll_width), template)
+                .map_err(|e| ApiError {
+                    message: format!("Failed to add {} tag template: {}", roll_width, e),
+                    code: Some("TEMPLATE_INIT_ERROR".to_string()),
+                })?;
+        }
+
         // Add custom filters
         tera.register_filter("round", Self::round_filter);
         tera.register_filter("default", Self::default_filter);
+        tera.register_filter("fixed", Self::fixed_filter);
+        tera.register_filter("money", Self::money_filter);
+        tera.register_filter("svg", SvgFilter);
+        tera.register_filter("terms", TermsFilter);
 
         Ok(Self { tera })
     }
 
-    fn get_embedded_template() -> String {
-        r#"<!DOCTYPE html>
-<html lang="en">
-<head>
-    <meta charset="UTF-8">
-    <meta name="viewport" content="width=device-width, initial-scale=1.0">
-    <title>Invoice No.- {{ invoice.invoice_no }}</title>
-    <style>
-/* Professional Invoice Styles - A5 Format */
-* {
-    margin: 0;
-    padding: 0;
-    box-sizing: border-box;
-}
-
-body {
-    font-family: 'Arial', sans-serif;
-    font-size: 9px;
-    line-height: 1.2;
-    color: #000;
-    background: white;
-}
-
-.invoice-container {
-    width: 148mm;
-    min-height: 210mm;
-    margin: 0 auto;
-    padding: 6mm;
-    background: white;
-    position: relative;
-    border: 1px solid #ddd;
-}
-
-/* Header Section */
-.invoice-header {
-    display: flex;
-    justify-content: space-between;
-    align-items: flex-start;
-    margin-bottom: 8px;
-    border-bottom: 1px solid #333;
-    padding-bottom: 6px;
-}
-
-.logo-section {
-    flex: 1;
-}
-
-.logo {
-    display: flex;
-    flex-direction: column;
-    align-items: flex-start;
-}
-
-.logo-text {
-    display: flex;
-    align-items: center;
-    margin-bottom: 1px;
-}
-
-.u-letter {
-    background: #000;
-    color: white;
-    font-weight: bold;
-    font-size: 14px;
-    padding: 3px 6px;
-    margin-right: 3px;
-    border-radius: 2px;
-}
-
-.clean-text {
-    font-weight: bold;
-    font-size: 14px;
-    color: #000;
-}
-
-.tagline {
-    font-size: 7px;
-    color: #666;
-    font-weight: normal;
-    margin-bottom: 1px;
-    letter-spacing: 0.3px;
-}
-
-.website {
-    font-size: 7px;
-    color: #666;
-}
-
-.invoice-number {
-    font-size: 10px;
-    font-weight: bold;
-    text-align: right;
-    color: #000;
-}
-
-/* Details Section */
-.details-section {
-    display: flex;
-    justify-content: space-between;
-    margin-bottom: 8px;
-    gap: 15px;
-}
-
-.customer-details,
-.store-details {
-    flex: 1;
-}
-
-.customer-details h3,
-.store-details h3 {
-    font-size: 10px;
-    font-weight: bold;
-    margin-bottom: 4px;
-    color: #000;
-    text-transform: uppercase;
-    border-bottom: 1px solid #333;
-    padding-bottom: 2px;
-}
-
-.detail-item {
-    display: flex;
-    margin-bottom: 2px;
-    align-items: flex-start;
-}
-
-.detail-item .label {
-    font-weight: bold;
-    min-width: 50px;
-    font-size: 9px;
-    color: #000;
-}
-
-.detail-item .value {
-    font-size: 9px;
-    color: #000;
-    flex: 1;
-    word-wrap: break-word;
-}
-
-/* Order Details Section */
-.order-details-section {
-    margin-bottom: 8px;
-    border-bottom: 1px solid #ccc;
-    padding-bottom: 6px;
-}
-
-.order-details-section h3 {
-    font-size: 10px;
-    font-weight: bold;
-    margin-bottom: 4px;
-    color: #000;
-    text-transform: uppercase;
-}
-
-.order-info {
-    display: flex;
-    justify-content: space-between;
-    gap: 10px;
-    font-size: 8px;
-}
-
-.order-info-item {
-    flex: 1;
-}
-
-.info-label {
-    font-size: 8px;
-    font-weight: bold;
-    color: #000;
-    margin-bottom: 1px;
-}
-
-.info-value {
-    font-size: 8px;
-    color: #444;
-}
-
-/* Items Table */
-.items-section {
-    margin-bottom: 8px;
-}
-
-.items-table {
-    width: 100%;
-    border-collapse: collapse;
-    font-size: 8px;
-    border: 1px solid #333;
-}
-
-.items-table th {
-    background-color: #f0f0f0;
-    font-weight: bold;
-    padding: 3px 2px;
-    text-align: center;
-    border: 1px solid #333;
-    font-size: 8px;
-    color: #000;
-    text-transform: uppercase;
-}
-
-.items-table td {
-    padding: 3px 2px;
-    border: 1px solid #333;
-    vertical-align: top;
-    font-size: 8px;
-}
-
-.items-table tbody tr:nth-child(even) {
-    background-color: #f9f9f9;
-}
-
-/* Column Widths */
-.col-sno { width: 6%; text-align: center; }
-.col-service { width: 14%; text-align: left; }
-.col-hsn { width: 8%; text-align: center; }
-.col-items { width: 28%; text-align: left; }
-.col-rate { width: 10%; text-align: right; }
-.col-qty { width: 6%; text-align: center; }
-.col-weight { width: 8%; text-align: center; }
-.col-addons { width: 8%; text-align: center; }
-.col-amount { width: 12%; text-align: right; }
-
-.item-list {
-    font-size: 7px;
-    line-height: 1.3;
-}
-
-.item-list ul {
-    list-style-type: none;
-    padding: 0;
-    margin: 0;
-}
-
-.item-list li {
-    margin-bottom: 1px;
-}
-
-.item-list li:before {
-    content: "• ";
-    font-weight: bold;
-}
-
-/* Totals Section */
-.totals-section {
-    margin-bottom: 15px;
-    display: flex;
-    justify-content: flex-end;
-    border-top: 1px solid #333;
-    padding-top: 4px;
-}
-
-.totals-table {
-    min-width: 200px;
-    border: 1px solid #333;
-}
-
-.total-row {
-    display: flex;
-    justify-content: space-between;
-    padding: 2px 4px;
-    border-bottom: 1px solid #ddd;
-}
-
-.total-row.final-total {
-    border-bottom: 2px solid #333;
-    border-top: 1px solid #333;
-    font-weight: bold;
-    padding: 4px;
-    background-color: #f0f0f0;
-}
-
-.total-label {
-    font-size: 9px;
-    color: #000;
-    font-weight: normal;
-}
-
-.total-value {
-    font-size: 9px;
-    color: #000;
-    font-weight: normal;
-    text-align: right;
-}
-
-.final-total .total-label,
-.final-total .total-value {
-    font-weight: bold;
-    font-size: 10px;
-}
-
-/* Footer */
-.invoice-footer {
-    position: absolute;
-    bottom: 6mm;
-    left: 6mm;
-    right: 6mm;
-    text-align: center;
-    border-top: 1px solid #ccc;
-    padding-top: 6px;
-}
-
-.footer-line {
-    margin-bottom: 2px;
-}
-
-.footer-line .website {
-    font-size: 8px;
-    font-weight: bold;
-    color: #000;
-}
+    /// Render an invoice with the template for its paper size, or with a
+    /// customised template source when one is given
+    pub fn render_invoice(
+        &self,
+        data: &InvoiceHtmlData,
+        settings: &HtmlSettings,
+        custom_template: Option<&str>,
+    ) -> ApiResult<String> {
+        let paper_size = &settings.template.paper_size;
+        let barcode = BarcodeService::invoice_barcode(&data.invoice.invoice_no, paper_size.barcode_width_mm())
+            .map(|barcode| barcode.svg)
+            .ok();
+        let items: Vec<InvoiceTemplateItem> = data.items
+            .iter()
+            .map(|details| InvoiceTemplateItem {
+                details,
+                garment_notes: HtmlGenerator::garment_note_lines(&details.garments),
+            })
+            .collect();
 
-.footer-line .disclaimer {
-    font-size: 7px;
-    color: #666;
-    font-style: italic;
-}
+        // Labels and amount in words follow the customer's or store's language
+        let language = DocumentLanguage::for_invoice(&data.store, &data.customer);
 
-.footer-line .contact {
-    font-size: 7px;
-    color: #444;
-}
-    </style>
-</head>
-<body>
-    <div class="invoice-container">
-        <!-- Header with Logo and Invoice Number -->
-        <header class="invoice-header">
-            <div class="logo-section">
-                <div class="logo">
-                    <div class="logo-text">
-                        <span class="u-letter">U</span>
-                        <span class="clean-text">CLEAN</span>
-                    </div>
-                    <div class="tagline">WE ❤ LAUNDRY</div>
-                    <div class="website">www.ucleanlaundry.com</div>
-                </div>
-            </div>
-            <div class="invoice-number">
-                <strong>Tax Invoice No. - {{ invoice.invoice_no }}</strong>
-            </div>
-        </header>
-
-        <!-- Customer and Store Details Section -->
-        <div class="details-section">
-            <div class="customer-details">
-                <h3>CUSTOMER DETAILS</h3>
-                <div class="detail-item">
-                    <span class="label">Name:</span>
-                    <span class="value">{{ customer.name }}</span>
-                </div>
-                <div class="detail-item">
-                    <span class="label">Ph No.:</span>
-                    <span class="value">{{ customer.phone | default(value="") }}</span>
-                </div>
-                <div class="detail-item">
-                    <span class="label">Address:</span>
-                    <span class="value">{{ customer.address | default(value="") }}</span>
-                </div>
-            </div>
-
-            <div class="store-details">
-                <h3>STORE DETAILS</h3>
-                <div class="detail-item">
-                    <span class="label">Code:</span>
-                    <span class="value">{{ store.code | default(value="UC001") }}</span>
-                </div>
-                <div class="detail-item">
-                    <span class="label">Name:</span>
-                    <span class="value">{{ store.name }}</span>
-                </div>
-                <div class="detail-item">
-                    <span class="label">GSTIN:</span>
-                    <span class="value">{{ store.gstin | default(value="") }}</span>
-                </div>
-                <div class="detail-item">
-                    <span class="label">Ph No.:</span>
-                    <span class="value">{{ store.phone | default(value="") }}</span>
-                </div>
-                <div class="detail-item">
-                    <span class="label">Address:</span>
-                    <span class="value">{{ store.address | default(value="") }}</span>
-                </div>
-            </div>
-        </div>
-
-        <!-- Order Details Section -->
-        <div class="order-details-section">
-            <h3>ORDER DETAILS</h3>
-            <div class="order-info">
-                <div class="order-info-item">
-                    <div class="info-label">ORDER NO. - {{ invoice.order_no | default(value=invoice.invoice_no) }} (WALK-IN)</div>
-                    <div class="info-value">{{ invoice.order_datetime }}</div>
-                </div>
-                <div class="order-info-item">
-                    <div class="info-label">PICKUP DONE DATE & TIME</div>
-                    <div class="info-value">{{ invoice.pickup_datetime | default(value="0000-00-00") }}</div>
-                </div>
-                <div class="order-info-item">
-                    <div class="info-label">DELIVERY DATE & TIME</div>
-                    <div class="info-value">{{ invoice.delivery_datetime | default(value="") }}</div>
-                </div>
-            </div>
-        </div>
-
-        <!-- Items Table -->
-        <div class="items-section">
-            <table class="items-table">
-                <thead>
-                    <tr>
-                        <th class="col-sno">S.NO.</th>
-                        <th class="col-service">SERVICE</th>
-                        <th class="col-hsn">HSN/SAC</th>
-                        <th class="col-items">ITEMS</th>
-                        <th class="col-rate">RATE</th>
-                        <th class="col-qty">QTY.</th>
-                        <th class="col-weight">WT./KG</th>
-                        <th class="col-addons">ADDONS</th>
-                        <th class="col-amount">AMOUNT</th>
-                    </tr>
-                </thead>
-                <tbody>
-                    {% for item in items %}
-                    {% if loop.first %}
-                    <tr>
-                        <td class="col-sno" rowspan="{{ items | length }}">1.</td>
-                        <td class="col-service" rowspan="{{ items | length }}">{{ item.service_name }}</td>
-                        <td class="col-hsn" rowspan="{{ items | length }}">999712</td>
-                        <td class="col-items">
-                            <div class="item-list">
-                                <ul>
-                                    {% for service_item in items %}
-                                    <li>{{ service_item.item.description | default(value=service_item.service_name) }}</li>
-                                    {% endfor %}
-                                </ul>
-                            </div>
-                        </td>
-                        <td class="col-rate">
-                            {% for service_item in items %}
-                            {{ service_item.item.rate | round(precision=2) }}<br>
-                            {% endfor %}
-                        </td>
-                        <td class="col-qty">
-                            {% for service_item in items %}
-                            {{ service_item.item.qty }}<br>
-                            {% endfor %}
-                        </td>
-                        <td class="col-weight">
-                            {% for service_item in items %}
-                            {{ service_item.item.weight_kg | default(value="-") }}<br>
-                            {% endfor %}
-                        </td>
-                        <td class="col-addons">
-                            {% for service_item in items %}
-                            {{ service_item.item.area_sqft | default(value="0.00") }}<br>
-                            {% endfor %}
-                        </td>
-                        <td class="col-amount" rowspan="{{ items | length }}">{{ totals.subtotal | round(precision=2) }}</td>
-                    </tr>
-                    {% endif %}
-                    {% endfor %}
-                </tbody>
-            </table>
-        </div>
-
-        <!-- Totals Section -->
-        <div class="totals-section">
-            <div class="totals-table">
-                <div class="total-row">
-                    <span class="total-label">Amount:</span>
-                    <span class="total-value">₹ {{ totals.subtotal | round(precision=2) }}</span>
-                </div>
-                <div class="total-row">
-                    <span class="total-label">Addon:</span>
-                    <span class="total-value">₹ {{ totals.addon_amount | default(value="0.00") | round(precision=2) }}</span>
-                </div>
-                <div class="total-row">
-                    <span class="total-label">Express Amount:</span>
-                    <span class="total-value">₹ {{ totals.express_charge | round(precision=2) }}</span>
-                </div>
-                <div class="total-row">
-                    <span class="total-label">Discount:</span>
-                    <span class="total-value">₹ {{ totals.discount_amount | round(precision=2) }}</span>
-                </div>
-                <div class="total-row">
-                    <span class="total-label">SGST:</span>
-                    <span class="total-value">{{ totals.sgst_amount | round(precision=2) }}</span>
-                </div>
-                <div class="total-row">
-                    <span class="total-label">CGST:</span>
-                    <span class="total-value">{{ totals.cgst_amount | round(precision=2) }}</span>
-                </div>
-                <div class="total-row final-total">
-                    <span class="total-label">TOTAL</span>
-                    <span class="total-value">₹ {{ totals.total_amount | round(precision=2) }}</span>
-                </div>
-            </div>
-        </div>
-
-        <!-- Footer -->
-        <footer class="invoice-footer">
-            <div class="footer-line">
-                <span class="website">www.ucleanlaundry.com</span>
-            </div>
-            <div class="footer-line">
-                <span class="disclaimer">Disclaimer: I hereby acknowledge that I accept the service terms & conditions*</span>
-            </div>
-            <div class="footer-line">
-                <span class="contact">Contact Support: {{ store.support_phone | default(value="9999759911") }} {{ store.support_email | default(value="support@uclean.in") }}</span>
-            </div>
-        </footer>
-    </div>
-</body>
-</html>"#.to_string()
-    }
-
-    pub fn render_invoice(&self, data: &InvoiceHtmlData) -> ApiResult<String> {
         let mut context = Context::new();
-
-        // Add invoice data to context
+        context.insert("locale", language.primary.code());
+        context.insert("t", &language.labels());
+        context.insert("amount_in_words", &language.amount_in_words(data.totals.total_amount));
         context.insert("invoice", &data.invoice);
         context.insert("customer", &data.customer);
         context.insert("store", &data.store);
-        context.insert("items", &data.items);
+        context.insert("items", &items);
         context.insert("totals", &data.totals);
+        // Shown next to SGST and CGST only when every line shares a rate
+        context.insert("split_gst_rate", &data.split_gst_rate().map(|rate| format!("{:.1}", rate)));
+        context.insert("layout", &settings.template.layout);
+        context.insert("branding", &settings.branding);
+        context.insert("footer", &settings.footer);
+        context.insert("page_size", &paper_size.css_size());
+        context.insert("barcode", &barcode);
+        // Saved invoices open the print dialog as soon as the browser loads them
+        context.insert("auto_print", &(settings.auto_print && !matches!(paper_size, PaperSize::Custom { .. })));
+        context.insert("duplicate_copy", &settings.duplicate_copy);
+
+        let rendered = match custom_template {
+            Some(template) => {
+                Self::check_custom_template(template)?;
+                let mut tera = self.tera.clone();
+                tera.add_raw_template("invoice/custom.html", template)
+                    .and_then(|_| tera.render("invoice/custom.html", &context))
+            }
+            None => self.tera.render(&settings.template.template_file, &context),
+        };
 
-        // Render the template
-        self.tera
-            .render("invoice/invoice_template.html", &context)
-            .map_err(|e| ApiError {
-                message: format!("Failed to render invoice template: {}", e),
-                code: Some("TEMPLATE_RENDER_ERROR".to_string()),
+        rendered.map_err(|e| ApiError {
+            message: format!("Failed to render invoice template: {}", Self::describe_tera_error(&e)),
+            code: Some("TEMPLATE_RENDER_ERROR".to_string()),
+        })
+    }
+
+    /// Built-in invoice template source: the shared base, or a format's
+    /// template that extends it
+    pub fn builtin_invoice_template(name: &str) -> ApiResult<&'static str> {
+        let file = format!("invoice/{}.html", name);
+        INVOICE_TEMPLATES
+            .iter()
+            .find(|(template_file, _)| *template_file == file)
+            .map(|(_, template)| *template)
+            .ok_or_else(|| ApiError {
+                message: format!("No built-in invoice template named {}", name),
+                code: Some("INVALID_FORMAT".to_string()),
+            })
+    }
+
+    /// Check an invoice template and layout by rendering a sample invoice
+    pub fn validate_invoice_template(&self, template: &str, format: &str, layout: &LayoutSettings) -> ApiResult<()> {
+        layout.validate()?;
+
+        let data = InvoiceHtmlData::sample();
+        let mut settings = HtmlGenerator::get_settings(format, &data.store)?;
+        settings.template.layout = layout.clone();
+
+        self.render_invoice(&data, &settings, Some(template)).map(|_| ())
+    }
+
+    /// Built-in tag template source for a roll width
+    pub fn builtin_tag_template(roll_width: &str) -> ApiResult<&'static str> {
+        TAG_TEMPLATES
+            .iter()
+            .find(|(width, _)| *width == roll_width)
+            .map(|(_, template)| *template)
+            .ok_or_else(|| ApiError {
+                message: format!("Unsupported tag roll width: {}", roll_width),
+                code: Some("INVALID_ROLL_WIDTH".to_string()),
             })
     }
 
-    pub fn render_invoice_with_custom_template(
+    /// Render clothing tags with the built-in template for the roll width, or
+    /// with a store's customised template when one is given, in the one
+    /// language `DocumentLanguage::for_tags` picks.
+    pub fn render_tags(
         &self,
-        data: &InvoiceHtmlData,
-        template_name: &str,
+        tags: &[TagData],
+        settings: &TagSettings,
+        locale: Locale,
+        custom_template: Option<&str>,
     ) -> ApiResult<String> {
+        let roll_width = settings.roll_width.as_str();
+        let roll_width = if Self::builtin_tag_template(roll_width).is_ok() { roll_width } else { "40mm" };
+        let template_style = if settings.template_style == "compact" { "compact" } else { "standard" };
+        let font_scale = settings.font_scale.clamp(TagSettings::MIN_FONT_SCALE, TagSettings::MAX_FONT_SCALE);
+        let labels = locale.catalog();
+        let count_format = labels.get("tag_count").map(String::as_str).unwrap_or("{number} of {total}");
+
+        let mut items = Vec::with_capacity(tags.len());
+        for tag in tags {
+            let barcode = if tag.include_barcode {
+                Some(BarcodeService::tag_barcode(&tag.tag_code, roll_width)?.svg)
+            } else {
+                None
+            };
+            let service_name_short = if tag.service_name.chars().count() > 15 {
+                format!("{}...", tag.service_name.chars().take(12).collect::<String>())
+            } else {
+                tag.service_name.clone()
+            };
+
+            items.push(TagTemplateItem {
+                tag,
+                service_name_short,
+                delivery_date_short: tag.delivery_date
+                    .as_ref()
+                    .map(|date| date.split_whitespace().take(2).collect::<Vec<_>>().join(" ")),
+                count_label: count_format
+                    .replace("{number}", &tag.tag_number.to_string())
+                    .replace("{total}", &tag.total_quantity.to_string()),
+                barcode,
+            });
+        }
+
         let mut context = Context::new();
+        context.insert("locale", locale.code());
+        context.insert("t", &labels);
+        context.insert("tags", &items);
+        context.insert("roll_width", roll_width);
+        context.insert("template_style", template_style);
+        context.insert("font_scale", &font_scale);
+
+        let rendered = match custom_template {
+            Some(template) => {
+                Self::check_custom_template(template)?;
+                let mut tera = self.tera.clone();
+                tera.add_raw_template("tags/custom.html", template)
+                    .and_then(|_| tera.render("tags/custom.html", &context))
+            }
+            None => self.tera.render(&format!("tags/tag_{}.html", roll_width), &context),
+        };
 
-        // Add invoice data to context
-        context.insert("invoice", &data.invoice);
-        context.insert("customer", &data.customer);
-        context.insert("store", &data.store);
-        context.insert("items", &data.items);
-        context.insert("totals", &data.totals);
+        rendered.map_err(|e| ApiError {
+            message: format!("Failed to render tag template: {}", Self::describe_tera_error(&e)),
+            code: Some("TEMPLATE_RENDER_ERROR".to_string()),
+        })
+    }
 
-        // Render the custom template
-        self.tera
-            .render(template_name, &context)
-            .map_err(|e| ApiError {
-                message: format!("Failed to render custom template '{}': {}", template_name, e),
-                code: Some("TEMPLATE_RENDER_ERROR".to_string()),
-            })
+    /// Check a customised tag template by rendering it with a sample tag
+    pub fn validate_tag_template(&self, template: &str, roll_width: &str) -> ApiResult<()> {
+        let sample = TagData {
+            invoice_no: "INV202501__001".to_string(),
+            customer_name: "Sample Customer".to_string(),
+            service_name: "Dry Clean - Shirt".to_string(),
+            addons: Some("Starch".to_string()),
+            tag_number: 1,
+            total_quantity: 2,
+            overall_piece_number: 1,
+            total_invoice_pieces: 3,
+            delivery_date: Some("24/09/2025 07:30 PM".to_string()),
+            tag_code: "INV202501__001-1-1".to_string(),
+            include_barcode: true,
+            garment: Some("Shirt, Blue".to_string()),
+            damage_notes: Some("Collar stain".to_string()),
+            customer_phone: Some("9876543210".to_string()),
+            store_code: Some("BLR01".to_string()),
+        };
+        let settings = TagSettings {
+            roll_width: roll_width.to_string(),
+            ..TagSettings::defaults(None)
+        };
+
+        self.render_tags(&[sample], &settings, Locale::En, Some(template)).map(|_| ())
     }
 
-    pub fn get_available_templates(&self) -> Vec<String> {
-        self.tera
-            .get_template_names()
-            .filter(|name| name.starts_with("invoice/"))
-            .map(|name| name.to_string())
-            .collect()
+    /// Customised templates are autoescaped like the built-ins, and may not
+    /// switch escaping off: barcodes go through `svg` and terms through
+    /// `terms`, which only let through markup we trust
+    fn check_custom_template(template: &str) -> ApiResult<()> {
+        let safe = Regex::new(r"\|\s*safe\b|\{%-?\s*filter\s+safe\b").unwrap();
+        if safe.is_match(template) {
+            return Err(ApiError {
+                message: "Templates may not use the safe filter; use svg for barcodes and terms for terms text".to_string(),
+                code: Some("UNSAFE_TEMPLATE".to_string()),
+            });
+        }
+        Ok(())
+    }
+
+    /// Tera's top-level error only says which template failed; the cause
+    /// carries the line and column
+    fn describe_tera_error(error: &tera::Error) -> String {
+        let mut message = error.to_string();
+        let mut source = std::error::Error::source(error);
+        while let Some(cause) = source {
+            message.push_str(&format!(": {}", cause));
+            source = cause.source();
+        }
+        message
     }
 
     // Custom filter for rounding numbers
@@ -636,176 +334,479 @@ body {
             _ => Ok(value.clone()),
         }
     }
+
+    // Number with a fixed count of decimals, e.g. quantities as "2.00"
+    fn fixed_filter(
+        value: &tera::Value,
+        args: &HashMap<String, tera::Value>,
+    ) -> tera::Result<tera::Value> {
+        let precision = args
+            .get("precision")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(2) as usize;
+
+        match value.as_f64() {
+            Some(f) => Ok(tera::Value::String(format!("{:.*}", precision, f))),
+            None => Ok(value.clone()),
+        }
+    }
+
+    // Rupee amount with two decimals, e.g. "₹354.00"
+    fn money_filter(
+        value: &tera::Value,
+        _args: &HashMap<String, tera::Value>,
+    ) -> tera::Result<tera::Value> {
+        match value.as_f64() {
+            Some(f) => Ok(tera::Value::String(format!("₹{:.2}", f))),
+            None => Ok(value.clone()),
+        }
+    }
 }
 
-// Configuration structure for customizable templates
-#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
-pub struct TemplateConfig {
-    pub name: String,
-    pub display_name: String,
-    pub template_file: String,
-    pub css_file: String,
-    pub paper_size: PaperSize,
-    pub settings: TemplateSettings,
+/// Outputs a barcode SVG unescaped; any other value is escaped as usual
+struct SvgFilter;
+
+impl tera::Filter for SvgFilter {
+    fn filter(&self, value: &tera::Value, _args: &HashMap<String, tera::Value>) -> tera::Result<tera::Value> {
+        let markup = value.as_str().unwrap_or_default();
+        if BarcodeService::is_barcode_svg(markup) {
+            Ok(tera::Value::String(markup.to_string()))
+        } else {
+            Ok(tera::Value::String(escape_html(markup)))
+        }
+    }
+
+    fn is_safe(&self) -> bool {
+        true
+    }
 }
 
-#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+/// Terms text with its inline formatting kept and everything else escaped
+struct TermsFilter;
+
+impl tera::Filter for TermsFilter {
+    fn filter(&self, value: &tera::Value, _args: &HashMap<String, tera::Value>) -> tera::Result<tera::Value> {
+        Ok(tera::Value::String(sanitize_terms_html(value.as_str().unwrap_or_default())))
+    }
+
+    fn is_safe(&self) -> bool {
+        true
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum PaperSize {
     A4,
     A5,
-    Thermal,
-    Custom { width_mm: f32, height_mm: f32 },
+    Thermal80mm,
+    Custom { width_mm: f64, height_mm: f64 },
+}
+
+impl PaperSize {
+    /// Value for the CSS `@page { size }` rule
+    pub fn css_size(&self) -> String {
+        match self {
+            PaperSize::A4 => "A4".to_string(),
+            PaperSize::A5 => "A5".to_string(),
+            PaperSize::Thermal80mm => "80mm 200mm".to_string(),
+            PaperSize::Custom { width_mm, height_mm } => format!("{}mm {}mm", width_mm, height_mm),
+        }
+    }
+
+    /// Widest the invoice number barcode may be drawn
+    pub fn barcode_width_mm(&self) -> f64 {
+        match self {
+            PaperSize::A4 | PaperSize::A5 => 60.0,
+            PaperSize::Thermal80mm => 40.0,
+            PaperSize::Custom { width_mm, .. } => width_mm / 2.0,
+        }
+    }
 }
 
-#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
-pub struct TemplateSettings {
-    pub show_logo: bool,
-    pub show_terms: bool,
-    pub show_addons: bool,
-    pub show_weight: bool,
-    pub currency_symbol: String,
-    pub date_format: String,
-    pub font_family: String,
-    pub font_size: u8,
-    pub margins: Margins,
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LayoutSettings {
+    pub margin_top: f64,
+    pub margin_bottom: f64,
+    pub margin_left: f64,
+    pub margin_right: f64,
+    pub font_size_normal: f64,
+    pub font_size_small: f64,
+    pub font_size_large: f64,
+    pub line_height: f64,
+    pub section_spacing: f64,
+}
+
+impl LayoutSettings {
+    /// Reject layouts that would print an unreadable or empty page
+    pub fn validate(&self) -> ApiResult<()> {
+        let checks = [
+            ("margin_top", self.margin_top, 0.0, 30.0),
+            ("margin_bottom", self.margin_bottom, 0.0, 30.0),
+            ("margin_left", self.margin_left, 0.0, 30.0),
+            ("margin_right", self.margin_right, 0.0, 30.0),
+            ("font_size_normal", self.font_size_normal, 4.0, 36.0),
+            ("font_size_small", self.font_size_small, 4.0, 36.0),
+            ("font_size_large", self.font_size_large, 4.0, 36.0),
+            ("line_height", self.line_height, 0.8, 3.0),
+            ("section_spacing", self.section_spacing, 0.0, 20.0),
+        ];
+
+        for (field, value, min, max) in checks {
+            if !(min..=max).contains(&value) {
+                return Err(ApiError {
+                    message: format!("{} must be between {} and {}", field, min, max),
+                    code: Some("VALIDATION_ERROR".to_string()),
+                });
+            }
+        }
+        Ok(())
+    }
 }
 
-#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
-pub struct Margins {
-    pub top: f32,
-    pub right: f32,
-    pub bottom: f32,
-    pub left: f32,
+/// An invoice format: the Tera template it renders with and its page layout
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TemplateConfig {
+    pub name: String,
+    pub display_name: String,
+    pub template_file: String,
+    /// Source of a store's saved template; None renders `template_file`
+    pub content: Option<String>,
+    pub paper_size: PaperSize,
+    pub layout: LayoutSettings,
+}
+
+impl TemplateConfig {
+    pub const FORMATS: [&'static str; 3] = ["a4", "a5", "thermal"];
+
+    /// Built-in config for a format name (a4, a5 or thermal)
+    pub fn for_format(format: &str) -> ApiResult<Self> {
+        let a5_layout = LayoutSettings {
+            margin_top: 5.0,
+            margin_bottom: 5.0,
+            margin_left: 5.0,
+            margin_right: 5.0,
+            font_size_normal: 9.0,
+            font_size_small: 7.0,
+            font_size_large: 12.0,
+            line_height: 1.2,
+            section_spacing: 3.0,
+        };
+
+        let (display_name, paper_size, layout) = match format {
+            "a5" => ("A5 Invoice", PaperSize::A5, a5_layout),
+            "a4" => (
+                "A4 Invoice",
+                PaperSize::A4,
+                LayoutSettings {
+                    margin_top: 8.0,
+                    margin_bottom: 8.0,
+                    margin_left: 8.0,
+                    margin_right: 8.0,
+                    font_size_normal: 10.0,
+                    font_size_small: 8.0,
+                    font_size_large: 14.0,
+                    ..a5_layout
+                },
+            ),
+            "thermal" => (
+                "Thermal Receipt",
+                PaperSize::Thermal80mm,
+                LayoutSettings {
+                    margin_top: 2.0,
+                    margin_bottom: 2.0,
+                    margin_left: 2.0,
+                    margin_right: 2.0,
+                    font_size_normal: 8.0,
+                    font_size_small: 6.0,
+                    font_size_large: 10.0,
+                    ..a5_layout
+                },
+            ),
+            _ => {
+                return Err(ApiError {
+                    message: format!("Invalid format: {}. Supported formats: {}", format, Self::FORMATS.join(", ")),
+                    code: Some("INVALID_FORMAT".to_string()),
+                })
+            }
+        };
+
+        Ok(Self {
+            name: format.to_string(),
+            display_name: display_name.to_string(),
+            template_file: format!("invoice/{}.html", format),
+            content: None,
+            paper_size,
+            layout,
+        })
+    }
+
+    pub fn builtin() -> Vec<Self> {
+        Self::FORMATS
+            .iter()
+            .filter_map(|format| Self::for_format(format).ok())
+            .collect()
+    }
 }
 
-impl Default for TemplateConfig {
-    fn default() -> Self {
-        Self {
-            name: "default".to_string(),
-            display_name: "Default UCLEAN Template".to_string(),
-            template_file: "invoice/invoice_template.html".to_string(),
-            css_file: "assets/invoice_styles.css".to_string(),
-            paper_size: PaperSize::A5,
-            settings: TemplateSettings::default(),
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ClothingTag;
+    use crate::services::html_generator::{test_data::sample_invoice, InvoiceAddonDetail};
+
+    /// Markup that must never reach a rendered page unescaped
+    const HOSTILE: &str = r#"<script>alert(1)</script><img src=x onerror="alert(2)">"#;
+    const HOSTILE_ESCAPED: &str =
+        "&lt;script&gt;alert(1)&lt;&#x2F;script&gt;&lt;img src=x onerror=&quot;alert(2)&quot;&gt;";
+
+    fn hostile_garment() -> ClothingTag {
+        ClothingTag {
+            id: 1,
+            invoice_id: 1,
+            invoice_item_id: 1,
+            tag_number: 1,
+            total_quantity: 1,
+            overall_piece_number: 1,
+            total_invoice_pieces: 1,
+            tag_code: "INV202510001-1-1".to_string(),
+            printed_at: None,
+            printed_by: None,
+            reprint_count: 0,
+            status: "received".to_string(),
+            status_updated_at: None,
+            is_void: false,
+            voided_at: None,
+            garment_type: Some(HOSTILE.to_string()),
+            colour: None,
+            brand: None,
+            damage_notes: Some(HOSTILE.to_string()),
+            photo_paths: "[]".to_string(),
+            created_at: String::new(),
         }
     }
-}
 
-impl Default for TemplateSettings {
-    fn default() -> Self {
-        Self {
-            show_logo: true,
-            show_terms: true,
-            show_addons: true,
-            show_weight: true,
-            currency_symbol: "₹".to_string(),
-            date_format: "%Y-%m-%d %H:%M:%S".to_string(),
-            font_family: "Arial".to_string(),
-            font_size: 11,
-            margins: Margins::default(),
+    fn assert_no_hostile_markup(html: &str) {
+        assert!(!html.contains("<script>alert"), "unescaped script in {}", html);
+        assert!(!html.contains("<img"), "unescaped img in {}", html);
+        assert!(!html.contains("onerror=\""), "unescaped attribute in {}", html);
+    }
+
+    fn sample_tag() -> TagData {
+        TagData {
+            invoice_no: "INV202510__001".to_string(),
+            customer_name: "<b>Rao & Sons</b>".to_string(),
+            service_name: "Dry Clean - Silk Saree".to_string(),
+            addons: None,
+            tag_number: 1,
+            total_quantity: 1,
+            overall_piece_number: 1,
+            total_invoice_pieces: 1,
+            delivery_date: None,
+            tag_code: "INV202510__001-1-1".to_string(),
+            include_barcode: true,
+            garment: None,
+            damage_notes: None,
+            customer_phone: None,
+            store_code: None,
         }
     }
-}
 
-impl Default for Margins {
-    fn default() -> Self {
-        Self {
-            top: 8.0,
-            right: 8.0,
-            bottom: 8.0,
-            left: 8.0,
+    fn settings(roll_width: &str) -> TagSettings {
+        TagSettings {
+            roll_width: roll_width.to_string(),
+            ..TagSettings::defaults(None)
         }
     }
-}
 
-pub struct TemplateManager {
-    pub configs: Vec<TemplateConfig>,
-    pub engine: TemplateEngine,
-}
+    #[test]
+    fn test_render_tags_escapes_and_handles_conditionals() {
+        let engine = TemplateEngine::new().unwrap();
+        let html = engine.render_tags(&[sample_tag(), sample_tag()], &settings("32mm"), Locale::En, None).unwrap();
 
-impl TemplateManager {
-    pub fn new() -> ApiResult<Self> {
-        let engine = TemplateEngine::new()?;
-        let configs = Self::load_template_configs()?;
+        assert_eq!(html.matches(r#"<div class="tag-32mm">"#).count(), 2);
+        assert!(html.contains("&lt;b&gt;Rao &amp; Sons&lt;&#x2F;b&gt;"));
+        assert!(html.contains("Dry Clean - ..."));
+        assert!(!html.contains(r#"<div class="tag-addons">"#));
+        assert!(html.contains("<svg"));
+    }
 
-        Ok(Self { configs, engine })
+    #[test]
+    fn test_render_tags_applies_store_settings() {
+        let engine = TemplateEngine::new().unwrap();
+        let tag = TagData {
+            addons: Some("Starch".to_string()),
+            customer_phone: Some("9876543210".to_string()),
+            store_code: Some("BLR01".to_string()),
+            ..sample_tag()
+        };
+
+        let standard = engine.render_tags(&[sample_tag()], &settings("40mm"), Locale::En, None).unwrap();
+        assert!(standard.contains(r#"<body class="standard">"#));
+        assert!(standard.contains("--font-scale: 1;") || standard.contains("--font-scale: 1.0;"));
+        assert!(!standard.contains("tag-phone\">"));
+
+        let compact = TagSettings {
+            template_style: "compact".to_string(),
+            font_scale: 4.0,
+            ..settings("50mm")
+        };
+        let html = engine.render_tags(&[tag], &compact, Locale::En, None).unwrap();
+        assert!(html.contains(r#"<body class="compact">"#));
+        assert!(html.contains("--font-scale: 1.5;"));
+        assert!(html.contains(r#"<div class="tag-phone">9876543210</div>"#));
+        assert!(html.contains("BLR01 · INV202510__001"));
     }
 
-    pub fn get_template_config(&self, name: &str) -> Option<&TemplateConfig> {
-        self.configs.iter().find(|config| config.name == name)
+    #[test]
+    fn test_validate_tag_template() {
+        let engine = TemplateEngine::new().unwrap();
+
+        assert!(engine.validate_tag_template("{% for tag in tags %}{{ tag.tag_code }}{% endfor %}", "40mm").is_ok());
+        assert!(engine.validate_tag_template("{% for tag in tags %}{{ tag.tag_code }}", "40mm").is_err());
+        assert!(engine.validate_tag_template("{{ tags.0.no_such_field }}", "40mm").is_err());
+        assert!(TemplateEngine::builtin_tag_template("45mm").is_err());
     }
 
-    pub fn render_with_config(
-        &self,
-        data: &InvoiceHtmlData,
-        config_name: &str,
-    ) -> ApiResult<String> {
-        let config = self
-            .get_template_config(config_name)
-            .ok_or_else(|| ApiError {
-                message: format!("Template config '{}' not found", config_name),
-                code: Some("TEMPLATE_CONFIG_NOT_FOUND".to_string()),
-            })?;
+    #[test]
+    fn test_render_invoice_for_each_format() {
+        let engine = TemplateEngine::new().unwrap();
+        let mut data = sample_invoice(1);
+        data.customer.name = "<script>alert(1)</script>".to_string();
+        data.items[0].addons.push(InvoiceAddonDetail {
+            addon_name: "Stain Removal".to_string(),
+            pricing_mode: "percent_of_line".to_string(),
+            quantity: 1.0,
+            rate: 10.0,
+            amount: 30.0,
+            discount_amount: 0.0,
+            taxable_value: 30.0,
+        });
+
+        for config in TemplateConfig::builtin() {
+            let settings = HtmlGenerator::get_settings(&config.name, &data.store).unwrap();
+            let html = engine.render_invoice(&data, &settings, None).unwrap();
+
+            assert!(html.contains(&format!("size: {};", config.paper_size.css_size())));
+            assert!(html.contains("&lt;script&gt;alert(1)&lt;&#x2F;script&gt;"));
+            assert!(html.contains("<td>+ Stain Removal</td>"));
+            assert!(html.contains(r#"<td class="numeric">10.00%</td>"#));
+            assert!(html.contains(r#"<strong>₹354.00</strong>"#));
+            assert!(html.contains("<td>SGST (9.0%):</td>"));
+            assert!(html.contains("<svg"));
+            assert_eq!(html.contains(r#"class="invoice-container thermal""#), config.name == "thermal");
+        }
 
-        self.engine
-            .render_invoice_with_custom_template(data, &config.template_file)
-    }
-
-    fn load_template_configs() -> ApiResult<Vec<TemplateConfig>> {
-        // For now, return default configuration
-        // In the future, this could load from a JSON file or database
-        Ok(vec![
-            TemplateConfig::default(),
-            TemplateConfig {
-                name: "a4".to_string(),
-                display_name: "A4 UCLEAN Template".to_string(),
-                template_file: "invoice/invoice_template.html".to_string(),
-                css_file: "assets/invoice_styles.css".to_string(),
-                paper_size: PaperSize::A4,
-                settings: TemplateSettings {
-                    font_size: 12,
-                    margins: Margins {
-                        top: 15.0,
-                        right: 15.0,
-                        bottom: 15.0,
-                        left: 15.0,
-                    },
-                    ..Default::default()
-                },
-            },
-            TemplateConfig {
-                name: "thermal".to_string(),
-                display_name: "Thermal Receipt Template".to_string(),
-                template_file: "invoice/invoice_template.html".to_string(),
-                css_file: "assets/invoice_styles.css".to_string(),
-                paper_size: PaperSize::Thermal,
-                settings: TemplateSettings {
-                    font_size: 9,
-                    show_weight: false,
-                    margins: Margins {
-                        top: 3.0,
-                        right: 3.0,
-                        bottom: 3.0,
-                        left: 3.0,
-                    },
-                    ..Default::default()
-                },
-            },
-        ])
+        assert!(TemplateConfig::for_format("letter").is_err());
+    }
+
+    #[test]
+    fn test_render_invoice_escapes_hostile_input() {
+        let engine = TemplateEngine::new().unwrap();
+        let mut data = InvoiceHtmlData::sample();
+        data.invoice.invoice_no = HOSTILE.to_string();
+        data.invoice.order_source = HOSTILE.to_string();
+        data.customer.name = HOSTILE.to_string();
+        data.customer.address = Some(HOSTILE.to_string());
+        data.customer.phone = Some(HOSTILE.to_string());
+        data.store.name = HOSTILE.to_string();
+        data.store.address = HOSTILE.to_string();
+        data.items[0].service_name = HOSTILE.to_string();
+        data.items[0].variant_name = Some(HOSTILE.to_string());
+        data.items[0].unit = HOSTILE.to_string();
+        data.items[0].addons[0].addon_name = HOSTILE.to_string();
+        data.items[0].garments.push(hostile_garment());
+
+        for config in TemplateConfig::builtin() {
+            let mut settings = HtmlGenerator::get_settings(&config.name, &data.store).unwrap();
+            settings.branding.business_tagline = Some(HOSTILE.to_string());
+            settings.footer.thank_you_message = Some(HOSTILE.to_string());
+            settings.footer.contact_info = vec![HOSTILE.to_string()];
+            settings.footer.terms_conditions = vec![format!("Pay <b>on delivery</b>{}", HOSTILE)];
+
+            let html = engine.render_invoice(&data, &settings, None).unwrap();
+            assert_no_hostile_markup(&html);
+            assert!(html.contains(&format!("<strong>{}</strong>", HOSTILE_ESCAPED)));
+            assert!(html.contains(&format!("<li>Pay <b>on delivery</b>{}</li>", HOSTILE_ESCAPED)));
+        }
     }
 
-    pub fn save_template_config(&mut self, config: TemplateConfig) -> ApiResult<()> {
-        // Remove existing config with same name
-        self.configs.retain(|c| c.name != config.name);
+    #[test]
+    fn test_custom_templates_cannot_disable_escaping() {
+        let engine = TemplateEngine::new().unwrap();
+        let mut data = sample_invoice(1);
+        data.customer.name = HOSTILE.to_string();
+        let settings = HtmlGenerator::get_settings("a4", &data.store).unwrap();
+
+        for template in [
+            "{{ customer.name | safe }}",
+            "{{ customer.name|upper|safe }}",
+            "{% filter safe %}{{ customer.name }}{% endfilter %}",
+        ] {
+            let error = engine.render_invoice(&data, &settings, Some(template)).unwrap_err();
+            assert_eq!(error.code.as_deref(), Some("UNSAFE_TEMPLATE"));
+        }
+        assert!(engine.validate_tag_template("{% for tag in tags %}{{ tag.barcode | safe }}{% endfor %}", "40mm").is_err());
+
+        // The trusted filters escape anything that isn't what they expect
+        let html = engine
+            .render_invoice(&data, &settings, Some("{% set barcode = customer.name %}{{ barcode | svg }}|{{ customer.name | terms }}"))
+            .unwrap();
+        assert_eq!(html, format!("{}|{}", HOSTILE_ESCAPED, HOSTILE_ESCAPED));
+        assert!(engine.render_invoice(&data, &settings, Some("<i>{{ barcode | svg }}</i>")).unwrap().starts_with("<i><svg"));
+    }
 
-        // Add new config
-        self.configs.push(config);
+    #[test]
+    fn test_render_tags_escapes_hostile_input() {
+        let engine = TemplateEngine::new().unwrap();
+        let tag = TagData {
+            customer_name: HOSTILE.to_string(),
+            service_name: HOSTILE.to_string(),
+            addons: Some(HOSTILE.to_string()),
+            delivery_date: Some(HOSTILE.to_string()),
+            garment: Some(HOSTILE.to_string()),
+            damage_notes: Some(HOSTILE.to_string()),
+            customer_phone: Some(HOSTILE.to_string()),
+            store_code: Some(HOSTILE.to_string()),
+            ..sample_tag()
+        };
+
+        for roll_width in ["32mm", "40mm", "50mm"] {
+            let html = engine.render_tags(std::slice::from_ref(&tag), &settings(roll_width), Locale::En, None).unwrap();
+            assert_no_hostile_markup(&html);
+            assert!(html.contains("<svg"));
+        }
+    }
 
-        // In the future, this would save to a file or database
-        Ok(())
+    #[test]
+    fn test_render_invoice_with_custom_template() {
+        let engine = TemplateEngine::new().unwrap();
+        let data = sample_invoice(2);
+        let settings = HtmlGenerator::get_settings("a5", &data.store).unwrap();
+
+        let html = engine
+            .render_invoice(&data, &settings, Some("{{ invoice.invoice_no }}: {{ items | length }} x {{ totals.total_amount | money }}"))
+            .unwrap();
+        assert_eq!(html, "INV202510001: 2 x ₹354.00");
+        assert!(engine.render_invoice(&data, &settings, Some("{{ invoice.no_such_field }}")).is_err());
     }
 
-    pub fn list_templates(&self) -> &[TemplateConfig] {
-        &self.configs
+    #[test]
+    fn test_render_in_customer_and_store_language() {
+        let engine = TemplateEngine::new().unwrap();
+        let mut data = sample_invoice(1);
+        data.customer.locale = Some("hi".to_string());
+        let invoice_settings = HtmlGenerator::get_settings("a5", &data.store).unwrap();
+
+        // Hindi first, with the store's English alongside
+        let html = engine.render_invoice(&data, &invoice_settings, None).unwrap();
+        assert!(html.contains(r#"<html lang="hi">"#));
+        assert!(html.contains("<strong>बिल संख्या &#x2F; Invoice No:</strong>"));
+        assert!(html.contains("तीन सौ चौवन रुपये मात्र<br>Three Hundred Fifty Four Rupees Only"));
+
+        let mut tag = sample_tag();
+        tag.tag_number = 1;
+        tag.total_quantity = 3;
+        let html = engine.render_tags(&[tag], &settings("40mm"), Locale::Ta, None).unwrap();
+        assert!(html.contains(r#"<div class="tag-service-count">3 இல் 1</div>"#));
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,53 @@
+use rustybuzz::{Face, UnicodeBuffer};
+use ttf_parser::GlyphId;
+
+/// A glyph placed by the shaper; advance and offsets are in font units
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ShapedGlyph {
+    pub id: GlyphId,
+    pub x_advance: i32,
+    pub x_offset: i32,
+    pub y_offset: i32,
+}
+
+/// Lays out text with the font's own OpenType tables through rustybuzz, so
+/// Devanagari conjuncts, half forms, reph and the i-matra come out the way
+/// they are written rather than as a row of separate letters
+pub struct TextShaper;
+
+impl TextShaper {
+    /// Glyphs for `text` in visual order. Fonts without layout tables for
+    /// the script get one glyph per character.
+    pub fn shape(face: &Face, text: &str) -> Vec<ShapedGlyph> {
+        let mut buffer = UnicodeBuffer::new();
+        buffer.push_str(text);
+        buffer.guess_segment_properties();
+
+        let shaped = rustybuzz::shape(face, &[], buffer);
+        shaped
+            .glyph_infos()
+            .iter()
+            .zip(shaped.glyph_positions())
+            .map(|(info, position)| ShapedGlyph {
+                id: GlyphId(info.glyph_id as u16),
+                x_advance: position.x_advance,
+                x_offset: position.x_offset,
+                y_offset: position.y_offset,
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fonts_without_devanagari_layout_map_characters() {
+        let face = Face::from_slice(include_bytes!("../templates/assets/fonts/DejaVuSans.ttf"), 0).unwrap();
+        let shaped = TextShaper::shape(&face, "Shirt");
+        let expected: Vec<GlyphId> = "Shirt".chars().map(|c| face.glyph_index(c).unwrap()).collect();
+        assert_eq!(shaped.iter().map(|glyph| glyph.id).collect::<Vec<_>>(), expected);
+        assert!(shaped.iter().all(|glyph| glyph.x_advance > 0));
+    }
+}
@@ -0,0 +1,99 @@
+use chrono::{Duration, NaiveDateTime};
+use sqlx::SqliteConnection;
+use crate::models::{TurnaroundTier, ApiResult, ApiError};
+
+pub const DEFAULT_TURNAROUND_TIER: &str = "standard";
+
+pub struct TurnaroundService;
+
+impl TurnaroundService {
+    /// Resolve a tier for a store, preferring the store's own configuration
+    /// over the global default (store_id NULL)
+    pub async fn resolve_tier(
+        conn: &mut SqliteConnection,
+        store_id: i64,
+        code: &str,
+    ) -> ApiResult<TurnaroundTier> {
+        sqlx::query_as::<_, TurnaroundTier>(
+            r#"
+            SELECT * FROM turnaround_tiers
+            WHERE code = ? AND (store_id = ? OR store_id IS NULL)
+            ORDER BY store_id IS NULL
+            LIMIT 1
+            "#
+        )
+        .bind(code)
+        .bind(store_id)
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(|e| ApiError {
+            message: format!("Database error: {}", e),
+            code: Some("DATABASE_ERROR".to_string()),
+        })?
+        .filter(|tier| tier.is_active.unwrap_or(1) == 1)
+        .ok_or_else(|| ApiError {
+            message: format!("Turnaround tier '{}' is not available for this store", code),
+            code: Some("INVALID_TURNAROUND_TIER".to_string()),
+        })
+    }
+
+    /// Effective tiers for a store: store overrides replace the global
+    /// default with the same code
+    pub async fn get_tiers_for_store(
+        conn: &mut SqliteConnection,
+        store_id: Option<i64>,
+    ) -> ApiResult<Vec<TurnaroundTier>> {
+        sqlx::query_as::<_, TurnaroundTier>(
+            r#"
+            SELECT t.* FROM turnaround_tiers t
+            WHERE (t.store_id = ?1 OR (t.store_id IS NULL AND NOT EXISTS (
+                SELECT 1 FROM turnaround_tiers o WHERE o.store_id = ?1 AND o.code = t.code
+            )))
+            ORDER BY t.turnaround_hours DESC
+            "#
+        )
+        .bind(store_id)
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| ApiError {
+            message: format!("Database error: {}", e),
+            code: Some("DATABASE_ERROR".to_string()),
+        })
+    }
+
+    /// Promised delivery time for an order placed at `order_datetime`
+    pub fn derive_delivery_datetime(order_datetime: NaiveDateTime, tier: &TurnaroundTier) -> String {
+        (order_datetime + Duration::hours(tier.turnaround_hours))
+            .format("%Y-%m-%dT%H:%M:%S")
+            .to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_derive_delivery_datetime() {
+        let tier = TurnaroundTier {
+            id: 3,
+            store_id: None,
+            code: "same_day".to_string(),
+            name: "Same Day".to_string(),
+            surcharge_type: "percent".to_string(),
+            surcharge_value: 100.0,
+            min_surcharge: 0.0,
+            turnaround_hours: 8,
+            excluded_category_ids: "[]".to_string(),
+            is_active: Some(1),
+            created_at: String::new(),
+            updated_at: String::new(),
+        };
+        let order = NaiveDateTime::parse_from_str("2025-09-24 18:30:00", "%Y-%m-%d %H:%M:%S").unwrap();
+
+        assert_eq!(
+            TurnaroundService::derive_delivery_datetime(order, &tier),
+            "2025-09-25T02:30:00"
+        );
+    }
+}
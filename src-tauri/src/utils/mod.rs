@@ -43,6 +43,92 @@ pub fn sanitize_string(input: &str) -> String {
     input.trim().to_string()
 }
 
+/// Escape text for HTML element content or a quoted attribute, matching
+/// what Tera's autoescape produces
+pub fn escape_html(input: &str) -> String {
+    let mut escaped = String::with_capacity(input.len());
+    for c in input.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#x27;"),
+            '/' => escaped.push_str("&#x2F;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Inline tags a store may use in its terms text. Attributes are never kept.
+const TERMS_ALLOWED_TAGS: [&str; 6] = ["b", "strong", "i", "em", "u", "br"];
+
+/// Escape terms text except for bare inline formatting tags (`<b>`, `<i>`,
+/// `<br>`...). Unmatched closing tags are dropped and open ones closed, so a
+/// term can't restyle the rest of the invoice.
+pub fn sanitize_terms_html(input: &str) -> String {
+    let mut output = String::with_capacity(input.len());
+    let mut open: Vec<&str> = Vec::new();
+    let mut rest = input;
+
+    while let Some(start) = rest.find('<') {
+        output.push_str(&escape_html(&rest[..start]));
+        rest = &rest[start..];
+
+        let tag = rest.find('>').and_then(|end| {
+            let inner = rest[1..end].trim().trim_end_matches('/').trim();
+            let (closing, name) = match inner.strip_prefix('/') {
+                Some(name) => (true, name.trim()),
+                None => (false, inner),
+            };
+            let name = name.to_ascii_lowercase();
+            TERMS_ALLOWED_TAGS
+                .iter()
+                .find(|allowed| **allowed == name)
+                .map(|allowed| (end, closing, *allowed))
+        });
+
+        match tag {
+            Some((end, _, "br")) => {
+                output.push_str("<br>");
+                rest = &rest[end + 1..];
+            }
+            Some((end, false, name)) => {
+                output.push_str(&format!("<{}>", name));
+                open.push(name);
+                rest = &rest[end + 1..];
+            }
+            Some((end, true, name)) => {
+                if let Some(position) = open.iter().rposition(|tag| *tag == name) {
+                    for tag in open.drain(position..).rev() {
+                        output.push_str(&format!("</{}>", tag));
+                    }
+                }
+                rest = &rest[end + 1..];
+            }
+            None => {
+                output.push_str("&lt;");
+                rest = &rest[1..];
+            }
+        }
+    }
+
+    output.push_str(&escape_html(rest));
+    for tag in open.into_iter().rev() {
+        output.push_str(&format!("</{}>", tag));
+    }
+    output
+}
+
+/// Terms text with its formatting tags removed, for plain-text output such
+/// as PDFs and receipts
+pub fn terms_plain_text(input: &str) -> String {
+    let tags = regex::Regex::new(r"(?i)</?(?:b|strong|i|em|u)\s*>").unwrap();
+    let breaks = regex::Regex::new(r"(?i)<br\s*/?>").unwrap();
+    tags.replace_all(&breaks.replace_all(input, " "), "").to_string()
+}
+
 pub fn format_currency(amount: f64) -> String {
     format!("₹{:.2}", amount)
 }
@@ -206,6 +292,28 @@ mod tests {
         assert_eq!(calculate_total_with_gst(100.0, 18.0), 118.0);
     }
 
+    #[test]
+    fn test_escape_html() {
+        assert_eq!(
+            escape_html(r#"<img src=x onerror="alert('1')"> & co"#),
+            "&lt;img src=x onerror=&quot;alert(&#x27;1&#x27;)&quot;&gt; &amp; co"
+        );
+    }
+
+    #[test]
+    fn test_sanitize_terms_html() {
+        assert_eq!(sanitize_terms_html("Pay <b>on delivery</b><br/>Thanks"), "Pay <b>on delivery</b><br>Thanks");
+        assert_eq!(sanitize_terms_html("<STRONG>7 days</strong>"), "<strong>7 days</strong>");
+        assert_eq!(
+            sanitize_terms_html(r#"<b onclick="x()">x</b><script>alert(1)</script>"#),
+            "&lt;b onclick=&quot;x()&quot;&gt;x&lt;script&gt;alert(1)&lt;&#x2F;script&gt;"
+        );
+        assert_eq!(sanitize_terms_html("<i>open <b>ended"), "<i>open <b>ended</b></i>");
+        assert_eq!(sanitize_terms_html("stray</em> and 2 < 3"), "stray and 2 &lt; 3");
+        assert_eq!(sanitize_terms_html("<i><b>x</i>"), "<i><b>x</b></i>");
+        assert_eq!(terms_plain_text("Pay <B>on delivery</b><br/>Thanks"), "Pay on delivery Thanks");
+    }
+
     #[test]
     fn test_format_currency() {
         assert_eq!(format_currency(123.45), "₹123.45");
@@ -2,6 +2,7 @@ import React from 'react';
 import { Navigation } from './Navigation';
 import { NotificationCenter } from './NotificationCenter';
 import { LoadingSpinner } from './LoadingSpinner';
+import { PrintQueueStatus } from './PrintQueueStatus';
 import { useApp } from '@/contexts/AppContext';
 
 interface LayoutProps {
@@ -9,7 +10,14 @@ interface LayoutProps {
 }
 
 export function Layout({ children }: LayoutProps) {
-  const { state } = useApp();
+  const { state, setOperator } = useApp();
+
+  const changeOperator = () => {
+    const name = window.prompt('Who is at the counter?', state.operator ?? '');
+    if (name !== null) {
+      setOperator(name);
+    }
+  };
 
   return (
     <div className="min-h-screen bg-gray-50 flex">
@@ -39,6 +47,22 @@ export function Layout({ children }: LayoutProps) {
             </div>
 
             <div className="flex items-center space-x-4">
+              {/* Operator */}
+              <button
+                data-testid="operator"
+                className="btn btn-ghost btn-sm"
+                onClick={changeOperator}
+                title="Change operator"
+              >
+                <svg className="w-4 h-4 mr-1" fill="none" stroke="currentColor" viewBox="0 0 24 24">
+                  <path strokeLinecap="round" strokeLinejoin="round" strokeWidth={2} d="M16 7a4 4 0 11-8 0 4 4 0 018 0zM12 14a7 7 0 00-7 7h14a7 7 0 00-7-7z" />
+                </svg>
+                {state.operator ?? 'Set operator'}
+              </button>
+
+              {/* Print Queue */}
+              <PrintQueueStatus />
+
               {/* Quick Actions */}
               <button
                 data-testid="quick-backup"
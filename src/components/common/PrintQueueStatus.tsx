@@ -0,0 +1,147 @@
+import React, { useCallback, useEffect, useState } from 'react';
+import { invoke } from '@tauri-apps/api/tauri';
+import { listen } from '@tauri-apps/api/event';
+import { useApp } from '@/contexts/AppContext';
+
+/** Emitted by the print worker whenever a queued print changes state */
+const PRINT_QUEUE_EVENT = 'print-queue-updated';
+const RECENT_LIMIT = 20;
+
+interface PrintQueueItem {
+  id: number;
+  invoice_id: number;
+  kind: 'receipt' | 'tags';
+  status: 'pending' | 'printing' | 'printed' | 'failed' | 'cancelled';
+  attempts: number;
+  max_attempts: number;
+  next_attempt_at: string;
+  last_error: string | null;
+  print_job_id: number | null;
+  created_at: string;
+  updated_at: string;
+}
+
+const statusClasses: Record<PrintQueueItem['status'], string> = {
+  pending: 'bg-warning-100 text-warning-800',
+  printing: 'bg-primary-100 text-primary-800',
+  printed: 'bg-success-100 text-success-800',
+  failed: 'bg-error-100 text-error-800',
+  cancelled: 'bg-gray-100 text-gray-600',
+};
+
+export function PrintQueueStatus() {
+  const { showNotification } = useApp();
+  const [items, setItems] = useState<PrintQueueItem[]>([]);
+  const [isOpen, setIsOpen] = useState(false);
+
+  const loadQueue = useCallback(async () => {
+    try {
+      setItems(await invoke<PrintQueueItem[]>('get_print_queue', { status: null, limit: RECENT_LIMIT }));
+    } catch (error) {
+      console.error('Failed to load print queue:', error);
+    }
+  }, []);
+
+  useEffect(() => {
+    loadQueue();
+
+    // Newest first; an update replaces the item it belongs to
+    const unlisten = listen<PrintQueueItem>(PRINT_QUEUE_EVENT, ({ payload }) => {
+      setItems(prev => [payload, ...prev.filter(item => item.id !== payload.id)]
+        .sort((a, b) => b.id - a.id)
+        .slice(0, RECENT_LIMIT));
+
+      if (payload.status === 'failed') {
+        showNotification({
+          type: 'error',
+          title: 'Print Failed',
+          message: `Invoice #${payload.invoice_id} ${payload.kind}: ${payload.last_error ?? 'unknown error'}`,
+          autoClose: false,
+        });
+      }
+    });
+
+    return () => {
+      unlisten.then(stop => stop());
+    };
+  }, [loadQueue, showNotification]);
+
+  const runAction = async (command: 'retry_print_queue_item' | 'cancel_print_queue_item', itemId: number) => {
+    try {
+      // The worker's event updates the list
+      await invoke<PrintQueueItem>(command, { itemId });
+    } catch (error) {
+      showNotification({
+        type: 'error',
+        title: 'Print Queue',
+        message: `${error}`,
+      });
+    }
+  };
+
+  const active = items.filter(item => item.status === 'pending' || item.status === 'printing').length;
+  const failed = items.filter(item => item.status === 'failed').length;
+
+  return (
+    <div className="relative">
+      <button
+        data-testid="print-queue-status"
+        className="btn btn-ghost btn-sm"
+        onClick={() => setIsOpen(open => !open)}
+        title="Print queue"
+      >
+        <svg className="w-4 h-4 mr-1" fill="none" stroke="currentColor" viewBox="0 0 24 24">
+          <path strokeLinecap="round" strokeLinejoin="round" strokeWidth={2} d="M17 17h2a2 2 0 002-2v-4a2 2 0 00-2-2H5a2 2 0 00-2 2v4a2 2 0 002 2h2m2 4h6a2 2 0 002-2v-4a2 2 0 00-2-2H9a2 2 0 00-2 2v4a2 2 0 002 2zm8-12V5a2 2 0 00-2-2H9a2 2 0 00-2 2v4h10z" />
+        </svg>
+        {active > 0 ? `Printing ${active}` : 'Prints'}
+        {failed > 0 && (
+          <span className="ml-1 px-1.5 rounded-full bg-error-500 text-white text-xs" data-testid="print-queue-failed">
+            {failed}
+          </span>
+        )}
+      </button>
+
+      {isOpen && (
+        <div className="absolute right-0 mt-2 w-96 bg-white border border-gray-200 rounded-lg shadow-soft z-20">
+          <div className="px-4 py-2 border-b border-gray-200 text-sm font-medium text-gray-900">
+            Print Queue
+          </div>
+          {items.length === 0 ? (
+            <div className="px-4 py-6 text-sm text-gray-500 text-center">Nothing queued</div>
+          ) : (
+            <ul className="max-h-80 overflow-y-auto divide-y divide-gray-100">
+              {items.map(item => (
+                <li key={item.id} className="px-4 py-2 text-sm" data-testid="print-queue-item">
+                  <div className="flex items-center justify-between">
+                    <span className="text-gray-900">
+                      Invoice #{item.invoice_id} {item.kind === 'tags' ? 'tags' : 'receipt'}
+                    </span>
+                    <span className={`px-2 py-0.5 rounded text-xs ${statusClasses[item.status]}`}>
+                      {item.status}
+                      {item.status === 'pending' && item.attempts > 0 && ` (retry ${item.attempts}/${item.max_attempts})`}
+                    </span>
+                  </div>
+                  {item.last_error && item.status !== 'printed' && (
+                    <div className="text-xs text-error-600 mt-1">{item.last_error}</div>
+                  )}
+                  {(item.status === 'failed' || item.status === 'pending') && (
+                    <div className="flex justify-end space-x-2 mt-1">
+                      {item.status === 'failed' && (
+                        <button className="btn btn-ghost btn-sm" onClick={() => runAction('retry_print_queue_item', item.id)}>
+                          Retry
+                        </button>
+                      )}
+                      <button className="btn btn-ghost btn-sm" onClick={() => runAction('cancel_print_queue_item', item.id)}>
+                        Cancel
+                      </button>
+                    </div>
+                  )}
+                </li>
+              ))}
+            </ul>
+          )}
+        </div>
+      )}
+    </div>
+  );
+}
@@ -0,0 +1,178 @@
+import React, { useEffect, useState } from 'react';
+import { invoke } from '@tauri-apps/api/tauri';
+import { listen } from '@tauri-apps/api/event';
+import { save } from '@tauri-apps/api/dialog';
+import { toast } from 'react-hot-toast';
+import { Button } from '../ui/button';
+import { Modal } from '../common/Modal';
+
+/** Emitted by the backend after each exported invoice and when the export ends */
+const INVOICE_EXPORT_EVENT = 'invoice-export-progress';
+
+interface InvoiceExportQuery {
+  query?: string;
+  customer_id?: number;
+  store_id?: number;
+  status?: string;
+  date_from?: string;
+  date_to?: string;
+}
+
+interface InvoiceExportProgress {
+  status: 'running' | 'completed' | 'cancelled' | 'failed';
+  done: number;
+  total: number;
+  invoice_no: string | null;
+  path: string | null;
+  error: string | null;
+}
+
+interface InvoiceExportButtonProps {
+  /** The list's current filters; every matching invoice is exported, not just this page */
+  query: InvoiceExportQuery;
+}
+
+export const InvoiceExportButton: React.FC<InvoiceExportButtonProps> = ({ query }) => {
+  const [isOpen, setIsOpen] = useState(false);
+  const [format, setFormat] = useState<'a4' | 'a5' | 'thermal'>('a4');
+  const [fileType, setFileType] = useState<'pdf' | 'html'>('pdf');
+  const [progress, setProgress] = useState<InvoiceExportProgress | null>(null);
+  const [isCancelling, setIsCancelling] = useState(false);
+
+  useEffect(() => {
+    const unlisten = listen<InvoiceExportProgress>(INVOICE_EXPORT_EVENT, ({ payload }) => {
+      setProgress(payload);
+
+      if (payload.status === 'completed') {
+        toast.success(`Exported ${payload.done} invoices to ${payload.path}`);
+      } else if (payload.status === 'cancelled') {
+        toast(`Export cancelled after ${payload.done} of ${payload.total} invoices`);
+      } else if (payload.status === 'failed') {
+        toast.error(`Export failed: ${payload.error ?? 'unknown error'}`);
+      }
+      if (payload.status !== 'running') {
+        setIsCancelling(false);
+      }
+    });
+
+    return () => {
+      unlisten.then(stop => stop());
+    };
+  }, []);
+
+  const isRunning = progress?.status === 'running';
+
+  const startExport = async () => {
+    try {
+      const outputPath = await save({
+        defaultPath: `invoices-${new Date().toISOString().split('T')[0]}.zip`,
+        filters: [{ name: 'ZIP archive', extensions: ['zip'] }],
+      });
+      if (!outputPath) return;
+
+      // Progress events can arrive before the command returns, so only fill
+      // in the total if none has been reported yet
+      setProgress({ status: 'running', done: 0, total: 0, invoice_no: null, path: null, error: null });
+      const total = await invoke<number>('start_invoice_export', {
+        request: { query, format, file_type: fileType, output_path: outputPath },
+      });
+      setProgress(prev => (prev && prev.total === 0 ? { ...prev, total } : prev));
+    } catch (error) {
+      setProgress(null);
+      console.error('Failed to start invoice export:', error);
+      toast.error(`Failed to start export: ${(error as { message?: string })?.message ?? error}`);
+    }
+  };
+
+  const cancelExport = async () => {
+    try {
+      setIsCancelling(true);
+      // The final progress event reports the cancellation
+      await invoke<boolean>('cancel_invoice_export');
+    } catch (error) {
+      console.error('Failed to cancel invoice export:', error);
+      setIsCancelling(false);
+    }
+  };
+
+  const percent = progress && progress.total > 0 ? Math.round((progress.done / progress.total) * 100) : 0;
+
+  return (
+    <>
+      <Button variant="outline" onClick={() => setIsOpen(true)} data-testid="invoice-export">
+        {isRunning && progress ? `Exporting ${progress.done}/${progress.total}` : 'Export'}
+      </Button>
+
+      <Modal isOpen={isOpen} onClose={() => setIsOpen(false)} title="Export Invoices">
+        <div className="space-y-4">
+          <p className="text-sm text-gray-600">
+            Every invoice matching the current filters goes into one ZIP, with an index.csv for accounts.
+          </p>
+
+          <div className="grid grid-cols-2 gap-3">
+            <div>
+              <label className="block text-sm font-medium mb-1 text-gray-600">Paper</label>
+              <select
+                value={format}
+                onChange={(e) => setFormat(e.target.value as typeof format)}
+                disabled={isRunning}
+                className="w-full px-3 py-2 border border-gray-300 rounded-md focus:outline-none focus:ring-2 focus:ring-blue-500"
+              >
+                <option value="a4">A4</option>
+                <option value="a5">A5</option>
+                <option value="thermal">Thermal 80mm</option>
+              </select>
+            </div>
+            <div>
+              <label className="block text-sm font-medium mb-1 text-gray-600">File type</label>
+              <select
+                value={fileType}
+                onChange={(e) => setFileType(e.target.value as typeof fileType)}
+                disabled={isRunning}
+                className="w-full px-3 py-2 border border-gray-300 rounded-md focus:outline-none focus:ring-2 focus:ring-blue-500"
+              >
+                <option value="pdf">PDF</option>
+                <option value="html">HTML</option>
+              </select>
+            </div>
+          </div>
+
+          {progress && (
+            <div className="space-y-1" data-testid="invoice-export-progress">
+              <div className="flex justify-between text-sm text-gray-600">
+                <span>
+                  {isRunning
+                    ? progress.invoice_no ? `Exported ${progress.invoice_no}` : 'Starting...'
+                    : progress.status.charAt(0).toUpperCase() + progress.status.slice(1)}
+                </span>
+                <span>{progress.done} / {progress.total}</span>
+              </div>
+              <div className="w-full h-2 bg-gray-200 rounded-full overflow-hidden">
+                <div
+                  className={`h-2 ${progress.status === 'failed' ? 'bg-error-500' : 'bg-primary-500'}`}
+                  style={{ width: `${percent}%` }}
+                />
+              </div>
+              {progress.error && <div className="text-xs text-error-600">{progress.error}</div>}
+            </div>
+          )}
+
+          <div className="flex justify-end space-x-2 pt-4 border-t">
+            {isRunning ? (
+              <Button variant="outline" onClick={cancelExport} disabled={isCancelling}>
+                {isCancelling ? 'Cancelling...' : 'Cancel Export'}
+              </Button>
+            ) : (
+              <>
+                <Button variant="outline" onClick={() => setIsOpen(false)}>
+                  Close
+                </Button>
+                <Button onClick={startExport}>Export</Button>
+              </>
+            )}
+          </div>
+        </div>
+      </Modal>
+    </>
+  );
+};
@@ -1,10 +1,12 @@
 import React, { useState, useEffect, useMemo, useCallback } from 'react';
 import { useNavigate, useParams } from 'react-router-dom';
 import { invoke } from '@tauri-apps/api/tauri';
+import { format } from 'date-fns';
 import { useApp } from '@/contexts/AppContext';
+import { DEFAULT_TURNAROUND_TIER, TurnaroundTier } from '@/lib/turnaround';
 import { CustomerSelector } from './CustomerSelector';
 import { ServiceSelector } from './ServiceSelector';
-import { InvoiceSummary } from './InvoiceSummary';
+import { InvoiceSummary, InvoicePreview } from './InvoiceSummary';
 import { PaymentSection } from './PaymentSection';
 
 interface InvoiceFormData {
@@ -15,7 +17,7 @@ interface InvoiceFormData {
   items: InvoiceItem[];
   discount: number;
   discountType: 'flat' | 'percent';
-  expressCharge: number;
+  turnaroundTier: string;
   gstInclusive: boolean;
   notes: string;
   paymentMethod?: string;
@@ -35,6 +37,7 @@ interface InvoiceItem {
   rate: number;
   amount: number;
   gstRate: number;
+  categoryId?: number;
   addons: InvoiceItemAddon[];
 }
 
@@ -44,6 +47,7 @@ interface InvoiceItemAddon {
   quantity: number;
   rate: number;
   amount: number;
+  gstRate?: number;
 }
 
 export function InvoiceForm() {
@@ -59,11 +63,13 @@ export function InvoiceForm() {
     items: [],
     discount: 0,
     discountType: 'flat',
-    expressCharge: 0,
+    turnaroundTier: DEFAULT_TURNAROUND_TIER,
     gstInclusive: false,
     notes: '',
   });
 
+  const [turnaroundTiers, setTurnaroundTiers] = useState<TurnaroundTier[]>([]);
+  const [preview, setPreview] = useState<InvoicePreview | null>(null);
   const [isSubmitting, setIsSubmitting] = useState(false);
   const [currentStep, setCurrentStep] = useState(1);
   const [validationErrors, setValidationErrors] = useState<Record<string, string>>({});
@@ -92,6 +98,23 @@ export function InvoiceForm() {
     }
   }, [id]);
 
+  // Tiers are per store; the backend charges the chosen tier's surcharge
+  useEffect(() => {
+    invoke<TurnaroundTier[]>('get_turnaround_tiers', { storeId: formData.storeId })
+      .then(tiers => setTurnaroundTiers(tiers.filter(tier => tier.is_active !== 0)))
+      .catch(error => console.error('Failed to load turnaround tiers:', error));
+  }, [formData.storeId]);
+
+  // Picking a tier moves the promised delivery to that tier's turnaround
+  const handleTurnaroundTierChange = (tier: TurnaroundTier) => {
+    const delivery = new Date(Date.now() + tier.turnaround_hours * 60 * 60 * 1000);
+    setFormData(prev => ({
+      ...prev,
+      turnaroundTier: tier.code,
+      deliveryDate: format(delivery, "yyyy-MM-dd'T'HH:mm"),
+    }));
+  };
+
   const loadInvoiceForEdit = async (invoiceId: number) => {
     try {
       setLoading(true);
@@ -106,7 +129,7 @@ export function InvoiceForm() {
         items: invoice.items || [],
         discount: invoice.discount || 0,
         discountType: invoice.discount_type || 'flat',
-        expressCharge: invoice.express_charge || 0,
+        turnaroundTier: invoice.turnaround_tier || DEFAULT_TURNAROUND_TIER,
         gstInclusive: invoice.gst_inclusive === 1,
         notes: invoice.notes || '',
       });
@@ -164,49 +187,57 @@ export function InvoiceForm() {
     }));
   }, []);
 
-  const calculateTotals = useMemo(() => {
-    const subtotal = formData.items.reduce((sum, item) => {
-      const itemTotal = item.amount + item.addons.reduce((addonSum, addon) => addonSum + addon.amount, 0);
-      return sum + itemTotal;
-    }, 0);
-
-    const discountAmount = formData.discountType === 'percent'
-      ? subtotal * (formData.discount / 100)
-      : formData.discount;
-
-    const baseAmount = subtotal - discountAmount + formData.expressCharge;
-
-    let sgstAmount = 0;
-    let cgstAmount = 0;
-    let total = 0;
-
-    if (formData.gstInclusive) {
-      // GST is included in the prices
-      total = baseAmount;
-      const gstAmount = baseAmount - (baseAmount / 1.18); // Assuming 18% GST
-      sgstAmount = gstAmount / 2;
-      cgstAmount = gstAmount / 2;
-    } else {
-      // GST is exclusive
-      const gstAmount = baseAmount * 0.18; // Assuming 18% GST
-      sgstAmount = gstAmount / 2;
-      cgstAmount = gstAmount / 2;
-      total = baseAmount + gstAmount;
+  // The request create_invoice takes; the preview prices exactly this
+  const invoiceRequest = useMemo(() => ({
+    customer_id: formData.customerId,
+    store_id: formData.storeId,
+    order_source: formData.orderSource,
+    delivery_datetime: formData.deliveryDate ? `${formData.deliveryDate}:00` : null,
+    items: formData.items.map(item => ({
+      service_id: item.serviceId,
+      variant_id: item.variantId ?? null,
+      description: item.description || null,
+      qty: item.quantity,
+      piece_count: item.pieceCount ?? null,
+      weight_kg: item.weight ?? null,
+      area_sqft: null,
+      addons: item.addons.length > 0 ? item.addons.map(addon => ({
+        addon_id: addon.addonId,
+        qty: addon.quantity,
+      })) : null,
+    })),
+    discount: formData.discount,
+    discount_type: formData.discountType,
+    turnaround_tier: formData.turnaroundTier,
+    gst_inclusive: formData.gstInclusive,
+    payment_method: formData.paymentMethod ?? null,
+    payment_amount: formData.paymentAmount ?? null,
+    notes: formData.notes,
+  }), [formData]);
+
+  // Totals come from the backend so rate cards, price lists, addon GST rates
+  // and the turnaround surcharge match what create_invoice will charge
+  useEffect(() => {
+    if (!invoiceRequest.customer_id || invoiceRequest.items.length === 0) {
+      setPreview(null);
+      return;
     }
 
-    return {
-      subtotal,
-      discountAmount,
-      baseAmount,
-      sgstAmount,
-      cgstAmount,
-      total,
+    let cancelled = false;
+    invoke<InvoicePreview>('preview_invoice', { request: invoiceRequest })
+      .then(result => {
+        if (!cancelled) setPreview(result);
+      })
+      .catch(error => {
+        console.error('Failed to preview invoice totals:', error);
+        if (!cancelled) setPreview(null);
+      });
+    return () => {
+      cancelled = true;
     };
-  }, [formData.items, formData.discount, formData.discountType, formData.expressCharge, formData.gstInclusive]);
+  }, [invoiceRequest]);
 
-  const calculateTotal = useMemo(() => {
-    return calculateTotals.total;
-  }, [calculateTotals]);
+  const calculateTotal = preview?.totals.total_amount ?? 0;
 
   const isFormValid = useMemo(() => {
     if (!formData.customerId) return false;
@@ -237,46 +268,16 @@ export function InvoiceForm() {
     try {
       setIsSubmitting(true);
 
-      const totals = calculateTotals;
-
-      const invoiceData = {
-        customer_id: formData.customerId,
-        store_id: formData.storeId,
-        order_source: formData.orderSource,
-        delivery_datetime: formData.deliveryDate ? `${formData.deliveryDate}:00` : null,
-        items: formData.items.map(item => ({
-          service_id: item.serviceId,
-          variant_id: item.variantId ?? null,
-          description: item.description || null,
-          qty: item.quantity,
-          piece_count: item.pieceCount ?? null,
-          weight_kg: item.weight ?? null,
-          area_sqft: null,
-          addons: item.addons.length > 0 ? item.addons.map(addon => ({
-            addon_id: addon.addonId,
-            qty: addon.quantity,
-          })) : null,
-        })),
-        discount: formData.discount,
-        discount_type: formData.discountType,
-        express_charge: formData.expressCharge,
-        gst_inclusive: formData.gstInclusive,
-        payment_method: formData.paymentMethod ?? null,
-        payment_amount: formData.paymentAmount ?? null,
-        notes: formData.notes,
-      };
-
-
       let invoice;
       if (id && id !== 'new') {
         // Update existing invoice
         invoice = await invoke('update_invoice', {
           invoiceId: parseInt(id),
-          request: invoiceData,
+          request: invoiceRequest,
         });
       } else {
         // Create new invoice
-        invoice = await invoke('create_invoice', { request: invoiceData });
+        invoice = await invoke('create_invoice', { request: invoiceRequest });
       }
 
       showNotification({
@@ -464,12 +465,14 @@ export function InvoiceForm() {
         <div className="lg:col-span-1">
           <InvoiceSummary
             items={formData.items}
+            preview={preview}
             discount={formData.discount}
             discountType={formData.discountType}
             onDiscountChange={(discount) => setFormData(prev => ({ ...prev, discount }))}
             onDiscountTypeChange={(discountType) => setFormData(prev => ({ ...prev, discountType }))}
-            expressCharge={formData.expressCharge}
-            onExpressChargeChange={(expressCharge) => setFormData(prev => ({ ...prev, expressCharge }))}
+            turnaroundTiers={turnaroundTiers}
+            turnaroundTier={formData.turnaroundTier}
+            onTurnaroundTierChange={handleTurnaroundTierChange}
             gstInclusive={formData.gstInclusive}
             onGstInclusiveChange={(gstInclusive) => setFormData(prev => ({ ...prev, gstInclusive }))}
             onAddService={handleAddService}
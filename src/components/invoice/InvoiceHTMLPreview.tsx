@@ -26,6 +26,8 @@ export const InvoiceHTMLPreview: React.FC<InvoiceHTMLPreviewProps> = ({
   const [htmlContent, setHtmlContent] = useState<string>('');
   const [loading, setLoading] = useState(false);
   const [selectedPaperSize, setSelectedPaperSize] = useState('A5');
+  // Set when the invoice has no usable archived copy and can only be printed as a duplicate
+  const [archiveError, setArchiveError] = useState<string | null>(null);
 
   useEffect(() => {
     if (isOpen && invoiceId) {
@@ -41,9 +43,16 @@ export const InvoiceHTMLPreview: React.FC<InvoiceHTMLPreviewProps> = ({
         paperSize: selectedPaperSize,
       });
       setHtmlContent(html);
+      setArchiveError(null);
     } catch (error) {
       console.error('Failed to load HTML preview:', error);
-      toast.error('Failed to load invoice preview');
+      const { code, message } = (error ?? {}) as { code?: string; message?: string };
+      if (code === 'ARCHIVE_NOT_FOUND' || code === 'ARCHIVE_TAMPERED') {
+        setHtmlContent('');
+        setArchiveError(message ?? 'This invoice has no archived copy');
+      } else {
+        toast.error('Failed to load invoice preview');
+      }
     } finally {
       setLoading(false);
     }
@@ -79,8 +88,49 @@ export const InvoiceHTMLPreview: React.FC<InvoiceHTMLPreviewProps> = ({
     }
   };
 
+  const handleSavePdf = async () => {
+    try {
+      setLoading(true);
+
+      const format = selectedPaperSize.toLowerCase() as 'a4' | 'a5' | 'thermal';
+      const filePath = await invoke<string>('save_invoice_pdf', {
+        invoiceId: invoiceId,
+        format: format,
+      });
+
+      toast.success(`PDF saved to ${filePath}`);
+    } catch (error) {
+      console.error('Failed to save invoice PDF:', error);
+      toast.error('Failed to save invoice PDF.');
+    } finally {
+      setLoading(false);
+    }
+  };
+
+  const handleSaveDuplicate = async () => {
+    try {
+      setLoading(true);
+
+      // Rendered from current data and marked "duplicate copy"
+      const format = selectedPaperSize.toLowerCase() as 'a4' | 'a5' | 'thermal';
+      const filePath = await invoke<string>('save_invoice_duplicate', {
+        invoiceId: invoiceId,
+        format: format,
+        fileType: 'pdf',
+      });
+
+      toast.success(`Duplicate copy saved to ${filePath}`);
+    } catch (error) {
+      console.error('Failed to save duplicate copy:', error);
+      toast.error('Failed to save duplicate copy.');
+    } finally {
+      setLoading(false);
+    }
+  };
+
   const handleClose = () => {
     setHtmlContent('');
+    setArchiveError(null);
     onClose();
   };
 
@@ -114,6 +164,16 @@ export const InvoiceHTMLPreview: React.FC<InvoiceHTMLPreviewProps> = ({
           </div>
 
           <div className="flex items-center space-x-2">
+            {/* Save PDF Button */}
+            <Button
+              onClick={handleSavePdf}
+              disabled={loading}
+              size="sm"
+              variant="outline"
+            >
+              📄 Save PDF
+            </Button>
+
             {/* Print Button */}
             <Button
               onClick={handlePrint}
@@ -150,6 +210,8 @@ export const InvoiceHTMLPreview: React.FC<InvoiceHTMLPreviewProps> = ({
               <iframe
                 id="invoice-preview-iframe"
                 srcDoc={htmlContent}
+                // No allow-scripts: the preview never runs script from invoice data or templates
+                sandbox="allow-same-origin allow-modals"
                 className="w-full h-full border-0"
                 style={{
                   maxWidth: selectedSize?.width || '148mm',
@@ -192,6 +254,15 @@ export const InvoiceHTMLPreview: React.FC<InvoiceHTMLPreviewProps> = ({
                 }}
               />
             </div>
+          ) : archiveError ? (
+            <div className="flex items-center justify-center h-full">
+              <div className="text-center space-y-3">
+                <p className="text-error-600">{archiveError}</p>
+                <Button onClick={handleSaveDuplicate} disabled={loading} variant="outline">
+                  Save Duplicate Copy (PDF)
+                </Button>
+              </div>
+            </div>
           ) : (
             <div className="flex items-center justify-center h-full">
               <p className="text-gray-500">No preview available</p>
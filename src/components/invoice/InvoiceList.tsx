@@ -7,6 +7,7 @@ import { SearchBar } from '../common/SearchBar';
 import { Modal } from '../common/Modal';
 import { InvoiceHTMLPreview } from './InvoiceHTMLPreview';
 import { TagPrintButton } from './TagPrintButton';
+import { InvoiceExportButton } from './InvoiceExportButton';
 import { invoke } from '@tauri-apps/api/tauri';
 import { open } from '@tauri-apps/api/shell';
 import { toast } from 'react-hot-toast';
@@ -550,8 +551,20 @@ export const InvoiceList: React.FC = () => {
                 Clear Filters
               </Button>
 
-              <div className="text-sm text-gray-500">
-                Total: {invoices.length} invoices
+              <div className="flex items-center space-x-4">
+                <div className="text-sm text-gray-500">
+                  Total: {invoices.length} invoices
+                </div>
+                <InvoiceExportButton
+                  query={{
+                    query: searchQuery || undefined,
+                    status: statusFilter || undefined,
+                    customer_id: customerFilter ? parseInt(customerFilter) : undefined,
+                    store_id: storeFilter ? parseInt(storeFilter) : undefined,
+                    date_from: dateFromFilter || undefined,
+                    date_to: dateToFilter || undefined,
+                  }}
+                />
               </div>
             </div>
           </div>
@@ -1,4 +1,5 @@
 import React from 'react';
+import { TurnaroundTier } from '@/lib/turnaround';
 
 interface InvoiceItem {
   id?: number;
@@ -18,23 +19,32 @@ interface InvoiceItemAddon {
   amount: number;
 }
 
-interface InvoiceTotals {
-  subtotal: number;
-  discountAmount: number;
-  baseAmount: number;
-  sgstAmount: number;
-  cgstAmount: number;
-  total: number;
+/** Totals from the preview_invoice command, priced the way create_invoice charges them */
+export interface InvoicePreview {
+  totals: {
+    subtotal: number;
+    discount_amount: number;
+    express_charge: number;
+    base_amount: number;
+    total_gst_amount: number;
+    sgst_amount: number;
+    cgst_amount: number;
+    total_amount: number;
+    gst_inclusive: boolean;
+  };
+  gst_rate: number | null; // shared by every line; null when rates are mixed
 }
 
 interface InvoiceSummaryProps {
   items: InvoiceItem[];
+  preview: InvoicePreview | null;
   discount: number;
   discountType: 'flat' | 'percent';
   onDiscountChange: (discount: number) => void;
   onDiscountTypeChange: (discountType: 'flat' | 'percent') => void;
-  expressCharge: number;
-  onExpressChargeChange: (expressCharge: number) => void;
+  turnaroundTiers: TurnaroundTier[];
+  turnaroundTier: string;
+  onTurnaroundTierChange: (tier: TurnaroundTier) => void;
   gstInclusive: boolean;
   onGstInclusiveChange: (gstInclusive: boolean) => void;
   onAddService?: (item: Omit<InvoiceItem, 'id'>) => void;
@@ -43,12 +53,14 @@ interface InvoiceSummaryProps {
 
 export function InvoiceSummary({
   items,
+  preview,
   discount,
   discountType,
   onDiscountChange,
   onDiscountTypeChange,
-  expressCharge,
-  onExpressChargeChange,
+  turnaroundTiers,
+  turnaroundTier,
+  onTurnaroundTierChange,
   gstInclusive,
   onGstInclusiveChange,
   onAddService,
@@ -63,43 +75,9 @@ export function InvoiceSummary({
     }).format(amount);
   };
 
-  // Calculate totals
-  const subtotal = items.reduce((sum, item) => {
-    const itemTotal = item.amount + item.addons.reduce((addonSum, addon) => addonSum + addon.amount, 0);
-    return sum + itemTotal;
-  }, 0);
-
-  const discountAmount = discountType === 'percent'
-    ? (subtotal * discount) / 100
-    : discount;
-
-  const baseAmount = subtotal - discountAmount + expressCharge;
-
-  // GST calculation (18% = 9% SGST + 9% CGST)
-  const gstRate = 18;
-  let sgstAmount, cgstAmount, total;
-
-  if (gstInclusive) {
-    // When GST inclusive, extract GST from base amount
-    const gstTotal = (baseAmount * gstRate) / (100 + gstRate);
-    sgstAmount = gstTotal / 2;
-    cgstAmount = gstTotal / 2;
-    total = baseAmount;
-  } else {
-    // When GST exclusive, add GST to base amount
-    sgstAmount = (baseAmount * (gstRate / 2)) / 100;
-    cgstAmount = (baseAmount * (gstRate / 2)) / 100;
-    total = baseAmount + sgstAmount + cgstAmount;
-  }
-
-  const totals = {
-    subtotal,
-    discountAmount,
-    baseAmount,
-    sgstAmount,
-    cgstAmount,
-    total
-  };
+  const selectedTier = turnaroundTiers.find(tier => tier.code === turnaroundTier);
+  const totals = preview?.totals;
+  const splitRate = preview?.gst_rate != null ? ` (${preview.gst_rate / 2}%)` : '';
 
   // Quick Action handlers
   const handleQuickAction = (serviceName: string, serviceId: number, quantity: number, rate: number, unit: string = 'kg') => {
@@ -111,7 +89,6 @@ export function InvoiceSummary({
       quantity,
       rate,
       amount: quantity * rate,
-      gstRate: 18,
       addons: []
     };
 
@@ -183,7 +160,7 @@ export function InvoiceSummary({
         </div>
 
         {/* Totals */}
-        {items.length > 0 && (
+        {items.length > 0 && totals && (
           <div className="pt-4 border-t border-gray-200 space-y-2">
             <div className="flex justify-between text-sm">
               <span className="text-gray-600">Subtotal:</span>
@@ -192,33 +169,42 @@ export function InvoiceSummary({
               </span>
             </div>
 
-            {totals.discountAmount > 0 && (
+            {totals.discount_amount > 0 && (
               <div className="flex justify-between text-sm">
                 <span className="text-gray-600">Discount:</span>
                 <span className="font-medium text-green-600" data-testid="discount">
-                  -{formatCurrency(totals.discountAmount)}
+                  -{formatCurrency(totals.discount_amount)}
+                </span>
+              </div>
+            )}
+
+            {totals.express_charge > 0 && (
+              <div className="flex justify-between text-sm">
+                <span className="text-gray-600">{selectedTier?.name} charge:</span>
+                <span className="font-medium" data-testid="turnaround-charge">
+                  {formatCurrency(totals.express_charge)}
                 </span>
               </div>
             )}
 
             <div className="flex justify-between text-sm">
-              <span className="text-gray-600">SGST (9%):</span>
+              <span className="text-gray-600">SGST{splitRate}:</span>
               <span className="font-medium" data-testid="sgst">
-                {formatCurrency(totals.sgstAmount)}
+                {formatCurrency(totals.sgst_amount)}
               </span>
             </div>
 
             <div className="flex justify-between text-sm">
-              <span className="text-gray-600">CGST (9%):</span>
+              <span className="text-gray-600">CGST{splitRate}:</span>
               <span className="font-medium" data-testid="cgst">
-                {formatCurrency(totals.cgstAmount)}
+                {formatCurrency(totals.cgst_amount)}
               </span>
             </div>
 
             <div className="flex justify-between text-base font-semibold pt-2 border-t border-gray-200">
               <span className="text-gray-900">Total:</span>
               <span className="text-gray-900" data-testid="total">
-                {formatCurrency(totals.total)}
+                {formatCurrency(totals.total_amount)}
               </span>
             </div>
 
@@ -259,19 +245,28 @@ export function InvoiceSummary({
           </div>
         </div>
 
-        {/* Express Delivery Controls */}
+        {/* Turnaround Controls */}
         <div className="pt-4 border-t border-gray-200 space-y-3">
-          <h4 className="text-sm font-medium text-gray-700">Express Delivery</h4>
+          <h4 className="text-sm font-medium text-gray-700">Turnaround</h4>
           <div>
-            <input
-              type="number"
-              value={expressCharge}
-              onChange={(e) => onExpressChargeChange(Number(e.target.value))}
-              placeholder="0"
-              min="0"
+            <select
+              value={turnaroundTier}
+              onChange={(e) => {
+                const tier = turnaroundTiers.find(t => t.code === e.target.value);
+                if (tier) onTurnaroundTierChange(tier);
+              }}
               className="form-input text-sm"
-              data-testid="express-charge"
-            />
+              data-testid="turnaround-tier"
+            >
+              {turnaroundTiers.map((tier) => (
+                <option key={tier.code} value={tier.code}>
+                  {tier.name}
+                  {tier.surcharge_value > 0 && (tier.surcharge_type === 'percent'
+                    ? ` (+${tier.surcharge_value}%)`
+                    : ` (+${formatCurrency(tier.surcharge_value)})`)}
+                </option>
+              ))}
+            </select>
           </div>
         </div>
 
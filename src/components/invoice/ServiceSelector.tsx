@@ -48,6 +48,7 @@ interface InvoiceItem {
   rate: number;
   amount: number;
   gstRate: number;
+  categoryId?: number;
   addons: InvoiceItemAddon[];
 }
 
@@ -57,6 +58,7 @@ interface InvoiceItemAddon {
   quantity: number;
   rate: number;
   amount: number;
+  gstRate?: number;
 }
 
 interface ServiceSelectorProps {
@@ -182,6 +184,7 @@ export function ServiceSelector({
           quantity: qty,
           rate: addon.price,
           amount: addon.price * qty,
+          gstRate: addon.gst_rate,
         };
       })
       .filter(Boolean) as InvoiceItemAddon[];
@@ -230,6 +233,7 @@ export function ServiceSelector({
       rate: getCurrentRate(),
       amount: calculateAmount(),
       gstRate: getCurrentGstRate(),
+      categoryId: selectedCategory ?? undefined,
       addons: getSelectedAddonsData(),
     };
 
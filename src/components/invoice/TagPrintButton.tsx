@@ -1,6 +1,7 @@
 import React, { useState } from 'react';
 import { invoke } from '@tauri-apps/api/tauri';
 import { toast } from 'react-hot-toast';
+import { useApp } from '@/contexts/AppContext';
 
 interface TagPrintButtonProps {
   invoiceId: number;
@@ -17,6 +18,7 @@ interface TagPrintResponse {
   success: boolean;
   message: string;
   tags_printed: number;
+  print_job_id?: number | null;
 }
 
 export const TagPrintButton: React.FC<TagPrintButtonProps> = ({
@@ -29,18 +31,41 @@ export const TagPrintButton: React.FC<TagPrintButtonProps> = ({
   variant = 'icon',
   className = '',
 }) => {
+  const { state, setOperator } = useApp();
   const [isPrinting, setIsPrinting] = useState(false);
 
   const handlePrintTags = async () => {
     if (isPrinting) return;
 
     try {
+      // Print jobs record who printed them
+      let operator = state.operator;
+      if (!operator) {
+        operator = window.prompt('Who is printing these tags?')?.trim() || null;
+        if (!operator) {
+          return;
+        }
+        setOperator(operator);
+      }
+
+      // Reprints are audited and need a reason
+      let reprintReason: string | null = null;
+      if (isPrinted) {
+        reprintReason = window.prompt('Reason for reprinting these tags (e.g. tag torn, lost, wrong printer)');
+        if (!reprintReason || !reprintReason.trim()) {
+          return;
+        }
+      }
+
       setIsPrinting(true);
 
       const response = await invoke<TagPrintResponse>('print_invoice_tags', {
         request: {
           invoice_id: invoiceId,
           item_ids: null, // Print all items
+          tag_ids: null,
+          printed_by: operator,
+          reprint_reason: reprintReason,
         },
       });
 
@@ -30,6 +30,8 @@ interface ServiceAddon {
   description: string;
   price: number;
   unit: string;
+  pricing_mode?: string;
+  gst_rate?: number | null;
   isActive: boolean;
 }
 
@@ -271,10 +273,13 @@ export function ServiceForm() {
           is_active: v.isActive,
         })),
         addons: formData.addons.map(a => ({
+          id: a.id ?? null,
           name: a.name.trim(),
           description: a.description.trim() || null,
           price: a.price,
           unit: a.unit.trim(),
+          pricing_mode: a.pricing_mode,
+          gst_rate: a.gst_rate ?? null,
           is_active: a.isActive,
         })),
       };
@@ -14,6 +14,10 @@ interface TagSettings {
   printer_name?: string;
   template_style: string;
   include_barcode: boolean;
+  printer_language: string;
+  show_customer_phone: boolean;
+  show_store_code: boolean;
+  font_scale: number;
   created_at: string;
   updated_at: string;
 }
@@ -24,6 +28,15 @@ interface UpdateTagSettingsRequest {
   printer_name: string | null;
   template_style: string;
   include_barcode: boolean;
+  printer_language: string;
+  show_customer_phone: boolean;
+  show_store_code: boolean;
+  font_scale: number;
+}
+
+interface StoreOption {
+  id: number;
+  name: string;
 }
 
 const ROLL_WIDTH_OPTIONS = [
@@ -32,11 +45,26 @@ const ROLL_WIDTH_OPTIONS = [
   { value: '50mm', label: '50mm (Large)' },
 ];
 
+const PRINTER_LANGUAGE_OPTIONS = [
+  { value: 'html', label: 'System print dialog' },
+  { value: 'zpl', label: 'ZPL (Zebra)' },
+  { value: 'tspl', label: 'TSPL (TSC, Xprinter)' },
+  { value: 'escpos', label: 'ESC/POS' },
+];
+
 const TEMPLATE_STYLE_OPTIONS = [
   { value: 'standard', label: 'Standard' },
   { value: 'compact', label: 'Compact' },
 ];
 
+const FONT_SCALE_OPTIONS = [
+  { value: 0.8, label: 'Smaller (80%)' },
+  { value: 0.9, label: 'Small (90%)' },
+  { value: 1.0, label: 'Normal' },
+  { value: 1.2, label: 'Large (120%)' },
+  { value: 1.5, label: 'Largest (150%)' },
+];
+
 export const TagSettings: React.FC = () => {
   const [settings, setSettings] = useState<TagSettings | null>(null);
   const [loading, setLoading] = useState(true);
@@ -48,16 +76,30 @@ export const TagSettings: React.FC = () => {
   const [printerName, setPrinterName] = useState('');
   const [templateStyle, setTemplateStyle] = useState('standard');
   const [includeBarcode, setIncludeBarcode] = useState(true);
+  const [printerLanguage, setPrinterLanguage] = useState('html');
+  const [showCustomerPhone, setShowCustomerPhone] = useState(false);
+  const [showStoreCode, setShowStoreCode] = useState(false);
+  const [fontScale, setFontScale] = useState(1.0);
+
+  // Settings are saved per store; no store selected edits the defaults
+  const [stores, setStores] = useState<StoreOption[]>([]);
+  const [storeId, setStoreId] = useState<number | null>(null);
 
   useEffect(() => {
-    loadSettings();
+    invoke<StoreOption[]>('get_active_stores')
+      .then(setStores)
+      .catch((error) => console.error('Failed to load stores:', error));
   }, []);
 
+  useEffect(() => {
+    loadSettings();
+  }, [storeId]);
+
   const loadSettings = async () => {
     try {
       setLoading(true);
       const result = await invoke<TagSettings>('get_tag_settings', {
-        store_id: null, // Global settings for now
+        store_id: storeId,
       });
 
       setSettings(result);
@@ -66,6 +108,10 @@ export const TagSettings: React.FC = () => {
       setPrinterName(result.printer_name || '');
       setTemplateStyle(result.template_style);
       setIncludeBarcode(Boolean(result.include_barcode));
+      setPrinterLanguage(result.printer_language || 'html');
+      setShowCustomerPhone(Boolean(result.show_customer_phone));
+      setShowStoreCode(Boolean(result.show_store_code));
+      setFontScale(result.font_scale || 1.0);
     } catch (error) {
       console.error('Failed to load tag settings:', error);
       toast.error('Failed to load tag settings');
@@ -84,10 +130,14 @@ export const TagSettings: React.FC = () => {
         printer_name: printerName || "",
         template_style: templateStyle,
         include_barcode: includeBarcode,
+        printer_language: printerLanguage,
+        show_customer_phone: showCustomerPhone,
+        show_store_code: showStoreCode,
+        font_scale: fontScale,
       };
 
       const result = await invoke<TagSettings>('save_tag_settings', {
-        store_id: null, // Global settings for now
+        store_id: storeId,
         request,
       });
       setSettings(result);
@@ -107,6 +157,10 @@ export const TagSettings: React.FC = () => {
       setPrinterName(settings.printer_name || '');
       setTemplateStyle(settings.template_style);
       setIncludeBarcode(Boolean(settings.include_barcode));
+      setPrinterLanguage(settings.printer_language || 'html');
+      setShowCustomerPhone(Boolean(settings.show_customer_phone));
+      setShowStoreCode(Boolean(settings.show_store_code));
+      setFontScale(settings.font_scale || 1.0);
     }
   };
 
@@ -128,6 +182,28 @@ export const TagSettings: React.FC = () => {
           </CardTitle>
         </CardHeader>
         <CardContent className="space-y-6">
+          {/* Store */}
+          <div className="space-y-2">
+            <label className="text-sm font-medium text-gray-700">
+              Store
+            </label>
+            <select
+              value={storeId ?? ''}
+              onChange={(e) => setStoreId(e.target.value ? Number(e.target.value) : null)}
+              className="w-full px-3 py-2 border border-gray-300 rounded-md shadow-sm focus:outline-none focus:ring-2 focus:ring-blue-500 focus:border-blue-500"
+            >
+              <option value="">All stores (default)</option>
+              {stores.map((store) => (
+                <option key={store.id} value={store.id}>
+                  {store.name}
+                </option>
+              ))}
+            </select>
+            <p className="text-xs text-gray-500">
+              Stores without their own settings use the defaults.
+            </p>
+          </div>
+
           {/* Roll Width */}
           <div className="space-y-2">
             <label className="text-sm font-medium text-gray-700">
@@ -180,7 +256,30 @@ export const TagSettings: React.FC = () => {
               className="w-full"
             />
             <p className="text-xs text-gray-500">
-              Leave blank to use system default printer.
+              {printerLanguage === 'html'
+                ? 'Leave blank to use system default printer.'
+                : 'Required: CUPS queue name, host:port for a network printer, or a device such as /dev/usb/lp0 or COM3.'}
+            </p>
+          </div>
+
+          {/* Printer Language */}
+          <div className="space-y-2">
+            <label className="text-sm font-medium text-gray-700">
+              Printer Language
+            </label>
+            <select
+              value={printerLanguage}
+              onChange={(e) => setPrinterLanguage(e.target.value)}
+              className="w-full px-3 py-2 border border-gray-300 rounded-md shadow-sm focus:outline-none focus:ring-2 focus:ring-blue-500 focus:border-blue-500"
+            >
+              {PRINTER_LANGUAGE_OPTIONS.map((option) => (
+                <option key={option.value} value={option.value}>
+                  {option.label}
+                </option>
+              ))}
+            </select>
+            <p className="text-xs text-gray-500">
+              Thermal label printers print fastest from their own command language. Raw labels use ASCII text only.
             </p>
           </div>
 
@@ -223,6 +322,57 @@ export const TagSettings: React.FC = () => {
             </p>
           </div>
 
+          {/* Customer Phone */}
+          <div className="space-y-2">
+            <label className="flex items-center space-x-3">
+              <input
+                type="checkbox"
+                checked={showCustomerPhone}
+                onChange={(e) => setShowCustomerPhone(e.target.checked)}
+                className="h-4 w-4 text-blue-600 focus:ring-blue-500 border-gray-300 rounded"
+              />
+              <span className="text-sm font-medium text-gray-700">
+                Show customer phone on tags
+              </span>
+            </label>
+          </div>
+
+          {/* Store Code */}
+          <div className="space-y-2">
+            <label className="flex items-center space-x-3">
+              <input
+                type="checkbox"
+                checked={showStoreCode}
+                onChange={(e) => setShowStoreCode(e.target.checked)}
+                className="h-4 w-4 text-blue-600 focus:ring-blue-500 border-gray-300 rounded"
+              />
+              <span className="text-sm font-medium text-gray-700">
+                Show store code on tags
+              </span>
+            </label>
+            <p className="text-xs text-gray-500 ml-7">
+              Helps sort garments when one plant serves several stores.
+            </p>
+          </div>
+
+          {/* Font Scale */}
+          <div className="space-y-2">
+            <label className="text-sm font-medium text-gray-700">
+              Text Size
+            </label>
+            <select
+              value={fontScale}
+              onChange={(e) => setFontScale(Number(e.target.value))}
+              className="w-full px-3 py-2 border border-gray-300 rounded-md shadow-sm focus:outline-none focus:ring-2 focus:ring-blue-500 focus:border-blue-500"
+            >
+              {FONT_SCALE_OPTIONS.map((option) => (
+                <option key={option.value} value={option.value}>
+                  {option.label}
+                </option>
+              ))}
+            </select>
+          </div>
+
           {/* Action Buttons */}
           <div className="flex items-center justify-between pt-4 border-t">
             <Button
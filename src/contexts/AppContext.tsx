@@ -7,6 +7,7 @@ interface AppState {
   error: string | null;
   stores: Store[];
   currentStore: Store | null;
+  operator: string | null; // staff member at the counter, recorded on prints and scans
   recentCustomers: Customer[];
   notifications: Notification[];
 }
@@ -43,6 +44,7 @@ type AppAction =
   | { type: 'SET_ERROR'; payload: string | null }
   | { type: 'SET_STORES'; payload: Store[] }
   | { type: 'SET_CURRENT_STORE'; payload: Store }
+  | { type: 'SET_OPERATOR'; payload: string | null }
   | { type: 'SET_RECENT_CUSTOMERS'; payload: Customer[] }
   | { type: 'ADD_NOTIFICATION'; payload: Notification }
   | { type: 'REMOVE_NOTIFICATION'; payload: string }
@@ -59,6 +61,17 @@ interface AppContextType {
   refreshStores: () => Promise<void>;
   refreshRecentCustomers: () => Promise<void>;
   selectStore: (store: Store) => void;
+  setOperator: (operator: string | null) => void;
+}
+
+const OPERATOR_STORAGE_KEY = 'uclean.operator';
+
+function loadOperator(): string | null {
+  try {
+    return localStorage.getItem(OPERATOR_STORAGE_KEY);
+  } catch {
+    return null;
+  }
 }
 
 const initialState: AppState = {
@@ -66,6 +79,7 @@ const initialState: AppState = {
   error: null,
   stores: [],
   currentStore: null,
+  operator: loadOperator(),
   recentCustomers: [],
   notifications: [],
 };
@@ -80,6 +94,8 @@ function appReducer(state: AppState, action: AppAction): AppState {
       return { ...state, stores: action.payload };
     case 'SET_CURRENT_STORE':
       return { ...state, currentStore: action.payload };
+    case 'SET_OPERATOR':
+      return { ...state, operator: action.payload };
     case 'SET_RECENT_CUSTOMERS':
       return { ...state, recentCustomers: action.payload };
     case 'ADD_NOTIFICATION':
@@ -173,6 +189,20 @@ export function AppProvider({ children }: { children: React.ReactNode }) {
     dispatch({ type: 'SET_CURRENT_STORE', payload: store });
   }, []);
 
+  const setOperator = useCallback((operator: string | null) => {
+    const name = operator?.trim() || null;
+    try {
+      if (name) {
+        localStorage.setItem(OPERATOR_STORAGE_KEY, name);
+      } else {
+        localStorage.removeItem(OPERATOR_STORAGE_KEY);
+      }
+    } catch (error) {
+      console.error('Failed to save operator:', error);
+    }
+    dispatch({ type: 'SET_OPERATOR', payload: name });
+  }, []);
+
   // Initialize app data
   useEffect(() => {
     const initializeApp = async () => {
@@ -203,7 +233,8 @@ export function AppProvider({ children }: { children: React.ReactNode }) {
     refreshStores,
     refreshRecentCustomers,
     selectStore,
-  }), [state, setLoading, setError, showNotification, clearError, refreshStores, refreshRecentCustomers, selectStore]);
+    setOperator,
+  }), [state, setLoading, setError, showNotification, clearError, refreshStores, refreshRecentCustomers, selectStore, setOperator]);
 
   return (
     <AppContext.Provider value={contextValue}>
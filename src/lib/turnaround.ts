@@ -0,0 +1,14 @@
+export const DEFAULT_TURNAROUND_TIER = 'standard';
+
+export interface TurnaroundTier {
+  id: number;
+  store_id: number | null;
+  code: string;
+  name: string;
+  surcharge_type: 'percent' | 'flat';
+  surcharge_value: number;
+  min_surcharge: number;
+  turnaround_hours: number;
+  excluded_category_ids: string; // JSON array of category ids
+  is_active: number | null;
+}